@@ -0,0 +1,68 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use MEV_Bot_Solana::common::maths::{clmm_swap, constant_product_out, FeeMultiplier, TickCrossing};
+
+// SOL/USDC-like Whirlpool: sqrt price around 150 USDC (decimals 9/6), 0.3% fee
+const SQRT_PRICE_X64: u128 = 7_143_265_591_698_411_110;
+const LIQUIDITY: u128 = 120_000_000_000_000;
+const FEE_RATE: u16 = 3_000;
+const AMOUNT_IN: u64 = 50_000_000_000;
+
+// Initialized ticks below the current price, one every ~0.5% of price
+fn ticks_down(count: usize) -> Vec<TickCrossing> {
+    (1..=count)
+        .map(|i| TickCrossing { sqrt_price_x64: (SQRT_PRICE_X64 as f64 * 0.9975f64.powi(i as i32)) as u128, liquidity_net: 10_000_000_000_000 })
+        .collect()
+}
+
+// The floating point version the fixed-point math replaced, kept as the baseline
+fn clmm_swap_f64(amount_in: u64, liquidity: u128, sqrt_price_x64: u128, fee_rate: u16, ticks: &[TickCrossing]) -> Option<u64> {
+    let mut remaining = amount_in as f64 * (1_000_000 - fee_rate as u32) as f64 / 1_000_000.0;
+    let mut liquidity = liquidity as f64;
+    let mut sqrt_price = sqrt_price_x64 as f64 / 2f64.powi(64);
+    let mut amount_out = 0.0;
+    for tick in ticks.iter().map(Some).chain(std::iter::once(None)) {
+        let target = tick.map(|tick| tick.sqrt_price_x64 as f64 / 2f64.powi(64));
+        if let Some(target) = target {
+            let max_in = liquidity * (1.0 / target - 1.0 / sqrt_price);
+            if remaining >= max_in {
+                remaining -= max_in;
+                amount_out += liquidity * (sqrt_price - target);
+                sqrt_price = target;
+                liquidity -= tick.unwrap().liquidity_net as f64;
+                continue;
+            }
+        }
+        let next_sqrt_price = liquidity * sqrt_price / (liquidity + remaining * sqrt_price);
+        amount_out += liquidity * (sqrt_price - next_sqrt_price);
+        break;
+    }
+    (amount_out.is_finite() && amount_out >= 0.0).then_some(amount_out as u64)
+}
+
+fn constant_product_out_div(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_numerator: u64, fee_denominator: u64) -> Option<u64> {
+    let amount_in = amount_in as u128 * (fee_denominator - fee_numerator) as u128 / fee_denominator as u128;
+    u64::try_from(amount_in * reserve_out as u128 / (reserve_in as u128 + amount_in)).ok()
+}
+
+fn bench_constant_product(c: &mut Criterion) {
+    let fee = FeeMultiplier::from_ratio(25, 10_000).unwrap();
+    let mut group = c.benchmark_group("constant_product");
+    group.bench_function("fixed_point", |b| b.iter(|| constant_product_out(black_box(AMOUNT_IN), black_box(8_000_000_000_000), black_box(1_200_000_000_000), fee)));
+    group.bench_function("division", |b| b.iter(|| constant_product_out_div(black_box(AMOUNT_IN), black_box(8_000_000_000_000), black_box(1_200_000_000_000), 25, 10_000)));
+    group.finish();
+}
+
+fn bench_clmm(c: &mut Criterion) {
+    let fee = FeeMultiplier::from_fee_rate(FEE_RATE).unwrap();
+    for crossings in [0, 4, 16] {
+        let ticks = ticks_down(crossings);
+        let mut group = c.benchmark_group(format!("clmm_{}_ticks", crossings));
+        group.bench_function("fixed_point", |b| b.iter(|| clmm_swap(black_box(AMOUNT_IN * 40), LIQUIDITY, SQRT_PRICE_X64, fee, true, black_box(&ticks))));
+        group.bench_function("f64", |b| b.iter(|| clmm_swap_f64(black_box(AMOUNT_IN * 40), LIQUIDITY, SQRT_PRICE_X64, FEE_RATE, black_box(&ticks))));
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_constant_product, bench_clmm);
+criterion_main!(benches);
@@ -0,0 +1,274 @@
+use std::mem::size_of;
+
+use bytemuck::{Pod, Zeroable};
+
+// Views over the raw pool accounts (addresses as their 32 bytes): fields read in place at their on-chain offsets,
+// no borsh/serde pass and no allocation on the account update path.
+// Only the leading fields the quotes need are laid out, the accounts are longer.
+
+// Raydium AMM v4 (752 bytes)
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct RaydiumAmmLayout {
+    _header: [u8; 144],
+    trade_fee_numerator: [u8; 8],
+    trade_fee_denominator: [u8; 8],
+    _fees: [u8; 32],
+    need_take_pnl_coin: [u8; 8],
+    need_take_pnl_pc: [u8; 8],
+    _state_data: [u8; 128],
+    coin_vault: [u8; 32],
+    pc_vault: [u8; 32],
+    coin_vault_mint: [u8; 32],
+    pc_vault_mint: [u8; 32],
+    lp_mint: [u8; 32],
+}
+
+impl RaydiumAmmLayout {
+    pub fn trade_fee(&self) -> (u64, u64) {
+        (u64::from_le_bytes(self.trade_fee_numerator), u64::from_le_bytes(self.trade_fee_denominator))
+    }
+
+    pub fn need_take_pnl(&self) -> (u64, u64) {
+        (u64::from_le_bytes(self.need_take_pnl_coin), u64::from_le_bytes(self.need_take_pnl_pc))
+    }
+
+    pub fn coin_vault(&self) -> [u8; 32] {
+        self.coin_vault
+    }
+
+    pub fn pc_vault(&self) -> [u8; 32] {
+        self.pc_vault
+    }
+
+    pub fn coin_vault_mint(&self) -> [u8; 32] {
+        self.coin_vault_mint
+    }
+
+    pub fn pc_vault_mint(&self) -> [u8; 32] {
+        self.pc_vault_mint
+    }
+
+    pub fn lp_mint(&self) -> [u8; 32] {
+        self.lp_mint
+    }
+}
+
+// Orca Whirlpool (653 bytes), same offsets as orca_whirpools::unpack_from_slice
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct WhirlpoolLayout {
+    _discriminator: [u8; 8],
+    whirlpools_config: [u8; 32],
+    _whirlpool_bump: [u8; 1],
+    tick_spacing: [u8; 2],
+    _tick_spacing_seed: [u8; 2],
+    fee_rate: [u8; 2],
+    _protocol_fee_rate: [u8; 2],
+    liquidity: [u8; 16],
+    sqrt_price: [u8; 16],
+    tick_current_index: [u8; 4],
+    _protocol_fee_owed: [u8; 16],
+    token_mint_a: [u8; 32],
+    token_vault_a: [u8; 32],
+    _fee_growth_global_a: [u8; 16],
+    token_mint_b: [u8; 32],
+    token_vault_b: [u8; 32],
+}
+
+impl WhirlpoolLayout {
+    pub fn whirlpools_config(&self) -> [u8; 32] {
+        self.whirlpools_config
+    }
+
+    pub fn tick_spacing(&self) -> u16 {
+        u16::from_le_bytes(self.tick_spacing)
+    }
+
+    // Hundredths of a basis point
+    pub fn fee_rate(&self) -> u16 {
+        u16::from_le_bytes(self.fee_rate)
+    }
+
+    pub fn liquidity(&self) -> u128 {
+        u128::from_le_bytes(self.liquidity)
+    }
+
+    // Q64.64
+    pub fn sqrt_price(&self) -> u128 {
+        u128::from_le_bytes(self.sqrt_price)
+    }
+
+    pub fn tick_current_index(&self) -> i32 {
+        i32::from_le_bytes(self.tick_current_index)
+    }
+
+    pub fn token_mint_a(&self) -> [u8; 32] {
+        self.token_mint_a
+    }
+
+    pub fn token_vault_a(&self) -> [u8; 32] {
+        self.token_vault_a
+    }
+
+    pub fn token_mint_b(&self) -> [u8; 32] {
+        self.token_mint_b
+    }
+
+    pub fn token_vault_b(&self) -> [u8; 32] {
+        self.token_vault_b
+    }
+}
+
+// Whirlpool tick array (9988 bytes): 88 ticks of 113 bytes after the start tick, then the whirlpool.
+// Packed on-chain, so the ticks are read at their offsets rather than through a Pod view
+pub static TICK_ARRAY_SIZE: i32 = 88;
+const TICK_ARRAY_TICKS_OFFSET: usize = 12;
+const TICK_ARRAY_TICK_LEN: usize = 113;
+const TICK_ARRAY_WHIRLPOOL_OFFSET: usize = 9956;
+
+#[derive(Clone, Copy)]
+pub struct WhirlpoolTickArray<'a> {
+    data: &'a [u8],
+}
+
+impl WhirlpoolTickArray<'_> {
+    pub fn start_tick_index(&self) -> i32 {
+        i32::from_le_bytes(self.data[8..12].try_into().unwrap())
+    }
+
+    pub fn whirlpool(&self) -> [u8; 32] {
+        self.data[TICK_ARRAY_WHIRLPOOL_OFFSET..TICK_ARRAY_WHIRLPOOL_OFFSET + 32].try_into().unwrap()
+    }
+
+    // Liquidity net of the i-th tick of the array, None when the tick is not initialized
+    pub fn liquidity_net(&self, i: usize) -> Option<i128> {
+        let offset = TICK_ARRAY_TICKS_OFFSET + i * TICK_ARRAY_TICK_LEN;
+        let tick = self.data.get(offset..offset + 17).filter(|_| i < TICK_ARRAY_SIZE as usize)?;
+        (tick[0] != 0).then(|| i128::from_le_bytes(tick[1..17].try_into().unwrap()))
+    }
+}
+
+// Meteora DLMM LbPair, same offsets as meteora::AccountData
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct MeteoraLbPairLayout {
+    _header: [u8; 76],
+    active_id: [u8; 4],
+    bin_step: [u8; 2],
+    status: [u8; 1],
+    _padding: [u8; 5],
+    token_x_mint: [u8; 32],
+    token_y_mint: [u8; 32],
+    reserve_x: [u8; 32],
+    reserve_y: [u8; 32],
+}
+
+impl MeteoraLbPairLayout {
+    pub fn active_id(&self) -> i32 {
+        i32::from_le_bytes(self.active_id)
+    }
+
+    pub fn bin_step(&self) -> u16 {
+        u16::from_le_bytes(self.bin_step)
+    }
+
+    pub fn status(&self) -> u8 {
+        self.status[0]
+    }
+
+    pub fn token_x_mint(&self) -> [u8; 32] {
+        self.token_x_mint
+    }
+
+    pub fn token_y_mint(&self) -> [u8; 32] {
+        self.token_y_mint
+    }
+
+    pub fn reserve_x(&self) -> [u8; 32] {
+        self.reserve_x
+    }
+
+    pub fn reserve_y(&self) -> [u8; 32] {
+        self.reserve_y
+    }
+}
+
+// None when the account is shorter than the layout
+fn view<T: Pod>(data: &[u8]) -> Option<&T> {
+    bytemuck::try_from_bytes(data.get(..size_of::<T>())?).ok()
+}
+
+pub fn raydium_amm(data: &[u8]) -> Option<&RaydiumAmmLayout> {
+    view(data)
+}
+
+pub fn whirlpool(data: &[u8]) -> Option<&WhirlpoolLayout> {
+    view(data)
+}
+
+pub fn whirlpool_tick_array(data: &[u8]) -> Option<WhirlpoolTickArray<'_>> {
+    (data.len() >= TICK_ARRAY_WHIRLPOOL_OFFSET + 32).then_some(WhirlpoolTickArray { data })
+}
+
+pub fn meteora_lb_pair(data: &[u8]) -> Option<&MeteoraLbPairLayout> {
+    view(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raydium_amm_fields_at_their_offsets() {
+        let mut data = vec![0u8; 752];
+        data[144..152].copy_from_slice(&25u64.to_le_bytes());
+        data[152..160].copy_from_slice(&10_000u64.to_le_bytes());
+        data[192..200].copy_from_slice(&7u64.to_le_bytes());
+        data[336..368].copy_from_slice(&[1; 32]);
+        data[368..400].copy_from_slice(&[2; 32]);
+        data[464..496].copy_from_slice(&[3; 32]);
+        let amm = raydium_amm(&data).unwrap();
+        assert_eq!(amm.trade_fee(), (25, 10_000));
+        assert_eq!(amm.need_take_pnl(), (7, 0));
+        assert_eq!((amm.coin_vault(), amm.pc_vault(), amm.lp_mint()), ([1; 32], [2; 32], [3; 32]));
+    }
+
+    #[test]
+    fn whirlpool_fields_at_their_offsets() {
+        let mut data = vec![0u8; 653];
+        data[41..43].copy_from_slice(&64u16.to_le_bytes());
+        data[45..47].copy_from_slice(&3_000u16.to_le_bytes());
+        data[49..65].copy_from_slice(&5u128.to_le_bytes());
+        data[65..81].copy_from_slice(&(1u128 << 64).to_le_bytes());
+        data[81..85].copy_from_slice(&(-42i32).to_le_bytes());
+        data[101..133].copy_from_slice(&[9; 32]);
+        let pool = whirlpool(&data).unwrap();
+        assert_eq!((pool.tick_spacing(), pool.fee_rate(), pool.liquidity(), pool.sqrt_price()), (64, 3_000, 5, 1 << 64));
+        assert_eq!((pool.tick_current_index(), pool.token_mint_a()), (-42, [9; 32]));
+    }
+
+    #[test]
+    fn tick_array_ticks_at_their_offsets() {
+        let mut data = vec![0u8; 9988];
+        data[8..12].copy_from_slice(&(-5_632i32).to_le_bytes());
+        // Tick 3 initialized with a negative liquidity net
+        let offset = TICK_ARRAY_TICKS_OFFSET + 3 * TICK_ARRAY_TICK_LEN;
+        data[offset] = 1;
+        data[offset + 1..offset + 17].copy_from_slice(&(-1_000i128).to_le_bytes());
+        data[9956..9988].copy_from_slice(&[4; 32]);
+        let tick_array = whirlpool_tick_array(&data).unwrap();
+        assert_eq!((tick_array.start_tick_index(), tick_array.whirlpool()), (-5_632, [4; 32]));
+        assert_eq!(tick_array.liquidity_net(3), Some(-1_000));
+        assert_eq!(tick_array.liquidity_net(2), None);
+        assert_eq!(tick_array.liquidity_net(TICK_ARRAY_SIZE as usize), None);
+    }
+
+    #[test]
+    fn short_accounts_are_not_decoded() {
+        assert!(raydium_amm(&[0u8; 100]).is_none());
+        assert!(whirlpool(&[0u8; 200]).is_none());
+        assert!(meteora_lb_pair(&[0u8; 100]).is_none());
+        assert!(whirlpool_tick_array(&[0u8; 9000]).is_none());
+    }
+}
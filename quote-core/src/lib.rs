@@ -0,0 +1,7 @@
+// Pool math and local quotes of the bot, without tokio, RPC or Solana SDK: what the wasm build
+// (feature wasm) compiles for the dashboards to re-verify the quotes
+pub mod layouts;
+pub mod maths;
+pub mod quote;
+#[cfg(feature = "wasm")]
+pub mod wasm;
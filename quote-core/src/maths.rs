@@ -0,0 +1,245 @@
+use uint::construct_uint;
+
+construct_uint! {
+    pub struct U256(4);
+}
+
+// Whirlpool fee rates are in hundredths of a basis point
+pub static FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+// Q64.64, the format of the Whirlpool sqrt prices
+const Q64: u128 = 1 << 64;
+
+// 1 - fee as a Q32 multiplier, computed once per pool: applying it is a multiply and a shift
+// instead of a division. Rounded down, the quote never gets more than the pool pays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeMultiplier(u64);
+
+impl FeeMultiplier {
+    pub fn from_ratio(fee_numerator: u64, fee_denominator: u64) -> Option<Self> {
+        if fee_denominator == 0 || fee_numerator > fee_denominator {
+            return None;
+        }
+        Some(FeeMultiplier(((((fee_denominator - fee_numerator) as u128) << 32) / fee_denominator as u128) as u64))
+    }
+
+    pub fn from_fee_rate(fee_rate: u16) -> Option<Self> {
+        FeeMultiplier::from_ratio(fee_rate as u64, FEE_RATE_DENOMINATOR)
+    }
+
+    pub fn apply(self, amount: u64) -> u64 {
+        ((amount as u128 * self.0 as u128) >> 32) as u64
+    }
+}
+
+// a * b / denominator, through 256 bits only when the product overflows. None when the result does not fit
+pub fn mul_div(a: u128, b: u128, denominator: u128, round_up: bool) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+    if let Some(product) = a.checked_mul(b) {
+        return Some(if round_up { product.div_ceil(denominator) } else { product / denominator });
+    }
+    let (quotient, remainder) = (U256::from(a) * U256::from(b)).div_mod(U256::from(denominator));
+    let quotient = if round_up && !remainder.is_zero() { quotient + 1 } else { quotient };
+    (quotient <= U256::from(u128::MAX)).then(|| quotient.as_u128())
+}
+
+// x * y = k, the fee taken on the amount in
+pub fn constant_product_out(amount_in: u64, reserve_in: u64, reserve_out: u64, fee: FeeMultiplier) -> Option<u64> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return None;
+    }
+    let amount_in = fee.apply(amount_in) as u128;
+    u64::try_from(amount_in * reserve_out as u128 / (reserve_in as u128 + amount_in)).ok()
+}
+
+// Token A between two sqrt prices: L * (1/√lower - 1/√upper)
+pub fn amount_a_delta(liquidity: u128, sqrt_price_lower: u128, sqrt_price_upper: u128, round_up: bool) -> Option<u128> {
+    let per_upper = mul_div(liquidity, sqrt_price_upper - sqrt_price_lower, sqrt_price_upper, round_up)?;
+    mul_div(per_upper, Q64, sqrt_price_lower, round_up)
+}
+
+// Token B between two sqrt prices: L * (√upper - √lower)
+pub fn amount_b_delta(liquidity: u128, sqrt_price_lower: u128, sqrt_price_upper: u128, round_up: bool) -> Option<u128> {
+    mul_div(liquidity, sqrt_price_upper - sqrt_price_lower, Q64, round_up)
+}
+
+// Bounds of the Whirlpool ticks
+pub static MIN_TICK_INDEX: i32 = -443_636;
+pub static MAX_TICK_INDEX: i32 = 443_636;
+
+// 1/√1.0001^(2^i) in Q128.128, one per bit of a tick index
+const TICK_RATIOS: [u128; 19] = [
+    0xfffcb933bd6fad37aa2d162d1a594001,
+    0xfff97272373d413259a46990580e213a,
+    0xfff2e50f5f656932ef12357cf3c7fdcc,
+    0xffe5caca7e10e4e61c3624eaa0941cd0,
+    0xffcb9843d60f6159c9db58835c926644,
+    0xff973b41fa98c081472e6896dfb254c0,
+    0xff2ea16466c96a3843ec78b326b52861,
+    0xfe5dee046a99a2a811c461f1969c3053,
+    0xfcbe86c7900a88aedcffc83b479aa3a4,
+    0xf987a7253ac413176f2b074cf7815e54,
+    0xf3392b0822b70005940c7a398e4b70f3,
+    0xe7159475a2c29b7443b29c7fa6e889d9,
+    0xd097f3bdfd2022b8845ad8f792aa5825,
+    0xa9f746462d870fdf8a65dc1f90e061e5,
+    0x70d869a156d2a1b890bb3df62baf32f7,
+    0x31be135f97d08fd981231505542fcfa6,
+    0x9aa508b5b7a84e1c677de54f3e99bc9,
+    0x5d6af8dedb81196699c329225ee604,
+    0x2216e584f5fa1ea926041bedfe98,
+];
+
+// √1.0001^tick in Q64.64, the tick clamped to the Whirlpool bounds
+pub fn sqrt_price_from_tick_index(tick: i32) -> u128 {
+    let tick = tick.clamp(MIN_TICK_INDEX, MAX_TICK_INDEX);
+    let abs_tick = tick.unsigned_abs();
+    let mut ratio = U256::one() << 128;
+    for (bit, tick_ratio) in TICK_RATIOS.iter().enumerate() {
+        if abs_tick & (1 << bit) != 0 {
+            ratio = (ratio * U256::from(*tick_ratio)) >> 128;
+        }
+    }
+    if tick > 0 {
+        ratio = U256::MAX / ratio;
+    }
+    (ratio >> 64).as_u128()
+}
+
+// First tick of the tick array holding the given tick
+pub fn tick_array_start_index(tick: i32, tick_spacing: u16) -> Option<i32> {
+    let span = (tick_spacing as i32).checked_mul(crate::layouts::TICK_ARRAY_SIZE).filter(|span| *span > 0)?;
+    Some(tick.div_euclid(span) * span)
+}
+
+// An initialized tick the swap may cross, given in the order the swap meets them
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TickCrossing {
+    pub sqrt_price_x64: u128,
+    pub liquidity_net: i128,
+}
+
+// Swap within one liquidity range, up to the target price when the amount is enough to reach it.
+// Amount in rounded up, amount out and next price rounded against the trader.
+// Returns the amount in consumed, the amount out and the sqrt price reached
+pub fn clmm_step(amount_remaining: u128, liquidity: u128, sqrt_price_x64: u128, target_sqrt_price_x64: Option<u128>, a_to_b: bool) -> Option<(u128, u128, u128)> {
+    if a_to_b {
+        if let Some(target) = target_sqrt_price_x64.filter(|target| *target < sqrt_price_x64) {
+            let max_in = amount_a_delta(liquidity, target, sqrt_price_x64, true)?;
+            if amount_remaining >= max_in {
+                return Some((max_in, amount_b_delta(liquidity, target, sqrt_price_x64, false)?, target));
+            }
+        }
+        // √p' = L * √p / (L + Δa * √p)
+        let product = mul_div(amount_remaining, sqrt_price_x64, Q64, false)?;
+        let next = mul_div(liquidity, sqrt_price_x64, liquidity.checked_add(product)?, true)?;
+        Some((amount_remaining, amount_b_delta(liquidity, next, sqrt_price_x64, false)?, next))
+    } else {
+        if let Some(target) = target_sqrt_price_x64.filter(|target| *target > sqrt_price_x64) {
+            let max_in = amount_b_delta(liquidity, sqrt_price_x64, target, true)?;
+            if amount_remaining >= max_in {
+                return Some((max_in, amount_a_delta(liquidity, sqrt_price_x64, target, false)?, target));
+            }
+        }
+        // √p' = √p + Δb / L
+        let next = sqrt_price_x64.checked_add(mul_div(amount_remaining, Q64, liquidity, false)?)?;
+        Some((amount_remaining, amount_a_delta(liquidity, sqrt_price_x64, next, false)?, next))
+    }
+}
+
+// Concentrated liquidity swap in integer math, crossing the given ticks. Without ticks the swap stays
+// in the current range: exact for the small amounts arbitraged, optimistic past the next initialized tick.
+// None when the swap runs out of liquidity or an intermediate does not fit
+pub fn clmm_swap(amount_in: u64, liquidity: u128, sqrt_price_x64: u128, fee: FeeMultiplier, a_to_b: bool, ticks: &[TickCrossing]) -> Option<u64> {
+    clmm_swap_to(amount_in, liquidity, sqrt_price_x64, fee, a_to_b, ticks, None)
+}
+
+// Same swap, None as well when it would go past the limit price: the end of the ticks known to the caller
+pub fn clmm_swap_to(amount_in: u64, liquidity: u128, sqrt_price_x64: u128, fee: FeeMultiplier, a_to_b: bool, ticks: &[TickCrossing], limit_sqrt_price_x64: Option<u128>) -> Option<u64> {
+    if sqrt_price_x64 == 0 {
+        return None;
+    }
+    let (mut liquidity, mut sqrt_price_x64) = (liquidity, sqrt_price_x64);
+    let mut remaining = fee.apply(amount_in) as u128;
+    let mut amount_out: u128 = 0;
+    let limit = limit_sqrt_price_x64.map(|sqrt_price_x64| TickCrossing { sqrt_price_x64, liquidity_net: 0 });
+    let mut ticks = ticks.iter().copied().chain(limit);
+    while remaining > 0 {
+        let tick = ticks.next();
+        if tick.is_none() && limit.is_some() {
+            return None;
+        }
+        if liquidity == 0 {
+            // Empty range, the price moves to the next tick for free
+            sqrt_price_x64 = tick?.sqrt_price_x64;
+        } else if tick.map_or(true, |tick| tick.sqrt_price_x64 != sqrt_price_x64) {
+            let (step_in, step_out, next) = clmm_step(remaining, liquidity, sqrt_price_x64, tick.map(|tick| tick.sqrt_price_x64), a_to_b)?;
+            remaining -= step_in;
+            amount_out += step_out;
+            sqrt_price_x64 = next;
+        }
+        match tick {
+            Some(tick) if sqrt_price_x64 == tick.sqrt_price_x64 => {
+                // Going down the price leaves the ranges the tick opened
+                let liquidity_net = if a_to_b { -tick.liquidity_net } else { tick.liquidity_net };
+                liquidity = liquidity.checked_add_signed(liquidity_net)?;
+            }
+            _ => break,
+        }
+    }
+    u64::try_from(amount_out).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqrt_price_of_a_tick() {
+        assert_eq!(sqrt_price_from_tick_index(0), Q64);
+        for tick in [-100_000, -1, 1, 64, 100_000] {
+            let expected = 1.0001f64.powf(tick as f64 / 2.0) * Q64 as f64;
+            let error = (sqrt_price_from_tick_index(tick) as f64 - expected).abs() / expected;
+            assert!(error < 1e-9, "tick {}: {} off", tick, error);
+        }
+        assert_eq!(sqrt_price_from_tick_index(MAX_TICK_INDEX + 1), sqrt_price_from_tick_index(MAX_TICK_INDEX));
+    }
+
+    #[test]
+    fn tick_array_start_rounds_down() {
+        assert_eq!(tick_array_start_index(0, 64), Some(0));
+        assert_eq!(tick_array_start_index(5_631, 64), Some(0));
+        assert_eq!(tick_array_start_index(-1, 64), Some(-5_632));
+        assert_eq!(tick_array_start_index(10, 0), None);
+    }
+
+    #[test]
+    fn mul_div_past_128_bits() {
+        assert_eq!(mul_div(u128::MAX, 4, 8, false), Some(u128::MAX / 2));
+        assert_eq!(mul_div(7, 3, 2, true), Some(11));
+        assert_eq!(mul_div(u128::MAX, u128::MAX, 1, false), None);
+        assert_eq!(mul_div(1, 1, 0, false), None);
+    }
+
+    #[test]
+    fn constant_product_takes_the_fee_on_the_amount_in() {
+        let fee = FeeMultiplier::from_ratio(25, 10_000).unwrap();
+        assert_eq!(constant_product_out(1_000, 1_000_000, 1_000_000, fee), Some(996));
+        assert_eq!(constant_product_out(1_000, 0, 1_000_000, fee), None);
+        assert!(FeeMultiplier::from_ratio(2, 1).is_none());
+    }
+
+    #[test]
+    fn clmm_swap_stops_at_the_limit_price() {
+        let fee = FeeMultiplier::from_fee_rate(0).unwrap();
+        let (liquidity, sqrt_price) = (1_000_000_000_000u128, Q64);
+        let limit = sqrt_price_from_tick_index(-64);
+        assert!(clmm_swap_to(1_000, liquidity, sqrt_price, fee, true, &[], Some(limit)).is_some());
+        assert_eq!(clmm_swap_to(u64::MAX / 2, liquidity, sqrt_price, fee, true, &[], Some(limit)), None);
+        // Crossing a tick that closes every range leaves nothing to swap against
+        let closing = TickCrossing { sqrt_price_x64: sqrt_price_from_tick_index(-1), liquidity_net: liquidity as i128 };
+        assert_eq!(clmm_swap(1_000_000_000, liquidity, sqrt_price, fee, true, &[closing]), None);
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use crate::layouts::{raydium_amm, whirlpool, whirlpool_tick_array, TICK_ARRAY_SIZE};
+use crate::maths::{clmm_swap_to, constant_product_out, sqrt_price_from_tick_index, tick_array_start_index, FeeMultiplier, TickCrossing};
+
+// Slippage taken on the local quotes for the min amount out
+pub static LOCAL_QUOTE_SLIPPAGE_BPS: u64 = 50;
+
+// Initialized ticks of the 3 tick arrays a Whirlpool swap takes, the farthest a quote may go
+const MAX_TICK_CROSSINGS: usize = 3 * 88;
+
+// Where the local quotes read the pool accounts from: the live cache, a snapshot of it, or the accounts
+// handed to the wasm module
+pub trait AccountSource: Sync {
+    fn read_account<R>(&self, address: &str, f: impl FnOnce(&[u8]) -> R) -> Option<R>;
+
+    // Address of the Whirlpool tick array starting at the given tick, a PDA the source derives or looks up
+    fn tick_array_address(&self, whirlpool: [u8; 32], start_tick_index: i32) -> Option<[u8; 32]>;
+
+    // Amount of an SPL token account (vault)
+    fn token_amount(&self, address: &str) -> Option<u64> {
+        self.read_account(address, |data| Some(u64::from_le_bytes(data.get(64..72)?.try_into().ok()?)))?
+    }
+
+    // Tick array of a Whirlpool, the address encoded on the stack
+    fn read_tick_array<R>(&self, whirlpool: [u8; 32], start_tick_index: i32, f: impl FnOnce(&[u8]) -> R) -> Option<R> {
+        let mut address = [0u8; 44];
+        let len = bs58::encode(self.tick_array_address(whirlpool, start_tick_index)?).onto(&mut address[..]).ok()?;
+        self.read_account(std::str::from_utf8(&address[..len]).ok()?, f)
+    }
+}
+
+// No program to derive the PDAs from: the tick array is found among the accounts by its whirlpool and start tick
+impl AccountSource for HashMap<String, Vec<u8>> {
+    fn read_account<R>(&self, address: &str, f: impl FnOnce(&[u8]) -> R) -> Option<R> {
+        self.get(address).map(|data| f(data))
+    }
+
+    fn tick_array_address(&self, whirlpool: [u8; 32], start_tick_index: i32) -> Option<[u8; 32]> {
+        self.iter().find_map(|(address, data)| {
+            let tick_array = whirlpool_tick_array(data)?;
+            (tick_array.whirlpool() == whirlpool && tick_array.start_tick_index() == start_tick_index).then(|| decode_address(address))?
+        })
+    }
+}
+
+// Pools with local math
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolKind {
+    RaydiumAmm,
+    Whirlpool,
+}
+
+// One swap of a path, the pool and everything it reads coming from the account source
+#[derive(Debug, Clone, Copy)]
+pub struct Hop<'a> {
+    pub kind: PoolKind,
+    pub pool: &'a str,
+    pub token_in: &'a str,
+}
+
+// Base58 address against a mint, decoded on the stack rather than encoding the mint
+pub fn is_mint(address: &str, mint: [u8; 32]) -> bool {
+    let mut bytes = [0u8; 32];
+    matches!(bs58::decode(address).into(&mut bytes), Ok(32)) && bytes == mint
+}
+
+pub fn decode_address(address: &str) -> Option<[u8; 32]> {
+    let mut bytes = [0u8; 32];
+    matches!(bs58::decode(address).into(&mut bytes), Ok(32)).then_some(bytes)
+}
+
+// Pool account from the source only: a quote never runs on the data the market was loaded with
+fn with_pool_data<R>(accounts: &impl AccountSource, hop: &Hop, f: impl FnOnce(&[u8]) -> Option<R>) -> Option<R> {
+    accounts.read_account(hop.pool, f)?
+}
+
+// Start ticks of the tick arrays a Whirlpool swap goes through from the current tick, in the swap order
+pub fn whirlpool_tick_array_starts(tick_current_index: i32, tick_spacing: u16, a_to_b: bool) -> Option<[i32; 3]> {
+    // Going up, a price right below the end of an array is quoted from the next one, as the program does
+    let first = tick_array_start_index(tick_current_index + if a_to_b { 0 } else { tick_spacing as i32 }, tick_spacing)?;
+    let span = tick_spacing as i32 * TICK_ARRAY_SIZE;
+    let step = if a_to_b { -span } else { span };
+    Some([first, first + step, first + 2 * step])
+}
+
+// Raydium vaults, the other accounts a Raydium quote reads
+pub fn raydium_vaults(pool_data: &[u8]) -> Option<([u8; 32], [u8; 32])> {
+    let amm = raydium_amm(pool_data)?;
+    Some((amm.coin_vault(), amm.pc_vault()))
+}
+
+// Amount out and min amount out, None when the source misses one of the accounts
+pub fn quote_pool(accounts: &impl AccountSource, hop: &Hop, amount_in: u64) -> Option<(u64, u64)> {
+    let amount_out = match hop.kind {
+        PoolKind::RaydiumAmm => quote_raydium(accounts, hop, amount_in)?,
+        PoolKind::Whirlpool => quote_whirlpool(accounts, hop, amount_in)?,
+    };
+    // In 128 bits: a large amount out times the basis points does not fit a u64
+    let slippage = (amount_out as u128 * LOCAL_QUOTE_SLIPPAGE_BPS as u128 / 10_000) as u64;
+    Some((amount_out, amount_out - slippage))
+}
+
+// Amount out of every hop, each one swapping the amount out of the previous one
+pub fn quote_path(accounts: &impl AccountSource, hops: &[Hop], amount_in: u64) -> Option<Vec<u64>> {
+    let mut amount = amount_in;
+    let mut amounts_out = Vec::with_capacity(hops.len());
+    for hop in hops {
+        amount = quote_pool(accounts, hop, amount)?.0;
+        amounts_out.push(amount);
+    }
+    Some(amounts_out)
+}
+
+fn quote_raydium(accounts: &impl AccountSource, hop: &Hop, amount_in: u64) -> Option<u64> {
+    let (coin_vault, pc_vault, coin_vault_mint, (need_take_pnl_coin, need_take_pnl_pc), (fee_numerator, fee_denominator)) = with_pool_data(accounts, hop, |data| {
+        let amm = raydium_amm(data)?;
+        Some((amm.coin_vault(), amm.pc_vault(), amm.coin_vault_mint(), amm.need_take_pnl(), amm.trade_fee()))
+    })?;
+    let coin = accounts.token_amount(&bs58::encode(coin_vault).into_string())?.saturating_sub(need_take_pnl_coin);
+    let pc = accounts.token_amount(&bs58::encode(pc_vault).into_string())?.saturating_sub(need_take_pnl_pc);
+    let (reserve_in, reserve_out) = if is_mint(hop.token_in, coin_vault_mint) { (coin, pc) } else { (pc, coin) };
+    constant_product_out(amount_in, reserve_in, reserve_out, FeeMultiplier::from_ratio(fee_numerator, fee_denominator)?)
+}
+
+fn quote_whirlpool(accounts: &impl AccountSource, hop: &Hop, amount_in: u64) -> Option<u64> {
+    let whirlpool_address = decode_address(hop.pool)?;
+    let (liquidity, sqrt_price, tick_current_index, tick_spacing, fee, a_to_b) = with_pool_data(accounts, hop, |data| {
+        let pool = whirlpool(data)?;
+        Some((pool.liquidity(), pool.sqrt_price(), pool.tick_current_index(), pool.tick_spacing(), FeeMultiplier::from_fee_rate(pool.fee_rate())?, is_mint(hop.token_in, pool.token_mint_a())))
+    })?;
+
+    // Initialized ticks of the tick arrays in the source, in the order the swap meets them
+    let mut crossings = [TickCrossing::default(); MAX_TICK_CROSSINGS];
+    let mut count = 0;
+    let mut end_tick_index = None;
+    for start_tick_index in whirlpool_tick_array_starts(tick_current_index, tick_spacing, a_to_b)? {
+        let loaded = accounts.read_tick_array(whirlpool_address, start_tick_index, |data| {
+            let tick_array = whirlpool_tick_array(data).filter(|tick_array| tick_array.whirlpool() == whirlpool_address && tick_array.start_tick_index() == start_tick_index)?;
+            for i in 0..TICK_ARRAY_SIZE as usize {
+                let i = if a_to_b { TICK_ARRAY_SIZE as usize - 1 - i } else { i };
+                let tick_index = start_tick_index + i as i32 * tick_spacing as i32;
+                let ahead = if a_to_b { tick_index <= tick_current_index } else { tick_index > tick_current_index };
+                if let Some(liquidity_net) = tick_array.liquidity_net(i).filter(|_| ahead) {
+                    crossings[count] = TickCrossing { sqrt_price_x64: sqrt_price_from_tick_index(tick_index), liquidity_net };
+                    count += 1;
+                }
+            }
+            Some(())
+        });
+        if loaded.flatten().is_none() {
+            break;
+        }
+        end_tick_index = Some(if a_to_b { start_tick_index } else { start_tick_index + tick_spacing as i32 * TICK_ARRAY_SIZE });
+    }
+    // Not even the current tick array is cached: no quote rather than one blind to the ticks
+    let limit = sqrt_price_from_tick_index(end_tick_index?);
+    clmm_swap_to(amount_in, liquidity, sqrt_price, fee, a_to_b, &crossings[..count], Some(limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(byte: u8) -> String {
+        bs58::encode([byte; 32]).into_string()
+    }
+
+    // Whirlpool with its current tick in the tick array starting at -22528, mint A [1; 32]
+    fn whirlpool_accounts(with_tick_array: bool) -> HashMap<String, Vec<u8>> {
+        let mut pool = vec![0u8; 653];
+        pool[41..43].copy_from_slice(&64u16.to_le_bytes());
+        pool[45..47].copy_from_slice(&3_000u16.to_le_bytes());
+        pool[49..65].copy_from_slice(&120_000_000_000_000u128.to_le_bytes());
+        pool[65..81].copy_from_slice(&7_143_265_591_698_411_110u128.to_le_bytes());
+        pool[81..85].copy_from_slice(&(-18_976i32).to_le_bytes());
+        pool[101..133].copy_from_slice(&[1; 32]);
+        let mut accounts = HashMap::from([(address(10), pool)]);
+        if with_tick_array {
+            let mut tick_array = vec![0u8; 9988];
+            tick_array[8..12].copy_from_slice(&(-22_528i32).to_le_bytes());
+            tick_array[9956..9988].copy_from_slice(&[10; 32]);
+            accounts.insert(address(11), tick_array);
+        }
+        accounts
+    }
+
+    // A whirlpool is not quoted from its pool account alone, nor past the tick arrays in the source
+    #[test]
+    fn whirlpool_quote_needs_its_tick_arrays() {
+        let (pool, token_in) = (address(10), address(1));
+        let hop = Hop { kind: PoolKind::Whirlpool, pool: &pool, token_in: &token_in };
+        assert_eq!(quote_pool(&whirlpool_accounts(false), &hop, 1_000_000_000), None);
+
+        let accounts = whirlpool_accounts(true);
+        assert_eq!(accounts.tick_array_address([10; 32], -22_528), Some([11; 32]));
+        assert!(quote_pool(&accounts, &hop, 1_000_000_000).is_some());
+        assert_eq!(quote_pool(&accounts, &hop, u64::MAX / 2), None);
+    }
+
+    // The min amount out of a quote near u64::MAX does not overflow
+    #[test]
+    fn min_amount_out_of_a_large_amount() {
+        let mut pool = vec![0u8; 752];
+        pool[144..152].copy_from_slice(&25u64.to_le_bytes());
+        pool[152..160].copy_from_slice(&10_000u64.to_le_bytes());
+        pool[336..368].copy_from_slice(&[2; 32]);
+        pool[368..400].copy_from_slice(&[3; 32]);
+        pool[400..432].copy_from_slice(&[4; 32]);
+        pool[432..464].copy_from_slice(&[5; 32]);
+        let vault = |amount: u64| {
+            let mut data = vec![0u8; 165];
+            data[64..72].copy_from_slice(&amount.to_le_bytes());
+            data
+        };
+        let accounts = HashMap::from([(address(20), pool), (address(2), vault(1_000)), (address(3), vault(u64::MAX))]);
+        let (pool, token_in) = (address(20), address(4));
+        let hop = Hop { kind: PoolKind::RaydiumAmm, pool: &pool, token_in: &token_in };
+
+        let (amount_out, min_amount_out) = quote_pool(&accounts, &hop, 1_000_000).unwrap();
+        assert!(amount_out > u64::MAX / 2);
+        assert_eq!(min_amount_out, amount_out - (amount_out as u128 * LOCAL_QUOTE_SLIPPAGE_BPS as u128 / 10_000) as u64);
+    }
+
+    #[test]
+    fn tick_arrays_in_the_swap_direction() {
+        assert_eq!(whirlpool_tick_array_starts(-18_976, 64, true), Some([-22_528, -28_160, -33_792]));
+        assert_eq!(whirlpool_tick_array_starts(-18_976, 64, false), Some([-22_528, -16_896, -11_264]));
+        // Right below the end of an array, going up starts from the next one
+        assert_eq!(whirlpool_tick_array_starts(-16_900, 64, false), Some([-16_896, -11_264, -5_632]));
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::maths::{clmm_swap, constant_product_out, FeeMultiplier};
+use crate::quote::{quote_path, quote_pool, Hop, PoolKind};
+
+// JS side of the quotes. The dashboard loads the pool and vault accounts of the quoted slot into an
+// Accounts, then re-runs the quotes the bot logged (dex labels as serialized by the bot: RAYDIUM, ORCA_WHIRLPOOLS).
+// Amounts are BigInt, u128 values (liquidity, sqrt prices) decimal strings
+
+fn pool_kind(dex: &str) -> Result<PoolKind, JsError> {
+    match dex {
+        "RAYDIUM" => Ok(PoolKind::RaydiumAmm),
+        "ORCA_WHIRLPOOLS" => Ok(PoolKind::Whirlpool),
+        _ => Err(JsError::new(&format!("No local math for {}", dex))),
+    }
+}
+
+fn parse_u128(value: &str) -> Result<u128, JsError> {
+    value.parse().map_err(|_| JsError::new(&format!("Not an unsigned integer: {}", value)))
+}
+
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct Accounts {
+    accounts: HashMap<String, Vec<u8>>,
+}
+
+#[wasm_bindgen]
+impl Accounts {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Accounts {
+        Accounts::default()
+    }
+
+    // Raw account data (base64 decoded), pools, vaults and Whirlpool tick arrays
+    pub fn set(&mut self, address: String, data: Vec<u8>) {
+        self.accounts.insert(address, data);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.accounts.len()
+    }
+}
+
+// [amount out, min amount out], undefined when an account is missing
+#[wasm_bindgen(js_name = quotePool)]
+pub fn js_quote_pool(accounts: &Accounts, dex: &str, pool: &str, token_in: &str, amount_in: u64) -> Result<Option<Vec<u64>>, JsError> {
+    let hop = Hop { kind: pool_kind(dex)?, pool, token_in };
+    Ok(quote_pool(&accounts.accounts, &hop, amount_in).map(|(amount_out, min_amount_out)| vec![amount_out, min_amount_out]))
+}
+
+// Amount out of every hop, the token in of each hop given as the bot logs it
+#[wasm_bindgen(js_name = quotePath)]
+pub fn js_quote_path(accounts: &Accounts, dexes: Vec<String>, pools: Vec<String>, tokens_in: Vec<String>, amount_in: u64) -> Result<Option<Vec<u64>>, JsError> {
+    if dexes.len() != pools.len() || pools.len() != tokens_in.len() {
+        return Err(JsError::new("dexes, pools and tokens_in of different lengths"));
+    }
+    let hops = dexes
+        .iter()
+        .zip(pools.iter().zip(tokens_in.iter()))
+        .map(|(dex, (pool, token_in))| Ok(Hop { kind: pool_kind(dex)?, pool, token_in }))
+        .collect::<Result<Vec<Hop>, JsError>>()?;
+    Ok(quote_path(&accounts.accounts, &hops, amount_in))
+}
+
+#[wasm_bindgen(js_name = constantProductOut)]
+pub fn js_constant_product_out(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_numerator: u64, fee_denominator: u64) -> Option<u64> {
+    constant_product_out(amount_in, reserve_in, reserve_out, FeeMultiplier::from_ratio(fee_numerator, fee_denominator)?)
+}
+
+// Whirlpool swap within the current range, fee rate in hundredths of a basis point
+#[wasm_bindgen(js_name = clmmSwap)]
+pub fn js_clmm_swap(amount_in: u64, liquidity: &str, sqrt_price_x64: &str, fee_rate: u16, a_to_b: bool) -> Result<Option<u64>, JsError> {
+    let fee = FeeMultiplier::from_fee_rate(fee_rate).ok_or_else(|| JsError::new("Fee rate above 100%"))?;
+    Ok(clmm_swap(amount_in, parse_u128(liquidity)?, parse_u128(sqrt_price_x64)?, fee, a_to_b, &[]))
+}
@@ -0,0 +1,382 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{TimeZone, Utc};
+use log::{error, info};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::arbitrage::path_files::read_paths;
+use crate::arbitrage::simulate::simulate_path_local;
+use crate::arbitrage::streams::get_fresh_accounts_states;
+use crate::arbitrage::types::{SwapPathSelected, TokenInArb, TokenInfos};
+use crate::common::circuit_breaker::quote_breaker;
+use crate::common::rpc_pool::nonblocking_client;
+use crate::common::utils::get_tokens_infos;
+use crate::markets::local_quote::quoted_accounts;
+use crate::markets::pool_cache::pool_cache;
+use crate::markets::pyth::{default_pyth_feeds, PythOracle};
+use crate::markets::types::Market;
+use crate::risk::kill_switch::FAILED_TX_COST_LAMPORTS;
+use crate::risk::sanity::SanityBounds;
+
+// One line of a snapshots file: the accounts the local quotes of the paths read and the Pyth price accounts, base64 encoded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedSnapshot {
+    // Milliseconds
+    pub timestamp: i64,
+    pub slot: u64,
+    pub accounts: HashMap<String, String>,
+}
+
+// Pool state at one point in time, what the replay quotes from
+#[derive(Debug, Clone, Default)]
+pub struct PoolStateSnapshot {
+    pub timestamp: i64,
+    pub slot: u64,
+    pub accounts: HashMap<String, Vec<u8>>,
+}
+
+impl RecordedSnapshot {
+    pub fn decode(&self) -> Result<PoolStateSnapshot> {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|(address, data)| Ok((address.clone(), STANDARD.decode(data).with_context(|| format!("account {} at slot {}", address, self.slot))?)))
+            .collect::<Result<_>>()?;
+        Ok(PoolStateSnapshot { timestamp: self.timestamp, slot: self.slot, accounts })
+    }
+}
+
+// Snapshots of a JSONL file, in time order
+pub fn read_snapshots(file: &str) -> Result<Vec<PoolStateSnapshot>> {
+    let reader = BufReader::new(fs::File::open(file).with_context(|| format!("reading {}", file))?);
+    let mut snapshots = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        snapshots.push(serde_json::from_str::<RecordedSnapshot>(&line)?.decode()?);
+    }
+    snapshots.sort_by_key(|snapshot| snapshot.timestamp);
+    Ok(snapshots)
+}
+
+// The pools and vaults of the paths and the Pyth prices fetched every `interval`, appended to the snapshots file.
+// Returns the number of snapshots written
+pub async fn record_snapshots(paths_file: &str, snapshots_file: &str, interval: Duration, count: usize) -> Result<usize> {
+    let paths = read_paths(paths_file)?.value;
+    let markets: HashMap<String, Market> = paths.iter().flat_map(|path| path.markets.iter()).map(|market| (market.id.clone(), market.clone())).collect();
+    let price_accounts: Vec<Pubkey> = default_pyth_feeds().iter().map(|feed| feed.account).collect();
+    let mut file = OpenOptions::new().create(true).append(true).open(snapshots_file)?;
+    let mut recorded = 0;
+    let mut ticker = tokio::time::interval(interval);
+    while recorded < count {
+        ticker.tick().await;
+        let Some(rpc_url) = quote_breaker().current_url() else {
+            error!("🔌 No healthy RPC endpoint, snapshot skipped");
+            continue;
+        };
+        let slot = nonblocking_client(&rpc_url).get_slot().await?;
+        let markets = get_fresh_accounts_states(markets.clone()).await;
+        let mut accounts: HashMap<String, String> = markets
+            .values()
+            .flat_map(quoted_accounts)
+            .filter_map(|address| pool_cache().read(&address, |data| (address.to_string(), STANDARD.encode(data))))
+            .collect();
+        let prices = nonblocking_client(&rpc_url).get_multiple_accounts(&price_accounts).await?;
+        for (address, account) in price_accounts.iter().zip(prices) {
+            if let Some(account) = account {
+                accounts.insert(address.to_string(), STANDARD.encode(account.data));
+            }
+        }
+        let snapshot = RecordedSnapshot { timestamp: Utc::now().timestamp_millis(), slot, accounts };
+        writeln!(file, "{}", serde_json::to_string(&snapshot)?)?;
+        recorded += 1;
+        info!("🎞️  Snapshot {}/{} at slot {}, {} accounts", recorded, count, slot, snapshot.accounts.len());
+    }
+    Ok(recorded)
+}
+
+// How a sent opportunity is assumed to execute, the replay has no chain to ask
+#[derive(Debug, Clone, Serialize)]
+pub struct FillAssumptions {
+    // Snapshots between the quote and the fill: the swap executes on the pool state that many snapshots later
+    pub latency_snapshots: usize,
+    // Share of the sent transactions that land
+    pub land_rate: f64,
+    // Fees and tip of a sent transaction (lamports), paid whether it lands or not
+    pub cost_lamports: f64,
+}
+
+impl Default for FillAssumptions {
+    fn default() -> Self {
+        FillAssumptions { latency_snapshots: 1, land_rate: 1.0, cost_lamports: FAILED_TX_COST_LAMPORTS }
+    }
+}
+
+// The strategy parameters under test, the live ones unless changed: each path quoted at the simulation
+// amount of its preset and sent above the threshold of its preset, through the same sanity and oracle checks
+#[derive(Debug, Clone)]
+pub struct BacktestConfig {
+    // Amount every path is quoted at instead of the one of its preset
+    pub amount: Option<u64>,
+    // Profit threshold of every path instead of the one of its preset
+    pub min_profit: Option<f64>,
+    pub sanity_bounds: SanityBounds,
+    // Recorded Pyth prices older than this at a snapshot are not checked against
+    pub oracle_max_age: Duration,
+    // Decimals of the tokens of the paths, the hops of a token missing here are not checked against the oracle
+    pub tokens_infos: HashMap<String, TokenInfos>,
+    pub fill: FillAssumptions,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        BacktestConfig {
+            amount: None,
+            min_profit: None,
+            sanity_bounds: SanityBounds::default(),
+            oracle_max_age: Duration::from_secs(60),
+            tokens_infos: HashMap::new(),
+            fill: FillAssumptions::default(),
+        }
+    }
+}
+
+impl BacktestConfig {
+    pub fn with_amount(mut self, amount: u64) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    pub fn with_min_profit(mut self, min_profit: f64) -> Self {
+        self.min_profit = Some(min_profit);
+        self
+    }
+
+    pub fn with_tokens_infos(mut self, tokens_infos: HashMap<String, TokenInfos>) -> Self {
+        self.tokens_infos = tokens_infos;
+        self
+    }
+
+    pub fn with_sanity_bounds(mut self, sanity_bounds: SanityBounds) -> Self {
+        self.sanity_bounds = sanity_bounds;
+        self
+    }
+
+    pub fn with_fill(mut self, fill: FillAssumptions) -> Self {
+        self.fill = fill;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum FillOutcome {
+    Filled,
+    // The pools moved past the min amount out before the fill, the swap fails on chain
+    Reverted,
+}
+
+// A hypothetical trade: quoted on one snapshot, filled on a later one
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestTrade {
+    pub timestamp: i64,
+    pub slot: u64,
+    pub fill_slot: u64,
+    // Pools of the path, joined by /
+    pub pools: String,
+    pub amount_in: u64,
+    pub expected_profit: f64,
+    // Profit of the swap on the fill snapshot, before costs
+    pub fill_profit: f64,
+    pub outcome: FillOutcome,
+    // Fill profit weighted by the land rate, minus the cost
+    pub pnl: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BacktestReport {
+    pub snapshots: usize,
+    pub from: i64,
+    pub to: i64,
+    pub paths: usize,
+    // Path x snapshot pairs with a local quote
+    pub quoted: usize,
+    // Implausible profit or a hop away from the oracle price
+    pub sanity_rejected: usize,
+    pub below_threshold: usize,
+    // Opportunities too close to the end of the replay to have a fill snapshot
+    pub unfilled: usize,
+    // Path x snapshot pairs skipped after a fill: the trade still in flight, or its pools unchanged since (the gap is taken)
+    pub consumed: usize,
+    pub trades: Vec<BacktestTrade>,
+    pub pnl: f64,
+}
+
+impl BacktestReport {
+    pub fn count(&self, outcome: FillOutcome) -> usize {
+        self.trades.iter().filter(|trade| trade.outcome == outcome).count()
+    }
+
+    // PnL and trades by path, best first
+    pub fn by_path(&self) -> Vec<(String, usize, f64)> {
+        let mut paths: HashMap<&str, (usize, f64)> = HashMap::new();
+        for trade in &self.trades {
+            let entry = paths.entry(&trade.pools).or_default();
+            entry.0 += 1;
+            entry.1 += trade.pnl;
+        }
+        let mut paths: Vec<(String, usize, f64)> = paths.into_iter().map(|(pools, (trades, pnl))| (pools.to_string(), trades, pnl)).collect();
+        paths.sort_by(|a, b| b.2.total_cmp(&a.2));
+        paths
+    }
+
+    pub fn print(&self, fill: &FillAssumptions) {
+        let date = |millis: i64| Utc.timestamp_millis_opt(millis).single().map(|date| date.to_rfc3339()).unwrap_or_default();
+        println!("Replay: {} snapshots from {} to {}, {} paths", self.snapshots, date(self.from), date(self.to), self.paths);
+        println!(
+            "Fills: {} snapshots of latency, {:.0}% landed, {} lamports per transaction",
+            fill.latency_snapshots,
+            fill.land_rate * 100.0,
+            fill.cost_lamports
+        );
+        println!(
+            "Quotes: {} quoted, {} rejected by the sanity bounds, {} below the threshold, {} without fill snapshot, {} skipped after a fill",
+            self.quoted, self.sanity_rejected, self.below_threshold, self.unfilled, self.consumed
+        );
+        println!("Trades: {} filled, {} reverted", self.count(FillOutcome::Filled), self.count(FillOutcome::Reverted));
+        println!("PnL: {:.6} SOL", self.pnl / 1e9);
+        println!("{:<100} {:>7} {:>14}", "Path", "Trades", "PnL (SOL)");
+        for (pools, trades, pnl) in self.by_path().iter().take(20) {
+            println!("{:<100} {:>7} {:>14.6}", pools, trades, pnl / 1e9);
+        }
+    }
+}
+
+enum PathOutcome {
+    NotQuoted,
+    SanityRejected,
+    BelowThreshold,
+    Unfilled,
+    Trade(BacktestTrade),
+}
+
+
+// Pools and vaults of the path in the same state on both snapshots
+fn same_pool_states(a: &PoolStateSnapshot, b: &PoolStateSnapshot, path: &SwapPathSelected) -> bool {
+    path.markets
+        .iter()
+        .flat_map(|market| [&market.id, &market.tokenVaultA, &market.tokenVaultB])
+        .all(|address| a.accounts.get(address) == b.accounts.get(address))
+}
+
+fn replay_path(snapshots: &[PoolStateSnapshot], index: usize, path: &SwapPathSelected, oracle: &PythOracle, config: &BacktestConfig) -> PathOutcome {
+    let snapshot = &snapshots[index];
+    let preset_params = path.preset.params();
+    let amount_in = config.amount.unwrap_or(preset_params.simulation_amount);
+    let Some((simulations, expected_profit)) = simulate_path_local(&snapshot.accounts, amount_in, &path.path, &path.markets) else { return PathOutcome::NotQuoted };
+    let Some(min_amount_out) = simulations.last().and_then(|simulation| simulation.estimated_min_amount_out.parse::<u64>().ok()) else { return PathOutcome::NotQuoted };
+    let pools = path.path.paths.iter().map(|route| route.pool_address.as_str()).collect::<Vec<&str>>().join("/");
+    // The live pass quotes again on fresh pools first, a replay has no fresher state than the snapshot
+    if !config.sanity_bounds.is_plausible(amount_in, expected_profit) || !config.sanity_bounds.check_routes(oracle, &pools, &simulations, &config.tokens_infos) {
+        return PathOutcome::SanityRejected;
+    }
+    if expected_profit <= config.min_profit.unwrap_or(preset_params.min_profit_threshold) {
+        return PathOutcome::BelowThreshold;
+    }
+    let Some(fill) = snapshots.get(index + config.fill.latency_snapshots) else { return PathOutcome::Unfilled };
+    // The same amount swapped on the later state, our own fills are not applied to the replayed pools
+    let fill_out = simulate_path_local(&fill.accounts, amount_in, &path.path, &path.markets).map(|(_, profit)| amount_in as f64 + profit);
+    let (outcome, fill_profit) = match fill_out {
+        Some(amount_out) if amount_out >= min_amount_out as f64 => (FillOutcome::Filled, amount_out - amount_in as f64),
+        _ => (FillOutcome::Reverted, 0.0),
+    };
+    PathOutcome::Trade(BacktestTrade {
+        timestamp: snapshot.timestamp,
+        slot: snapshot.slot,
+        fill_slot: fill.slot,
+        pools,
+        amount_in,
+        expected_profit,
+        fill_profit,
+        outcome,
+        pnl: fill_profit * config.fill.land_rate - config.fill.cost_lamports,
+    })
+}
+
+// Every path quoted and checked on every snapshot with the local quotes the strategies use, against the oracle
+// prices recorded with it, the opportunities filled on the state `latency_snapshots` later.
+// Our fills are not applied to the replayed pools: a filled path is skipped until its pools move past the fill
+// state, or a persistent gap would be traded again on every snapshot
+pub fn run_backtest(snapshots: &[PoolStateSnapshot], paths: &[SwapPathSelected], config: &BacktestConfig) -> BacktestReport {
+    let mut report = BacktestReport {
+        snapshots: snapshots.len(),
+        from: snapshots.first().map(|snapshot| snapshot.timestamp).unwrap_or_default(),
+        to: snapshots.last().map(|snapshot| snapshot.timestamp).unwrap_or_default(),
+        paths: paths.len(),
+        ..BacktestReport::default()
+    };
+    // Fill snapshot of the last filled trade of each path
+    let mut filled_at: Vec<Option<usize>> = vec![None; paths.len()];
+    for index in 0..snapshots.len() {
+        let oracle = PythOracle::recorded(default_pyth_feeds(), config.oracle_max_age, snapshots[index].timestamp / 1000, &snapshots[index].accounts);
+        let outcomes: Vec<Option<PathOutcome>> = paths
+            .par_iter()
+            .zip(&filled_at)
+            .map(|(path, filled_at)| match filled_at {
+                Some(fill) if index <= *fill || same_pool_states(&snapshots[index], &snapshots[*fill], path) => None,
+                _ => Some(replay_path(snapshots, index, path, &oracle, config)),
+            })
+            .collect();
+        for (path_index, outcome) in outcomes.into_iter().enumerate() {
+            let Some(outcome) = outcome else {
+                report.consumed += 1;
+                continue;
+            };
+            if !matches!(outcome, PathOutcome::NotQuoted) {
+                report.quoted += 1;
+            }
+            match outcome {
+                PathOutcome::NotQuoted => {}
+                PathOutcome::SanityRejected => report.sanity_rejected += 1,
+                PathOutcome::BelowThreshold => report.below_threshold += 1,
+                PathOutcome::Unfilled => report.unfilled += 1,
+                PathOutcome::Trade(trade) => {
+                    if trade.outcome == FillOutcome::Filled {
+                        filled_at[path_index] = Some(index + config.fill.latency_snapshots);
+                    }
+                    report.pnl += trade.pnl;
+                    report.trades.push(trade);
+                }
+            }
+        }
+    }
+    report
+}
+
+// A paths file replayed over a snapshots file, the report printed and optionally written as JSON.
+// The decimals of the tokens of the paths are fetched for the oracle check
+pub async fn backtest_files(paths_file: &str, snapshots_file: &str, config: &BacktestConfig, output: Option<&str>) -> Result<BacktestReport> {
+    let paths = read_paths(paths_file)?.value;
+    let snapshots = read_snapshots(snapshots_file)?;
+    if snapshots.is_empty() {
+        return Err(anyhow!("No snapshot in {}", snapshots_file));
+    }
+    let mints: HashSet<&str> = paths.iter().flat_map(|path| path.path.paths.iter()).flat_map(|route| [route.tokenIn.as_str(), route.tokenOut.as_str()]).collect();
+    let tokens = mints.into_iter().map(|mint| TokenInArb { address: mint.to_string(), symbol: mint.to_string() }).collect();
+    let config = config.clone().with_tokens_infos(get_tokens_infos(tokens).await);
+    let report = run_backtest(&snapshots, &paths, &config);
+    report.print(&config.fill);
+    if let Some(output) = output {
+        fs::write(output, serde_json::to_string_pretty(&report)?)?;
+        info!("🎞️  Backtest report written to {}", output);
+    }
+    Ok(report)
+}
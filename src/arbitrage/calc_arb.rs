@@ -1,10 +1,13 @@
 use std::collections::{HashMap, HashSet};
 use log::{debug, info};
-use crate::markets::types::{Dex, DexLabel, Market};
+use solana_program::pubkey::Pubkey;
+use crate::common::utils::from_str;
+use crate::markets::state::MarketState;
+use crate::markets::types::{DexLabel, Market};
 use crate::arbitrage::types::{TokenInArb, Route, SwapPath};
 use crate::strategies::pools::get_fresh_pools;
 
-pub async fn get_markets_arb(get_fresh_pools_bool: bool, restrict_sol_usdc: bool, dexs: Vec<Dex>, tokens: Vec<TokenInArb>) -> HashMap<String, Market> {
+pub async fn get_markets_arb(get_fresh_pools_bool: bool, restrict_sol_usdc: bool, market_state: &MarketState, tokens: Vec<TokenInArb>) -> HashMap<String, Market> {
 
     let sol_addr = format!("So11111111111111111111111111111111111111112");
     let usdc_addr = format!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
@@ -12,32 +15,29 @@ pub async fn get_markets_arb(get_fresh_pools_bool: bool, restrict_sol_usdc: bool
 
     let mut markets_arb: HashMap<String, Market> = HashMap::new();
     let token_addresses: HashSet<String> = tokens.clone().into_iter().map(|token| token.address).collect();
-
-    for dex in dexs {
-        for (pair, market) in dex.pairToMarkets {
-            //The first token is the base token (SOL)
-            for market_iter in market {
-                if token_addresses.contains(&market_iter.tokenMintA) && token_addresses.contains(&market_iter.tokenMintB) {
-                    if restrict_sol_usdc {
-                        if (&market_iter.tokenMintA == &sol_addr || &market_iter.tokenMintA == &usdc_addr) && (&market_iter.tokenMintB == &sol_addr || &market_iter.tokenMintB == &usdc_addr) {
-                            if sol_usdc_count > 2 {
-                                continue;
-                            } else {
-                                let key = format!("{}", market_iter.clone().id);
-                                markets_arb.insert(key, market_iter.clone());
-                                sol_usdc_count += 1;
-                            }
-                        }
-                    }
+    let token_mints: HashSet<Pubkey> = token_addresses.iter().filter_map(|address| from_str(address).ok()).collect();
+
+    market_state.ensure_covered(&token_mints).await;
+    //The first token is the base token (SOL)
+    for market_iter in market_state.markets_within(&token_mints) {
+        if restrict_sol_usdc {
+            if (&market_iter.tokenMintA == &sol_addr || &market_iter.tokenMintA == &usdc_addr) && (&market_iter.tokenMintB == &sol_addr || &market_iter.tokenMintB == &usdc_addr) {
+                if sol_usdc_count > 2 {
+                    continue;
+                } else {
                     let key = format!("{}", market_iter.clone().id);
-                    // key is the address of the pool
-                    markets_arb.insert(key, market_iter);
+                    markets_arb.insert(key, market_iter.clone());
+                    sol_usdc_count += 1;
                 }
             }
         }
+        let key = format!("{}", market_iter.clone().id);
+        // key is the address of the pool
+        markets_arb.insert(key, market_iter);
     }
     if get_fresh_pools_bool {
         let new_markets_arb = get_fresh_pools(tokens).await;
+        market_state.add_markets(new_markets_arb.values());
         let mut count_new_pools = 0;
     
         for (key, market) in new_markets_arb {
@@ -64,6 +64,16 @@ pub async fn get_markets_arb(get_fresh_pools_bool: bool, restrict_sol_usdc: bool
     return markets_arb;
 }
 
+// With bridge tokens, only keep the paths going through at least one of the target tokens
+// (the first token is the base token)
+pub fn paths_through_targets(paths: Vec<SwapPath>, tokens: &[TokenInArb]) -> Vec<SwapPath> {
+    let targets: HashSet<&String> = tokens.iter().skip(1).map(|token| &token.address).collect();
+    paths
+        .into_iter()
+        .filter(|path| path.paths.iter().any(|route| targets.contains(&route.tokenOut)))
+        .collect()
+}
+
 pub fn calculate_arb(include_1hop: bool, include_2hop: bool, markets_arb: HashMap<String, Market>, tokens: Vec<TokenInArb>) -> (HashMap<String, Market>, Vec<SwapPath>) {
 
     //Sort valuables markets: ex: Remove low liquidity markets
@@ -165,15 +175,21 @@ pub fn generate_swap_paths(include_1hop: bool, include_2hop: bool, all_routes: V
     // On part du postulat que les pools de même jetons, du même Dex mais avec des fees différents peuvent avoir un prix différent,
     // donc on peut créer des routes 
     let mut all_swap_paths: Vec<SwapPath> = Vec::new();
-    let starting_routes: Vec<&Route> = all_routes.iter().filter(|route| route.tokenIn == tokens[0].address).collect();
+    // Routes by input token, in the all_routes order
+    let mut routes_by_token_in: HashMap<&str, Vec<&Route>> = HashMap::new();
+    for route in all_routes.iter() {
+        routes_by_token_in.entry(route.tokenIn.as_str()).or_default().push(route);
+    }
+    let routes_from = |token: &str| routes_by_token_in.get(token).map(|routes| routes.as_slice()).unwrap_or(&[]);
+    let starting_routes: Vec<&Route> = routes_from(&tokens[0].address).to_vec();
 
     //One hop
     // Sol -> token -> Sol
 
     if include_1hop == true {
         for route_x in starting_routes.clone() {
-            for route_y in all_routes.clone() {
-                if (route_y.tokenOut == tokens[0].address && route_x.tokenOut == route_y.tokenIn && route_x.pool_address != route_y.pool_address) {
+            for &route_y in routes_from(&route_x.tokenOut) {
+                if (route_y.tokenOut == tokens[0].address && route_x.pool_address != route_y.pool_address) {
                     let paths = vec![route_x.clone(), route_y.clone()];
                     let id_paths = vec![route_x.clone().id, route_y.clone().id];
                     all_swap_paths.push(SwapPath{hops: 1, paths: paths.clone(), id_paths: id_paths});
@@ -189,11 +205,10 @@ pub fn generate_swap_paths(include_1hop: bool, include_2hop: bool, all_routes: V
     // Sol -> token1 -> token2 -> Sol
     if include_2hop == true {
         for route_1 in starting_routes {
-            let all_routes_2: Vec<&Route> = all_routes.iter().filter(|route| route.tokenIn == route_1.tokenOut && route_1.pool_address != route.pool_address && route.tokenOut != tokens[0].address).collect();
+            let all_routes_2: Vec<&Route> = routes_from(&route_1.tokenOut).iter().copied().filter(|route| route_1.pool_address != route.pool_address && route.tokenOut != tokens[0].address).collect();
             for route_2 in all_routes_2 {
-                let all_routes_3: Vec<&Route> = all_routes.iter().filter(|route| 
-                    route.tokenIn == route_2.tokenOut 
-                    && route_2.pool_address != route.pool_address 
+                let all_routes_3: Vec<&Route> = routes_from(&route_2.tokenOut).iter().copied().filter(|route| 
+                    route_2.pool_address != route.pool_address 
                     && route_1.pool_address != route.pool_address
                     && route.tokenOut == tokens[0].address
                 ).collect();
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use log::debug;
+
+use crate::arbitrage::types::SwapPathResult;
+
+// Pools of the path, sorted: the same pool set found by two strategies is the same opportunity
+pub fn pool_set_key(spr: &SwapPathResult) -> String {
+    let mut pools: Vec<String> = spr.route_simulations.iter().map(|route| route.pool_address.clone()).collect();
+    pools.sort();
+    pools.dedup();
+    pools.join("|")
+}
+
+#[derive(Debug, Clone)]
+struct ClaimEntry {
+    strategy: String,
+    claimed_at: Instant,
+}
+
+// In-process claims so only one transaction is built for a given pool set at a time,
+// whatever the strategy or task which found it. A claim is released when dropped, or
+// considered expired after `ttl` if its holder got stuck.
+#[derive(Debug)]
+pub struct ClaimsRegistry {
+    pub ttl: Duration,
+    claims: Mutex<HashMap<String, ClaimEntry>>,
+}
+
+impl ClaimsRegistry {
+    pub fn new(ttl: Duration) -> Self {
+        ClaimsRegistry {
+            ttl,
+            claims: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn try_claim(self: &Arc<Self>, strategy: &str, spr: &SwapPathResult) -> Option<Claim> {
+        let key = pool_set_key(spr);
+        let mut claims = self.claims.lock().unwrap();
+        if let Some(entry) = claims.get(&key) {
+            if entry.claimed_at.elapsed() < self.ttl {
+                debug!("🤝 {} already claimed by {}, skipped for {}", key, entry.strategy, strategy);
+                return None;
+            }
+        }
+        claims.insert(key.clone(), ClaimEntry { strategy: strategy.to_string(), claimed_at: Instant::now() });
+        Some(Claim { registry: self.clone(), key })
+    }
+
+    pub fn is_claimed(&self, spr: &SwapPathResult) -> bool {
+        match self.claims.lock().unwrap().get(&pool_set_key(spr)) {
+            Some(entry) => entry.claimed_at.elapsed() < self.ttl,
+            None => false,
+        }
+    }
+
+    fn release(&self, key: &str) {
+        self.claims.lock().unwrap().remove(key);
+    }
+}
+
+pub struct Claim {
+    registry: Arc<ClaimsRegistry>,
+    key: String,
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        self.registry.release(&self.key);
+    }
+}
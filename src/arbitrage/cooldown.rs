@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use log::info;
+
+#[derive(Debug, Clone)]
+struct CooldownState {
+    consecutive_failures: u32,
+    until: Instant,
+}
+
+// Exponential backoff per path (keyed by ranking::path_key) after failed executions,
+// so a drained pool or a reverting transaction isn't retried every tick
+#[derive(Debug)]
+pub struct PathCooldowns {
+    pub base: Duration,
+    pub max: Duration,
+    state: Mutex<HashMap<String, CooldownState>>,
+}
+
+impl PathCooldowns {
+    pub fn new(base: Duration, max: Duration) -> Self {
+        PathCooldowns {
+            base,
+            max,
+            state: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // base * 2^(failures - 1), capped to max
+    pub fn cooldown_for(&self, consecutive_failures: u32) -> Duration {
+        if consecutive_failures == 0 {
+            return Duration::ZERO;
+        }
+        let factor = 2u32.saturating_pow(consecutive_failures - 1);
+        self.base.saturating_mul(factor).min(self.max)
+    }
+
+    pub fn record_failure(&self, key: &str) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let entry = state.entry(key.to_string()).or_insert(CooldownState {
+            consecutive_failures: 0,
+            until: Instant::now(),
+        });
+        entry.consecutive_failures += 1;
+        let cooldown = self.cooldown_for(entry.consecutive_failures);
+        entry.until = Instant::now() + cooldown;
+        info!("🧊 Path on cooldown for {:?} after {} failures: {}", cooldown, entry.consecutive_failures, key);
+        cooldown
+    }
+
+    pub fn record_success(&self, key: &str) {
+        self.state.lock().unwrap().remove(key);
+    }
+
+    pub fn is_cooling_down(&self, key: &str) -> bool {
+        match self.state.lock().unwrap().get(key) {
+            Some(entry) => Instant::now() < entry.until,
+            None => false,
+        }
+    }
+}
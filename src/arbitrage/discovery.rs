@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use log::{error, info};
+use tokio::sync::RwLock;
+
+use crate::arbitrage::presets::StrategyPreset;
+use crate::arbitrage::types::TokenInArb;
+use crate::common::birdeye::BirdeyeClient;
+use crate::common::constants::{SOL_MINT, USDC_MINT, USDT_MINT};
+use crate::common::dexscreener::DexScreenerClient;
+use crate::common::types::InputVec;
+
+#[derive(Debug, Clone)]
+pub struct DiscoveredToken {
+    pub address: String,
+    pub symbol: String,
+    pub liquidity_usd: f64,
+    pub volume_24h_usd: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiscoveryFilters {
+    pub min_liquidity_usd: f64,
+    pub min_volume_24h_usd: f64,
+    pub max_tokens: usize,
+    pub excluded_mints: HashSet<String>,
+}
+
+impl Default for DiscoveryFilters {
+    fn default() -> Self {
+        DiscoveryFilters {
+            min_liquidity_usd: 50_000.0,
+            min_volume_24h_usd: 100_000.0,
+            max_tokens: 4,
+            excluded_mints: HashSet::from([SOL_MINT.to_string(), USDC_MINT.to_string(), USDT_MINT.to_string()]),
+        }
+    }
+}
+
+// ::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::
+// :::::::::::::::::::::::::::::::::::::                    SOURCES                   :::::::::::::::::::::::::::::::::::::::::::::
+// ::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::
+
+pub async fn fetch_birdeye_trending(birdeye: &BirdeyeClient, limit: usize) -> Result<Vec<DiscoveredToken>> {
+    Ok(birdeye
+        .trending(limit)
+        .await?
+        .into_iter()
+        .map(|token| DiscoveredToken {
+            address: token.address,
+            symbol: token.symbol,
+            liquidity_usd: token.liquidity.unwrap_or(0.0),
+            volume_24h_usd: token.volume24h_usd.unwrap_or(0.0),
+        })
+        .collect())
+}
+
+pub async fn fetch_dexscreener_boosted(dexscreener: &DexScreenerClient) -> Result<Vec<DiscoveredToken>> {
+    let addresses: Vec<String> = dexscreener.boosted_tokens().await?.into_iter().take(30).collect();
+    if addresses.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Liquidity/volume of the deepest pair of each token
+    let mut tokens: Vec<DiscoveredToken> = Vec::new();
+    for pair in dexscreener.tokens_pairs(&addresses).await? {
+        let liquidity_usd = pair.liquidity_usd();
+        let volume_24h_usd = pair.volume_24h_usd();
+        match tokens.iter_mut().find(|token| token.address == pair.base_token.address) {
+            Some(token) if token.liquidity_usd < liquidity_usd => {
+                token.liquidity_usd = liquidity_usd;
+                token.volume_24h_usd = volume_24h_usd;
+            }
+            Some(_) => {}
+            None => tokens.push(DiscoveredToken {
+                address: pair.base_token.address,
+                symbol: pair.base_token.symbol,
+                liquidity_usd,
+                volume_24h_usd,
+            }),
+        }
+    }
+    Ok(tokens)
+}
+
+// ::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::
+// :::::::::::::::::::::::::::::::::::::                   DISCOVERY                  :::::::::::::::::::::::::::::::::::::::::::::
+// ::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::
+
+pub fn apply_filters(tokens: Vec<DiscoveredToken>, filters: &DiscoveryFilters) -> Vec<DiscoveredToken> {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut selected: Vec<DiscoveredToken> = tokens
+        .into_iter()
+        .filter(|token| !filters.excluded_mints.contains(&token.address))
+        .filter(|token| token.liquidity_usd >= filters.min_liquidity_usd)
+        .filter(|token| token.volume_24h_usd >= filters.min_volume_24h_usd)
+        .filter(|token| seen.insert(token.address.clone()))
+        .collect();
+    selected.sort_by(|a, b| b.volume_24h_usd.total_cmp(&a.volume_24h_usd));
+    selected.truncate(filters.max_tokens);
+    selected
+}
+
+// One SOL/token group per discovered token
+pub fn discovered_inputs(tokens: &[DiscoveredToken]) -> Vec<InputVec> {
+    tokens
+        .iter()
+        .map(|token| InputVec {
+            tokens_to_arb: vec![
+                TokenInArb {
+                    address: SOL_MINT.into(),
+                    symbol: "SOL".into(),
+                },
+                TokenInArb {
+                    address: token.address.clone(),
+                    symbol: token.symbol.clone(),
+                },
+            ],
+            include_1hop: true,
+            include_2hop: true,
+            numbers_of_best_paths: 2,
+            get_fresh_pools_bool: false,
+            preset: StrategyPreset::MemeTokens,
+            time_budget_secs: None,
+            bridge_tokens: Vec::new(),
+        })
+        .collect()
+}
+
+pub async fn discover_tokens(filters: &DiscoveryFilters) -> Result<Vec<DiscoveredToken>> {
+    let raw_tokens = match BirdeyeClient::from_env() {
+        Some(birdeye) => fetch_birdeye_trending(&birdeye, 20).await?,
+        None => fetch_dexscreener_boosted(&DexScreenerClient::new()).await?,
+    };
+    let tokens = apply_filters(raw_tokens, filters);
+    info!("🔭 Discovery selected {} tokens: {:?}", tokens.len(), tokens.iter().map(|token| token.symbol.clone()).collect::<Vec<String>>());
+    Ok(tokens)
+}
+
+// Refresh the discovered InputVec list on a schedule
+pub async fn run_discovery(filters: DiscoveryFilters, discovered: Arc<RwLock<Vec<InputVec>>>, interval: Duration) {
+    loop {
+        match discover_tokens(&filters).await {
+            Ok(tokens) => *discovered.write().await = discovered_inputs(&tokens),
+            Err(e) => error!("❌ Token discovery failed: {:?}", e),
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
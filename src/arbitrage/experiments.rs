@@ -0,0 +1,137 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+use crate::arbitrage::opportunity::Opportunity;
+use crate::common::database::insert_experiment_report;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperimentArm {
+    pub name: String,
+    // Opportunities under this expected profit (lamports) are skipped
+    pub min_profit_threshold: f64,
+    // Opportunities older than this are skipped
+    pub max_opportunity_age_ms: u64,
+}
+
+impl ExperimentArm {
+    pub fn accepts(&self, opportunity: &Opportunity) -> bool {
+        opportunity.expected_profit >= self.min_profit_threshold
+            && opportunity.detected_at.elapsed() <= Duration::from_millis(self.max_opportunity_age_ms)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArmResults {
+    pub assigned: u64,
+    pub skipped: u64,
+    pub sent: u64,
+    pub landed: u64,
+    // Expected profit of the landed transactions
+    pub landed_expected_profit: f64,
+}
+
+impl ArmResults {
+    // Expected profit landed per assigned opportunity, comparable between arms
+    pub fn profit_per_opportunity(&self) -> f64 {
+        if self.assigned == 0 {
+            return 0.0;
+        }
+        self.landed_expected_profit / self.assigned as f64
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperimentReport {
+    pub experiment: String,
+    pub arms: Vec<ExperimentArm>,
+    pub results: Vec<ArmResults>,
+    pub leader: Option<String>,
+    pub reported_at: String,
+}
+
+// Two parameterizations applied on interleaved opportunities (even -> A, odd -> B)
+#[derive(Debug)]
+pub struct Experiment {
+    pub name: String,
+    pub arms: [ExperimentArm; 2],
+    counter: AtomicU64,
+    results: Mutex<[ArmResults; 2]>,
+}
+
+impl Experiment {
+    pub fn new(name: &str, arm_a: ExperimentArm, arm_b: ExperimentArm) -> Self {
+        Experiment {
+            name: name.to_string(),
+            arms: [arm_a, arm_b],
+            counter: AtomicU64::new(0),
+            results: Mutex::new([ArmResults::default(), ArmResults::default()]),
+        }
+    }
+
+    // Arm index for the next opportunity
+    pub fn assign(&self) -> usize {
+        let arm = (self.counter.fetch_add(1, Ordering::Relaxed) % 2) as usize;
+        self.results.lock().unwrap()[arm].assigned += 1;
+        arm
+    }
+
+    pub fn record_skipped(&self, arm: usize) {
+        self.results.lock().unwrap()[arm].skipped += 1;
+    }
+
+    pub fn record_outcome(&self, arm: usize, landed: bool, expected_profit: f64) {
+        let mut results = self.results.lock().unwrap();
+        results[arm].sent += 1;
+        if landed {
+            results[arm].landed += 1;
+            results[arm].landed_expected_profit += expected_profit;
+        }
+    }
+
+    pub fn report(&self) -> ExperimentReport {
+        let results = self.results.lock().unwrap().clone();
+        let leader = match results[0].profit_per_opportunity().total_cmp(&results[1].profit_per_opportunity()) {
+            std::cmp::Ordering::Greater => Some(self.arms[0].name.clone()),
+            std::cmp::Ordering::Less => Some(self.arms[1].name.clone()),
+            std::cmp::Ordering::Equal => None,
+        };
+        ExperimentReport {
+            experiment: self.name.clone(),
+            arms: self.arms.to_vec(),
+            results: results.to_vec(),
+            leader,
+            reported_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    pub async fn persist(&self) -> Result<ExperimentReport> {
+        let report = self.report();
+        insert_experiment_report("experiments", report.clone()).await?;
+        Ok(report)
+    }
+}
+
+// Log and persist the experiment results on a schedule
+pub async fn run_experiment_reports(experiment: std::sync::Arc<Experiment>, interval: Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+        match experiment.persist().await {
+            Ok(report) => info!(
+                "🧪 Experiment {}: {} {:.0} vs {} {:.0} lamports/opportunity, leader {:?}",
+                report.experiment,
+                report.arms[0].name,
+                report.results[0].profit_per_opportunity(),
+                report.arms[1].name,
+                report.results[1].profit_per_opportunity(),
+                report.leader
+            ),
+            Err(e) => error!("❌ Unable to persist experiment {}: {:?}", experiment.name, e),
+        }
+    }
+}
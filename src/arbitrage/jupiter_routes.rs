@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::fs;
+
+use anyhow::{anyhow, Context, Result};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+use crate::arbitrage::path_files::{read_paths, write_paths};
+use crate::arbitrage::simulate::{simulate_path_local, simulate_paths_local};
+use crate::arbitrage::streams::get_fresh_accounts_states;
+use crate::arbitrage::types::{Route, SwapPath, SwapPathSelected, SwapRouteSimulation, VecSwapPathSelected};
+use crate::common::types::StrategyPreset;
+use crate::markets::local_quote::LOCAL_QUOTE_SLIPPAGE_BPS;
+use crate::markets::pool_cache::pool_cache;
+use crate::markets::pools::load_all_pools;
+use crate::markets::types::{Dex, DexLabel, Market};
+
+// Route of a Jupiter quote response (/swap/v1/quote), the fields other tools read
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterQuote {
+    pub input_mint: String,
+    pub in_amount: String,
+    pub output_mint: String,
+    pub out_amount: String,
+    pub other_amount_threshold: String,
+    pub swap_mode: String,
+    pub slippage_bps: u64,
+    #[serde(default)]
+    pub price_impact_pct: String,
+    pub route_plan: Vec<JupiterRoutePlan>,
+    #[serde(default)]
+    pub context_slot: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterRoutePlan {
+    pub swap_info: JupiterSwapInfo,
+    pub percent: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterSwapInfo {
+    // The pool account
+    pub amm_key: String,
+    pub label: String,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub in_amount: String,
+    pub out_amount: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_amount: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_mint: Option<String>,
+}
+
+// Jupiter label of the venue
+pub fn jupiter_label(dex: &DexLabel) -> &'static str {
+    match dex {
+        DexLabel::ORCA => "Orca V2",
+        DexLabel::ORCA_WHIRLPOOLS => "Whirlpool",
+        DexLabel::RAYDIUM => "Raydium",
+        DexLabel::RAYDIUM_CLMM => "Raydium CLMM",
+        DexLabel::METEORA => "Meteora DLMM",
+    }
+}
+
+// Loaded pools by address, to resolve the amm keys of the imported routes
+pub fn markets_by_address(dexs: &[Dex]) -> HashMap<String, Market> {
+    dexs.iter().flat_map(|dex| dex.pairToMarkets.values().flatten()).map(|market| (market.id.clone(), market.clone())).collect()
+}
+
+// Path of a Jupiter route, every hop on a pool we load. Split routes (several legs for the same
+// amount) have no SwapPath equivalent and are refused. The result is the route's out minus in amount
+pub fn import_jupiter_route(quote: &JupiterQuote, markets: &HashMap<String, Market>) -> Result<SwapPathSelected> {
+    if quote.route_plan.is_empty() {
+        return Err(anyhow!("Jupiter route without any hop"));
+    }
+    let mut routes: Vec<Route> = Vec::with_capacity(quote.route_plan.len());
+    let mut path_markets: Vec<Market> = Vec::with_capacity(quote.route_plan.len());
+    let mut token = quote.input_mint.as_str();
+    for (index, plan) in quote.route_plan.iter().enumerate() {
+        let swap = &plan.swap_info;
+        if plan.percent != 100 || swap.input_mint != token {
+            return Err(anyhow!("Split Jupiter route, hop {} on {} takes {}% of {}", index, swap.amm_key, plan.percent, swap.input_mint));
+        }
+        let market = markets.get(&swap.amm_key).ok_or_else(|| anyhow!("Pool {} ({}) of the route not loaded", swap.amm_key, swap.label))?;
+        let token_0to1 = match swap.input_mint.as_str() {
+            mint if mint == market.tokenMintA && swap.output_mint == market.tokenMintB => true,
+            mint if mint == market.tokenMintB && swap.output_mint == market.tokenMintA => false,
+            _ => return Err(anyhow!("Pool {} does not trade {} for {}", swap.amm_key, swap.input_mint, swap.output_mint)),
+        };
+        routes.push(Route {
+            id: index as u32,
+            dex: market.dexLabel.clone(),
+            pool_address: market.id.clone(),
+            token_0to1,
+            tokenIn: swap.input_mint.clone(),
+            tokenOut: swap.output_mint.clone(),
+            fee: market.fee,
+        });
+        path_markets.push(market.clone());
+        token = swap.output_mint.as_str();
+    }
+    if token != quote.output_mint {
+        return Err(anyhow!("Jupiter route ends on {}, {} expected", token, quote.output_mint));
+    }
+    let (in_amount, out_amount): (f64, f64) = (quote.in_amount.parse()?, quote.out_amount.parse()?);
+    Ok(SwapPathSelected {
+        result: out_amount - in_amount,
+        path: SwapPath { hops: routes.len() as u8, id_paths: routes.iter().map(|route| route.id).collect(), paths: routes },
+        markets: path_markets,
+        preset: StrategyPreset::default(),
+    })
+}
+
+// Quote response shape of a simulated path, for the tools reading Jupiter routes
+pub fn export_jupiter_route(simulations: &[SwapRouteSimulation], slippage_bps: u64) -> Result<JupiterQuote> {
+    let (first, last) = simulations.first().zip(simulations.last()).ok_or_else(|| anyhow!("Path without any simulated hop"))?;
+    Ok(JupiterQuote {
+        input_mint: first.token_in.clone(),
+        in_amount: first.amount_in.to_string(),
+        output_mint: last.token_out.clone(),
+        out_amount: last.estimated_amount_out.clone(),
+        other_amount_threshold: last.estimated_min_amount_out.clone(),
+        swap_mode: "ExactIn".to_string(),
+        slippage_bps,
+        price_impact_pct: "0".to_string(),
+        route_plan: simulations
+            .iter()
+            .map(|simulation| JupiterRoutePlan {
+                swap_info: JupiterSwapInfo {
+                    amm_key: simulation.pool_address.clone(),
+                    label: jupiter_label(&simulation.dex_label).to_string(),
+                    input_mint: simulation.token_in.clone(),
+                    output_mint: simulation.token_out.clone(),
+                    in_amount: simulation.amount_in.to_string(),
+                    out_amount: simulation.estimated_amount_out.clone(),
+                    fee_amount: None,
+                    fee_mint: None,
+                },
+                percent: 100,
+            })
+            .collect(),
+        context_slot: 0,
+    })
+}
+
+// Our quote against Jupiter's, hop by hop: (pool, Jupiter amount out, our amount out)
+pub fn compare_with_jupiter(quote: &JupiterQuote, simulations: &[SwapRouteSimulation]) -> Vec<(String, String, String)> {
+    quote
+        .route_plan
+        .iter()
+        .zip(simulations)
+        .map(|(plan, simulation)| (plan.swap_info.amm_key.clone(), plan.swap_info.out_amount.clone(), simulation.estimated_amount_out.clone()))
+        .collect()
+}
+
+// Pool accounts and vaults of the paths fetched into the pool cache, for the local quotes
+async fn refresh_path_accounts(paths: &[SwapPathSelected]) {
+    let markets: HashMap<String, Market> = paths.iter().flat_map(|path| path.markets.iter()).map(|market| (market.id.clone(), market.clone())).collect();
+    get_fresh_accounts_states(markets).await;
+}
+
+// A Jupiter quote response (JSON file) written as a best paths file, its hops quoted locally against Jupiter's amounts
+pub async fn import_jupiter_file(quote_file: &str, paths_file: &str) -> Result<()> {
+    let quote: JupiterQuote = serde_json::from_str(&fs::read_to_string(quote_file).with_context(|| format!("reading {}", quote_file))?)?;
+    let path = import_jupiter_route(&quote, &markets_by_address(&load_all_pools(false).await))?;
+    refresh_path_accounts(std::slice::from_ref(&path)).await;
+    match simulate_path_local(pool_cache(), quote.in_amount.parse()?, &path.path, &path.markets) {
+        Some((simulations, _)) => {
+            for (pool, jupiter_out, local_out) in compare_with_jupiter(&quote, &simulations) {
+                info!("🪐 {}: {} out on Jupiter, {} quoted locally", pool, jupiter_out, local_out);
+            }
+        }
+        None => error!("Jupiter route not quoted locally, a hop without local math or account data"),
+    }
+    write_paths(paths_file, &VecSwapPathSelected { value: vec![path] })?;
+    info!("🪐 Jupiter route of {} hops written to {}", quote.route_plan.len(), paths_file);
+    Ok(())
+}
+
+// The paths of a best paths file quoted locally for `amount_in`, written as a JSON array of Jupiter quote responses.
+// The paths without a local quote are left out
+pub async fn export_jupiter_file(paths_file: &str, amount_in: u64, quotes_file: &str) -> Result<()> {
+    let paths = read_paths(paths_file)?.value;
+    refresh_path_accounts(&paths).await;
+    let quotes: Vec<JupiterQuote> = simulate_paths_local(amount_in, &paths)
+        .into_iter()
+        .flatten()
+        .map(|(simulations, _)| export_jupiter_route(&simulations, LOCAL_QUOTE_SLIPPAGE_BPS))
+        .collect::<Result<_>>()?;
+    fs::write(quotes_file, serde_json::to_string_pretty(&quotes)?)?;
+    info!("🪐 {}/{} paths written to {} as Jupiter routes", quotes.len(), paths.len(), quotes_file);
+    Ok(())
+}
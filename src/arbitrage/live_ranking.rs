@@ -0,0 +1,106 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::RwLock;
+
+use log::info;
+
+use crate::arbitrage::simulate::simulate_path_local;
+use crate::arbitrage::types::SwapPathSelected;
+use crate::markets::local_quote::quoted_accounts;
+use crate::markets::pool_cache::pool_cache;
+
+#[derive(Debug, Default)]
+struct RankedPaths {
+    paths: Vec<SwapPathSelected>,
+    // Current profit of each path, lamports
+    scores: Vec<i64>,
+    order: BTreeSet<(Reverse<i64>, usize)>,
+    // Pool and vault accounts to the paths quoting them
+    by_account: HashMap<String, Vec<usize>>,
+}
+
+impl RankedPaths {
+    fn set_score(&mut self, index: usize, score: i64) {
+        self.order.remove(&(Reverse(self.scores[index]), index));
+        self.scores[index] = score;
+        self.order.insert((Reverse(score), index));
+    }
+}
+
+// The selected paths kept ranked by their local quote at the size of their preset. An account update re-scores
+// only the paths going through it, through a reverse index built with the path set
+#[derive(Debug, Default)]
+pub struct LiveRanking {
+    ranked: RwLock<RankedPaths>,
+}
+
+impl LiveRanking {
+    pub fn new() -> Self {
+        LiveRanking::default()
+    }
+
+    // Paths without local math keep the result they were selected with
+    fn score(&self, path: &SwapPathSelected) -> i64 {
+        match simulate_path_local(pool_cache(), path.preset.params().simulation_amount, &path.path, &path.markets) {
+            Some((_, profit)) => profit as i64,
+            None => path.result as i64,
+        }
+    }
+
+    pub fn set_paths(&self, paths: &[SwapPathSelected]) {
+        let mut ranked = RankedPaths { paths: paths.to_vec(), scores: vec![0; paths.len()], ..RankedPaths::default() };
+        for (index, path) in paths.iter().enumerate() {
+            for market in path.markets.iter() {
+                for address in quoted_accounts(market) {
+                    let indexes = ranked.by_account.entry(address.into_owned()).or_default();
+                    if indexes.last() != Some(&index) {
+                        indexes.push(index);
+                    }
+                }
+            }
+            let score = self.score(path);
+            ranked.scores[index] = score;
+            ranked.order.insert((Reverse(score), index));
+        }
+        info!("📈 Live ranking over {} paths, {} accounts indexed", ranked.paths.len(), ranked.by_account.len());
+        *self.ranked.write().unwrap() = ranked;
+    }
+
+    // Re-score the paths through the account, returns how many were
+    pub fn on_account_update(&self, address: &str) -> usize {
+        let indexes = match self.ranked.read().unwrap().by_account.get(address) {
+            Some(indexes) => indexes.clone(),
+            None => return 0,
+        };
+        let scores: Vec<(usize, i64)> = {
+            let ranked = self.ranked.read().unwrap();
+            indexes.iter().filter_map(|index| Some((*index, self.score(ranked.paths.get(*index)?)))).collect()
+        };
+        let mut ranked = self.ranked.write().unwrap();
+        for (index, score) in scores.iter() {
+            // The path set may have been replaced meanwhile
+            if *index < ranked.scores.len() {
+                ranked.set_score(*index, *score);
+            }
+        }
+        scores.len()
+    }
+
+    // Best first, each result set to the current score
+    pub fn ranked(&self) -> Vec<SwapPathSelected> {
+        let ranked = self.ranked.read().unwrap();
+        ranked
+            .order
+            .iter()
+            .map(|(Reverse(score), index)| SwapPathSelected { result: *score as f64, ..ranked.paths[*index].clone() })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranked.read().unwrap().paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
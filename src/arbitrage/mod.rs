@@ -2,4 +2,20 @@ pub mod calc_arb;
 pub mod types;
 pub mod streams;
 pub mod strategies;
-pub mod simulate;
\ No newline at end of file
+pub mod simulate;
+pub mod presets;
+pub mod registry;
+pub mod opportunity;
+pub mod discovery;
+pub mod ranking;
+pub mod cooldown;
+pub mod token_health;
+pub mod experiments;
+pub mod claims;
+pub mod submissions;
+pub mod research;
+pub mod path_files;
+pub mod live_ranking;
+pub mod plugins;
+pub mod jupiter_routes;
+pub mod backtest;
@@ -0,0 +1,430 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use log::{error, info};
+use tokio::sync::{broadcast, Notify, Semaphore};
+
+use crate::arbitrage::claims::ClaimsRegistry;
+use crate::arbitrage::cooldown::PathCooldowns;
+use crate::arbitrage::experiments::Experiment;
+use crate::arbitrage::ranking::{path_result_key, PathRanking};
+use crate::arbitrage::research::{is_dry_run, record_opportunity, record_skipped, SkipReason};
+use crate::arbitrage::submissions::SubmissionGuard;
+use crate::arbitrage::token_health::TokenHealth;
+use crate::arbitrage::types::SwapPathResult;
+use crate::common::circuit_breaker::send_breaker;
+use crate::common::tasks::spawn_named;
+use crate::monitoring::dex_stats::DexStats;
+use crate::monitoring::error_reporting::{report_error, ErrorContext};
+use crate::monitoring::events::{publish, BotEvent};
+use crate::monitoring::latency::{Stage, StageTimestamps};
+use crate::monitoring::metrics::metrics;
+use crate::monitoring::pnl::PnlTracker;
+use crate::risk::balance::BalanceMonitor;
+use crate::risk::capital::CapitalManager;
+use crate::risk::congestion::{CongestionMonitor, SendOutcome};
+use crate::risk::exposure::ExposureLimiter;
+use crate::risk::fee_budget::fee_budget;
+use crate::risk::kill_switch::{KillSwitch, FAILED_TX_COST_LAMPORTS};
+use crate::transactions::create_transaction::{create_and_send_swap_transaction_timed, ChainType, SendOrSimulate, BASE_COMPUTE_UNIT_PRICE};
+use crate::transactions::verify::TradeVerifier;
+
+#[derive(Debug, Clone)]
+pub struct Opportunity {
+    pub strategy: String,
+    pub swap_path_result: SwapPathResult,
+    // Expected profit in lamports of the base token
+    pub expected_profit: f64,
+    pub detected_at: Instant,
+    pub stages: StageTimestamps,
+}
+
+impl Opportunity {
+    pub fn new(strategy: &str, swap_path_result: SwapPathResult, stages: StageTimestamps) -> Self {
+        Opportunity {
+            strategy: strategy.to_string(),
+            expected_profit: swap_path_result.result,
+            swap_path_result,
+            detected_at: Instant::now(),
+            stages,
+        }
+    }
+
+    // Pools touched by the opportunity, used to detect duplicates in the queue
+    pub fn pools_key(&self) -> Vec<String> {
+        self.swap_path_result.route_simulations.iter().map(|route| route.pool_address.clone()).collect()
+    }
+}
+
+// Opportunities scored by expected profit, linearly decayed by age until max_age
+pub struct OpportunityQueue {
+    items: Mutex<Vec<Opportunity>>,
+    notify: Notify,
+    // Every pushed opportunity, queued or not, for the library consumers
+    found: broadcast::Sender<Opportunity>,
+    pub capacity: usize,
+    pub max_age: Duration,
+}
+
+impl OpportunityQueue {
+    pub fn new(capacity: usize, max_age: Duration) -> Self {
+        OpportunityQueue {
+            items: Mutex::new(Vec::new()),
+            notify: Notify::new(),
+            found: broadcast::channel(256).0,
+            capacity,
+            max_age,
+        }
+    }
+
+    pub fn score(&self, opportunity: &Opportunity, now: Instant) -> f64 {
+        let age = now.saturating_duration_since(opportunity.detected_at).as_secs_f64();
+        let freshness = 1.0 - age / self.max_age.as_secs_f64();
+        opportunity.expected_profit * freshness.max(0.0)
+    }
+
+    fn evict_stale(&self, items: &mut Vec<Opportunity>, now: Instant) {
+        let before = items.len();
+        items.retain(|opportunity| now.saturating_duration_since(opportunity.detected_at) < self.max_age);
+        if items.len() < before {
+            info!("🗑️  {} stale opportunities evicted", before - items.len());
+        }
+    }
+
+    pub fn push(&self, opportunity: Opportunity) {
+        // No subscriber is not an error
+        let _ = self.found.send(opportunity.clone());
+        let now = Instant::now();
+        {
+            let mut items = self.items.lock().unwrap();
+            self.evict_stale(&mut items, now);
+
+            // Same pools already queued: keep only the freshest quote
+            let key = opportunity.pools_key();
+            items.retain(|item| item.pools_key() != key);
+
+            if items.len() >= self.capacity {
+                let worst = items
+                    .iter()
+                    .enumerate()
+                    .min_by(|a, b| self.score(a.1, now).total_cmp(&self.score(b.1, now)))
+                    .map(|(index, item)| (index, self.score(item, now)));
+                match worst {
+                    Some((index, worst_score)) if worst_score < self.score(&opportunity, now) => {
+                        items.swap_remove(index);
+                    }
+                    _ => return,
+                }
+            }
+            items.push(opportunity);
+        }
+        self.notify.notify_one();
+    }
+
+    pub fn pop_best(&self) -> Option<Opportunity> {
+        let now = Instant::now();
+        let mut items = self.items.lock().unwrap();
+        self.evict_stale(&mut items, now);
+
+        let best = items
+            .iter()
+            .enumerate()
+            .max_by(|a, b| self.score(a.1, now).total_cmp(&self.score(b.1, now)))
+            .map(|(index, _)| index)?;
+        Some(items.swap_remove(best))
+    }
+
+    // Wait until an opportunity is available
+    pub async fn next(&self) -> Opportunity {
+        loop {
+            if let Some(opportunity) = self.pop_best() {
+                return opportunity;
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    // Opportunities pushed from now on, whether the executor pool picks them or not
+    pub fn subscribe(&self) -> broadcast::Receiver<Opportunity> {
+        self.found.subscribe()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// Everything the executor pool needs to send an opportunity
+pub struct ExecutorContext {
+    pub max_in_flight: usize,
+    pub capital: Arc<CapitalManager>,
+    pub path_ranking: Arc<PathRanking>,
+    pub cooldowns: Arc<PathCooldowns>,
+    pub token_health: Arc<TokenHealth>,
+    // Venues failing too often are left out for a while
+    pub dex_stats: Arc<DexStats>,
+    // Parameterizations compared on interleaved opportunities
+    pub experiment: Option<Arc<Experiment>>,
+    // Turns sending into simulation once the daily loss limit is hit
+    pub kill_switch: Arc<KillSwitch>,
+    pub exposure: Arc<ExposureLimiter>,
+    pub balance: Arc<BalanceMonitor>,
+    pub congestion: Arc<CongestionMonitor>,
+    pub pnl: Arc<PnlTracker>,
+    pub verifier: Option<Arc<TradeVerifier>>,
+    // Shared with every task able to build a transaction
+    pub claims: Arc<ClaimsRegistry>,
+    // Pool sets submitted recently, this run or a previous one
+    pub submissions: Arc<SubmissionGuard>,
+    pub chain: ChainType,
+    pub simulate_or_send: SendOrSimulate,
+}
+
+// Drain the queue, never having more than max_in_flight transactions at the same time
+pub async fn run_executor_pool(queue: Arc<OpportunityQueue>, ctx: Arc<ExecutorContext>) {
+    let permits = Arc::new(Semaphore::new(ctx.max_in_flight));
+    info!("🧵 Executor pool started with {} in-flight transactions max", ctx.max_in_flight);
+
+    loop {
+        let permit = match permits.clone().acquire_owned().await {
+            Ok(permit) => permit,
+            Err(_) => break,
+        };
+        let mut opportunity = queue.next().await;
+        opportunity.stages.mark(Stage::Dequeue);
+        let skip = |reason: SkipReason| record_skipped(&opportunity.strategy, &opportunity.swap_path_result, reason);
+        // Every send endpoint broken: drop the opportunities until one recovers
+        if ctx.chain == ChainType::Mainnet && send_breaker().is_halted() {
+            skip(SkipReason::SendHalted);
+            continue;
+        }
+        // Nothing is sent anymore today
+        if ctx.simulate_or_send == SendOrSimulate::Send && fee_budget().is_exhausted() {
+            skip(SkipReason::FeeBudgetExhausted);
+            continue;
+        }
+        let key = path_result_key(&opportunity.swap_path_result);
+        if ctx.cooldowns.is_cooling_down(&key) {
+            skip(SkipReason::CoolingDown);
+            continue;
+        }
+        if ctx.submissions.is_duplicate(&opportunity.swap_path_result) {
+            skip(SkipReason::DuplicateSubmission);
+            continue;
+        }
+        if opportunity.swap_path_result.route_simulations.iter().any(|route| !ctx.token_health.is_active(&route.token_out)) {
+            skip(SkipReason::TokenInactive);
+            continue;
+        }
+        if ctx.simulate_or_send == SendOrSimulate::Send && !ctx.balance.can_send(opportunity.swap_path_result.amount_in) {
+            skip(SkipReason::LowBalance);
+            continue;
+        }
+        if opportunity.swap_path_result.route_simulations.iter().any(|route| !ctx.dex_stats.is_venue_active(&route.dex_label)) {
+            skip(SkipReason::FlakyVenue);
+            continue;
+        }
+        if !ctx.exposure.can_trade(&opportunity.swap_path_result) {
+            skip(SkipReason::ExposureLimit);
+            continue;
+        }
+        // Don't burn fees into a congested leader for a small profit
+        if !ctx.congestion.accepts(opportunity.expected_profit) {
+            skip(SkipReason::Congestion);
+            continue;
+        }
+        // Research dataset only, nothing is claimed, reserved or sent
+        if is_dry_run() {
+            skip(SkipReason::DryRun);
+            continue;
+        }
+        let experiment_arm = match &ctx.experiment {
+            Some(experiment) => {
+                let arm = experiment.assign();
+                if !experiment.arms[arm].accepts(&opportunity) {
+                    experiment.record_skipped(arm);
+                    skip(SkipReason::ExperimentArm);
+                    continue;
+                }
+                Some(arm)
+            }
+            None => None,
+        };
+        let claim = match ctx.claims.try_claim(&opportunity.strategy, &opportunity.swap_path_result) {
+            Some(claim) => claim,
+            None => {
+                skip(SkipReason::Claimed);
+                continue;
+            }
+        };
+        let reservation = match ctx.capital.try_reserve(&opportunity.strategy, opportunity.swap_path_result.amount_in) {
+            Some(reservation) => reservation,
+            None => {
+                skip(SkipReason::NoCapital);
+                continue;
+            }
+        };
+        record_opportunity(&opportunity.strategy, &opportunity.swap_path_result, true, None);
+        let ctx = ctx.clone();
+        spawn_named("execute_opportunity", async move {
+            info!("💸 Execute opportunity from {} // expected profit {}", opportunity.strategy, opportunity.expected_profit);
+            let simulate_or_send = ctx.kill_switch.gate(ctx.simulate_or_send);
+            // Block times are in seconds, leave some margin for clock drift
+            let sent_at = Utc::now().timestamp() - 5;
+            let started_at = Instant::now();
+            let compute_unit_price = ctx.congestion.compute_unit_price(BASE_COMPUTE_UNIT_PRICE);
+            if simulate_or_send == SendOrSimulate::Send {
+                ctx.submissions.record(&opportunity.strategy, &opportunity.swap_path_result);
+            }
+            let result = create_and_send_swap_transaction_timed(simulate_or_send, ctx.chain.clone(), opportunity.swap_path_result.clone(), compute_unit_price, &opportunity.strategy, &mut opportunity.stages).await;
+            opportunity.stages.observe();
+            if simulate_or_send == SendOrSimulate::Send {
+                if let Some(outcome) = SendOutcome::classify(&result, started_at.elapsed()) {
+                    ctx.congestion.record(outcome);
+                }
+            }
+            let landed = match result {
+                Ok(landed) => landed,
+                // Sending the same route again fails the same way, its tokens are set aside
+                Err(e) if e.is_program_error() => {
+                    report_error(&ErrorContext::for_path(&opportunity.strategy, &opportunity.swap_path_result), &format!("❌ Route rejected by its programs: {}", e));
+                    ctx.token_health.quarantine_path(&opportunity.swap_path_result, &e.to_string());
+                    false
+                }
+                Err(e) => {
+                    report_error(&ErrorContext::for_path(&opportunity.strategy, &opportunity.swap_path_result), &format!("❌ Executor failed: {:?}", e));
+                    false
+                }
+            };
+            if simulate_or_send == SendOrSimulate::Send {
+                // Realized profit from the confirmed balance deltas, when a verifier is set
+                let mut realized_profit: Option<f64> = None;
+                if landed {
+                    if let Some(verifier) = &ctx.verifier {
+                        match verifier.verify_path(&opportunity.swap_path_result, sent_at).await {
+                            Ok(verification) => realized_profit = Some(verification.realized_profit),
+                            Err(e) => error!("❌ Trade verification failed: {:?}", e),
+                        }
+                    }
+                }
+                if landed {
+                    ctx.cooldowns.record_success(&key);
+                    ctx.kill_switch.record_pnl(realized_profit.unwrap_or(opportunity.expected_profit));
+                    metrics().profit_lamports.add(realized_profit.unwrap_or(opportunity.expected_profit));
+                    ctx.pnl.record_trade(&opportunity.strategy, &opportunity.swap_path_result, opportunity.expected_profit, realized_profit.unwrap_or(opportunity.expected_profit), true, realized_profit.is_some());
+                } else {
+                    ctx.cooldowns.record_failure(&key);
+                    ctx.kill_switch.record_pnl(-FAILED_TX_COST_LAMPORTS);
+                    ctx.pnl.record_trade(&opportunity.strategy, &opportunity.swap_path_result, opportunity.expected_profit, -FAILED_TX_COST_LAMPORTS, false, false);
+                }
+                publish(BotEvent::TradeExecuted {
+                    strategy: opportunity.strategy.clone(),
+                    tokens_path: opportunity.swap_path_result.tokens_path.clone(),
+                    expected_profit: opportunity.expected_profit,
+                    realized_profit,
+                    landed,
+                    amount_in: opportunity.swap_path_result.amount_in,
+                    dexes: opportunity.swap_path_result.route_simulations.iter().map(|route| route.dex_label.clone()).collect(),
+                });
+                ctx.path_ranking.record_outcome(&key, opportunity.expected_profit, landed, realized_profit);
+                // Only a verified trade says whether the tokens made money
+                if let Some(profitable) = realized_profit.map(|profit| profit > 0.0) {
+                    ctx.token_health.record_path_outcome(&opportunity.swap_path_result, profitable);
+                }
+            }
+            if let (Some(experiment), Some(arm)) = (&ctx.experiment, experiment_arm) {
+                experiment.record_outcome(arm, landed, opportunity.expected_profit);
+            }
+            drop(claim);
+            drop(reservation);
+            drop(permit);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::types::SwapRouteSimulation;
+    use crate::markets::types::DexLabel;
+
+    // Opportunity through the pools, detected `age` ago
+    fn opportunity(pools: &[&str], expected_profit: f64, age: Duration) -> Opportunity {
+        let route_simulations = pools
+            .iter()
+            .map(|pool| SwapRouteSimulation {
+                id_route: 0,
+                pool_address: pool.to_string(),
+                dex_label: DexLabel::RAYDIUM,
+                token_0to1: true,
+                token_in: String::new(),
+                token_out: String::new(),
+                amount_in: 0,
+                estimated_amount_out: "0".to_string(),
+                estimated_min_amount_out: "0".to_string(),
+            })
+            .collect();
+        let spr = SwapPathResult {
+            path_id: 0,
+            hops: pools.len() as u8,
+            tokens_path: String::new(),
+            route_simulations,
+            token_in: String::new(),
+            token_in_symbol: String::new(),
+            token_out: String::new(),
+            token_out_symbol: String::new(),
+            amount_in: 0,
+            estimated_amount_out: "0".to_string(),
+            estimated_min_amount_out: "0".to_string(),
+            result: expected_profit,
+        };
+        let detected_at = Instant::now() - age;
+        Opportunity { detected_at, ..Opportunity::new("best_paths", spr, StageTimestamps::new(detected_at)) }
+    }
+
+    fn popped_profits(queue: &OpportunityQueue) -> Vec<f64> {
+        std::iter::from_fn(|| queue.pop_best()).map(|opportunity| opportunity.expected_profit).collect()
+    }
+
+    #[test]
+    fn score_decays_linearly_until_max_age() {
+        let queue = OpportunityQueue::new(4, Duration::from_secs(1));
+        let fresh = opportunity(&["a"], 1_000.0, Duration::ZERO);
+        let detected_at = fresh.detected_at;
+        assert_eq!(queue.score(&fresh, detected_at), 1_000.0);
+        assert_eq!(queue.score(&fresh, detected_at + Duration::from_millis(250)), 750.0);
+        assert_eq!(queue.score(&fresh, detected_at + Duration::from_secs(2)), 0.0);
+    }
+
+    #[test]
+    fn best_score_popped_first_and_stale_ones_evicted() {
+        let queue = OpportunityQueue::new(4, Duration::from_secs(10));
+        queue.push(opportunity(&["a"], 1_000.0, Duration::from_secs(9)));
+        queue.push(opportunity(&["b"], 500.0, Duration::ZERO));
+        queue.push(opportunity(&["c"], 5_000.0, Duration::from_secs(20)));
+        assert_eq!(popped_profits(&queue), vec![500.0, 1_000.0]);
+    }
+
+    #[test]
+    fn same_pools_keep_the_latest_quote() {
+        let queue = OpportunityQueue::new(4, Duration::from_secs(10));
+        queue.push(opportunity(&["a", "b"], 1_000.0, Duration::ZERO));
+        queue.push(opportunity(&["a", "b"], 400.0, Duration::ZERO));
+        assert_eq!(popped_profits(&queue), vec![400.0]);
+    }
+
+    #[test]
+    fn full_queue_replaces_its_worst_with_a_better_one_only() {
+        let queue = OpportunityQueue::new(2, Duration::from_secs(10));
+        queue.push(opportunity(&["a"], 1_000.0, Duration::ZERO));
+        queue.push(opportunity(&["b"], 200.0, Duration::ZERO));
+        queue.push(opportunity(&["c"], 100.0, Duration::ZERO));
+        queue.push(opportunity(&["d"], 500.0, Duration::ZERO));
+        assert_eq!(popped_profits(&queue), vec![1_000.0, 500.0]);
+    }
+}
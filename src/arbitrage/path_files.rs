@@ -0,0 +1,82 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+use log::{error, info};
+
+use crate::arbitrage::types::VecSwapPathSelected;
+
+// Encoding of the best paths files, told apart by the extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathsFormat {
+    // Readable, slow to parse once the ultra strategies hold thousands of paths
+    Json,
+    // bincode, several times smaller and faster to load
+    Binary,
+}
+
+impl PathsFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PathsFormat::Json => "json",
+            PathsFormat::Binary => "bin",
+        }
+    }
+
+    pub fn of_file(path: &str) -> Self {
+        match Path::new(path).extension().and_then(|extension| extension.to_str()) {
+            Some("bin") => PathsFormat::Binary,
+            _ => PathsFormat::Json,
+        }
+    }
+}
+
+static PATHS_FORMAT: OnceLock<PathsFormat> = OnceLock::new();
+
+// To call once at startup, the best paths files are written as JSON otherwise
+pub fn init_paths_format(format: PathsFormat) {
+    info!("📂 Best paths files written as {:?}", format);
+    let _ = PATHS_FORMAT.set(format);
+}
+
+pub fn paths_format() -> PathsFormat {
+    PATHS_FORMAT.get().copied().unwrap_or(PathsFormat::Json)
+}
+
+// best_paths_selected/<name>.<extension of the configured format>
+pub fn paths_file_name(dir: &str, name: &str) -> String {
+    format!("{}/{}.{}", dir, name, paths_format().extension())
+}
+
+pub fn write_paths(path: &str, content: &VecSwapPathSelected) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    match PathsFormat::of_file(path) {
+        PathsFormat::Json => serde_json::to_writer(&mut writer, content)?,
+        PathsFormat::Binary => bincode::serialize_into(&mut writer, content)?,
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// A binary file missing or unreadable falls back to the JSON file of the same name
+pub fn read_paths(path: &str) -> Result<VecSwapPathSelected> {
+    match (read_paths_as(path, PathsFormat::of_file(path)), PathsFormat::of_file(path)) {
+        (Ok(content), _) => Ok(content),
+        (Err(e), PathsFormat::Binary) => {
+            let json_path = PathBuf::from(path).with_extension(PathsFormat::Json.extension());
+            error!("❌ {} unreadable ({}), falling back to {}", path, e, json_path.display());
+            read_paths_as(&json_path.to_string_lossy(), PathsFormat::Json)
+        }
+        (Err(e), PathsFormat::Json) => Err(e),
+    }
+}
+
+fn read_paths_as(path: &str, format: PathsFormat) -> Result<VecSwapPathSelected> {
+    let reader = BufReader::new(File::open(path).with_context(|| format!("opening {}", path))?);
+    Ok(match format {
+        PathsFormat::Json => serde_json::from_reader(reader)?,
+        PathsFormat::Binary => bincode::deserialize_from(reader)?,
+    })
+}
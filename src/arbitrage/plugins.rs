@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use log::info;
+use tokio::sync::broadcast;
+
+use crate::arbitrage::opportunity::OpportunityQueue;
+use crate::arbitrage::registry::{Strategy, StrategyEvent};
+use crate::arbitrage::strategies::RotationStrategy;
+use crate::common::types::InputVec;
+use crate::markets::state::MarketState;
+
+// Bumped on every breaking change of StrategyPlugin, PluginContext or Strategy
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+// Shared pieces a plugin strategy may hook into, the same ones the built-in strategies get
+#[derive(Clone)]
+pub struct PluginContext {
+    pub markets: Arc<MarketState>,
+    pub opportunity_queue: Arc<OpportunityQueue>,
+    pub events: broadcast::Sender<StrategyEvent>,
+    pub inputs: Vec<InputVec>,
+}
+
+// Factory of a strategy, the only thing a crate adding a strategy implements (with Strategy).
+// The config is the string map of the operator, parsed by the plugin
+pub trait StrategyPlugin: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn api_version(&self) -> u32 {
+        PLUGIN_API_VERSION
+    }
+
+    fn create(&self, ctx: &PluginContext, config: &HashMap<String, String>) -> Result<Box<dyn Strategy>>;
+}
+
+// Plugins by name. A strategy crate exposes `pub fn register(plugins: &mut PluginRegistry)`, behind a
+// feature of this crate (optional dependency) called from builtin_plugins
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: HashMap<String, Arc<dyn StrategyPlugin>>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        PluginRegistry::default()
+    }
+
+    pub fn register(&mut self, plugin: Arc<dyn StrategyPlugin>) -> Result<()> {
+        if plugin.api_version() != PLUGIN_API_VERSION {
+            return Err(anyhow!("Plugin {} built for the plugin API v{}, v{} expected", plugin.name(), plugin.api_version(), PLUGIN_API_VERSION));
+        }
+        if self.plugins.contains_key(plugin.name()) {
+            return Err(anyhow!("Plugin {} already registered", plugin.name()));
+        }
+        info!("🔌 Strategy plugin registered: {}", plugin.name());
+        self.plugins.insert(plugin.name().to_string(), plugin);
+        Ok(())
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.plugins.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub fn create(&self, name: &str, ctx: &PluginContext, config: &HashMap<String, String>) -> Result<Box<dyn Strategy>> {
+        let plugin = self.plugins.get(name).ok_or_else(|| anyhow!("No strategy plugin {}, available: {:?}", name, self.names()))?;
+        plugin.create(ctx, config)
+    }
+}
+
+// rotation: the inputs of the context one after the other. Config: time_budget_secs (60), fetch_new_pools (false)
+pub struct RotationPlugin;
+
+impl StrategyPlugin for RotationPlugin {
+    fn name(&self) -> &'static str {
+        "rotation"
+    }
+
+    fn create(&self, ctx: &PluginContext, config: &HashMap<String, String>) -> Result<Box<dyn Strategy>> {
+        let time_budget_secs: u64 = config.get("time_budget_secs").map(|secs| secs.parse()).transpose()?.unwrap_or(60);
+        let fetch_new_pools: bool = config.get("fetch_new_pools").map(|fetch| fetch.parse()).transpose()?.unwrap_or(false);
+        let strategy = RotationStrategy::new(ctx.inputs.clone(), fetch_new_pools, true, Duration::from_secs(time_budget_secs))
+            .with_market_state(ctx.markets.clone())
+            .with_opportunity_queue(ctx.opportunity_queue.clone());
+        Ok(Box::new(strategy))
+    }
+}
+
+// The plugins shipped with the crate, and those of the strategy crates enabled by feature
+pub fn builtin_plugins() -> PluginRegistry {
+    let mut plugins = PluginRegistry::new();
+    // Unique names of the same API version
+    plugins.register(Arc::new(RotationPlugin)).expect("Built-in plugin not registered");
+    plugins
+}
@@ -0,0 +1,48 @@
+use crate::arbitrage::types::TokenInArb;
+use crate::common::constants::{BSOL_MINT, JITOSOL_MINT, MSOL_MINT, SOL_MINT, USDC_MINT, USDT_MINT};
+use crate::common::types::InputVec;
+
+// The preset types live with InputVec
+pub use crate::common::types::{PresetParams, StrategyPreset};
+
+fn token(address: &str, symbol: &str) -> TokenInArb {
+    TokenInArb {
+        address: address.into(),
+        symbol: symbol.into(),
+    }
+}
+
+// SOL -> USDC -> USDT -> SOL triangles and SOL/LST pairs
+pub fn stable_lst_inputs() -> Vec<InputVec> {
+    vec![
+        InputVec {
+            tokens_to_arb: vec![
+                token(SOL_MINT, "SOL"),
+                token(USDC_MINT, "USDC"),
+                token(USDT_MINT, "USDT"),
+            ],
+            include_1hop: true,
+            include_2hop: true,
+            numbers_of_best_paths: 4,
+            get_fresh_pools_bool: false,
+            preset: StrategyPreset::StableLst,
+            time_budget_secs: None,
+            bridge_tokens: Vec::new(),
+        },
+        InputVec {
+            tokens_to_arb: vec![
+                token(SOL_MINT, "SOL"),
+                token(MSOL_MINT, "mSOL"),
+                token(JITOSOL_MINT, "JitoSOL"),
+                token(BSOL_MINT, "bSOL"),
+            ],
+            include_1hop: true,
+            include_2hop: true,
+            numbers_of_best_paths: 4,
+            get_fresh_pools_bool: false,
+            preset: StrategyPreset::StableLst,
+            time_budget_secs: None,
+            bridge_tokens: Vec::new(),
+        },
+    ]
+}
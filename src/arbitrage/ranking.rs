@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+use crate::arbitrage::types::{SwapPath, SwapPathResult, SwapPathSelected};
+
+pub static PATH_STATS_FILE: &str = "best_paths_selected/path_stats.json";
+
+// Route ids change between runs, so paths are identified by their pools and directions
+pub fn path_key(path: &SwapPath) -> String {
+    path.paths
+        .iter()
+        .map(|route| format!("{}:{}", route.pool_address, route.token_0to1))
+        .collect::<Vec<String>>()
+        .join("|")
+}
+
+pub fn path_result_key(spr: &SwapPathResult) -> String {
+    spr.route_simulations
+        .iter()
+        .map(|route| format!("{}:{}", route.pool_address, route.token_0to1))
+        .collect::<Vec<String>>()
+        .join("|")
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PathStats {
+    pub attempts: u32,
+    pub landed: u32,
+    // Only outcomes where the realized profit is known
+    pub measured: u32,
+    pub simulated_profit_sum: f64,
+    pub realized_profit_sum: f64,
+}
+
+impl PathStats {
+    // Smoothed landing rate, an unknown path starts at 50%
+    pub fn conversion_rate(&self) -> f64 {
+        (self.landed as f64 + 1.0) / (self.attempts as f64 + 2.0)
+    }
+
+    // Share of the simulated profit actually realized
+    pub fn realization_ratio(&self) -> f64 {
+        if self.measured == 0 || self.simulated_profit_sum <= 0.0 {
+            return 1.0;
+        }
+        (self.realized_profit_sum / self.simulated_profit_sum).clamp(0.0, 1.5)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PathStatsFile {
+    value: HashMap<String, PathStats>,
+}
+
+fn read_path_stats(file_path: &str) -> Result<HashMap<String, PathStats>> {
+    if !Path::new(file_path).exists() {
+        return Ok(HashMap::new());
+    }
+    let file = File::open(file_path)?;
+    let content: PathStatsFile = serde_json::from_reader(file)?;
+    Ok(content.value)
+}
+
+// Execution history per path, used to re-rank the selected paths
+#[derive(Debug)]
+pub struct PathRanking {
+    file_path: String,
+    stats: Mutex<HashMap<String, PathStats>>,
+    // Outcomes recorded since the last write, flushed by run_path_stats_writer
+    dirty: AtomicBool,
+}
+
+impl PathRanking {
+    pub fn load(file_path: &str) -> Self {
+        let stats = match read_path_stats(file_path) {
+            Ok(stats) => stats,
+            Err(e) => {
+                error!("❌ Unable to read path stats {}: {:?}", file_path, e);
+                HashMap::new()
+            }
+        };
+        info!("📊 Path ranking loaded with {} paths history", stats.len());
+        PathRanking {
+            file_path: file_path.to_string(),
+            stats: Mutex::new(stats),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    pub fn record_outcome(&self, key: &str, simulated_profit: f64, landed: bool, realized_profit: Option<f64>) {
+        {
+            let mut stats = self.stats.lock().unwrap();
+            let entry = stats.entry(key.to_string()).or_default();
+            entry.attempts += 1;
+            if landed {
+                entry.landed += 1;
+            }
+            if let Some(realized_profit) = realized_profit {
+                entry.measured += 1;
+                entry.simulated_profit_sum += simulated_profit;
+                entry.realized_profit_sum += realized_profit;
+            }
+        }
+        self.dirty.store(true, Ordering::Release);
+    }
+
+    pub fn stats_for(&self, key: &str) -> PathStats {
+        self.stats.lock().unwrap().get(key).cloned().unwrap_or_default()
+    }
+
+    // Expected realized profit of a selected path
+    pub fn score(&self, path: &SwapPathSelected) -> f64 {
+        let stats = self.stats_for(&path_key(&path.path));
+        path.result * stats.conversion_rate() * stats.realization_ratio()
+    }
+
+    pub fn rerank(&self, paths: &mut [SwapPathSelected]) {
+        paths.sort_by(|a, b| self.score(b).total_cmp(&self.score(a)));
+    }
+
+    // Written only when an outcome was recorded since the last write
+    pub fn save_if_dirty(&self) -> Result<bool> {
+        if !self.dirty.swap(false, Ordering::AcqRel) {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = PathStatsFile { value: self.stats.lock().unwrap().clone() };
+        let file = File::create(&self.file_path)?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer(&mut writer, &content)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+// Path stats written off the send path, at most once per interval
+pub async fn run_path_stats_writer(ranking: Arc<PathRanking>, interval: Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+        let ranking = ranking.clone();
+        match tokio::task::spawn_blocking(move || ranking.save_if_dirty()).await {
+            Ok(Err(e)) => error!("❌ Unable to write path stats: {:?}", e),
+            Err(e) => error!("❌ Path stats writer panicked: {:?}", e),
+            Ok(Ok(_)) => {}
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use log::{error, info};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio::task::JoinSet;
+
+use crate::common::tasks::spawn_named_in;
+use crate::monitoring::error_reporting::{report_error, ErrorContext};
+use crate::monitoring::events::{publish, BotEvent};
+
+// Events pushed by the registry to every running strategy
+#[derive(Debug, Clone)]
+pub enum StrategyEvent {
+    PoolUpdated { pool_address: String },
+    // Swap through some of our pools seen in a block being produced, before the RPC confirms it
+    SwapObserved { signature: String, slot: u64, pools: Vec<String> },
+    // Operator control of one strategy, by name, handled by the registry: a paused strategy gets no tick
+    Pause { strategy: String },
+    Resume { strategy: String },
+    SetParam { strategy: String, name: String, value: String },
+    Shutdown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickOutcome {
+    Continue,
+    Done,
+}
+
+#[async_trait]
+pub trait Strategy: Send {
+    fn name(&self) -> String;
+
+    // Delay between two on_tick calls
+    fn tick_interval(&self) -> Duration {
+        Duration::from_millis(200)
+    }
+
+    // Parameters tunable while running, by name
+    fn params(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    fn set_param(&mut self, name: &str, _value: &str) -> Result<()> {
+        Err(anyhow!("Unknown parameter {}", name))
+    }
+
+    async fn init(&mut self) -> Result<()>;
+
+    async fn on_event(&mut self, event: StrategyEvent) -> Result<()>;
+
+    async fn on_tick(&mut self) -> Result<TickOutcome>;
+
+    async fn shutdown(&mut self) -> Result<()>;
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StrategyState {
+    pub status: String,
+    pub paused: bool,
+    pub params: HashMap<String, String>,
+}
+
+// State of every registered strategy, by name, kept up to date while they run
+pub type StrategyStates = Arc<RwLock<HashMap<String, StrategyState>>>;
+
+pub struct StrategyRegistry {
+    strategies: Vec<Box<dyn Strategy>>,
+    events_tx: broadcast::Sender<StrategyEvent>,
+    states: StrategyStates,
+}
+
+impl Default for StrategyRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StrategyRegistry {
+    pub fn new() -> Self {
+        let (events_tx, _) = broadcast::channel(1024);
+        StrategyRegistry {
+            strategies: Vec::new(),
+            events_tx,
+            states: StrategyStates::default(),
+        }
+    }
+
+    pub fn register(&mut self, strategy: Box<dyn Strategy>) {
+        info!("🧩 Strategy registered: {}", strategy.name());
+        let state = StrategyState { status: "registered".to_string(), paused: false, params: strategy.params() };
+        self.states.write().unwrap().insert(strategy.name(), state);
+        self.strategies.push(strategy);
+    }
+
+    pub fn len(&self) -> usize {
+        self.strategies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strategies.is_empty()
+    }
+
+    // Handle to broadcast events to all the strategies once they are running
+    pub fn events(&self) -> broadcast::Sender<StrategyEvent> {
+        self.events_tx.clone()
+    }
+
+    pub fn states(&self) -> StrategyStates {
+        self.states.clone()
+    }
+
+    // Run every registered strategy concurrently until all of them are done
+    pub async fn run_all(self) -> Result<()> {
+        let mut set: JoinSet<(String, Result<()>)> = JoinSet::new();
+
+        for strategy in self.strategies {
+            let events_rx = self.events_tx.subscribe();
+            let states = self.states.clone();
+            let task_name = format!("strategy:{}", strategy.name());
+            spawn_named_in(&mut set, &task_name, async move {
+                let name = strategy.name();
+                let result = run_strategy(strategy, events_rx, states).await;
+                (name, result)
+            });
+        }
+
+        while let Some(res) = set.join_next().await {
+            match res {
+                Ok((name, Ok(()))) => {
+                    info!("🏁 Strategy {} finished", name);
+                    set_status(&self.states, &name, "finished");
+                    publish(BotEvent::StrategyStatus { strategy: name, status: "finished".to_string() });
+                }
+                Ok((name, Err(e))) => {
+                    set_status(&self.states, &name, &format!("stopped: {}", e));
+                    report_error(&ErrorContext { strategy: Some(name.clone()), ..ErrorContext::default() }, &format!("❌ Strategy {} stopped with error: {:?}", name, e));
+                    publish(BotEvent::StrategyStatus { strategy: name, status: format!("stopped: {}", e) });
+                }
+                Err(e) => error!("❌ Strategy task panicked: {:?}", e),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn set_status(states: &StrategyStates, name: &str, status: &str) {
+    if let Some(state) = states.write().unwrap().get_mut(name) {
+        state.status = status.to_string();
+    }
+}
+
+async fn run_strategy(mut strategy: Box<dyn Strategy>, mut events_rx: broadcast::Receiver<StrategyEvent>, states: StrategyStates) -> Result<()> {
+    let name = strategy.name();
+    info!("▶️  Init strategy {}", name);
+    set_status(&states, &name, "initializing");
+    publish(BotEvent::StrategyStatus { strategy: name.clone(), status: "initializing".to_string() });
+    strategy.init().await?;
+    set_status(&states, &name, "running");
+    publish(BotEvent::StrategyStatus { strategy: name.clone(), status: "running".to_string() });
+
+    let mut paused = false;
+    let mut interval = tokio::time::interval(strategy.tick_interval());
+    loop {
+        tokio::select! {
+            event = events_rx.recv() => {
+                match event {
+                    Ok(StrategyEvent::Shutdown) | Err(broadcast::error::RecvError::Closed) => break,
+                    Ok(StrategyEvent::Pause { strategy: target }) if target == name => {
+                        paused = true;
+                        info!("⏸️  Strategy {} paused", name);
+                        set_paused(&states, &name, true);
+                        publish(BotEvent::StrategyStatus { strategy: name.clone(), status: "paused".to_string() });
+                    }
+                    Ok(StrategyEvent::Resume { strategy: target }) if target == name => {
+                        paused = false;
+                        info!("▶️  Strategy {} resumed", name);
+                        set_paused(&states, &name, false);
+                        publish(BotEvent::StrategyStatus { strategy: name.clone(), status: "running".to_string() });
+                    }
+                    Ok(StrategyEvent::SetParam { strategy: target, name: param, value }) if target == name => {
+                        // A rejected value leaves the strategy running with its previous one
+                        match strategy.set_param(&param, &value) {
+                            Ok(()) => info!("🎛️  Strategy {}: {} = {}", name, param, value),
+                            Err(e) => error!("❌ Strategy {}: {} not set to {}: {:?}", name, param, value, e),
+                        }
+                        if let Some(state) = states.write().unwrap().get_mut(&name) {
+                            state.params = strategy.params();
+                        }
+                    }
+                    // Control of another strategy
+                    Ok(StrategyEvent::Pause { .. } | StrategyEvent::Resume { .. } | StrategyEvent::SetParam { .. }) => {}
+                    // A paused strategy doesn't react to the market either
+                    Ok(_) if paused => {}
+                    Ok(event) => strategy.on_event(event).await?,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        error!("Strategy {} lagged, {} events skipped", name, skipped);
+                    }
+                }
+            }
+            _ = interval.tick() => {
+                if paused {
+                    continue;
+                }
+                if strategy.on_tick().await? == TickOutcome::Done {
+                    break;
+                }
+            }
+        }
+    }
+
+    strategy.shutdown().await
+}
+
+fn set_paused(states: &StrategyStates, name: &str, paused: bool) {
+    if let Some(state) = states.write().unwrap().get_mut(name) {
+        state.paused = paused;
+        state.status = if paused { "paused" } else { "running" }.to_string();
+    }
+}
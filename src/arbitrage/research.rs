@@ -0,0 +1,88 @@
+use std::sync::OnceLock;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::arbitrage::types::SwapPathResult;
+use crate::monitoring::events::{publish, BotEvent};
+
+pub static RESEARCH_COLLECTION: &str = "research";
+
+// Why a detected opportunity was not sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    BelowThreshold,
+    SanityRejected,
+    SendHalted,
+    FeeBudgetExhausted,
+    CoolingDown,
+    DuplicateSubmission,
+    TokenInactive,
+    FlakyVenue,
+    LowBalance,
+    ExposureLimit,
+    Congestion,
+    ExperimentArm,
+    // Another strategy is already sending through these pools
+    Claimed,
+    NoCapital,
+    // Passed every check, but research mode never sends
+    DryRun,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResearchRecord {
+    // Unix seconds
+    pub timestamp: i64,
+    pub strategy: String,
+    pub tokens_path: String,
+    pub pools: Vec<String>,
+    pub amount_in: u64,
+    pub expected_profit: f64,
+    pub executed: bool,
+    pub skip_reason: Option<SkipReason>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResearchConfig {
+    // Opportunities simulated above this profit are recorded, far under the execution threshold
+    pub min_profit: f64,
+    // Evaluate every check but never send
+    pub dry_run: bool,
+}
+
+static RESEARCH: OnceLock<ResearchConfig> = OnceLock::new();
+
+// To call once at startup, nothing is recorded otherwise
+pub fn init_research(config: ResearchConfig) {
+    let _ = RESEARCH.set(config);
+}
+
+pub fn is_dry_run() -> bool {
+    RESEARCH.get().is_some_and(|config| config.dry_run)
+}
+
+// Persisted by the database writer, executed or not
+pub fn record_opportunity(strategy: &str, spr: &SwapPathResult, executed: bool, skip_reason: Option<SkipReason>) {
+    let Some(config) = RESEARCH.get() else { return };
+    if spr.result < config.min_profit {
+        return;
+    }
+    publish(BotEvent::ResearchRecorded {
+        record: ResearchRecord {
+            timestamp: Utc::now().timestamp(),
+            strategy: strategy.to_string(),
+            tokens_path: spr.tokens_path.clone(),
+            pools: spr.route_simulations.iter().map(|route| route.pool_address.clone()).collect(),
+            amount_in: spr.amount_in,
+            expected_profit: spr.result,
+            executed,
+            skip_reason,
+        },
+    });
+}
+
+pub fn record_skipped(strategy: &str, spr: &SwapPathResult, reason: SkipReason) {
+    record_opportunity(strategy, spr, false, Some(reason));
+}
@@ -1,16 +1,79 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::future::Future;
 
-use log::info;
-use log::error;
-use rust_socketio::asynchronous::Client;
+use log::{error, info, Level};
+use rayon::prelude::*;
 
+use crate::common::log_sampling::SAMPLED_LOG_INTERVAL;
+use crate::markets::errors::QuoteError;
+use crate::markets::local_quote::{quote_local, quote_local_in, quoted_accounts};
+use crate::markets::pool_cache::{pool_cache, AccountSource};
+use crate::{log_every, log_every_n};
 use crate::markets::meteora::simulate_route_meteora;
 use crate::markets::{orca_whirpools::simulate_route_orca_whirpools, raydium::simulate_route_raydium, types::{DexLabel, Market}};
-use super::types::{SwapPath, SwapRouteSimulation, TokenInfos};
+use super::types::{Route, SwapPath, SwapPathSelected, SwapRouteSimulation, TokenInfos};
 
-pub async fn simulate_path(simulation_amount: u64, path: SwapPath, markets: Vec<Market>, tokens_infos: HashMap<String, TokenInfos>, mut route_simulation: HashMap<Vec<u32>, Vec<SwapRouteSimulation>>) -> (HashMap<Vec<u32>, Vec<SwapRouteSimulation>>, Vec<SwapRouteSimulation>, f64) {
-    println!("🚕🚕🚕🚕  NEW PATH  🚕🚕🚕🚕");
-    println!("Nb. Hops : {}", path.hops);
+// A quote the simulator could not answer is tried once more, a swap rejected by the pool is final
+async fn retry_quote<F, Fut>(quote: F) -> Result<(String, String), QuoteError>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<(String, String), QuoteError>>,
+{
+    match quote().await {
+        Err(e) if e.is_retryable() => {
+            log_every!(Level::Error, SAMPLED_LOG_INTERVAL, "🔁 Quote retried: {}", e);
+            quote().await
+        }
+        result => result,
+    }
+}
+
+// Local quote from the pool cache, the simulator only answers for the pools without local math
+async fn quote_route<F, Fut>(route: &Route, market: &Market, amount_in: u64, simulator_quote: F) -> Result<(String, String), QuoteError>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<(String, String), QuoteError>>,
+{
+    match quote_local(route, market, amount_in) {
+        Some((amount_out, min_amount_out)) => Ok((amount_out.to_string(), min_amount_out.to_string())),
+        None => retry_quote(simulator_quote).await,
+    }
+}
+
+// Every hop quoted from the accounts without awaiting anything, None as soon as a hop has no local math
+pub fn simulate_path_local(accounts: &impl AccountSource, simulation_amount: u64, path: &SwapPath, markets: &[Market]) -> Option<(Vec<SwapRouteSimulation>, f64)> {
+    let mut amount_in = simulation_amount;
+    let mut swap_simulation_result: Vec<SwapRouteSimulation> = Vec::with_capacity(path.paths.len());
+    for route in path.paths.iter() {
+        let market = markets.iter().find(|market| market.id == route.pool_address)?;
+        let (amount_out, min_amount_out) = quote_local_in(accounts, route, market, amount_in)?;
+        swap_simulation_result.push(SwapRouteSimulation {
+            id_route: route.id,
+            pool_address: route.pool_address.clone(),
+            dex_label: route.dex.clone(),
+            token_0to1: route.token_0to1,
+            token_in: route.tokenIn.clone(),
+            token_out: route.tokenOut.clone(),
+            amount_in,
+            estimated_amount_out: amount_out.to_string(),
+            estimated_min_amount_out: min_amount_out.to_string(),
+        });
+        amount_in = amount_out;
+    }
+    Some((swap_simulation_result, amount_in as f64 - simulation_amount as f64))
+}
+
+// Local quotes of all the paths on the rayon pool: pure CPU work once the pool state is cached.
+// All the paths are priced from one snapshot, a stream update landing meanwhile is seen by none of them
+pub fn simulate_paths_local(simulation_amount: u64, paths: &[SwapPathSelected]) -> Vec<Option<(Vec<SwapRouteSimulation>, f64)>> {
+    let addresses: Vec<Cow<str>> = paths.iter().flat_map(|path| path.markets.iter().flat_map(quoted_accounts)).collect();
+    let snapshot = pool_cache().snapshot(addresses.iter().map(|address| address.as_ref()));
+    paths.par_iter().map(|path| simulate_path_local(&snapshot, simulation_amount, &path.path, &path.markets)).collect()
+}
+
+pub async fn simulate_path(simulation_amount: u64, path: SwapPath, markets: Vec<Market>, tokens_infos: &HashMap<String, TokenInfos>, mut route_simulation: HashMap<Vec<u32>, Vec<SwapRouteSimulation>>) -> (HashMap<Vec<u32>, Vec<SwapRouteSimulation>>, Vec<SwapRouteSimulation>, f64) {
+    log_every_n!(Level::Info, 1000, "🚕 New path, {} hops", path.hops);
     let decimals = 9;
     let mut amount_in = simulation_amount;
     let amount_begin= amount_in;
@@ -25,7 +88,7 @@ pub async fn simulate_path(simulation_amount: u64, path: SwapPath, markets: Vec<
                 if i == 0 && route_simulation.contains_key(&vec![path.id_paths[i]]) {
                     let swap_sim = route_simulation.get(&vec![path.id_paths[i]]).unwrap();
                     amount_in = swap_sim[0].estimated_amount_out.as_str().parse().expect("Bad conversion String to f64");
-                    println!("📌 NO SIMULATION Route Id: {}", swap_sim[0].id_route);
+                    log_every_n!(Level::Info, 1000, "📌 NO SIMULATION Route Id: {}", swap_sim[0].id_route);
                     swap_simulation_result.push(swap_sim[0].clone());
                     continue;
                 }
@@ -34,7 +97,7 @@ pub async fn simulate_path(simulation_amount: u64, path: SwapPath, markets: Vec<
                 if i == 0 && route_simulation.contains_key(&vec![path.id_paths[i]]) {
                     let swap_sim = route_simulation.get(&vec![path.id_paths[i]]).unwrap();
                     amount_in = swap_sim[0].estimated_amount_out.as_str().parse().expect("Bad conversion String to f64");
-                    println!("📌 NO SIMULATION Route 1 Id: {}", swap_sim[0].id_route);
+                    log_every_n!(Level::Info, 1000, "📌 NO SIMULATION Route 1 Id: {}", swap_sim[0].id_route);
                     swap_simulation_result.push(swap_sim[0].clone());
                     continue;
                 }
@@ -42,7 +105,7 @@ pub async fn simulate_path(simulation_amount: u64, path: SwapPath, markets: Vec<
                     if route_simulation.contains_key(&vec![path.id_paths[i - 1], path.id_paths[i]]) {
                         let swap_sim = route_simulation.get(&vec![path.id_paths[i - 1], path.id_paths[i]]).unwrap();
                         amount_in = swap_sim[1].estimated_amount_out.as_str().parse().expect("Bad conversion String to f64");
-                        println!("📌 NO SIMULATION Route 2 Id: {}", swap_sim[1].id_route);
+                        log_every_n!(Level::Info, 1000, "📌 NO SIMULATION Route 2 Id: {}", swap_sim[1].id_route);
                         swap_simulation_result.push(swap_sim[1].clone());
                         continue;
                     }
@@ -58,9 +121,7 @@ pub async fn simulate_path(simulation_amount: u64, path: SwapPath, markets: Vec<
                 println!(" ⚠️⚠️ ONE ORCA POOL ");
             },
             DexLabel::ORCA_WHIRLPOOLS => {
-                println!("🏊 ORCA_WHIRLPOOLS - POOL");
-                println!("Address: {:?}", route.pool_address);
-                match simulate_route_orca_whirpools(true, amount_in, route.clone(), market.unwrap(), tokens_infos.clone()).await {
+                match quote_route(route, market.as_ref().unwrap(), amount_in, || simulate_route_orca_whirpools(false, amount_in, route.clone(), market.clone().unwrap(), tokens_infos)).await {
                     Ok(value) => {
                         let (amount_out, min_amount_out) = value;
                         // println!("Amount out: {}", amount_out);
@@ -92,22 +153,14 @@ pub async fn simulate_path(simulation_amount: u64, path: SwapPath, markets: Vec<
                         amount_in = amount_out.as_str().parse().expect("Bad conversion String to f64");
                     }
                     Err(value) => {
-                        // println!("❌ ERROR HANDLED for route: {:?}", path.id_paths);
-                        error!("❌ ERROR HANDLED for route: {:?}", path.id_paths);
-                        error!("ORCA_WHIRLPOOLS POOL");
-                        error!("Address: {:?}", route.pool_address);
-                        // println!("❌ ERROR {:?}", value);
-                        error!("ERROR {:?}", value);
-                        println!("🔚 Skipped Path");
+                        log_every!(Level::Error, SAMPLED_LOG_INTERVAL, "❌ Path {:?} skipped, ORCA_WHIRLPOOLS pool {} not quoted: {}", path.id_paths, route.pool_address, value);
                         let empty_result: Vec<SwapRouteSimulation> = Vec::new();
                         return (route_simulation, empty_result, 0.0);
                     }
                 }
             },
             DexLabel::RAYDIUM => {
-                println!("🏊 RAYDIUM - POOL");
-                println!("Address: {:?}", route.pool_address);
-                match simulate_route_raydium(true, amount_in, route.clone(), market.unwrap(), tokens_infos.clone()).await {
+                match quote_route(route, market.as_ref().unwrap(), amount_in, || simulate_route_raydium(false, amount_in, route.clone(), market.clone().unwrap(), tokens_infos)).await {
                     Ok(value) => {
                         let (amount_out, min_amount_out) = value;
                         // println!("Amount out: {}", amount_out);
@@ -138,13 +191,7 @@ pub async fn simulate_path(simulation_amount: u64, path: SwapPath, markets: Vec<
                         amount_in = amount_out.as_str().parse().expect("Bad conversion String to f64");
                     }
                     Err(value) => {
-                        // println!("❌ ERROR HANDLED for route: {:?}", path.id_paths);
-                        error!("❌ ERROR HANDLED for route: {:?}", path.id_paths);
-                        error!("RAYDIUM POOL");
-                        error!("Address: {:?}", route.pool_address);
-                        // println!("❌ ERROR {:?}", value);
-                        error!("ERROR {:?}", value);
-                        println!("🔚 Skipped Path");
+                        log_every!(Level::Error, SAMPLED_LOG_INTERVAL, "❌ Path {:?} skipped, RAYDIUM pool {} not quoted: {}", path.id_paths, route.pool_address, value);
                         let empty_result: Vec<SwapRouteSimulation> = Vec::new();
                         return (route_simulation, empty_result, 0.0);
                     }
@@ -156,9 +203,7 @@ pub async fn simulate_path(simulation_amount: u64, path: SwapPath, markets: Vec<
             },
             DexLabel::METEORA => {
                 // println!(" ⚠️⚠️ ONE METEORA POOL ");
-                println!("🏊 METEORA - POOL");
-                println!("Address: {:?}", route.pool_address);
-                match simulate_route_meteora(true, amount_in, route.clone(), market.unwrap(), tokens_infos.clone()).await {
+                match quote_route(route, market.as_ref().unwrap(), amount_in, || simulate_route_meteora(false, amount_in, route.clone(), market.clone().unwrap(), tokens_infos)).await {
                     Ok(value) => {
                         let (amount_out, min_amount_out) = value;
                         // println!("Amount out: {}", amount_out);
@@ -189,13 +234,7 @@ pub async fn simulate_path(simulation_amount: u64, path: SwapPath, markets: Vec<
                         amount_in = amount_out.as_str().parse().expect("Bad conversion String to f64");
                     }
                     Err(value) => {
-                        // println!("❌ ERROR HANDLED for route: {:?}", path.id_paths);
-                        error!("❌ ERROR HANDLED for route: {:?}", path.id_paths);
-                        error!("METEORA POOL");
-                        error!("Address: {:?}", route.pool_address);
-                        // println!("❌ ERROR {:?}", value);
-                        error!("ERROR {:?}", value);
-                        println!("🔚 Skipped Path");
+                        log_every!(Level::Error, SAMPLED_LOG_INTERVAL, "❌ Path {:?} skipped, METEORA pool {} not quoted: {}", path.id_paths, route.pool_address, value);
                         let empty_result: Vec<SwapRouteSimulation> = Vec::new();
                         return (route_simulation, empty_result, 0.0);
                     }
@@ -203,7 +242,7 @@ pub async fn simulate_path(simulation_amount: u64, path: SwapPath, markets: Vec<
             },
         }
     }
-    info!("💵💵 Simulation of Swap Path [Id: {:?}] // Amount In: {} {} // Amount Out: {} {}", path.id_paths, amount_begin as f64 / 10_f64.powf(decimals as f64) , "SOL", amount_in as f64 / 10_f64.powf(decimals as f64), "SOL" );
+    log_every_n!(Level::Info, 100, "💵💵 Simulation of Swap Path [Id: {:?}] // Amount In: {} {} // Amount Out: {} {}", path.id_paths, amount_begin as f64 / 10_f64.powf(decimals as f64) , "SOL", amount_in as f64 / 10_f64.powf(decimals as f64), "SOL" );
 
     //If interesting path
     let difference = amount_in as f64 - amount_begin as f64;
@@ -214,7 +253,7 @@ pub async fn simulate_path(simulation_amount: u64, path: SwapPath, markets: Vec<
     return (route_simulation, swap_simulation_result, difference);
 }
 
-pub async fn simulate_path_precision(amount_input: u64, socket: Client, path: SwapPath, markets: Vec<Market>, tokens_infos: HashMap<String, TokenInfos>) -> (Vec<SwapRouteSimulation>, f64) {
+pub async fn simulate_path_precision(amount_input: u64, path: SwapPath, markets: Vec<Market>, tokens_infos: &HashMap<String, TokenInfos>) -> (Vec<SwapRouteSimulation>, f64) {
     // println!("🚕🚕🚕🚕     NEW PRECISION PATH    🚕🚕🚕🚕");
     // println!("Nb. Hops : {}", path.hops);
 
@@ -234,7 +273,7 @@ pub async fn simulate_path_precision(amount_input: u64, socket: Client, path: Sw
             DexLabel::ORCA_WHIRLPOOLS => {
                 // println!("ORCA_WHIRLPOOLS - POOL");
                 // println!("Address: {:?}", route.pool_address);
-                match simulate_route_orca_whirpools(false, amount_in, route.clone(), market.unwrap(), tokens_infos.clone()).await {
+                match quote_route(route, market.as_ref().unwrap(), amount_in, || simulate_route_orca_whirpools(false, amount_in, route.clone(), market.clone().unwrap(), tokens_infos)).await {
                     Ok(value) => {
                         let (amount_out, min_amount_out) = value;
                         // println!("Amount out: {}", amount_out);
@@ -270,7 +309,7 @@ pub async fn simulate_path_precision(amount_input: u64, socket: Client, path: Sw
             DexLabel::RAYDIUM => {
                 // println!("RAYDIUM - POOL");
                 // println!("Address: {:?}", route.pool_address);
-                match simulate_route_raydium(false, amount_in, route.clone(), market.unwrap(), tokens_infos.clone()).await {
+                match quote_route(route, market.as_ref().unwrap(), amount_in, || simulate_route_raydium(false, amount_in, route.clone(), market.clone().unwrap(), tokens_infos)).await {
                     Ok(value) => {
                         let (amount_out, min_amount_out) = value;
                         // println!("Amount out: {}", amount_out);
@@ -311,7 +350,7 @@ pub async fn simulate_path_precision(amount_input: u64, socket: Client, path: Sw
                 // println!(" ⚠️⚠️ ONE METEORA POOL ");
                 // println!("METEORA - POOL");
                 // println!("Address: {:?}", route.pool_address);
-                match simulate_route_meteora(false, amount_in, route.clone(), market.unwrap(), tokens_infos.clone()).await {
+                match quote_route(route, market.as_ref().unwrap(), amount_in, || simulate_route_meteora(false, amount_in, route.clone(), market.clone().unwrap(), tokens_infos)).await {
                     Ok(value) => {
                         let (amount_out, min_amount_out) = value;
                         // println!("Amount out: {}", amount_out);
@@ -1,34 +1,75 @@
-use std::{collections::HashMap, fs::{File, OpenOptions}, thread::sleep, time::{self, SystemTime}};
+use std::{collections::{HashMap, HashSet}, fs::File, sync::Arc, time::{self, Duration, Instant, SystemTime}};
+use async_trait::async_trait;
+use serde::Serialize;
 use borsh::error;
 use chrono::{Datelike, Utc};
 use indicatif::{ProgressBar, ProgressStyle};
 use itertools::enumerate;
 use mongodb::bson::doc;
-use rust_socketio::{asynchronous::{Client}};
 use solana_sdk::pubkey::Pubkey;
-use std::io::{BufWriter, Write};
 use crate::{arbitrage::{
-    calc_arb::{calculate_arb, get_markets_arb}, simulate::simulate_path, streams::get_fresh_accounts_states, types::{SwapPathResult, SwapPathSelected, SwapRouteSimulation, VecSwapPathResult, VecSwapPathSelected}
+    calc_arb::{calculate_arb, get_markets_arb, paths_through_targets}, path_files::{paths_file_name, read_paths, write_paths}, simulate::{simulate_path, simulate_paths_local}, streams::{get_fresh_accounts_states, last_pool_refresh, PoolRefresher}, types::{with_bridge_tokens, SwapPathResult, SwapPathSelected, SwapRouteSimulation, VecSwapPathResult, VecSwapPathSelected}
 }, common::{database::{insert_vec_swap_path_selected_collection, insert_swap_path_result_collection}, utils::{from_str, write_file_swap_path_result}}, transactions::create_transaction::{self, create_and_send_swap_transaction, create_ata_extendlut_transaction, ChainType, SendOrSimulate}};
-use crate::markets::types::{Dex,Market};
+use crate::markets::types::Market;
+use crate::transactions::warmup::{warm_up_paths, WarmupConfig, WARMUP_RETRY_INTERVAL};
+use crate::common::log_sampling::SAMPLED_LOG_INTERVAL;
+use crate::common::compute::compute_pool;
+use crate::common::tasks::spawn_named_in;
+use crate::log_every;
+use crate::monitoring::events::{publish, BotEvent};
+use crate::monitoring::latency::{Stage, StageTimestamps};
+use crate::monitoring::metrics::metrics;
+use crate::risk::authority_monitor::AuthorityMonitor;
+use crate::risk::honeypot::HoneypotDetector;
+use crate::risk::sanity::{refetch_markets, SanityBounds};
+use crate::risk::safety::TokenSafetyChecker;
+use crate::transactions::templates::instruction_templates;
+use crate::transactions::presigned::presigned_transactions;
+use crate::markets::state::MarketState;
+use crate::common::{circuit_breaker::quote_breaker, types::InputVec, utils::get_tokens_infos};
+use crate::storage::query::{BEST_PATHS_COLLECTION, SWAP_PATH_RESULTS_COLLECTION};
+use super::research::{record_opportunity, record_skipped, SkipReason};
+use super::cooldown::PathCooldowns;
+use super::opportunity::{Opportunity, OpportunityQueue};
+use super::live_ranking::LiveRanking;
+use super::ranking::{path_key, PathRanking};
+use super::token_health::TokenHealth;
+use super::presets::{PresetParams, StrategyPreset};
+use super::registry::{Strategy, StrategyEvent, TickOutcome};
 use super::{simulate::simulate_path_precision, types::{SwapPath, TokenInArb, TokenInfos}};
-use log::{debug, error, info};
-use anyhow::Result;
+use log::{debug, error, info, warn, Level};
+use anyhow::{anyhow, Result};
 
 use tokio::net::TcpStream;
+use tokio::sync::{RwLock, Semaphore};
+use tokio::task::JoinSet;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
-pub async fn run_arbitrage_strategy(simulation_amount: u64, get_fresh_pools_bool: bool, restrict_sol_usdc: bool, include_1hop: bool, include_2hop: bool, numbers_of_best_paths: usize, dexs: Vec<Dex>, tokens: Vec<TokenInArb>, tokens_infos: HashMap<String, TokenInfos>) -> Result<(String, VecSwapPathSelected)> {
+// Amounts in (lamports of a 9 decimals token) a path is sized on, the most profitable one is kept
+pub static PRECISION_AMOUNTS: [u64; 5] = [500_000_000, 1_000_000_000, 5_000_000_000, 10_000_000_000, 20_000_000_000];
+// Profit (lamports) from which a quoted path is sent to the executor
+pub static MIN_OPPORTUNITY_PROFIT: f64 = 20_000_000.0;
+// Swaps (signature, pool) already quoted on, forgotten all at once past this
+const OBSERVED_SWAPS_CAPACITY: usize = 4096;
+
+pub async fn run_arbitrage_strategy(preset: StrategyPreset, get_fresh_pools_bool: bool, restrict_sol_usdc: bool, include_1hop: bool, include_2hop: bool, numbers_of_best_paths: usize, market_state: &MarketState, tokens: Vec<TokenInArb>, bridge_tokens: Vec<TokenInArb>, tokens_infos: &HashMap<String, TokenInfos>) -> Result<(String, VecSwapPathSelected)> {
     info!("👀 Run Arbitrage Strategies...");
+    let PresetParams { simulation_amount, min_profit_threshold, .. } = preset.params();
 
-    let markets_arb = get_markets_arb(get_fresh_pools_bool, restrict_sol_usdc, dexs, tokens.clone()).await;
+    // tokens_infos must also contain the bridge tokens
+    let tokens_with_bridges = with_bridge_tokens(&tokens, &bridge_tokens);
+    let markets_arb = get_markets_arb(get_fresh_pools_bool, restrict_sol_usdc, market_state, tokens_with_bridges.clone()).await;
 
     // println!("DEBUG {:?}", fresh_markets_arb);
     // debug!("DEBUG {:?}", markets_arb.get(&"3s3CzbFzkqLvXYA93M3uHCes2nc4SiuZ11emtpDJwCht".to_string()));
     // debug!("DEBUG {:?}", fresh_markets_arb.get(&"65shmpuYmxx5p7ggNCZbyrGLCXVqbBR1ZD5aAocRBUNG".to_string()));
 
     // Sort markets with low liquidity
-    let (sorted_markets_arb, all_paths) = calculate_arb(include_1hop, include_2hop, markets_arb.clone(), tokens.clone());
+    let (sorted_markets_arb, mut all_paths) = calculate_arb(include_1hop, include_2hop, markets_arb.clone(), tokens_with_bridges.clone());
+    if !bridge_tokens.is_empty() {
+        all_paths = paths_through_targets(all_paths, &tokens);
+        info!("🌉 {} paths through the target tokens with bridges", all_paths.len());
+    }
 
     //Get fresh account state
     let fresh_markets_arb = get_fresh_accounts_states(sorted_markets_arb.clone()).await;  
@@ -66,7 +107,7 @@ pub async fn run_arbitrage_strategy(simulation_amount: u64, get_fresh_pools_bool
             None => {},
             Some(value) => {
                 if value >= &3 {
-                    error!("🔴⏭️  Skip the {:?} path because previous errors", path.id_paths);
+                    log_every!(Level::Error, SAMPLED_LOG_INTERVAL, "🔴⏭️  Skip the {:?} path because previous errors", path.id_paths);
                     bar.inc(1);
                     counter_failed_paths += 1;
                     bar.set_message(format!("❌ Failed routes: {}/{} 💸 Positive routes: {}/{}", counter_failed_paths, bar.position(), counter_positive_paths, bar.position()));
@@ -80,13 +121,13 @@ pub async fn run_arbitrage_strategy(simulation_amount: u64, get_fresh_pools_bool
         let pubkeys: Vec<String> = path.paths.clone().iter().map(|route| route.clone().pool_address).collect();
         let markets: Vec<Market> = pubkeys.iter().filter_map(|key| fresh_markets_arb.get(key)).cloned().collect();
 
-        let (new_route_simulation, swap_simulation_result, result_difference) = simulate_path(simulation_amount, path.clone(), markets.clone(), tokens_infos.clone(), route_simulation.clone()).await;
+        let (new_route_simulation, swap_simulation_result, result_difference) = simulate_path(simulation_amount, path.clone(), markets.clone(), tokens_infos, route_simulation.clone()).await;
         
         //If no error in swap path
         if swap_simulation_result.len() >= path.hops as usize {
             // tokens.iter().map(|token| &token.symbol).cloned().collect::<Vec<String>>().join("-");
 
-            let mut tokens_path = swap_simulation_result.iter().map(|swap_sim| tokens_infos.get(&swap_sim.token_in).unwrap().symbol.clone()).collect::<Vec<String>>().join("-");
+            let mut tokens_path = swap_simulation_result.iter().map(|swap_sim| token_symbol(tokens_infos, &swap_sim.token_in)).collect::<Vec<&str>>().join("-");
             tokens_path = format!("{}-{}",tokens_path, tokens[0].symbol.clone());
 
             let sp_result: SwapPathResult = SwapPathResult{ 
@@ -105,7 +146,7 @@ pub async fn run_arbitrage_strategy(simulation_amount: u64, get_fresh_pools_bool
             };
             swap_paths_results.result.push(sp_result.clone());
 
-            if result_difference > 20000000.0 {
+            if result_difference > min_profit_threshold {
                 println!("💸💸💸💸💸💸💸💸💸 Begin Execute the tx 💸💸💸💸💸💸💸💸💸");
                 info!("💸💸💸💸💸💸💸💸💸 Send transaction execution... 💸💸💸💸💸💸💸💸💸");
                 
@@ -113,7 +154,7 @@ pub async fn run_arbitrage_strategy(simulation_amount: u64, get_fresh_pools_bool
                 let date = format!("{}-{}-{}", now.day(), now.month(), now.year());
 
                 let path = format!("optimism_transactions/{}-{}-{}.json", date, tokens_path.clone(), counter_sp_result);
-                let _ = insert_swap_path_result_collection("optimism_transactions", sp_result.clone()).await;  
+                let _ = insert_swap_path_result_collection(SWAP_PATH_RESULTS_COLLECTION, sp_result.clone()).await;  
                 let _ = write_file_swap_path_result(path.clone(), sp_result);
                 counter_sp_result += 1;
                 
@@ -134,7 +175,7 @@ pub async fn run_arbitrage_strategy(simulation_amount: u64, get_fresh_pools_bool
 
             //Custom Queue FIFO for best results
             if best_paths_for_strat.len() < numbers_of_best_paths {
-                best_paths_for_strat.push(SwapPathSelected{result: result_difference, path: path.clone(), markets: markets});
+                best_paths_for_strat.push(SwapPathSelected{result: result_difference, path: path.clone(), markets: markets, preset});
                 if best_paths_for_strat.len() == numbers_of_best_paths {
                     best_paths_for_strat.sort_by(|a, b| b.result.partial_cmp(&a.result).unwrap());
                 }
@@ -143,7 +184,7 @@ pub async fn run_arbitrage_strategy(simulation_amount: u64, get_fresh_pools_bool
                     if result_difference < path_in_vec.result {
                         continue;
                     } else {
-                        best_paths_for_strat[index] = SwapPathSelected{result: result_difference, path: path.clone(), markets: markets};
+                        best_paths_for_strat[index] = SwapPathSelected{result: result_difference, path: path.clone(), markets: markets, preset};
                         break;
                     }
                 }
@@ -159,7 +200,7 @@ pub async fn run_arbitrage_strategy(simulation_amount: u64, get_fresh_pools_bool
                 counter_positive_paths += 1;
                 bar.set_message(format!("❌ Failed routes: {}/{} 💸 Positive routes: {}/{}", counter_failed_paths, bar.position(), counter_positive_paths, bar.position()));
 
-                // precision_strategy(socket.clone(), path.clone(), markets, tokens.clone(), tokens_infos.clone()).await;
+                // precision_strategy(path.clone(), markets, tokens.clone(), tokens_infos).await;
             }
         } else {
             counter_failed_paths += 1;
@@ -217,49 +258,35 @@ pub async fn run_arbitrage_strategy(simulation_amount: u64, get_fresh_pools_bool
     }
 
     
-    let mut path = format!("best_paths_selected/{}.json", tokens_list);
-    File::create(path.clone());
-    
-    let file = OpenOptions::new().read(true).write(true).open(path.clone())?;
-    let mut writer = BufWriter::new(&file);
+    let mut path = paths_file_name("best_paths_selected", &tokens_list);
     
     let mut content = VecSwapPathSelected{value: best_paths_for_strat.clone()};
-    writer.write_all(serde_json::to_string(&content)?.as_bytes())?;
-    writer.flush()?;
+    write_paths(&path, &content)?;
     info!("Data written to '{}' successfully.", path);
     
-    insert_vec_swap_path_selected_collection("best_paths_selected", content.clone()).await;
+    insert_vec_swap_path_selected_collection(BEST_PATHS_COLLECTION, content.clone()).await;
 
     return_path = path;
     bar.finish();
     return Ok((return_path, VecSwapPathSelected{ value: best_paths_for_strat}));
 }
 
-pub async fn precision_strategy(socket: Client, path: SwapPath, markets: Vec<Market>, tokens: Vec<TokenInArb>, tokens_infos: HashMap<String, TokenInfos>) {
+pub async fn precision_strategy(path: SwapPath, markets: Vec<Market>, tokens: Vec<TokenInArb>, tokens_infos: &HashMap<String, TokenInfos>) {
 
     info!("🔎🔎 Run a Precision SImulation on Path Id: {:?}", path.id_paths);
 
     let mut swap_paths_results: VecSwapPathResult = VecSwapPathResult{result: Vec::new()};
 
-    let decimals = 9;
-    let amounts_simulations = vec![
-        5 * 10_u64.pow(decimals - 1),
-        1 * 10_u64.pow(decimals),
-        // 2 * 10_u64.pow(decimals),
-        // 3 * 10_u64.pow(decimals),
-        5 * 10_u64.pow(decimals),
-        10 * 10_u64.pow(decimals),
-        20 * 10_u64.pow(decimals)
-    ];
-    
+    let amounts_simulations = PRECISION_AMOUNTS;
+
     let mut result_amt = 0.0;
     let mut sp_to_tx: Option<SwapPathResult> = None;
 
     for (index, amount_in) in amounts_simulations.iter().enumerate() {
-        let (swap_simulation_result, result_difference) = simulate_path_precision(amount_in.clone(), socket.clone(), path.clone(), markets.clone(), tokens_infos.clone()).await;
+        let (swap_simulation_result, result_difference) = simulate_path_precision(amount_in.clone(), path.clone(), markets.clone(), tokens_infos).await;
 
         if swap_simulation_result.len() >= path.hops as usize {
-            let mut tokens_path = swap_simulation_result.iter().map(|swap_sim| tokens_infos.get(&swap_sim.token_in).unwrap().symbol.clone()).collect::<Vec<String>>().join("-");
+            let mut tokens_path = swap_simulation_result.iter().map(|swap_sim| token_symbol(tokens_infos, &swap_sim.token_in)).collect::<Vec<&str>>().join("-");
             tokens_path = format!("{}-{}",tokens_path, tokens[0].symbol.clone());
 
             let sp_result: SwapPathResult = SwapPathResult{ 
@@ -296,99 +323,912 @@ pub async fn precision_strategy(socket: Client, path: SwapPath, markets: Vec<Mar
 
 pub async fn sorted_interesting_path_strategy(simulation_amount: u64, path:String, tokens: Vec<TokenInArb>, tokens_infos: HashMap<String, TokenInfos>) -> Result<()>{
 
-    let file_read = OpenOptions::new().read(true).write(true).open(path)?;
-    let mut paths_vec: VecSwapPathSelected = serde_json::from_reader(&file_read).unwrap();
+    let paths_vec: VecSwapPathSelected = read_paths(&path)?;
     let mut counter_sp_result = 0;
 
-    let paths: Vec<SwapPathSelected> = paths_vec.value;
-    let mut route_simulation: HashMap<Vec<u32>, Vec<SwapRouteSimulation>> = HashMap::new();
-    let tokens_for_tx: Vec<Pubkey> = tokens.iter().map(|tk| from_str(&tk.address).unwrap()).collect();
     loop {
-        for (index, path) in paths.iter().enumerate() {
-            let (new_route_simulation, swap_simulation_result, result_difference) = simulate_path(simulation_amount, path.path.clone(), path.markets.clone(), tokens_infos.clone(), route_simulation.clone()).await;
-            //If no error in swap path
-            if swap_simulation_result.len() >= path.path.hops as usize {
-                // tokens.iter().map(|token| &token.symbol).cloned().collect::<Vec<String>>().join("-");
-        
-                let mut tokens_path = swap_simulation_result.iter().map(|swap_sim| tokens_infos.get(&swap_sim.token_in).unwrap().symbol.clone()).collect::<Vec<String>>().join("-");
-                tokens_path = format!("{}-{}",tokens_path, tokens[0].symbol.clone());
-        
-                let sp_result: SwapPathResult = SwapPathResult{ 
-                    path_id: index as u32, 
-                    hops: path.path.hops,
-                    tokens_path: tokens_path.clone(), 
-                    route_simulations: swap_simulation_result.clone(), 
-                    token_in: tokens[0].address.clone(), 
-                    token_in_symbol: tokens[0].symbol.clone(), 
-                    token_out: tokens[0].address.clone(), 
-                    token_out_symbol: tokens[0].symbol.clone(), 
-                    amount_in: swap_simulation_result[0].amount_in.clone(), 
-                    estimated_amount_out: swap_simulation_result[swap_simulation_result.len() - 1].estimated_amount_out.clone(), 
-                    estimated_min_amount_out: swap_simulation_result[swap_simulation_result.len() - 1].estimated_min_amount_out.clone(), 
-                    result: result_difference
-                };
-                
-                if result_difference > 20000000.0 {
-                    println!("💸💸💸💸💸💸💸💸💸 Begin Execute the tx 💸💸💸💸💸💸💸💸💸");
-                    info!("💸💸💸💸💸💸💸💸💸 Send transaction execution... 💸💸💸💸💸💸💸💸💸");
-                    // let _ = create_ata_extendlut_transaction(
-                        //     ChainType::Mainnet,
-                        //     SendOrSimulate::Send,
-                    //     sp_result.clone(),
-                    //     from_str("6nGymM5X1djYERKZtoZ3Yz3thChMVF6jVRDzhhcmxuee").unwrap(),
-                    //     tokens_for_tx.clone()
-                    // ).await;
-                    // let _ = create_and_send_swap_transaction(
+        sorted_interesting_path_pass("best_paths", simulation_amount, MIN_OPPORTUNITY_PROFIT, &paths_vec.value, &tokens, &tokens_infos, &mut counter_sp_result, None, &SanityBounds::default()).await?;
+    }
+    // Ok(())
+
+}
+
+// Paths and distinct pools of a newly loaded path set, for the dashboard
+fn paths_loaded(strategy: &str, paths: &[SwapPathSelected]) -> BotEvent {
+    let mut pools: Vec<String> = paths.iter().flat_map(|path| path.path.paths.iter().map(|route| route.pool_address.clone())).collect();
+    pools.sort();
+    pools.dedup();
+    BotEvent::PathsLoaded { strategy: strategy.to_string(), paths: paths.len(), pools }
+}
+
+// Symbol of the token, its mint when tokens_infos doesn't know it (discovered or imported tokens)
+fn token_symbol<'a>(tokens_infos: &'a HashMap<String, TokenInfos>, mint: &'a str) -> &'a str {
+    tokens_infos.get(mint).map(|token| token.symbol.as_str()).unwrap_or(mint)
+}
+
+// Simulated path as sent to the executor, None when a hop failed to quote
+// Takes the simulations over, the symbols are written in one buffer sized upfront
+fn path_result(index: usize, path: &SwapPathSelected, swap_simulation_result: Vec<SwapRouteSimulation>, tokens: &[TokenInArb], tokens_infos: &HashMap<String, TokenInfos>, result_difference: f64) -> Option<SwapPathResult> {
+    if swap_simulation_result.is_empty() || swap_simulation_result.len() < path.path.hops as usize {
+        return None;
+    }
+    let symbols = swap_simulation_result.iter().map(|swap_sim| token_symbol(tokens_infos, &swap_sim.token_in)).chain(std::iter::once(tokens[0].symbol.as_str()));
+    let mut tokens_path = String::with_capacity(symbols.clone().map(|symbol| symbol.len() + 1).sum());
+    for symbol in symbols {
+        if !tokens_path.is_empty() {
+            tokens_path.push('-');
+        }
+        tokens_path.push_str(symbol);
+    }
+    let last = &swap_simulation_result[swap_simulation_result.len() - 1];
+    Some(SwapPathResult {
+        path_id: index as u32,
+        hops: path.path.hops,
+        tokens_path,
+        token_in: tokens[0].address.clone(),
+        token_in_symbol: tokens[0].symbol.clone(),
+        token_out: tokens[0].address.clone(),
+        token_out_symbol: tokens[0].symbol.clone(),
+        amount_in: swap_simulation_result[0].amount_in,
+        estimated_amount_out: last.estimated_amount_out.clone(),
+        estimated_min_amount_out: last.estimated_min_amount_out.clone(),
+        route_simulations: swap_simulation_result,
+        result: result_difference,
+    })
+}
+
+// One simulation pass over the selected paths, sending the interesting ones to the executor under the strategy name
+pub async fn sorted_interesting_path_pass(strategy: &str, simulation_amount: u64, min_profit_threshold: f64, paths: &[SwapPathSelected], tokens: &[TokenInArb], tokens_infos: &HashMap<String, TokenInfos>, counter_sp_result: &mut u32, queue: Option<&OpportunityQueue>, sanity_bounds: &SanityBounds) -> Result<()> {
+    let route_simulation: HashMap<Vec<u32>, Vec<SwapRouteSimulation>> = HashMap::new();
+    if quote_breaker().is_halted() {
+        debug!("🔌 No healthy RPC endpoint, quoting paused");
+        return Ok(());
+    }
+
+    // Paths quotable from the pool cache are all quoted at once in parallel on the compute pool, the others go through the simulator
+    let local_quotes = compute_pool().run(|| simulate_paths_local(simulation_amount, paths)).await;
+
+    // The local quotes are moved out rather than cloned
+    for (index, (path, local_quote)) in paths.iter().zip(local_quotes).enumerate() {
+        let quote_started_at = Instant::now();
+        let mut stages = StageTimestamps::new(last_pool_refresh().unwrap_or(quote_started_at));
+        let (mut new_route_simulation, mut swap_simulation_result, mut result_difference) = match local_quote {
+            Some((swap_simulation_result, result_difference)) => (HashMap::new(), swap_simulation_result, result_difference),
+            None => simulate_path(simulation_amount, path.path.clone(), path.markets.clone(), tokens_infos, route_simulation.clone()).await,
+        };
+        metrics().quotes.inc();
+        metrics().quote_latency.observe(quote_started_at.elapsed().as_secs_f64());
+        stages.mark(Stage::Quote);
+        // Too good to be true: quote again on pool data fresh from the chain, and drop the path if it still is.
+        // The label is only built for the paths checked again
+        if !sanity_bounds.is_plausible(simulation_amount, result_difference) {
+            let path_label = path.path.paths.iter().map(|route| route.pool_address.as_str()).collect::<Vec<&str>>().join("/");
+            sanity_bounds.check(&path_label, simulation_amount, result_difference);
+            let fresh_markets = refetch_markets(&path.markets).await;
+            stages = StageTimestamps::new(last_pool_refresh().unwrap_or(quote_started_at));
+            (new_route_simulation, swap_simulation_result, result_difference) = simulate_path(simulation_amount, path.path.clone(), fresh_markets, tokens_infos, route_simulation.clone()).await;
+            stages.mark(Stage::Quote);
+            if !sanity_bounds.check(&path_label, simulation_amount, result_difference) {
+                warn!("🧪 Quote on {} rejected after refetching its pools", path_label);
+                if let Some(spr) = path_result(index, path, swap_simulation_result, tokens, tokens_infos, result_difference) {
+                    record_skipped(strategy, &spr, SkipReason::SanityRejected);
+                }
+                continue;
+            }
+        }
+        //If no error in swap path
+        if let Some(sp_result) = path_result(index, path, swap_simulation_result, tokens, tokens_infos, result_difference) {
+            // A hop far from the oracle price comes from stale pool data
+            if !sanity_bounds.check_oracle(&sp_result, tokens_infos) {
+                record_skipped(strategy, &sp_result, SkipReason::SanityRejected);
+                continue;
+            }
+            if result_difference > min_profit_threshold {
+                metrics().opportunities_found.inc();
+                publish(BotEvent::OpportunityFound {
+                    strategy: strategy.to_string(),
+                    tokens_path: sp_result.tokens_path.clone(),
+                    pools: sp_result.route_simulations.iter().map(|route| route.pool_address.clone()).collect(),
+                    expected_profit: result_difference,
+                });
+                // Signed ahead at every tip level and re-signed on each blockhash while the quote holds
+                if let Some(presigned) = presigned_transactions() {
+                    presigned.prepare_in_background(&sp_result);
+                }
+                // Executor pool running: let it pick the best opportunity
+                if let Some(queue) = queue {
+                    queue.push(Opportunity::new(strategy, sp_result, stages));
+                    continue;
+                }
+                println!("💸💸💸💸💸💸💸💸💸 Begin Execute the tx 💸💸💸💸💸💸💸💸💸");
+                info!("💸💸💸💸💸💸💸💸💸 Send transaction execution... 💸💸💸💸💸💸💸💸💸");
+                // let _ = create_ata_extendlut_transaction(
+                    //     ChainType::Mainnet,
                     //     SendOrSimulate::Send,
-                    //     ChainType::Mainnet, 
-                    //     sp_result.clone()
-                    // ).await;
-                    
-                    let now = Utc::now();
-                    let date = format!("{}-{}-{}", now.day(), now.month(), now.year());
-
-                    let path = format!("optimism_transactions/{}-{}-{}.json", date, tokens_path, counter_sp_result);
-                    let _ = write_file_swap_path_result(path.clone(), sp_result);
-                    counter_sp_result += 1;
-                    
-                    //Send message to Rust execution program
-                    let mut stream = TcpStream::connect("127.0.0.1:8080").await?;
-    
-                    let message = path.as_bytes();
-                    stream.write_all(message).await?;
-                    info!("🛜  Sent: {} tx to executor", String::from_utf8_lossy(message));
-                    // let mut buffer = [0; 512];
-                    // let n = stream.read(&mut buffer).await?;
-                    // info!("Received: {}", String::from_utf8_lossy(&buffer[0..n]));
+                //     sp_result.clone(),
+                //     from_str("6nGymM5X1djYERKZtoZ3Yz3thChMVF6jVRDzhhcmxuee").unwrap(),
+                //     tokens_for_tx.clone()
+                // ).await;
+                // let _ = create_and_send_swap_transaction(
+                //     SendOrSimulate::Send,
+                //     ChainType::Mainnet, 
+                //     sp_result.clone()
+                // ).await;
+                
+                let now = Utc::now();
+                let date = format!("{}-{}-{}", now.day(), now.month(), now.year());
+
+                let path = format!("optimism_transactions/{}-{}-{}.json", date, sp_result.tokens_path, counter_sp_result);
+                record_opportunity(strategy, &sp_result, true, None);
+                let _ = write_file_swap_path_result(path.clone(), sp_result);
+                *counter_sp_result += 1;
+                
+                //Send message to Rust execution program
+                let mut stream = TcpStream::connect("127.0.0.1:8080").await?;
+
+                let message = path.as_bytes();
+                stream.write_all(message).await?;
+                info!("🛜  Sent: {} tx to executor", String::from_utf8_lossy(message));
+                // let mut buffer = [0; 512];
+                // let n = stream.read(&mut buffer).await?;
+                // info!("Received: {}", String::from_utf8_lossy(&buffer[0..n]));
+            } else {
+                record_skipped(strategy, &sp_result, SkipReason::BelowThreshold);
+            }
+        }
+        tokio::time::sleep(time::Duration::from_millis(200)).await;
+    }
+    Ok(())
+}
+
+// Optimism transaction files of a directory in name order, or the file itself
+pub fn optimism_files(path: &str) -> Result<Vec<String>> {
+    if !std::path::Path::new(path).is_dir() {
+        return Ok(vec![path.to_string()]);
+    }
+    let mut files: Vec<String> = Vec::new();
+    for entry in std::fs::read_dir(path)? {
+        let entry_path = entry?.path();
+        if entry_path.extension().map(|ext| ext == "json").unwrap_or(false) {
+            files.push(entry_path.to_string_lossy().to_string());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// Push an optimism transaction to the executor pool: it goes through the same risk gates, LIVE_TRADING mode
+// and PnL accounting as the live opportunities
+pub fn optimism_tx_strategy(path: &str, queue: &OpportunityQueue) -> Result<()> {
+    let spr: SwapPathResult = serde_json::from_reader(File::open(path)?)?;
+    info!("💸 Optimism tx {} pushed to the executor pool", path);
+    queue.push(Opportunity::new("optimism", spr, StageTimestamps::new(Instant::now())));
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UltraStrategySource {
+    pub file: String,
+    pub paths_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UltraStrategyManifest {
+    pub created_at: String,
+    pub sources: Vec<UltraStrategySource>,
+    pub paths_before_dedup: usize,
+    pub paths_kept: usize,
+}
+
+// Merge the best paths files of several token groups into one ultra strategy file:
+// duplicated paths are dropped, the rest is ranked by expected profit and capped to max_paths within each preset,
+// the results of two presets being quoted at different sizes
+pub async fn merge_ultra_strategy(vec_best_paths: &[String], max_paths: usize) -> Result<String> {
+    let mut all_paths: Vec<SwapPathSelected> = Vec::new();
+    let mut sources: Vec<UltraStrategySource> = Vec::new();
+    let mut name_parts: Vec<String> = Vec::new();
+    for (index, path) in vec_best_paths.iter().enumerate() {
+        let file_stem = std::path::Path::new(path).file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        name_parts.push(format!("{}-{}", index, file_stem));
+
+        let paths_vec: VecSwapPathSelected = read_paths(path)?;
+        sources.push(UltraStrategySource { file: path.clone(), paths_count: paths_vec.value.len() });
+        all_paths.extend(paths_vec.value);
+    }
+    let paths_before_dedup = all_paths.len();
+
+    // Same pools in the same order and same preset: keep the best simulated result
+    let mut best_by_key: HashMap<(StrategyPreset, String), SwapPathSelected> = HashMap::new();
+    for path in all_paths {
+        let key = (path.preset, path_key(&path.path));
+        match best_by_key.get(&key) {
+            Some(existing) if existing.result >= path.result => {}
+            _ => {
+                best_by_key.insert(key, path);
+            }
+        }
+    }
+    let mut vec_to_ultra_strategy: Vec<SwapPathSelected> = best_by_key.into_values().collect();
+    vec_to_ultra_strategy.sort_by(|a, b| a.preset.name().cmp(b.preset.name()).then(b.result.total_cmp(&a.result)));
+    let mut kept_by_preset: HashMap<StrategyPreset, usize> = HashMap::new();
+    vec_to_ultra_strategy.retain(|path| {
+        let kept = kept_by_preset.entry(path.preset).or_default();
+        *kept += 1;
+        *kept <= max_paths
+    });
+    info!("🧬 Ultra strategy: {} paths merged, {} kept", paths_before_dedup, vec_to_ultra_strategy.len());
+
+    let ultra_strategy_name = name_parts.join("-");
+    let path = paths_file_name("best_paths_selected/ultra_strategies", &ultra_strategy_name);
+    let content = VecSwapPathSelected { value: vec_to_ultra_strategy };
+    write_paths(&path, &content)?;
+    info!("Written to {}", path);
+
+    let manifest = UltraStrategyManifest {
+        created_at: Utc::now().to_rfc3339(),
+        sources,
+        paths_before_dedup,
+        paths_kept: content.value.len(),
+    };
+    let manifest_path = format!("best_paths_selected/ultra_strategies/{}.manifest.json", ultra_strategy_name);
+    serde_json::to_writer_pretty(File::create(&manifest_path)?, &manifest)?;
+    info!("Written to {}", manifest_path);
+
+    insert_vec_swap_path_selected_collection("ultra_strategies", content).await?;
+    Ok(path)
+}
+
+// ::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::
+// :::::::::::::::::::::::::::::::::::::               STRATEGY IMPLEMENTATIONS        ::::::::::::::::::::::::::::::::::::::::::::
+// ::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::::
+
+// Run the massive simulation on every InputVec, then publish the resulting best paths file
+pub struct MassiveStrategy {
+    pub inputs: Vec<InputVec>,
+    pub fetch_new_pools: bool,
+    pub restrict_sol_usdc: bool,
+    pub best_paths_file: Arc<RwLock<Option<String>>>,
+    // Max token groups simulated at the same time, each one hammering the RPC
+    pub max_concurrent_inputs: usize,
+    pub max_ultra_paths: usize,
+    // Token groups found by the discovery module, added to `inputs` on every run
+    pub discovered_inputs: Option<Arc<RwLock<Vec<InputVec>>>>,
+    pub token_health: Option<Arc<TokenHealth>>,
+    pub safety_checker: Option<Arc<TokenSafetyChecker>>,
+    // Re-run the simulation on the same tokens to refresh the best paths, once if None
+    pub refresh_interval: Option<Duration>,
+    last_run: Option<Instant>,
+    // Shared with the other strategies, loaded on init
+    market_state: Arc<MarketState>,
+}
+
+impl MassiveStrategy {
+    pub fn new(inputs: Vec<InputVec>, fetch_new_pools: bool, restrict_sol_usdc: bool, best_paths_file: Arc<RwLock<Option<String>>>) -> Self {
+        MassiveStrategy {
+            inputs,
+            fetch_new_pools,
+            restrict_sol_usdc,
+            best_paths_file,
+            max_concurrent_inputs: 2,
+            max_ultra_paths: 16,
+            discovered_inputs: None,
+            token_health: None,
+            safety_checker: None,
+            refresh_interval: None,
+            last_run: None,
+            market_state: Arc::new(MarketState::new()),
+        }
+    }
+
+    // Token groups containing a token failing the safety checks are skipped
+    pub fn with_safety_checker(mut self, safety_checker: Arc<TokenSafetyChecker>) -> Self {
+        self.safety_checker = Some(safety_checker);
+        self
+    }
+
+    pub fn with_refresh_interval(mut self, refresh_interval: Duration) -> Self {
+        self.refresh_interval = Some(refresh_interval);
+        self
+    }
+
+    pub fn with_market_state(mut self, market_state: Arc<MarketState>) -> Self {
+        self.market_state = market_state;
+        self
+    }
+
+    pub fn with_max_concurrent_inputs(mut self, max_concurrent_inputs: usize) -> Self {
+        self.max_concurrent_inputs = max_concurrent_inputs.max(1);
+        self
+    }
+
+    pub fn with_discovered_inputs(mut self, discovered_inputs: Arc<RwLock<Vec<InputVec>>>) -> Self {
+        self.discovered_inputs = Some(discovered_inputs);
+        self
+    }
+
+    // Token groups containing a quarantined token are skipped
+    pub fn with_token_health(mut self, token_health: Arc<TokenHealth>) -> Self {
+        self.token_health = Some(token_health);
+        self
+    }
+}
+
+#[async_trait]
+impl Strategy for MassiveStrategy {
+    fn name(&self) -> String {
+        "massive".to_string()
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        self.market_state.ensure_loaded(self.fetch_new_pools).await;
+        Ok(())
+    }
+
+    async fn on_event(&mut self, _event: StrategyEvent) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_tick(&mut self) -> Result<TickOutcome> {
+        if let (Some(last_run), Some(refresh_interval)) = (self.last_run, self.refresh_interval) {
+            if last_run.elapsed() < refresh_interval {
+                return Ok(TickOutcome::Continue);
+            }
+            info!("♻️  Refreshing best paths...");
+        }
+        self.last_run = Some(Instant::now());
+        let done = if self.refresh_interval.is_some() { TickOutcome::Continue } else { TickOutcome::Done };
+
+        let mut inputs = self.inputs.clone();
+        if let Some(discovered_inputs) = &self.discovered_inputs {
+            inputs.extend(discovered_inputs.read().await.iter().cloned());
+        }
+        if let Some(token_health) = &self.token_health {
+            inputs.retain(|input| input.tokens_to_arb.iter().skip(1).all(|token| token_health.is_active(&token.address)));
+        }
+        if let Some(safety_checker) = &self.safety_checker {
+            let mut safe_inputs: Vec<InputVec> = Vec::new();
+            for input in inputs {
+                let mut safe = true;
+                let base = input.tokens_to_arb.first().and_then(|token| from_str(&token.address).ok());
+                for token in input.tokens_to_arb.iter().skip(1) {
+                    // The pools the token would be traded on against the base token
+                    let pools = match (base, from_str(&token.address).ok()) {
+                        (Some(base), Some(mint)) => self.market_state.markets_within(&HashSet::from([base, mint])),
+                        _ => Vec::new(),
+                    };
+                    if !safety_checker.is_allowed(&token.address, &pools).await {
+                        safe = false;
+                        break;
+                    }
+                }
+                if safe {
+                    safe_inputs.push(input);
+                }
+            }
+            inputs = safe_inputs;
+        }
+        info!("📈 Starting arbitrage on {} token groups...", inputs.len());
+        let rpc_permits = Arc::new(Semaphore::new(self.max_concurrent_inputs));
+        let mut set: JoinSet<Result<(usize, String)>> = JoinSet::new();
+
+        for (index, input_iter) in inputs.into_iter().enumerate() {
+            let market_state = self.market_state.clone();
+            let rpc_permits = rpc_permits.clone();
+            let restrict_sol_usdc = self.restrict_sol_usdc;
+            spawn_named_in(&mut set, &format!("arbitrage:{}", index), async move {
+                let _permit = rpc_permits.acquire_owned().await?;
+                let tokens_infos = get_tokens_infos(input_iter.all_tokens()).await;
+
+                let (path_for_best_strategy, _) = run_arbitrage_strategy(
+                    input_iter.preset,
+                    input_iter.get_fresh_pools_bool,
+                    restrict_sol_usdc && input_iter.preset.params().restrict_sol_usdc,
+                    input_iter.include_1hop,
+                    input_iter.include_2hop,
+                    input_iter.numbers_of_best_paths,
+                    &market_state,
+                    input_iter.tokens_to_arb.clone(),
+                    input_iter.bridge_tokens.clone(),
+                    &tokens_infos,
+                )
+                .await?;
+                Ok((index, path_for_best_strategy))
+            });
+        }
+
+        let mut results: Vec<(usize, String)> = Vec::new();
+        while let Some(res) = set.join_next().await {
+            match res {
+                Ok(Ok(result)) => results.push(result),
+                Ok(Err(e)) => error!("❌ Token group failed: {:?}", e),
+                Err(e) => error!("❌ Token group task panicked: {:?}", e),
+            }
+        }
+        // Keep the InputVec order for the ultra strategy name
+        results.sort_by_key(|(index, _)| *index);
+        let mut vec_best_paths: Vec<String> = results.into_iter().map(|(_, path)| path).collect();
+
+        let best_paths_path = if vec_best_paths.len() > 1 {
+            merge_ultra_strategy(&vec_best_paths, self.max_ultra_paths).await?
+        } else {
+            match vec_best_paths.pop() {
+                Some(path) => path,
+                None => return Ok(done),
+            }
+        };
+        *self.best_paths_file.write().await = Some(best_paths_path);
+        Ok(done)
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+// Loop over the best paths file published by the massive strategy (or given at startup).
+// The paths of each preset are quoted together, with the sizing and profit threshold of the preset
+pub struct BestPathStrategy {
+    pub preset_params: HashMap<StrategyPreset, PresetParams>,
+    pub tokens: Vec<TokenInArb>,
+    pub best_paths_file: Arc<RwLock<Option<String>>>,
+    pub opportunity_queue: Option<Arc<OpportunityQueue>>,
+    pub path_ranking: Option<Arc<PathRanking>>,
+    pub cooldowns: Option<Arc<PathCooldowns>>,
+    pub token_health: Option<Arc<TokenHealth>>,
+    pub warmup: Option<WarmupConfig>,
+    pub honeypot_detector: Option<Arc<HoneypotDetector>>,
+    pub authority_monitor: Option<Arc<AuthorityMonitor>>,
+    pub pool_refresher: Option<Arc<PoolRefresher>>,
+    // Paths ordered by their live quote, re-scored on every update of one of their pools
+    pub live_ranking: Option<Arc<LiveRanking>>,
+    // Compile the swap instructions of every loaded path set ahead of execution
+    pub instruction_templates: bool,
+    pub sanity_bounds: SanityBounds,
+    // File and modification time of the active path set, a refresh rewrites the same file
+    loaded_file: Option<(String, SystemTime)>,
+    // Set while the warm-up of the active paths is incomplete
+    warmup_retry_at: Option<Instant>,
+    paths: Vec<SwapPathSelected>,
+    tokens_infos: HashMap<String, TokenInfos>,
+    counter_sp_result: u32,
+    // The shredstream and the logs subscriptions both report the same swaps
+    observed_swaps: HashSet<(String, String)>,
+}
+
+impl BestPathStrategy {
+    pub fn new(tokens: Vec<TokenInArb>, best_paths_file: Arc<RwLock<Option<String>>>) -> Self {
+        BestPathStrategy {
+            preset_params: StrategyPreset::ALL.iter().map(|preset| (*preset, preset.params())).collect(),
+            tokens,
+            best_paths_file,
+            opportunity_queue: None,
+            path_ranking: None,
+            cooldowns: None,
+            token_health: None,
+            warmup: None,
+            honeypot_detector: None,
+            authority_monitor: None,
+            pool_refresher: None,
+            live_ranking: None,
+            instruction_templates: false,
+            sanity_bounds: SanityBounds::default(),
+            loaded_file: None,
+            warmup_retry_at: None,
+            paths: Vec::new(),
+            tokens_infos: HashMap::new(),
+            counter_sp_result: 0,
+            observed_swaps: HashSet::new(),
+        }
+    }
+
+    // Warm-up of the paths, scheduled again after WARMUP_RETRY_INTERVAL while incomplete
+    async fn warm_up(&mut self, paths: &[SwapPathSelected]) {
+        let Some(warmup) = &self.warmup else { return };
+        self.warmup_retry_at = match warm_up_paths(warmup, paths).await {
+            Ok(report) if !report.is_incomplete() => None,
+            Ok(_) => Some(Instant::now() + WARMUP_RETRY_INTERVAL),
+            Err(e) => {
+                error!("❌ Warm-up failed: {:?}", e);
+                Some(Instant::now() + WARMUP_RETRY_INTERVAL)
+            }
+        };
+    }
+
+    // One pass over the paths not cooling down nor through a quarantined token, each preset at its own size
+    async fn quote_paths(&mut self, paths: &[SwapPathSelected]) -> Result<()> {
+        let active_paths: Vec<SwapPathSelected> = paths
+            .iter()
+            .filter(|path| self.cooldowns.as_ref().map_or(true, |cooldowns| !cooldowns.is_cooling_down(&path_key(&path.path))))
+            .filter(|path| self.token_health.as_ref().map_or(true, |token_health| token_health.is_path_active(&path.path)))
+            .cloned()
+            .collect();
+        let strategy = self.name();
+        for preset in StrategyPreset::ALL {
+            let preset_paths: Vec<SwapPathSelected> = active_paths.iter().filter(|path| path.preset == preset).cloned().collect();
+            if preset_paths.is_empty() {
+                continue;
+            }
+            let PresetParams { simulation_amount, min_profit_threshold, .. } = self.preset_params[&preset];
+            sorted_interesting_path_pass(&strategy, simulation_amount, min_profit_threshold, &preset_paths, &self.tokens, &self.tokens_infos, &mut self.counter_sp_result, self.opportunity_queue.as_deref(), &self.sanity_bounds).await?;
+        }
+        Ok(())
+    }
+
+    pub fn with_opportunity_queue(mut self, queue: Arc<OpportunityQueue>) -> Self {
+        self.opportunity_queue = Some(queue);
+        self
+    }
+
+    // Prefer the paths which historically turned simulations into realized profit
+    pub fn with_path_ranking(mut self, path_ranking: Arc<PathRanking>) -> Self {
+        self.path_ranking = Some(path_ranking);
+        self
+    }
+
+    // Skip the paths which recently failed to execute
+    pub fn with_cooldowns(mut self, cooldowns: Arc<PathCooldowns>) -> Self {
+        self.cooldowns = Some(cooldowns);
+        self
+    }
+
+    pub fn with_token_health(mut self, token_health: Arc<TokenHealth>) -> Self {
+        self.token_health = Some(token_health);
+        self
+    }
+
+    // Round-trip simulation of the new tokens of every loaded path set
+    pub fn with_honeypot_detector(mut self, honeypot_detector: Arc<HoneypotDetector>) -> Self {
+        self.honeypot_detector = Some(honeypot_detector);
+        self
+    }
+
+    // Quotes above these bounds are re-checked on fresh pool data, then rejected
+    pub fn with_sanity_bounds(mut self, sanity_bounds: SanityBounds) -> Self {
+        self.sanity_bounds = sanity_bounds;
+        self
+    }
+
+    // Watch the mint/freeze authorities of the tokens of every loaded path set
+    pub fn with_authority_monitor(mut self, authority_monitor: Arc<AuthorityMonitor>) -> Self {
+        self.authority_monitor = Some(authority_monitor);
+        self
+    }
+
+    // Refresh the pools of every loaded path set in batches
+    pub fn with_pool_refresher(mut self, pool_refresher: Arc<PoolRefresher>) -> Self {
+        self.pool_refresher = Some(pool_refresher);
+        self
+    }
+
+    pub fn with_live_ranking(mut self, live_ranking: Arc<LiveRanking>) -> Self {
+        self.live_ranking = Some(live_ranking);
+        self
+    }
+
+    pub fn with_instruction_templates(mut self) -> Self {
+        self.instruction_templates = true;
+        self
+    }
+
+    // Create the missing ATAs / LUT entries every time a new path set is loaded
+    pub fn with_warmup(mut self, warmup: WarmupConfig) -> Self {
+        self.warmup = Some(warmup);
+        self
+    }
+}
+
+#[async_trait]
+impl Strategy for BestPathStrategy {
+    fn name(&self) -> String {
+        "best_paths".to_string()
+    }
+
+    // Sizing and threshold by preset, as <preset>.simulation_amount and <preset>.min_profit_threshold
+    fn params(&self) -> HashMap<String, String> {
+        let mut params = HashMap::from([
+            ("max_profit_ratio".to_string(), self.sanity_bounds.max_profit_ratio.to_string()),
+            ("max_oracle_deviation".to_string(), self.sanity_bounds.max_oracle_deviation.to_string()),
+        ]);
+        for (preset, preset_params) in &self.preset_params {
+            params.insert(format!("{}.simulation_amount", preset.name()), preset_params.simulation_amount.to_string());
+            params.insert(format!("{}.min_profit_threshold", preset.name()), preset_params.min_profit_threshold.to_string());
+        }
+        params
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<()> {
+        if let Some((preset, param)) = name.split_once('.') {
+            let preset_params = StrategyPreset::ALL
+                .iter()
+                .find(|known| known.name() == preset)
+                .and_then(|preset| self.preset_params.get_mut(preset))
+                .ok_or_else(|| anyhow!("Unknown preset {}", preset))?;
+            match param {
+                "simulation_amount" => preset_params.simulation_amount = value.parse()?,
+                "min_profit_threshold" => preset_params.min_profit_threshold = value.parse()?,
+                _ => return Err(anyhow!("Unknown parameter {}", name)),
+            }
+            return Ok(());
+        }
+        match name {
+            "max_profit_ratio" => self.sanity_bounds.max_profit_ratio = value.parse()?,
+            "max_oracle_deviation" => self.sanity_bounds.max_oracle_deviation = value.parse()?,
+            _ => return Err(anyhow!("Unknown parameter {}", name)),
+        }
+        Ok(())
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        self.tokens_infos = get_tokens_infos(self.tokens.clone()).await;
+        Ok(())
+    }
+
+    // A swap moves its pools: the paths through them are quoted again right away instead of waiting for their
+    // turn in the next pass. Seen in the shreds before its confirmation, or in the logs of a pool once processed
+    async fn on_event(&mut self, event: StrategyEvent) -> Result<()> {
+        let StrategyEvent::SwapObserved { signature, slot, pools } = event else { return Ok(()) };
+        if self.observed_swaps.len() > OBSERVED_SWAPS_CAPACITY {
+            self.observed_swaps.clear();
+        }
+        let pools: Vec<String> = pools.into_iter().filter(|pool| self.observed_swaps.insert((signature.clone(), pool.clone()))).collect();
+        let touched: Vec<SwapPathSelected> = self
+            .paths
+            .iter()
+            .filter(|path| path.path.paths.iter().any(|route| pools.contains(&route.pool_address)))
+            .cloned()
+            .collect();
+        if touched.is_empty() {
+            return Ok(());
+        }
+        debug!("👀 Swap {} (slot {}) through {:?}, {} paths quoted again", signature, slot, pools, touched.len());
+        self.quote_paths(&touched).await
+    }
+
+    async fn on_tick(&mut self) -> Result<TickOutcome> {
+        let current_file = self.best_paths_file.read().await.clone();
+        if let Some(path) = current_file {
+            let modified = std::fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+            let current = modified.map(|modified| (path.clone(), modified));
+            if current.is_some() && current != self.loaded_file {
+                // Keep looping on the previous set if the new one can't be read
+                match read_paths(&path) {
+                    Ok(paths_vec) => {
+                        info!("📂 Best paths loaded from {} ({} paths)", path, paths_vec.value.len());
+                        let mut new_paths = paths_vec.value;
+                        if let Some(honeypot_detector) = &self.honeypot_detector {
+                            new_paths = honeypot_detector.screen_paths(new_paths).await;
+                        }
+                        self.warm_up(&new_paths).await;
+                        if let Some(authority_monitor) = &self.authority_monitor {
+                            authority_monitor.watch_paths(&new_paths);
+                        }
+                        if let Some(pool_refresher) = &self.pool_refresher {
+                            pool_refresher.watch_paths(&new_paths);
+                            pool_refresher.refresh().await;
+                        }
+                        if self.instruction_templates {
+                            instruction_templates().compile_paths(&new_paths).await;
+                        }
+                        if let Some(live_ranking) = &self.live_ranking {
+                            live_ranking.set_paths(&new_paths);
+                        }
+                        publish(paths_loaded(&self.name(), &new_paths));
+                        self.paths = new_paths;
+                        self.loaded_file = current;
+                    }
+                    Err(e) => error!("❌ Unable to load best paths {}: {:?}", path, e),
                 }
             }
-            sleep(time::Duration::from_millis(200))
         }
+        if self.paths.is_empty() {
+            return Ok(TickOutcome::Continue);
+        }
+        // ATAs or LUT entries the last warm-up could not create
+        if self.warmup_retry_at.is_some_and(|retry_at| Instant::now() >= retry_at) {
+            let paths = self.paths.clone();
+            self.warm_up(&paths).await;
+        }
+        if let Some(live_ranking) = &self.live_ranking {
+            self.paths = live_ranking.ranked();
+        }
+        if let Some(path_ranking) = &self.path_ranking {
+            path_ranking.rerank(&mut self.paths);
+        }
+
+        let paths = self.paths.clone();
+        self.quote_paths(&paths).await?;
+        Ok(TickOutcome::Continue)
     }
-    // Ok(())
 
+    async fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
 }
 
-pub async fn optimism_tx_strategy(path:String) -> Result<()>{
+// Cycle through the token groups, giving each one a time budget: the best paths of the
+// current group are computed, then looped until the budget is spent
+pub struct RotationStrategy {
+    pub inputs: Vec<InputVec>,
+    pub fetch_new_pools: bool,
+    pub restrict_sol_usdc: bool,
+    // Used when the InputVec has no time_budget_secs
+    pub default_time_budget: Duration,
+    pub opportunity_queue: Option<Arc<OpportunityQueue>>,
+    pub sanity_bounds: SanityBounds,
+    market_state: Arc<MarketState>,
+    current: usize,
+    started_at: Option<Instant>,
+    paths: Vec<SwapPathSelected>,
+    tokens_infos: HashMap<String, TokenInfos>,
+    counter_sp_result: u32,
+}
 
-    let file_read = OpenOptions::new().read(true).write(true).open(path)?;
-    let mut spr: SwapPathResult = serde_json::from_reader(&file_read).unwrap();
-    let mut counter_sp_result = 0;
+impl RotationStrategy {
+    pub fn new(inputs: Vec<InputVec>, fetch_new_pools: bool, restrict_sol_usdc: bool, default_time_budget: Duration) -> Self {
+        RotationStrategy {
+            inputs,
+            fetch_new_pools,
+            restrict_sol_usdc,
+            default_time_budget,
+            opportunity_queue: None,
+            sanity_bounds: SanityBounds::default(),
+            market_state: Arc::new(MarketState::new()),
+            current: 0,
+            started_at: None,
+            paths: Vec::new(),
+            tokens_infos: HashMap::new(),
+            counter_sp_result: 0,
+        }
+    }
 
-    println!("💸💸💸💸💸💸💸💸💸 Begin Execute the tx 💸💸💸💸💸💸💸💸💸");
-    // let _ = create_ata_extendlut_transaction(
-    //     ChainType::Mainnet,
-    //     SendOrSimulate::Send,
-    //     sp_result.clone(),
-    //     from_str("6nGymM5X1djYERKZtoZ3Yz3thChMVF6jVRDzhhcmxuee").unwrap(),
-    //     tokens_for_tx.clone()
-    // ).await;
-    let _ = create_and_send_swap_transaction(
-        SendOrSimulate::Send,
-        ChainType::Mainnet, 
-        spr.clone()
-    ).await;
+    pub fn with_opportunity_queue(mut self, queue: Arc<OpportunityQueue>) -> Self {
+        self.opportunity_queue = Some(queue);
+        self
+    }
 
-    Ok(())
+    pub fn with_sanity_bounds(mut self, sanity_bounds: SanityBounds) -> Self {
+        self.sanity_bounds = sanity_bounds;
+        self
+    }
+
+    pub fn with_market_state(mut self, market_state: Arc<MarketState>) -> Self {
+        self.market_state = market_state;
+        self
+    }
+
+    fn time_budget(&self) -> Duration {
+        self.inputs[self.current].time_budget_secs.map(Duration::from_secs).unwrap_or(self.default_time_budget)
+    }
+
+    async fn start_group(&mut self) -> Result<()> {
+        let input = self.inputs[self.current].clone();
+        let symbols = input.tokens_to_arb.iter().map(|token| token.symbol.clone()).collect::<Vec<String>>().join("-");
+        info!("🔄 Rotation on group {}/{} ({}) for {:?}", self.current + 1, self.inputs.len(), symbols, self.time_budget());
+        self.started_at = Some(Instant::now());
+        self.paths = Vec::new();
+
+        self.tokens_infos = get_tokens_infos(input.all_tokens()).await;
+        let (_, best_paths) = run_arbitrage_strategy(
+            input.preset,
+            input.get_fresh_pools_bool,
+            self.restrict_sol_usdc && input.preset.params().restrict_sol_usdc,
+            input.include_1hop,
+            input.include_2hop,
+            input.numbers_of_best_paths,
+            &self.market_state,
+            input.tokens_to_arb.clone(),
+            input.bridge_tokens.clone(),
+            &self.tokens_infos,
+        )
+        .await?;
+        publish(paths_loaded(&self.name(), &best_paths.value));
+        self.paths = best_paths.value;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Strategy for RotationStrategy {
+    fn name(&self) -> String {
+        "rotation".to_string()
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        self.market_state.ensure_loaded(self.fetch_new_pools).await;
+        Ok(())
+    }
+
+    async fn on_event(&mut self, _event: StrategyEvent) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_tick(&mut self) -> Result<TickOutcome> {
+        if self.inputs.is_empty() {
+            return Ok(TickOutcome::Done);
+        }
+        let budget_spent = match self.started_at {
+            Some(started_at) => started_at.elapsed() >= self.time_budget(),
+            None => true,
+        };
+        if budget_spent {
+            if self.started_at.is_some() {
+                self.current = (self.current + 1) % self.inputs.len();
+            }
+            // A failing group simply has no paths until its budget is spent
+            if let Err(e) = self.start_group().await {
+                error!("❌ Rotation group failed: {:?}", e);
+            }
+        }
+        if self.paths.is_empty() {
+            return Ok(TickOutcome::Continue);
+        }
+
+        let strategy = self.name();
+        let input = &self.inputs[self.current];
+        let preset_params = input.preset.params();
+        sorted_interesting_path_pass(&strategy, preset_params.simulation_amount, preset_params.min_profit_threshold, &self.paths, &input.tokens_to_arb, &self.tokens_infos, &mut self.counter_sp_result, self.opportunity_queue.as_deref(), &self.sanity_bounds).await?;
+        Ok(TickOutcome::Continue)
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+// Replay an optimism transaction file, or every file of a directory, through the executor pool
+pub struct OptimismStrategy {
+    pub path: String,
+    // Files pushed to the executor pool per tick
+    pub max_concurrent: usize,
+    pub opportunity_queue: Arc<OpportunityQueue>,
+    // Files not pushed yet, listed on the first tick
+    pending: Option<Vec<String>>,
+}
+
+impl OptimismStrategy {
+    pub fn new(path: String, opportunity_queue: Arc<OpportunityQueue>) -> Self {
+        OptimismStrategy { path, max_concurrent: 4, opportunity_queue, pending: None }
+    }
+
+    pub fn with_max_concurrent(mut self, max_concurrent: usize) -> Self {
+        self.max_concurrent = max_concurrent.max(1);
+        self
+    }
+}
+
+#[async_trait]
+impl Strategy for OptimismStrategy {
+    fn name(&self) -> String {
+        "optimism".to_string()
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_event(&mut self, _event: StrategyEvent) -> Result<()> {
+        Ok(())
+    }
 
-}
\ No newline at end of file
+    async fn on_tick(&mut self) -> Result<TickOutcome> {
+        if self.pending.is_none() {
+            let mut files = optimism_files(&self.path)?;
+            info!("📂 {} optimism transactions found in {}", files.len(), self.path);
+            // Popped from the end
+            files.reverse();
+            self.pending = Some(files);
+        }
+        let pending = self.pending.get_or_insert_with(Vec::new);
+        for _ in 0..self.max_concurrent {
+            let Some(file) = pending.pop() else { break };
+            if let Err(e) = optimism_tx_strategy(&file, &self.opportunity_queue) {
+                error!("❌ Optimism tx {} failed: {:?}", file, e);
+            }
+        }
+        Ok(if pending.is_empty() { TickOutcome::Done } else { TickOutcome::Continue })
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
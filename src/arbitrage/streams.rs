@@ -1,21 +1,42 @@
 
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 use anyhow::Result;
-use log::info;
+use chrono::Utc;
+use log::{error, info};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
 use crate::{
     common::{
-        constants::Env,
-        utils::from_str,
+        circuit_breaker::quote_breaker,
+        rate_limiter::throttle,
+        rpc_pool::blocking_client,
+        utils::{from_Pubkey, from_str},
     }, 
-    markets::types::{DexLabel, Market}
+    arbitrage::types::SwapPathSelected,
+    markets::{local_quote::whirlpool_tick_arrays, pool_cache::pool_cache, pubsub::PubsubSubscriptions, types::{DexLabel, Market}, zero_copy::raydium_amm},
+    monitoring::metrics::metrics
 };
 
+static LAST_POOL_REFRESH: Mutex<Option<Instant>> = Mutex::new(None);
+
+// When pool accounts were last fetched from the chain
+pub fn last_pool_refresh() -> Option<Instant> {
+    *LAST_POOL_REFRESH.lock().unwrap()
+}
+
 //Get fresh data on all acounts with getMultipleAccounts
 pub async fn get_fresh_accounts_states(mut accounts: HashMap<String, Market>) -> HashMap<String, Market> {
-    let env = Env::new();
-    let rpc_client = RpcClient::new(env.rpc_url);
+    let rpc_url = match quote_breaker().current_url() {
+        Some(rpc_url) => rpc_url,
+        None => {
+            error!("🔌 No healthy RPC endpoint, markets not refreshed");
+            return accounts;
+        }
+    };
+    let rpc_client = blocking_client(&rpc_url);
+    let refresh_started_at = Instant::now();
     let mut counter_fresh_markets = 0;
 
     let mut markets_vec: Vec<Market> = Vec::new();
@@ -31,21 +52,132 @@ pub async fn get_fresh_accounts_states(mut accounts: HashMap<String, Market>) ->
         let maxLength = std::cmp::min(i + 100, pubkeys_vec.len());
         let batch = &pubkeys_vec[(i..maxLength)];
         
-        let batch_results = rpc_client.get_multiple_accounts(&batch).unwrap();
-        // println!("BatchResult {:?}", batch_results);
-        for (j, account) in batch_results.iter().enumerate() {
-            let account = account.clone().unwrap();
-            // println!("WhirpoolAccount: {:?}", data);
+        throttle(&rpc_client.url()).await;
+        let batch_results = rpc_client.get_multiple_accounts(&batch);
+        quote_breaker().record(&rpc_url, &batch_results);
+        let batch_results = match batch_results {
+            Ok(batch_results) => batch_results,
+            Err(e) => {
+                error!("❌ getMultipleAccounts failed on {}: {}", rpc_url, e);
+                continue;
+            }
+        };
+        for (j, account) in batch_results.into_iter().enumerate() {
+            let Some(account) = account else { continue };
             let account_data = account.data;
+            pool_cache().insert(&from_Pubkey(batch[j]), account_data.clone());
 
-            markets_vec[j].account_data = Some(account_data);
-            markets_vec[j].id = key_vec[j].clone();
+            let market = &mut markets_vec[i + j];
+            market.account_data = Some(account_data);
+            market.id = key_vec[i + j].clone();
             counter_fresh_markets += 1;
-            accounts.insert(key_vec[j].clone(), markets_vec[j].clone());
+            accounts.insert(key_vec[i + j].clone(), market.clone());
         }
     }
 
+    refresh_vaults(&rpc_client, &rpc_url, &accounts).await;
+
+    *LAST_POOL_REFRESH.lock().unwrap() = Some(Instant::now());
+    metrics().pool_refresh_duration.observe(refresh_started_at.elapsed().as_secs_f64());
+    metrics().pool_last_refresh.set(Utc::now().timestamp() as f64);
     info!("💦💦 Fresh data for {:?} markets", counter_fresh_markets);
     return accounts;
 }
 
+
+// Raydium reserves live in the vault token accounts and Whirlpool ticks in the tick arrays around
+// the current one, fetched for the local quotes
+async fn refresh_vaults(rpc_client: &RpcClient, rpc_url: &str, accounts: &HashMap<String, Market>) {
+    let mut vaults: Vec<Pubkey> = Vec::new();
+    for market in accounts.values() {
+        let Some(data) = market.account_data.as_deref() else { continue };
+        match market.dexLabel {
+            DexLabel::RAYDIUM => {
+                let Some(amm) = raydium_amm(data) else { continue };
+                vaults.push(Pubkey::new_from_array(amm.coin_vault()));
+                vaults.push(Pubkey::new_from_array(amm.pc_vault()));
+            }
+            DexLabel::ORCA_WHIRLPOOLS => vaults.extend(whirlpool_tick_arrays(&market.id, data).iter().filter_map(|address| from_str(address).ok())),
+            _ => {}
+        }
+    }
+
+    for batch in vaults.chunks(100) {
+        throttle(&rpc_client.url()).await;
+        let batch_results = rpc_client.get_multiple_accounts(batch);
+        quote_breaker().record(rpc_url, &batch_results);
+        match batch_results {
+            Ok(batch_results) => {
+                for (vault, account) in batch.iter().zip(batch_results) {
+                    if let Some(account) = account {
+                        pool_cache().insert(&from_Pubkey(*vault), account.data);
+                    }
+                }
+            }
+            Err(e) => error!("❌ getMultipleAccounts failed on {} for the vaults and tick arrays: {}", rpc_url, e),
+        }
+    }
+}
+
+// Pools of the active paths, kept fresh in the pool cache with chunked getMultipleAccounts
+// (100 accounts per request) instead of one request per pool, and pushed by the pubsub subscriptions when set
+#[derive(Debug, Default)]
+pub struct PoolRefresher {
+    pools: RwLock<HashMap<String, Market>>,
+    subscriptions: Option<Arc<PubsubSubscriptions>>,
+}
+
+impl PoolRefresher {
+    pub fn new() -> Self {
+        PoolRefresher::default()
+    }
+
+    pub fn with_subscriptions(mut self, subscriptions: Arc<PubsubSubscriptions>) -> Self {
+        self.subscriptions = Some(subscriptions);
+        self
+    }
+
+    // The pools of a new path set replace the previous ones
+    pub fn watch_paths(&self, paths: &[SwapPathSelected]) {
+        let pools: HashMap<String, Market> = paths.iter().flat_map(|path| path.markets.iter()).map(|market| (market.id.clone(), market.clone())).collect();
+        info!("💦 {} pools watched for {} paths", pools.len(), paths.len());
+        if let Some(subscriptions) = &self.subscriptions {
+            let addresses: Vec<String> = pools.keys().cloned().collect();
+            subscriptions.watch_accounts(&addresses);
+            subscriptions.watch_log_mentions(&addresses);
+        }
+        *self.pools.write().unwrap() = pools;
+    }
+
+    pub fn len(&self) -> usize {
+        self.pools.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub async fn refresh(&self) {
+        let pools = self.pools.read().unwrap().clone();
+        if pools.is_empty() {
+            return;
+        }
+        let fresh_pools = get_fresh_accounts_states(pools).await;
+        // The Raydium vaults are found from the fresh account data on the next refresh
+        let mut pools = self.pools.write().unwrap();
+        for (key, market) in fresh_pools {
+            if let Some(pool) = pools.get_mut(&key) {
+                *pool = market;
+            }
+        }
+    }
+}
+
+pub async fn run_pool_refresher(refresher: Arc<PoolRefresher>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        refresher.refresh().await;
+    }
+}
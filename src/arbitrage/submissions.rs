@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
+
+use crate::arbitrage::claims::pool_set_key;
+use crate::arbitrage::types::SwapPathResult;
+use crate::common::database::find_submission_fingerprints_since;
+use crate::common::rpc_pool::nonblocking_client;
+use crate::monitoring::events::{publish, BotEvent};
+
+pub static SUBMISSIONS_COLLECTION: &str = "submissions";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionFingerprint {
+    pub pool_set: String,
+    pub slot: u64,
+    pub strategy: String,
+    pub attempted_at: String,
+}
+
+// Pool sets already submitted in the last `slot_window` slots, persisted in the DB and reloaded
+// at startup: a crash/restart loop doesn't resubmit the same stale opportunity every time
+#[derive(Debug)]
+pub struct SubmissionGuard {
+    pub slot_window: u64,
+    current_slot: AtomicU64,
+    // Pool set -> slot of the last submission
+    recent: Mutex<HashMap<String, u64>>,
+}
+
+impl SubmissionGuard {
+    pub fn new(slot_window: u64) -> Self {
+        SubmissionGuard {
+            slot_window,
+            current_slot: AtomicU64::new(0),
+            recent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Fingerprints of the previous runs still inside the window
+    pub async fn load(slot_window: u64, rpc_url: &str) -> Result<Self> {
+        let guard = SubmissionGuard::new(slot_window);
+        let slot = nonblocking_client(rpc_url).get_slot().await?;
+        guard.current_slot.store(slot, Ordering::Relaxed);
+        let fingerprints = find_submission_fingerprints_since(SUBMISSIONS_COLLECTION, slot.saturating_sub(slot_window)).await?;
+        info!("🧷 {} recent submissions reloaded, not resubmitted before slot {}", fingerprints.len(), slot + slot_window);
+        {
+            let mut recent = guard.recent.lock().unwrap();
+            for fingerprint in fingerprints {
+                let last_slot = recent.entry(fingerprint.pool_set).or_insert(fingerprint.slot);
+                *last_slot = (*last_slot).max(fingerprint.slot);
+            }
+        }
+        Ok(guard)
+    }
+
+    pub fn current_slot(&self) -> u64 {
+        self.current_slot.load(Ordering::Relaxed)
+    }
+
+    pub fn is_duplicate(&self, spr: &SwapPathResult) -> bool {
+        let key = pool_set_key(spr);
+        match self.recent.lock().unwrap().get(&key) {
+            Some(slot) if self.current_slot().saturating_sub(*slot) < self.slot_window => {
+                debug!("🧷 {} already submitted in slot {}, skipped", key, slot);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // To call right before sending, the DB write doesn't delay the transaction
+    pub fn record(&self, strategy: &str, spr: &SwapPathResult) {
+        let fingerprint = SubmissionFingerprint {
+            pool_set: pool_set_key(spr),
+            slot: self.current_slot(),
+            strategy: strategy.to_string(),
+            attempted_at: Utc::now().to_rfc3339(),
+        };
+        self.recent.lock().unwrap().insert(fingerprint.pool_set.clone(), fingerprint.slot);
+        publish(BotEvent::SubmissionRecorded { fingerprint });
+    }
+
+    fn prune(&self) {
+        let current_slot = self.current_slot();
+        self.recent.lock().unwrap().retain(|_, slot| current_slot.saturating_sub(*slot) < self.slot_window);
+    }
+}
+
+// Keep the current slot up to date and drop the fingerprints out of the window
+pub async fn run_submission_slot_refresh(guard: Arc<SubmissionGuard>, rpc_url: String, interval: Duration) {
+    let rpc_client = nonblocking_client(&rpc_url);
+    loop {
+        match rpc_client.get_slot().await {
+            Ok(slot) => {
+                guard.current_slot.store(slot, Ordering::Relaxed);
+                guard.prune();
+            }
+            Err(e) => error!("❌ Submission guard slot refresh failed: {:?}", e),
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use log::info;
+
+use crate::arbitrage::types::{SwapPath, SwapPathResult};
+
+#[derive(Debug, Clone, Default)]
+struct TokenHealthState {
+    consecutive_losses: u32,
+    quarantined_until: Option<Instant>,
+}
+
+// Take a token out of rotation after too many unprofitable attempts in a row,
+// and put it back once the quarantine is over
+#[derive(Debug)]
+pub struct TokenHealth {
+    pub max_consecutive_losses: u32,
+    pub quarantine: Duration,
+    state: Mutex<HashMap<String, TokenHealthState>>,
+}
+
+impl TokenHealth {
+    pub fn new(max_consecutive_losses: u32, quarantine: Duration) -> Self {
+        TokenHealth {
+            max_consecutive_losses: max_consecutive_losses.max(1),
+            quarantine,
+            state: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn record_outcome(&self, token: &str, profitable: bool) {
+        let mut state = self.state.lock().unwrap();
+        let entry = state.entry(token.to_string()).or_default();
+        if profitable {
+            entry.consecutive_losses = 0;
+            return;
+        }
+        entry.consecutive_losses += 1;
+        if entry.consecutive_losses >= self.max_consecutive_losses && entry.quarantined_until.is_none() {
+            entry.quarantined_until = Some(Instant::now() + self.quarantine);
+            info!("🚫 Token {} disabled for {:?} after {} unprofitable attempts", token, self.quarantine, entry.consecutive_losses);
+        }
+    }
+
+    // Immediate quarantine, whatever the past outcomes
+    pub fn quarantine_for(&self, token: &str, duration: Duration, reason: &str) {
+        let mut state = self.state.lock().unwrap();
+        let entry = state.entry(token.to_string()).or_default();
+        entry.quarantined_until = Some(Instant::now() + duration);
+        info!("🚫 Token {} disabled for {:?}: {}", token, duration, reason);
+    }
+
+    // Immediate quarantine of every intermediate token of the path
+    pub fn quarantine_path(&self, spr: &SwapPathResult, reason: &str) {
+        for token in path_result_tokens(spr) {
+            self.quarantine_for(&token, self.quarantine, reason);
+        }
+    }
+
+    // Record the outcome for every intermediate token of an executed path
+    pub fn record_path_outcome(&self, spr: &SwapPathResult, profitable: bool) {
+        for token in path_result_tokens(spr) {
+            self.record_outcome(&token, profitable);
+        }
+    }
+
+    pub fn is_active(&self, token: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let entry = match state.get_mut(token) {
+            Some(entry) => entry,
+            None => return true,
+        };
+        match entry.quarantined_until {
+            Some(until) if Instant::now() < until => false,
+            Some(_) => {
+                info!("✅ Token {} re-enabled after quarantine", token);
+                entry.quarantined_until = None;
+                entry.consecutive_losses = 0;
+                true
+            }
+            None => true,
+        }
+    }
+
+    pub fn is_path_active(&self, path: &SwapPath) -> bool {
+        path.paths.iter().all(|route| self.is_active(&route.tokenOut))
+    }
+
+    pub fn quarantined(&self) -> Vec<String> {
+        let now = Instant::now();
+        self.state
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, entry)| entry.quarantined_until.map(|until| now < until).unwrap_or(false))
+            .map(|(token, _)| token.clone())
+            .collect()
+    }
+}
+
+// Tokens traded in the middle of the path, the base token is never quarantined
+fn path_result_tokens(spr: &SwapPathResult) -> Vec<String> {
+    spr.route_simulations
+        .iter()
+        .map(|route| route.token_out.clone())
+        .filter(|token| *token != spr.token_in)
+        .collect()
+}
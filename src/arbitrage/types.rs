@@ -1,6 +1,7 @@
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use mongodb::bson;
 
+use crate::common::types::StrategyPreset;
 use crate::markets::types::{DexLabel, Market};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -9,6 +10,17 @@ pub struct TokenInArb {
     pub symbol: String,
 }
 
+// Tokens followed by the bridge tokens not already listed
+pub fn with_bridge_tokens(tokens: &[TokenInArb], bridge_tokens: &[TokenInArb]) -> Vec<TokenInArb> {
+    let mut all_tokens = tokens.to_vec();
+    for bridge_token in bridge_tokens {
+        if !all_tokens.iter().any(|token| token.address == bridge_token.address) {
+            all_tokens.push(bridge_token.clone());
+        }
+    }
+    all_tokens
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Route {
     pub id: u32,
@@ -31,6 +43,8 @@ pub struct TokenInfos {
     pub address: String,
     pub decimals: u8,
     pub symbol: String,
+    // From the price provider, None until its first refresh with the token
+    pub usd_price: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,7 +83,10 @@ pub struct VecSwapPathResult {
 pub struct SwapPathSelected {
     pub result: f64,
     pub path: SwapPath,
-    pub markets: Vec<Market>
+    pub markets: Vec<Market>,
+    // Preset of the token group the path was selected in: it is quoted with the same size and threshold
+    #[serde(default)]
+    pub preset: StrategyPreset,
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VecSwapPathSelected {
@@ -0,0 +1,140 @@
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::common::constants::Env;
+use crate::common::rate_limiter::throttle;
+use crate::common::rpc_cache::{rpc_cache, CachedMethod};
+
+pub static BIRDEYE_API_URL: &str = "https://public-api.birdeye.so";
+
+#[derive(Debug, Deserialize)]
+struct BirdeyeResponse<T> {
+    success: bool,
+    data: Option<T>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BirdeyeTokenOverview {
+    pub address: String,
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+    pub price: Option<f64>,
+    pub liquidity: Option<f64>,
+    #[serde(rename = "v24hUSD")]
+    pub volume_24h_usd: Option<f64>,
+    #[serde(rename = "mc")]
+    pub market_cap: Option<f64>,
+    #[serde(rename = "holder")]
+    pub holders: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BirdeyeTokenSecurity {
+    pub creator_address: Option<String>,
+    // Share of the supply held by the 10 largest holders, 0 to 1
+    pub top10_holder_percent: Option<f64>,
+    pub freezeable: Option<bool>,
+    pub is_token2022: Option<bool>,
+    pub transfer_fee_enable: Option<bool>,
+    pub mutable_metadata: Option<bool>,
+    pub jup_strict_list: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BirdeyeTrendingData {
+    pub tokens: Vec<BirdeyeTrendingToken>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BirdeyeTrendingToken {
+    pub address: String,
+    pub symbol: String,
+    pub liquidity: Option<f64>,
+    #[serde(rename = "volume24hUSD")]
+    pub volume24h_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BirdeyeOhlcv {
+    pub items: Vec<BirdeyeCandle>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BirdeyeCandle {
+    #[serde(rename = "o")]
+    pub open: f64,
+    #[serde(rename = "h")]
+    pub high: f64,
+    #[serde(rename = "l")]
+    pub low: f64,
+    #[serde(rename = "c")]
+    pub close: f64,
+    #[serde(rename = "v")]
+    pub volume: f64,
+    pub unix_time: i64,
+}
+
+// Typed Birdeye public API on Solana. Requests are throttled by the rate limiter (birdeye.so host)
+// and the responses kept in the response cache for the TokenAnalytics TTL
+#[derive(Debug, Clone)]
+pub struct BirdeyeClient {
+    api_key: String,
+    client: reqwest::Client,
+}
+
+impl BirdeyeClient {
+    pub fn new(api_key: &str) -> Self {
+        BirdeyeClient { api_key: api_key.to_string(), client: reqwest::Client::new() }
+    }
+
+    // BIRDEYE_API_KEY, None when not configured
+    pub fn from_env() -> Option<Self> {
+        let env = Env::new();
+        if env.birdeye_api_key.is_empty() {
+            return None;
+        }
+        Some(BirdeyeClient::new(&env.birdeye_api_key))
+    }
+
+    async fn get<T: DeserializeOwned + Clone + Send + Sync + 'static>(&self, path: &str) -> Result<T> {
+        let url = format!("{}{}", BIRDEYE_API_URL, path);
+        rpc_cache()
+            .get_or_fetch(CachedMethod::TokenAnalytics, &url, || async {
+                throttle(&url).await;
+                let response = self.client.get(&url).header("X-API-KEY", &self.api_key).header("x-chain", "solana").send().await?;
+                if !response.status().is_success() {
+                    return Err(anyhow!("Birdeye {} failed: {}", path, response.status()));
+                }
+                let body: BirdeyeResponse<T> = response.json().await?;
+                match body.data {
+                    Some(data) if body.success => Ok(data),
+                    _ => Err(anyhow!("Birdeye {} returned no data", path)),
+                }
+            })
+            .await
+    }
+
+    pub async fn token_overview(&self, mint: &str) -> Result<BirdeyeTokenOverview> {
+        self.get(&format!("/defi/token_overview?address={}", mint)).await
+    }
+
+    pub async fn token_security(&self, mint: &str) -> Result<BirdeyeTokenSecurity> {
+        self.get(&format!("/defi/token_security?address={}", mint)).await
+    }
+
+    pub async fn trending(&self, limit: usize) -> Result<Vec<BirdeyeTrendingToken>> {
+        let data: BirdeyeTrendingData = self.get(&format!("/defi/token_trending?sort_by=rank&sort_type=asc&offset=0&limit={}", limit)).await?;
+        Ok(data.tokens)
+    }
+
+    // Candles between two unix times, interval as Birdeye names them ("1m", "15m", "1H", "1D"...)
+    pub async fn ohlcv(&self, mint: &str, interval: &str, time_from: i64, time_to: i64) -> Result<Vec<BirdeyeCandle>> {
+        let data: BirdeyeOhlcv = self.get(&format!("/defi/ohlcv?address={}&type={}&time_from={}&time_to={}", mint, interval, time_from, time_to)).await?;
+        Ok(data.items)
+    }
+}
@@ -0,0 +1,164 @@
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use log::{error, info, warn};
+
+use crate::common::constants::Env;
+use crate::common::rpc_pool::nonblocking_client;
+use crate::monitoring::notifier::{notify, NotifyEvent};
+
+#[derive(Debug, Clone)]
+struct EndpointState {
+    url: String,
+    consecutive_failures: u32,
+    // Set when the endpoint failed `failure_threshold` calls in a row
+    open_since: Option<Instant>,
+}
+
+// Ordered list of equivalent RPC endpoints: calls go to the first healthy one, an endpoint
+// failing N calls in a row is taken out until the background probe sees it recover.
+// With every endpoint broken the breaker is halted and quoting/sending stops.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    pub name: String,
+    pub failure_threshold: u32,
+    endpoints: Mutex<Vec<EndpointState>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: &str, urls: Vec<String>, failure_threshold: u32) -> Self {
+        let endpoints = urls
+            .into_iter()
+            .filter(|url| !url.is_empty())
+            .map(|url| EndpointState { url, consecutive_failures: 0, open_since: None })
+            .collect();
+        CircuitBreaker {
+            name: name.to_string(),
+            failure_threshold: failure_threshold.max(1),
+            endpoints: Mutex::new(endpoints),
+        }
+    }
+
+    // First healthy endpoint, None when all of them are broken
+    pub fn current_url(&self) -> Option<String> {
+        self.endpoints
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|endpoint| endpoint.open_since.is_none())
+            .map(|endpoint| endpoint.url.clone())
+    }
+
+    // Every endpoint not taken out, in order
+    pub fn healthy_urls(&self) -> Vec<String> {
+        self.endpoints
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|endpoint| endpoint.open_since.is_none())
+            .map(|endpoint| endpoint.url.clone())
+            .collect()
+    }
+
+    pub fn urls(&self) -> Vec<String> {
+        self.endpoints.lock().unwrap().iter().map(|endpoint| endpoint.url.clone()).collect()
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.current_url().is_none()
+    }
+
+    pub fn record_success(&self, url: &str) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        if let Some(endpoint) = endpoints.iter_mut().find(|endpoint| endpoint.url == url) {
+            endpoint.consecutive_failures = 0;
+            if let Some(open_since) = endpoint.open_since.take() {
+                info!("🟢 {} endpoint {} recovered after {:?}", self.name, url, open_since.elapsed());
+            }
+        }
+    }
+
+    pub fn record_failure(&self, url: &str) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let Some(endpoint) = endpoints.iter_mut().find(|endpoint| endpoint.url == url) else {
+            return;
+        };
+        endpoint.consecutive_failures += 1;
+        if endpoint.open_since.is_some() || endpoint.consecutive_failures < self.failure_threshold {
+            return;
+        }
+        endpoint.open_since = Some(Instant::now());
+        match endpoints.iter().find(|endpoint| endpoint.open_since.is_none()) {
+            Some(next) => {
+                warn!("🔌 {} endpoint {} failed {} calls in a row, failover to {}", self.name, url, self.failure_threshold, next.url);
+                notify(NotifyEvent::RpcFailover, &format!("{} RPC failover", self.name), &format!("{} failed {} calls in a row, now using {}", url, self.failure_threshold, next.url));
+            }
+            None => {
+                error!("🔌 {} endpoint {} failed {} calls in a row, no healthy endpoint left: halted", self.name, url, self.failure_threshold);
+                notify(NotifyEvent::RpcFailover, &format!("{} RPC halted", self.name), &format!("{} failed {} calls in a row, no healthy endpoint left", url, self.failure_threshold));
+            }
+        }
+    }
+
+    // Record the outcome of a call made on `url`
+    pub fn record<T, E>(&self, url: &str, result: &Result<T, E>) {
+        match result {
+            Ok(_) => self.record_success(url),
+            Err(_) => self.record_failure(url),
+        }
+    }
+
+    fn broken_urls(&self) -> Vec<String> {
+        self.endpoints
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|endpoint| endpoint.open_since.is_some())
+            .map(|endpoint| endpoint.url.clone())
+            .collect()
+    }
+}
+
+// RPC_URL then RPC_URL_FALLBACKS (comma separated) for the quotes, RPC_URL_TX then the fallbacks for the sends
+fn endpoints_from_env(primary: String) -> Vec<String> {
+    let env = Env::new();
+    let mut urls = vec![primary];
+    for url in env.rpc_url_fallbacks.split(',').map(|url| url.trim().to_string()) {
+        if !url.is_empty() && !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+    urls
+}
+
+static QUOTE_BREAKER: OnceLock<CircuitBreaker> = OnceLock::new();
+static SEND_BREAKER: OnceLock<CircuitBreaker> = OnceLock::new();
+
+// To call once at startup, a threshold of 3 failures is used otherwise
+pub fn init_circuit_breakers(failure_threshold: u32) {
+    let env = Env::new();
+    let _ = QUOTE_BREAKER.set(CircuitBreaker::new("quote", endpoints_from_env(env.rpc_url), failure_threshold));
+    let _ = SEND_BREAKER.set(CircuitBreaker::new("send", endpoints_from_env(env.rpc_url_tx), failure_threshold));
+    info!("🔌 Circuit breakers armed: failover after {} failures in a row", failure_threshold);
+}
+
+pub fn quote_breaker() -> &'static CircuitBreaker {
+    QUOTE_BREAKER.get_or_init(|| CircuitBreaker::new("quote", endpoints_from_env(Env::new().rpc_url), 3))
+}
+
+pub fn send_breaker() -> &'static CircuitBreaker {
+    SEND_BREAKER.get_or_init(|| CircuitBreaker::new("send", endpoints_from_env(Env::new().rpc_url_tx), 3))
+}
+
+// Probe the broken endpoints with getSlot and put them back once they answer
+pub async fn run_recovery_probe(breaker: &'static CircuitBreaker, interval: Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+        for url in breaker.broken_urls() {
+            match nonblocking_client(&url).get_slot().await {
+                Ok(_) => breaker.record_success(&url),
+                Err(e) => info!("🔌 {} endpoint {} still down: {}", breaker.name, url, e),
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::sync::OnceLock;
+
+use log::info;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use tokio::sync::Semaphore;
+
+pub static DEFAULT_MAX_PENDING_JOBS: usize = 4;
+
+// The path math off the tokio runtime: stream ingestion and RPC calls stay on tokio, quotes run on
+// a dedicated rayon pool. At most max_pending jobs are queued, the next caller waits its turn
+// (backpressure) rather than piling more work onto the pool
+pub struct ComputePool {
+    pool: ThreadPool,
+    pending: Semaphore,
+}
+
+impl ComputePool {
+    pub fn new(threads: usize, max_pending: usize) -> Self {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|index| format!("compute-{}", index))
+            .build()
+            .expect("Compute pool not built");
+        ComputePool { pool, pending: Semaphore::new(max_pending) }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    // f and its par_iter run on the pool. Meanwhile the tokio worker hands its other tasks over
+    // to the rest of the runtime, so a burst of quotes never holds up the account updates
+    pub async fn run<R: Send>(&self, f: impl FnOnce() -> R + Send) -> R {
+        let _permit = self.pending.acquire().await.expect("Compute pool closed");
+        tokio::task::block_in_place(|| self.pool.install(f))
+    }
+}
+
+static COMPUTE_POOL: OnceLock<ComputePool> = OnceLock::new();
+
+// Half the cores by default, the other half left to tokio
+fn default_threads() -> usize {
+    std::thread::available_parallelism().map(|cores| (cores.get() / 2).max(1)).unwrap_or(1)
+}
+
+// To call once at startup, 0 threads for the default
+pub fn init_compute_pool(threads: usize, max_pending: usize) {
+    let threads = if threads == 0 { default_threads() } else { threads };
+    info!("🧮 Compute pool: {} threads, {} jobs queued at most", threads, max_pending);
+    let _ = COMPUTE_POOL.set(ComputePool::new(threads, max_pending));
+}
+
+pub fn compute_pool() -> &'static ComputePool {
+    COMPUTE_POOL.get_or_init(|| ComputePool::new(default_threads(), DEFAULT_MAX_PENDING_JOBS))
+}
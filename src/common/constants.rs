@@ -7,35 +7,86 @@ pub fn get_env(key: &str) -> String {
 #[derive(Debug, Clone)]
 pub struct Env {
     pub block_engine_url: String,
+    pub jito_auth_token: String,
     pub mainnet_rpc_url: String,
     pub rpc_url_tx: String,
+    pub private_rpc_url: String,
     pub devnet_rpc_url: String,
     pub rpc_url: String,
+    pub rpc_url_fallbacks: String,
     pub wss_rpc_url: String,
     pub geyser_url: String,
     pub geyser_access_token: String,
+    pub shredstream_url: String,
     pub simulator_url: String,
-    pub ws_simulator_url: String,
     pub payer_keypair_path: String,
+    pub signer_url: String,
+    pub signer_pubkey: String,
+    pub database_url: String,
     pub database_name: String,
-
+    pub birdeye_api_key: String,
+    pub jupiter_api_key: String,
+    pub cold_wallet_address: String,
+    pub telegram_bot_token: String,
+    pub telegram_chat_id: String,
+    pub discord_webhook_url: String,
+    pub slack_webhook_url: String,
+    pub google_service_account_file: String,
+    pub google_sheet_id: String,
+    pub google_sheet_tab: String,
+    pub heartbeat_url: String,
+    pub sentry_dsn: String,
+    // "true" to let the executor pool send real transactions, it only simulates otherwise
+    pub live_trading: String,
+    // Bearer token of the HTTP and gRPC control APIs, required to serve them on another interface than loopback
+    pub control_api_token: String,
+    // Lookup table of the wallet the warm-up extends with the accounts of new paths, no warm-up when unset
+    pub warmup_lut_address: String,
 }
 
 impl Env {
     pub fn new() -> Self {
         Env {
             block_engine_url: get_env("BLOCK_ENGINE_URL"),
+            jito_auth_token: get_env("JITO_AUTH_TOKEN"),
             rpc_url: get_env("RPC_URL"),
+            rpc_url_fallbacks: get_env("RPC_URL_FALLBACKS"),
             mainnet_rpc_url: get_env("MAINNET_RPC_URL"),
             rpc_url_tx: get_env("RPC_URL_TX"),
+            private_rpc_url: get_env("PRIVATE_RPC_URL"),
             devnet_rpc_url: get_env("DEVNET_RPC_URL"),
             wss_rpc_url: get_env("WSS_RPC_URL"),
             geyser_url: get_env("GEYSER_URL"),
             geyser_access_token: get_env("GEYSER_ACCESS_TOKEN"),
+            shredstream_url: get_env("SHREDSTREAM_URL"),
             simulator_url: get_env("SIMULATOR_URL"),
-            ws_simulator_url: get_env("WS_SIMULATOR_URL"),
             payer_keypair_path: get_env("PAYER_KEYPAIR_PATH"),
-            database_name: get_env("DATABASE_NAME")
+            signer_url: get_env("SIGNER_URL"),
+            signer_pubkey: get_env("SIGNER_PUBKEY"),
+            database_url: get_env("DATABASE_URL"),
+            database_name: get_env("DATABASE_NAME"),
+            birdeye_api_key: get_env("BIRDEYE_API_KEY"),
+            jupiter_api_key: get_env("JUPITER_API_KEY"),
+            cold_wallet_address: get_env("COLD_WALLET_ADDRESS"),
+            telegram_bot_token: get_env("TELEGRAM_BOT_TOKEN"),
+            telegram_chat_id: get_env("TELEGRAM_CHAT_ID"),
+            discord_webhook_url: get_env("DISCORD_WEBHOOK_URL"),
+            slack_webhook_url: get_env("SLACK_WEBHOOK_URL"),
+            google_service_account_file: get_env("GOOGLE_SERVICE_ACCOUNT_FILE"),
+            google_sheet_id: get_env("GOOGLE_SHEET_ID"),
+            google_sheet_tab: get_env("GOOGLE_SHEET_TAB"),
+            heartbeat_url: get_env("HEARTBEAT_URL"),
+            sentry_dsn: get_env("SENTRY_DSN"),
+            live_trading: get_env("LIVE_TRADING"),
+            control_api_token: get_env("CONTROL_API_TOKEN"),
+            warmup_lut_address: get_env("WARMUP_LUT_ADDRESS"),
         }
     }
 }
+
+pub static SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+pub static USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+pub static USDT_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
+pub static MSOL_MINT: &str = "mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So";
+pub static JITOSOL_MINT: &str = "J1toso1uCk3RLmjorhTtrVwY9HJ7X8V9yYac6Y7kGCPn";
+pub static BSOL_MINT: &str = "bSo13r4TkiE4KumL71LsHTPpL2euBYLFx6h9HP3piy1";
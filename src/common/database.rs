@@ -1,38 +1,113 @@
-use log::info;
-use mongodb::bson::{doc, Document};
-use mongodb::Collection;
-use mongodb::{Client as MongoDbCLient, options::ClientOptions};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 
+use crate::arbitrage::experiments::ExperimentReport;
+use crate::arbitrage::research::ResearchRecord;
+use crate::arbitrage::submissions::SubmissionFingerprint;
+use crate::monitoring::daily_summary::DailySummary;
+use crate::monitoring::landing::LandingRecord;
+use crate::monitoring::pnl::PnlRecord;
 use crate::arbitrage::types::{SwapPathResult, VecSwapPathSelected};
+use crate::markets::types::DexLabel;
+use crate::storage::{storage, TradeQuery};
 
-pub async fn insert_swap_path_result_collection(collection_name: &str, sp_result: SwapPathResult) -> Result<()> {
-    let db_name = "MEV_Bot";
-    let client_options = ClientOptions::parse("mongodb://localhost:27017").await.unwrap();
-    let client = MongoDbCLient::with_options(client_options).unwrap();
-
-    let db = client.database(db_name);
-    let coll: Collection<SwapPathResult> = db.collection::<SwapPathResult>(collection_name);
-    
+pub static TRADES_COLLECTION: &str = "trades";
 
-    coll.insert_one(sp_result, None).await.unwrap();
-    info!("📊 {} writed in DB", collection_name);
-    Ok(())
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TradeStatus {
+    Landed,
+    // Landed with an error, fees paid
+    Failed,
+    Expired,
+    // Older records, the missing statuses are Unknown now
+    RolledBack,
+    // Status missing after being processed, the wallet is reconciled
+    Unknown,
+    // Rejected by the sender, never reached a leader
+    SendFailed,
 }
-pub async fn insert_vec_swap_path_selected_collection(collection_name: &str, best_paths_for_strat: VecSwapPathSelected) -> Result<()> {
-    for bp in best_paths_for_strat.value.iter().enumerate() {
-
-    }
-    let db_name = "MEV_Bot";
 
-    let client_options = ClientOptions::parse("mongodb://localhost:27017").await.unwrap();
-    let client = MongoDbCLient::with_options(client_options).unwrap();
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeHop {
+    pub pool_address: String,
+    pub dex: DexLabel,
+    pub token_in: String,
+    pub token_out: String,
+    pub amount_in: u64,
+    // Simulated amounts, the realized ones are in the verifier calibration
+    pub amount_out: String,
+    pub min_amount_out: String,
+}
 
-    let db = client.database(db_name);
-    let coll: Collection<VecSwapPathSelected> = db.collection::<VecSwapPathSelected>(collection_name);
-    
-    coll.insert_one(best_paths_for_strat, None).await.unwrap();
-    info!("📊 {} writed in DB", collection_name);
+// One document per swap transaction sent, in the "trades" collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeRecord {
+    pub signature: String,
+    // Unix seconds
+    pub timestamp: i64,
+    pub strategy: String,
+    pub path_id: u32,
+    pub tokens_path: String,
+    pub hops: Vec<TradeHop>,
+    // Base + priority fees, lamports
+    pub fee_lamports: u64,
+    pub tip_lamports: u64,
+    pub compute_unit_price: u64,
+    pub compute_units: u64,
+    pub slot: Option<u64>,
+    pub status: TradeStatus,
+    pub error: Option<String>,
+}
 
-    Ok(())
-}
\ No newline at end of file
+// Thin wrappers over the configured storage backend, see crate::storage
+pub async fn insert_swap_path_result_collection(collection_name: &str, sp_result: SwapPathResult) -> Result<()> {
+    storage()?.insert_swap_path_result(collection_name, sp_result).await
+}
+pub async fn insert_vec_swap_path_selected_collection(collection_name: &str, best_paths_for_strat: VecSwapPathSelected) -> Result<()> {
+    storage()?.insert_best_paths(collection_name, best_paths_for_strat).await
+}
+pub async fn insert_experiment_report(collection_name: &str, report: ExperimentReport) -> Result<()> {
+    storage()?.insert_experiment_report(collection_name, report).await
+}
+pub async fn insert_submission_fingerprint(collection_name: &str, fingerprint: SubmissionFingerprint) -> Result<()> {
+    storage()?.insert_submission_fingerprint(collection_name, fingerprint).await
+}
+pub async fn find_submission_fingerprints_since(collection_name: &str, since_slot: u64) -> Result<Vec<SubmissionFingerprint>> {
+    storage()?.find_submission_fingerprints_since(collection_name, since_slot).await
+}
+pub async fn insert_research_record(collection_name: &str, record: ResearchRecord) -> Result<()> {
+    storage()?.insert_research_record(collection_name, record).await
+}
+pub async fn insert_landing_record(collection_name: &str, record: LandingRecord) -> Result<()> {
+    storage()?.insert_landing_record(collection_name, record).await
+}
+pub async fn insert_daily_summary(collection_name: &str, summary: DailySummary) -> Result<()> {
+    storage()?.insert_daily_summary(collection_name, summary).await
+}
+pub async fn insert_pnl_record(collection_name: &str, record: PnlRecord) -> Result<()> {
+    storage()?.insert_pnl_record(collection_name, record).await
+}
+pub async fn find_pnl_records_since(collection_name: &str, since_timestamp: i64) -> Result<Vec<PnlRecord>> {
+    storage()?.find_pnl_records_since(collection_name, since_timestamp).await
+}
+pub async fn insert_trade(trade: TradeRecord) -> Result<()> {
+    storage()?.insert_trade(trade).await
+}
+pub async fn find_trade_by_signature(signature: &str) -> Result<Option<TradeRecord>> {
+    storage()?.find_trade_by_signature(signature).await
+}
+pub async fn find_trades(query: TradeQuery) -> Result<Vec<TradeRecord>> {
+    storage()?.find_trades(query).await
+}
+pub async fn find_trades_since(since_timestamp: i64) -> Result<Vec<TradeRecord>> {
+    find_trades(TradeQuery { since_timestamp, ..TradeQuery::default() }).await
+}
+pub async fn find_trades_by_strategy(strategy: &str, since_timestamp: i64) -> Result<Vec<TradeRecord>> {
+    find_trades(TradeQuery { since_timestamp, strategy: Some(strategy.to_string()), ..TradeQuery::default() }).await
+}
+pub async fn find_trades_by_status(status: TradeStatus, since_timestamp: i64) -> Result<Vec<TradeRecord>> {
+    find_trades(TradeQuery { since_timestamp, status: Some(status), ..TradeQuery::default() }).await
+}
+pub async fn find_trades_through_pool(pool_address: &str, since_timestamp: i64) -> Result<Vec<TradeRecord>> {
+    find_trades(TradeQuery { since_timestamp, pool_address: Some(pool_address.to_string()), ..TradeQuery::default() }).await
+}
@@ -1,6 +1,22 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufReader, Seek, SeekFrom, Read};
+use std::io::Write as _;
+
+use anyhow::{anyhow, Result};
+use borsh::BorshDeserialize;
 use serde_json::Value;
+use solana_client::rpc_client::RpcClient;
+use tokio::io::{AsyncBufReadExt, BufReader as AsyncBufReader};
+
+use crate::arbitrage::types::{Route, SwapPathResult, SwapRouteSimulation, TokenInArb, TokenInfos};
+use crate::common::utils::{from_str, get_tokens_infos};
+use crate::markets::meteora::{simulate_route_meteora, AccountData};
+use crate::markets::orca_whirpools::{simulate_route_orca_whirpools, unpack_from_slice};
+use crate::markets::pools::load_all_pools;
+use crate::markets::raydium::{simulate_route_raydium, AmmInfo};
+use crate::markets::types::{DexLabel, Market};
+use crate::transactions::create_transaction::construct_transaction;
 
 pub fn print_json_segment(file_path: &str, start: u64, length: usize) -> Result<(), Box<dyn std::error::Error>> {
     let file = File::open(file_path)?;
@@ -14,4 +30,182 @@ pub fn print_json_segment(file_path: &str, start: u64, length: usize) -> Result<
     println!("{}", segment);
 
     Ok(())
-}
\ No newline at end of file
+}
+
+pub static DEBUG_HELP: &str = "\
+pools <mint>                          loaded pools trading the mint
+pool <address>                        decoded on-chain state of a pool
+quote <amount> <mint in> <pool>...    quote the amount through the pools, in order
+accounts <amount> <mint in> <pool>... accounts of the swap instructions of the route
+help
+quit";
+
+// Interactive console over the cached pools lists, for the `debug` subcommand
+pub struct DebugConsole {
+    rpc_client: RpcClient,
+    // By pool address
+    markets: HashMap<String, Market>,
+}
+
+impl DebugConsole {
+    pub async fn load(rpc_url: &str) -> Result<Self> {
+        let mut markets = HashMap::new();
+        for dex in load_all_pools(false).await {
+            for market in dex.pairToMarkets.into_values().flatten() {
+                markets.insert(market.id.clone(), market);
+            }
+        }
+        println!("{} pools loaded", markets.len());
+        Ok(DebugConsole { rpc_client: RpcClient::new(rpc_url.to_string()), markets })
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        println!("{}", DEBUG_HELP);
+        let mut lines = AsyncBufReader::new(tokio::io::stdin()).lines();
+        loop {
+            print!("debug> ");
+            std::io::stdout().flush()?;
+            let Some(line) = lines.next_line().await? else { break };
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let result = match words.as_slice() {
+                [] => Ok(()),
+                ["quit"] | ["exit"] => break,
+                ["help"] => {
+                    println!("{}", DEBUG_HELP);
+                    Ok(())
+                }
+                ["pools", mint] => {
+                    self.print_pools(mint);
+                    Ok(())
+                }
+                ["pool", address] => self.print_pool_state(address),
+                ["quote", amount, mint_in, pools @ ..] if !pools.is_empty() => self.quote(amount, mint_in, pools).await.map(|spr| {
+                    for route in spr.route_simulations.iter() {
+                        println!("{} {}: {} {} -> {} {} (min {})", route.dex_label.str(), route.pool_address, route.amount_in, route.token_in, route.estimated_amount_out, route.token_out, route.estimated_min_amount_out);
+                    }
+                    println!("Result: {}", spr.result);
+                }),
+                ["accounts", amount, mint_in, pools @ ..] if !pools.is_empty() => self.print_accounts(amount, mint_in, pools).await,
+                _ => Err(anyhow!("unknown command, try help")),
+            };
+            if let Err(e) = result {
+                println!("❌ {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    fn print_pools(&self, mint: &str) {
+        let mut pools: Vec<&Market> = self.markets.values().filter(|market| market.tokenMintA == mint || market.tokenMintB == mint).collect();
+        pools.sort_by_key(|market| std::cmp::Reverse(market.liquidity.unwrap_or(0)));
+        for market in pools.iter() {
+            let other = if market.tokenMintA == mint { &market.tokenMintB } else { &market.tokenMintA };
+            println!("{} {} {} fee {} liquidity {}", market.dexLabel.str(), market.id, other, market.fee, market.liquidity.unwrap_or(0));
+        }
+        println!("{} pools", pools.len());
+    }
+
+    fn print_pool_state(&self, address: &str) -> Result<()> {
+        let market = self.market(address)?;
+        let account = self.rpc_client.get_account(&from_str(address)?)?;
+        match market.dexLabel {
+            DexLabel::RAYDIUM => println!("{:#?}", AmmInfo::try_from_slice(&account.data)?),
+            DexLabel::ORCA_WHIRLPOOLS => println!("{:#?}", unpack_from_slice(&account.data)?),
+            DexLabel::METEORA => println!("{:#?}", AccountData::try_from_slice(&account.data)?),
+            _ => println!("No decoder for {}, {} bytes owned by {}", market.dexLabel.str(), account.data.len(), account.owner),
+        }
+        Ok(())
+    }
+
+    fn market(&self, address: &str) -> Result<&Market> {
+        self.markets.get(address).ok_or_else(|| anyhow!("pool {} not loaded", address))
+    }
+
+    // Route of the pools, in order, starting with `mint_in`
+    fn routes(&self, mint_in: &str, pools: &[&str]) -> Result<Vec<(Route, Market)>> {
+        let mut token_in = mint_in.to_string();
+        let mut routes = Vec::new();
+        for (i, address) in pools.iter().enumerate() {
+            let market = self.market(address)?;
+            let token_0to1 = market.tokenMintA == token_in;
+            if !token_0to1 && market.tokenMintB != token_in {
+                return Err(anyhow!("pool {} doesn't trade {}", address, token_in));
+            }
+            let token_out = if token_0to1 { market.tokenMintB.clone() } else { market.tokenMintA.clone() };
+            let route = Route {
+                id: i as u32,
+                dex: market.dexLabel.clone(),
+                pool_address: market.id.clone(),
+                token_0to1,
+                tokenIn: token_in.clone(),
+                tokenOut: token_out.clone(),
+                fee: market.fee,
+            };
+            routes.push((route, market.clone()));
+            token_in = token_out;
+        }
+        Ok(routes)
+    }
+
+    async fn quote(&self, amount: &str, mint_in: &str, pools: &[&str]) -> Result<SwapPathResult> {
+        let amount_in: u64 = amount.parse()?;
+        let routes = self.routes(mint_in, pools)?;
+        let mut tokens: Vec<TokenInArb> = vec![TokenInArb { address: mint_in.to_string(), symbol: short(mint_in) }];
+        tokens.extend(routes.iter().map(|(route, _)| TokenInArb { address: route.tokenOut.clone(), symbol: short(&route.tokenOut) }));
+        let tokens_infos: HashMap<String, TokenInfos> = get_tokens_infos(tokens.clone()).await;
+
+        let mut amount = amount_in;
+        let mut route_simulations: Vec<SwapRouteSimulation> = Vec::new();
+        for (route, mut market) in routes {
+            market.account_data = Some(self.rpc_client.get_account(&from_str(&market.id)?)?.data);
+            let (amount_out, min_amount_out) = match route.dex {
+                DexLabel::RAYDIUM => simulate_route_raydium(false, amount, route.clone(), market, &tokens_infos).await?,
+                DexLabel::ORCA_WHIRLPOOLS => simulate_route_orca_whirpools(false, amount, route.clone(), market, &tokens_infos).await?,
+                DexLabel::METEORA => simulate_route_meteora(false, amount, route.clone(), market, &tokens_infos).await?,
+                _ => return Err(anyhow!("no quote for {} pools", route.dex.str())),
+            };
+            route_simulations.push(SwapRouteSimulation {
+                id_route: route.id,
+                pool_address: route.pool_address.clone(),
+                dex_label: route.dex.clone(),
+                token_0to1: route.token_0to1,
+                token_in: route.tokenIn.clone(),
+                token_out: route.tokenOut.clone(),
+                amount_in: amount,
+                estimated_amount_out: amount_out.clone(),
+                estimated_min_amount_out: min_amount_out,
+            });
+            amount = amount_out.parse()?;
+        }
+        let last = route_simulations.last().ok_or_else(|| anyhow!("empty route"))?.clone();
+        Ok(SwapPathResult {
+            path_id: 0,
+            hops: route_simulations.len() as u8,
+            tokens_path: tokens.iter().map(|token| token.symbol.clone()).collect::<Vec<String>>().join("-"),
+            token_in: mint_in.to_string(),
+            token_in_symbol: short(mint_in),
+            token_out: last.token_out.clone(),
+            token_out_symbol: short(&last.token_out),
+            amount_in,
+            estimated_amount_out: last.estimated_amount_out.clone(),
+            estimated_min_amount_out: last.estimated_min_amount_out.clone(),
+            result: amount as f64 - amount_in as f64,
+            route_simulations,
+        })
+    }
+
+    async fn print_accounts(&self, amount: &str, mint_in: &str, pools: &[&str]) -> Result<()> {
+        let spr = self.quote(amount, mint_in, pools).await?;
+        for details in construct_transaction(spr).await {
+            println!("{} ({})", details.details, details.instruction.program_id);
+            for account in details.instruction.accounts.iter() {
+                println!("  {} {}{}", account.pubkey, if account.is_signer { "signer " } else { "" }, if account.is_writable { "writable" } else { "readonly" });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn short(mint: &str) -> String {
+    mint.chars().take(6).collect()
+}
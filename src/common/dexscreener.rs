@@ -0,0 +1,103 @@
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::common::rate_limiter::throttle;
+use crate::common::rpc_cache::cached_http_json;
+
+pub static DEXSCREENER_API_URL: &str = "https://api.dexscreener.com";
+
+// Addresses per request of the tokens endpoint
+pub static DEXSCREENER_TOKENS_BATCH: usize = 30;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DexScreenerBoost {
+    pub chain_id: String,
+    pub token_address: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DexScreenerPairsResponse {
+    pub pairs: Option<Vec<DexScreenerPair>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DexScreenerPair {
+    pub chain_id: String,
+    // e.g. "raydium", "orca", "meteora"
+    pub dex_id: String,
+    // The pool account on Solana
+    pub pair_address: String,
+    // Pool flavour within the DEX, e.g. "CLMM", "CPMM", "wp", "DLMM"
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub base_token: DexScreenerToken,
+    pub quote_token: DexScreenerToken,
+    pub price_usd: Option<String>,
+    pub liquidity: Option<DexScreenerLiquidity>,
+    pub volume: Option<DexScreenerVolume>,
+}
+
+impl DexScreenerPair {
+    pub fn liquidity_usd(&self) -> f64 {
+        self.liquidity.as_ref().and_then(|liquidity| liquidity.usd).unwrap_or(0.0)
+    }
+
+    pub fn volume_24h_usd(&self) -> f64 {
+        self.volume.as_ref().and_then(|volume| volume.h24).unwrap_or(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DexScreenerToken {
+    pub address: String,
+    pub symbol: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DexScreenerLiquidity {
+    pub usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DexScreenerVolume {
+    pub h24: Option<f64>,
+}
+
+// Keyless DexScreener API, Solana pairs only. Throttled by the rate limiter (dexscreener.com host),
+// pair lists kept in the response cache
+#[derive(Debug, Clone, Default)]
+pub struct DexScreenerClient {}
+
+impl DexScreenerClient {
+    pub fn new() -> Self {
+        DexScreenerClient {}
+    }
+
+    // Mints of the top boosted tokens
+    pub async fn boosted_tokens(&self) -> Result<Vec<String>> {
+        let url = format!("{}/token-boosts/top/v1", DEXSCREENER_API_URL);
+        throttle(&url).await;
+        let boosted: Vec<DexScreenerBoost> = reqwest::get(&url).await?.json().await?;
+        Ok(boosted.into_iter().filter(|boost| boost.chain_id == "solana").map(|boost| boost.token_address).collect())
+    }
+
+    // Every live pair of a mint, on any DEX, whichever side the mint is on
+    pub async fn token_pairs(&self, mint: &str) -> Result<Vec<DexScreenerPair>> {
+        let url = format!("{}/token-pairs/v1/solana/{}", DEXSCREENER_API_URL, mint);
+        let pairs: Vec<DexScreenerPair> = cached_http_json(&url).await?;
+        Ok(pairs.into_iter().filter(|pair| pair.chain_id == "solana").collect())
+    }
+
+    // Pairs of several mints, batched by DEXSCREENER_TOKENS_BATCH
+    pub async fn tokens_pairs(&self, mints: &[String]) -> Result<Vec<DexScreenerPair>> {
+        let mut pairs = Vec::new();
+        for chunk in mints.chunks(DEXSCREENER_TOKENS_BATCH) {
+            let url = format!("{}/latest/dex/tokens/{}", DEXSCREENER_API_URL, chunk.join(","));
+            let response: DexScreenerPairsResponse = cached_http_json(&url).await?;
+            pairs.extend(response.pairs.unwrap_or_default().into_iter().filter(|pair| pair.chain_id == "solana"));
+        }
+        Ok(pairs)
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use log::{error, info};
+
+use crate::common::constants::Env;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeedKind {
+    // Account and logs subscriptions of the RPC (WSS_RPC_URL)
+    AccountWs,
+    Geyser,
+    Shredstream,
+    JitoBlockEngine,
+}
+
+impl FeedKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FeedKind::AccountWs => "account WebSocket",
+            FeedKind::Geyser => "Geyser",
+            FeedKind::Shredstream => "shredstream",
+            FeedKind::JitoBlockEngine => "Jito block engine",
+        }
+    }
+}
+
+// How the token of a feed is sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeedAuth {
+    None,
+    // e.g. x-token for Geyser, x-jito-auth for the block engine
+    Header { name: String, token: String },
+    // e.g. api-key on the WebSocket url of the RPC providers
+    Query { param: String, token: String },
+}
+
+// Wait before connecting again after a failure, multiplied on every failure in a row up to the max
+#[derive(Debug, Clone, Copy)]
+pub struct BackoffPolicy {
+    pub initial: Duration,
+    pub max: Duration,
+    pub multiplier: u32,
+    // Failures in a row before giving up on the feed, None to retry forever
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        BackoffPolicy { initial: Duration::from_millis(500), max: Duration::from_secs(30), multiplier: 2, max_attempts: None }
+    }
+}
+
+impl BackoffPolicy {
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.initial.saturating_mul(self.multiplier.saturating_pow(attempt)).min(self.max)
+    }
+
+    pub fn is_exhausted(&self, failures: u32) -> bool {
+        self.max_attempts.is_some_and(|max_attempts| failures >= max_attempts)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FeedEndpoint {
+    pub url: String,
+    pub auth: FeedAuth,
+    pub backoff: BackoffPolicy,
+}
+
+impl FeedEndpoint {
+    pub fn new(url: &str) -> Self {
+        FeedEndpoint { url: url.to_string(), auth: FeedAuth::None, backoff: BackoffPolicy::default() }
+    }
+
+    pub fn with_auth(mut self, auth: FeedAuth) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    pub fn with_backoff(mut self, backoff: BackoffPolicy) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    // Url to connect to, with the token when it goes in the query
+    pub fn authenticated_url(&self) -> String {
+        match &self.auth {
+            FeedAuth::Query { param, token } => {
+                let separator = if self.url.contains('?') { '&' } else { '?' };
+                format!("{}{}{}={}", self.url, separator, param, token)
+            }
+            _ => self.url.clone(),
+        }
+    }
+
+    pub fn auth_header(&self) -> Option<(&str, &str)> {
+        match &self.auth {
+            FeedAuth::Header { name, token } => Some((name.as_str(), token.as_str())),
+            _ => None,
+        }
+    }
+}
+
+// Endpoint of every configured feed, a feed without url is off
+#[derive(Debug, Clone, Default)]
+pub struct FeedsConfig {
+    pub endpoints: HashMap<FeedKind, FeedEndpoint>,
+}
+
+impl FeedsConfig {
+    // WSS_RPC_URL, GEYSER_URL (+ GEYSER_ACCESS_TOKEN), SHREDSTREAM_URL and BLOCK_ENGINE_URL (+ JITO_AUTH_TOKEN)
+    pub fn from_env() -> Self {
+        let env = Env::new();
+        let header = |name: &str, token: &str| if token.is_empty() { FeedAuth::None } else { FeedAuth::Header { name: name.to_string(), token: token.to_string() } };
+        [
+            (FeedKind::AccountWs, &env.wss_rpc_url, FeedAuth::None),
+            (FeedKind::Geyser, &env.geyser_url, header("x-token", &env.geyser_access_token)),
+            (FeedKind::Shredstream, &env.shredstream_url, FeedAuth::None),
+            (FeedKind::JitoBlockEngine, &env.block_engine_url, header("x-jito-auth", &env.jito_auth_token)),
+        ]
+        .into_iter()
+        .filter(|(_, url, _)| !url.is_empty())
+        .fold(FeedsConfig::default(), |config, (kind, url, auth)| config.with_feed(kind, FeedEndpoint::new(url).with_auth(auth)))
+    }
+
+    pub fn with_feed(mut self, kind: FeedKind, endpoint: FeedEndpoint) -> Self {
+        self.endpoints.insert(kind, endpoint);
+        self
+    }
+
+    // Same backoff for every feed
+    pub fn with_backoff(mut self, backoff: BackoffPolicy) -> Self {
+        for endpoint in self.endpoints.values_mut() {
+            endpoint.backoff = backoff;
+        }
+        self
+    }
+
+    pub fn with_feed_backoff(mut self, kind: FeedKind, backoff: BackoffPolicy) -> Self {
+        if let Some(endpoint) = self.endpoints.get_mut(&kind) {
+            endpoint.backoff = backoff;
+        }
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FeedStatus {
+    pub sessions: u64,
+    pub failures: u64,
+    pub last_error: Option<String>,
+}
+
+// Connections of every feed, opened and reopened with the backoff of their endpoint
+#[derive(Debug)]
+pub struct FeedManager {
+    config: FeedsConfig,
+    statuses: Mutex<HashMap<FeedKind, FeedStatus>>,
+}
+
+impl FeedManager {
+    pub fn new(config: FeedsConfig) -> Self {
+        FeedManager { config, statuses: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn endpoint(&self, kind: FeedKind) -> Option<FeedEndpoint> {
+        self.config.endpoints.get(&kind).cloned()
+    }
+
+    pub fn url(&self, kind: FeedKind) -> Option<String> {
+        self.config.endpoints.get(&kind).map(|endpoint| endpoint.authenticated_url())
+    }
+
+    pub fn statuses(&self) -> HashMap<FeedKind, FeedStatus> {
+        self.statuses.lock().unwrap().clone()
+    }
+
+    fn record_session(&self, kind: FeedKind) {
+        self.statuses.lock().unwrap().entry(kind).or_default().sessions += 1;
+    }
+
+    fn record_failure(&self, kind: FeedKind, error: &anyhow::Error) {
+        let mut statuses = self.statuses.lock().unwrap();
+        let status = statuses.entry(kind).or_default();
+        status.failures += 1;
+        status.last_error = Some(error.to_string());
+    }
+
+    // Connection to a feed which stays up by itself, None when the feed is off or its attempts are exhausted
+    pub async fn connect<T, F, Fut>(&self, kind: FeedKind, mut connect: F) -> Option<T>
+    where
+        F: FnMut(FeedEndpoint) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let endpoint = self.endpoint(kind)?;
+        let mut failures = 0;
+        loop {
+            match connect(endpoint.clone()).await {
+                Ok(connection) => {
+                    self.record_session(kind);
+                    info!("🔗 {} feed connected to {}", kind.name(), endpoint.url);
+                    return Some(connection);
+                }
+                Err(e) => {
+                    self.record_failure(kind, &e);
+                    failures += 1;
+                    if endpoint.backoff.is_exhausted(failures) {
+                        error!("❌ {} feed {} given up after {} failures: {:?}", kind.name(), endpoint.url, failures, e);
+                        return None;
+                    }
+                    let delay = endpoint.backoff.delay(failures - 1);
+                    error!("❌ {} feed {} failed: {:?}, reconnecting in {:?}", kind.name(), endpoint.url, e, delay);
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    // Keep a streaming feed open: `session` runs until the stream ends (Ok) or breaks (Err), then it is opened again.
+    // A session lasting longer than the max backoff resets the failure count
+    pub async fn run<F, Fut>(&self, kind: FeedKind, mut session: F)
+    where
+        F: FnMut(FeedEndpoint) -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        let Some(endpoint) = self.endpoint(kind) else { return };
+        let mut failures = 0;
+        loop {
+            self.record_session(kind);
+            let started_at = Instant::now();
+            let result = session(endpoint.clone()).await;
+            if started_at.elapsed() > endpoint.backoff.max {
+                failures = 0;
+            }
+            match result {
+                Ok(()) => {
+                    info!("🔗 {} feed {} closed, reconnecting", kind.name(), endpoint.url);
+                    tokio::time::sleep(endpoint.backoff.initial).await;
+                }
+                Err(e) => {
+                    self.record_failure(kind, &e);
+                    failures += 1;
+                    if endpoint.backoff.is_exhausted(failures) {
+                        error!("❌ {} feed {} given up after {} failures: {:?}", kind.name(), endpoint.url, failures, e);
+                        return;
+                    }
+                    let delay = endpoint.backoff.delay(failures - 1);
+                    error!("❌ {} feed {} failed: {:?}, reconnecting in {:?}", kind.name(), endpoint.url, e, delay);
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+}
+
+static FEEDS: OnceLock<FeedManager> = OnceLock::new();
+
+// To call once at startup, the feeds are read from the env with the default backoff otherwise
+pub fn init_feeds(config: FeedsConfig) -> &'static FeedManager {
+    let mut kinds: Vec<&str> = config.endpoints.keys().map(|kind| kind.name()).collect();
+    kinds.sort();
+    info!("🔗 Data feeds configured: {:?}", kinds);
+    FEEDS.get_or_init(|| FeedManager::new(config))
+}
+
+pub fn feeds() -> &'static FeedManager {
+    FEEDS.get_or_init(|| FeedManager::new(FeedsConfig::from_env()))
+}
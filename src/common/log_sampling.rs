@@ -0,0 +1,76 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Summaries of the sampled lines of the hot paths
+pub static SAMPLED_LOG_INTERVAL: Duration = Duration::from_secs(60);
+
+// State of one sampled log line, a static next to the call site (see log_every_n! and log_every!)
+pub struct LogSampler {
+    occurrences: AtomicU64,
+    // Last line logged, occurrences at that time
+    last_logged: Mutex<Option<(Instant, u64)>>,
+}
+
+impl LogSampler {
+    pub const fn new() -> Self {
+        LogSampler { occurrences: AtomicU64::new(0), last_logged: Mutex::new(None) }
+    }
+
+    // Occurrences so far on the 1st, n+1th, 2n+1th... occurrence
+    pub fn every_n(&self, n: u64) -> Option<u64> {
+        let occurrences = self.occurrences.fetch_add(1, Ordering::Relaxed) + 1;
+        ((occurrences - 1) % n.max(1) == 0).then_some(occurrences)
+    }
+
+    // Occurrences suppressed since the last line, at most once per interval
+    pub fn every(&self, interval: Duration) -> Option<u64> {
+        let occurrences = self.occurrences.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut last_logged = self.last_logged.lock().unwrap();
+        match *last_logged {
+            Some((at, _)) if at.elapsed() < interval => None,
+            Some((_, logged)) => {
+                *last_logged = Some((Instant::now(), occurrences));
+                Some(occurrences - logged - 1)
+            }
+            None => {
+                *last_logged = Some((Instant::now(), occurrences));
+                Some(0)
+            }
+        }
+    }
+}
+
+impl Default for LogSampler {
+    fn default() -> Self {
+        LogSampler::new()
+    }
+}
+
+// Log the 1st, n+1th, 2n+1th... occurrence of the line with its count:
+// log_every_n!(Level::Info, 100, "📌 Route {} from cache", id)
+#[macro_export]
+macro_rules! log_every_n {
+    ($level:expr, $n:expr, $($arg:tt)+) => {{
+        static SAMPLER: $crate::common::log_sampling::LogSampler = $crate::common::log_sampling::LogSampler::new();
+        if let Some(occurrences) = SAMPLER.every_n($n) {
+            ::log::log!($level, "{} (x{})", format_args!($($arg)+), occurrences);
+        }
+    }};
+}
+
+// Log the line at most once per interval, with the number of occurrences suppressed in between:
+// log_every!(Level::Error, SAMPLED_LOG_INTERVAL, "❌ Quote failed: {}", e)
+#[macro_export]
+macro_rules! log_every {
+    ($level:expr, $interval:expr, $($arg:tt)+) => {{
+        static SAMPLER: $crate::common::log_sampling::LogSampler = $crate::common::log_sampling::LogSampler::new();
+        if let Some(suppressed) = SAMPLER.every($interval) {
+            if suppressed > 0 {
+                ::log::log!($level, "{} (+{} similar)", format_args!($($arg)+), suppressed);
+            } else {
+                ::log::log!($level, "{}", format_args!($($arg)+));
+            }
+        }
+    }};
+}
@@ -27,4 +27,7 @@ pub fn from_x64_orca_wp(num: u128, decimals_0: f64, decimals_1: f64) -> Decimal
     //       .mul(Decimal.pow(10, decimalsA - decimalsB));
     //   }
     
-}
\ No newline at end of file
+}
+
+// The integer swap math lives in quote-core, shared with the wasm build
+pub use quote_core::maths::*;
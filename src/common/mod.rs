@@ -4,4 +4,16 @@ pub mod utils;
 pub mod maths;
 pub mod debug;
 pub mod types;
-pub mod database;
\ No newline at end of file
+pub mod database;
+pub mod rate_limiter;
+pub mod circuit_breaker;
+pub mod tasks;
+pub mod log_sampling;pub mod rpc_pool;
+pub mod token_cache;
+pub mod compute;
+pub mod rpc_cache;
+pub mod rpc_race;
+pub mod feeds;
+pub mod prices;
+pub mod birdeye;
+pub mod dexscreener;
@@ -0,0 +1,160 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use log::{error, info};
+use serde::Deserialize;
+
+use crate::common::constants::{Env, SOL_MINT};
+use crate::common::rate_limiter::throttle;
+use crate::common::token_cache::token_info_cache;
+
+// Ids per request of the Jupiter price API
+pub static JUPITER_PRICE_BATCH: usize = 50;
+
+// USD prices of mints from an external source
+#[async_trait]
+pub trait PriceProvider: Send + Sync {
+    fn name(&self) -> String;
+
+    // Mints without a price are left out
+    async fn usd_prices(&self, mints: &[String]) -> Result<HashMap<String, f64>>;
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JupiterPrice {
+    usd_price: f64,
+}
+
+// Price API v3 of Jupiter: the keyless lite endpoint, or the pro one with JUPITER_API_KEY
+pub struct JupiterPriceProvider {
+    base_url: String,
+    api_key: Option<String>,
+    client: reqwest::Client,
+}
+
+impl JupiterPriceProvider {
+    pub fn new(api_key: Option<String>) -> Self {
+        let base_url = if api_key.is_some() { "https://api.jup.ag/price/v3" } else { "https://lite-api.jup.ag/price/v3" };
+        JupiterPriceProvider { base_url: base_url.to_string(), api_key, client: reqwest::Client::new() }
+    }
+
+    pub fn from_env() -> Self {
+        let env = Env::new();
+        JupiterPriceProvider::new(Some(env.jupiter_api_key).filter(|api_key| !api_key.is_empty()))
+    }
+}
+
+#[async_trait]
+impl PriceProvider for JupiterPriceProvider {
+    fn name(&self) -> String {
+        "jupiter".to_string()
+    }
+
+    async fn usd_prices(&self, mints: &[String]) -> Result<HashMap<String, f64>> {
+        let mut prices = HashMap::new();
+        for chunk in mints.chunks(JUPITER_PRICE_BATCH) {
+            let url = format!("{}?ids={}", self.base_url, chunk.join(","));
+            throttle(&url).await;
+            let mut request = self.client.get(&url);
+            if let Some(api_key) = &self.api_key {
+                request = request.header("x-api-key", api_key);
+            }
+            let response = request.send().await?;
+            if !response.status().is_success() {
+                return Err(anyhow!("Jupiter price API failed: {}", response.status()));
+            }
+            // Unknown mints are missing from the response, or null
+            let body: HashMap<String, Option<JupiterPrice>> = response.json().await?;
+            prices.extend(body.into_iter().filter_map(|(mint, price)| Some((mint, price?.usd_price))));
+        }
+        Ok(prices)
+    }
+}
+
+// Last USD price of the watched mints, SOL always included, refreshed in the background.
+// Readers never wait on the provider: a mint is valued once the next refresh has priced it
+pub struct TokenPrices {
+    provider: Arc<dyn PriceProvider>,
+    max_age: Duration,
+    watched: RwLock<HashSet<String>>,
+    prices: RwLock<HashMap<String, (f64, Instant)>>,
+}
+
+impl TokenPrices {
+    pub fn new(provider: Arc<dyn PriceProvider>, max_age: Duration) -> Self {
+        TokenPrices {
+            provider,
+            max_age,
+            watched: RwLock::new(HashSet::from([SOL_MINT.to_string()])),
+            prices: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn watch<'a>(&self, mints: impl IntoIterator<Item = &'a str>) {
+        self.watched.write().unwrap().extend(mints.into_iter().map(str::to_string));
+    }
+
+    pub async fn refresh(&self) -> Result<usize> {
+        let mints: Vec<String> = self.watched.read().unwrap().iter().cloned().collect();
+        let fetched = self.provider.usd_prices(&mints).await?;
+        let now = Instant::now();
+        let mut prices = self.prices.write().unwrap();
+        for (mint, price) in fetched.iter() {
+            prices.insert(mint.clone(), (*price, now));
+        }
+        Ok(fetched.len())
+    }
+
+    pub fn usd_price(&self, mint: &str) -> Option<f64> {
+        self.prices.read().unwrap().get(mint).filter(|(_, fetched_at)| fetched_at.elapsed() <= self.max_age).map(|(price, _)| *price)
+    }
+
+    // USD value of a raw token amount, None without price or decimals
+    pub fn usd_value(&self, mint: &str, amount: u64) -> Option<f64> {
+        let decimals = if mint == SOL_MINT { 9 } else { token_info_cache().get(mint)?.decimals };
+        Some(amount as f64 / 10f64.powi(decimals as i32) * self.usd_price(mint)?)
+    }
+
+    // SOL lamports worth a raw token amount
+    pub fn lamports_value(&self, mint: &str, amount: u64) -> Option<u64> {
+        let sol_price = self.usd_price(SOL_MINT).filter(|price| *price > 0.0)?;
+        Some((self.usd_value(mint, amount)? / sol_price * 1e9) as u64)
+    }
+
+    // Lamports of a USD amount at the current SOL price, for thresholds set in USD
+    pub fn lamports_for_usd(&self, usd: f64) -> Option<u64> {
+        let sol_price = self.usd_price(SOL_MINT).filter(|price| *price > 0.0)?;
+        Some((usd / sol_price * 1e9) as u64)
+    }
+
+    pub fn lamports_to_usd(&self, lamports: f64) -> Option<f64> {
+        Some(lamports / 1e9 * self.usd_price(SOL_MINT)?)
+    }
+}
+
+static TOKEN_PRICES: OnceLock<TokenPrices> = OnceLock::new();
+
+// To call once at startup, nothing is priced otherwise
+pub fn init_token_prices(prices: TokenPrices) -> &'static TokenPrices {
+    info!("💲 Token prices from {}", prices.provider.name());
+    TOKEN_PRICES.get_or_init(|| prices)
+}
+
+pub fn token_prices() -> Option<&'static TokenPrices> {
+    TOKEN_PRICES.get()
+}
+
+pub async fn run_price_refresh(prices: &'static TokenPrices, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        if let Err(e) = prices.refresh().await {
+            error!("❌ Price refresh failed: {:?}", e);
+        }
+    }
+}
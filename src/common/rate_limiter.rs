@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use log::{debug, info};
+
+#[derive(Debug, Clone)]
+pub struct RateLimiterConfig {
+    // Requests per second for hosts without a specific limit
+    pub default_per_sec: f64,
+    // Host suffix (ex: "quiknode.pro", "birdeye.so") -> requests per second
+    pub per_endpoint: HashMap<String, f64>,
+}
+
+impl Default for RateLimiterConfig {
+    fn default() -> Self {
+        RateLimiterConfig {
+            default_per_sec: 10.0,
+            per_endpoint: HashMap::from([
+                ("quiknode.pro".to_string(), 25.0),
+                ("birdeye.so".to_string(), 1.0),
+                ("dexscreener.com".to_string(), 4.0),
+                ("api.raydium.io".to_string(), 2.0),
+                ("meteora.ag".to_string(), 2.0),
+                ("orca.so".to_string(), 2.0),
+            ]),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    per_sec: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl Bucket {
+    // Take a token, possibly going negative: the caller waits for the debt to be refilled,
+    // so concurrent callers are served in order
+    fn reserve(&mut self) -> Duration {
+        let now = Instant::now();
+        self.tokens = (self.tokens + now.duration_since(self.last).as_secs_f64() * self.per_sec).min(self.per_sec.max(1.0));
+        self.last = now;
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.per_sec)
+        }
+    }
+}
+
+// Token bucket per host, shared by the RPC calls and the HTTP clients
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimiterConfig,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimiterConfig) -> Self {
+        RateLimiter {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn per_sec_for(&self, host: &str) -> f64 {
+        self.config
+            .per_endpoint
+            .iter()
+            .find(|(suffix, _)| host.ends_with(suffix.as_str()))
+            .map(|(_, per_sec)| *per_sec)
+            .unwrap_or(self.config.default_per_sec)
+    }
+
+    fn reserve(&self, url: &str) -> Duration {
+        let host = endpoint_host(url);
+        let per_sec = self.per_sec_for(&host);
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(host.clone()).or_insert(Bucket { per_sec, tokens: per_sec.max(1.0), last: Instant::now() });
+        let wait = bucket.reserve();
+        if !wait.is_zero() {
+            debug!("🚦 {} throttled for {:?}", host, wait);
+        }
+        wait
+    }
+
+    pub async fn acquire(&self, url: &str) {
+        let wait = self.reserve(url);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    // For the blocking RpcClient call sites
+    pub fn acquire_blocking(&self, url: &str) {
+        let wait = self.reserve(url);
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+    }
+}
+
+fn endpoint_host(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => parsed.host_str().unwrap_or(url).to_string(),
+        Err(_) => url.to_string(),
+    }
+}
+
+static RATE_LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+
+// To call once at startup, the default config is used otherwise
+pub fn init_rate_limiter(config: RateLimiterConfig) {
+    info!("🚦 Rate limiter: {} req/s by default, {:?}", config.default_per_sec, config.per_endpoint);
+    let _ = RATE_LIMITER.set(RateLimiter::new(config));
+}
+
+pub fn rate_limiter() -> &'static RateLimiter {
+    RATE_LIMITER.get_or_init(|| RateLimiter::new(RateLimiterConfig::default()))
+}
+
+pub async fn throttle(url: &str) {
+    rate_limiter().acquire(url).await
+}
+
+pub fn throttle_blocking(url: &str) {
+    rate_limiter().acquire_blocking(url)
+}
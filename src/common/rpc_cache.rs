@@ -0,0 +1,159 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use dashmap::DashMap;
+use log::info;
+use serde::de::DeserializeOwned;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_response::RpcTokenAccountBalance;
+use solana_sdk::account::Account;
+use solana_sdk::epoch_info::EpochInfo;
+use solana_sdk::pubkey::Pubkey;
+use solana_account_decoder::parse_token::UiTokenAmount;
+
+use crate::common::rate_limiter::throttle;
+
+// Past this many entries the expired ones are purged on the next insert
+pub static RPC_CACHE_PURGE_THRESHOLD: usize = 10_000;
+
+// Idempotent calls whose responses can be served again for a while
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CachedMethod {
+    TokenSupply,
+    // getAccountInfo of a mint: authorities, extensions, decimals
+    MintInfo,
+    TokenLargestAccounts,
+    EpochInfo,
+    // Pools lists and token metadata from the DEX and aggregator HTTP APIs
+    PoolMetadata,
+    // Token overview, security and candles of the analytics APIs (Birdeye)
+    TokenAnalytics,
+}
+
+impl CachedMethod {
+    pub fn default_ttl(&self) -> Duration {
+        match self {
+            CachedMethod::TokenSupply => Duration::from_secs(60),
+            CachedMethod::MintInfo => Duration::from_secs(10 * 60),
+            CachedMethod::TokenLargestAccounts => Duration::from_secs(5 * 60),
+            // An epoch lasts ~2 days, the slot index in it is only informational
+            CachedMethod::EpochInfo => Duration::from_secs(30),
+            CachedMethod::PoolMetadata => Duration::from_secs(5 * 60),
+            CachedMethod::TokenAnalytics => Duration::from_secs(2 * 60),
+        }
+    }
+}
+
+type CachedValue = Arc<dyn Any + Send + Sync>;
+
+// Responses by method and key (address, URL), each method with its own TTL.
+// Repeated strategy passes read the same mints and lists instead of spending rate limit on them
+pub struct ResponseCache {
+    ttls: HashMap<CachedMethod, Duration>,
+    entries: DashMap<(CachedMethod, String), (Instant, CachedValue)>,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        ResponseCache { ttls: HashMap::new(), entries: DashMap::new() }
+    }
+}
+
+impl ResponseCache {
+    pub fn new() -> Self {
+        ResponseCache::default()
+    }
+
+    // Zero disables the cache for the method
+    pub fn with_ttl(mut self, method: CachedMethod, ttl: Duration) -> Self {
+        self.ttls.insert(method, ttl);
+        self
+    }
+
+    pub fn ttl(&self, method: CachedMethod) -> Duration {
+        self.ttls.get(&method).copied().unwrap_or_else(|| method.default_ttl())
+    }
+
+    // The cached response when fresh, fetched and cached otherwise. Errors are never cached
+    pub async fn get_or_fetch<T, F, Fut>(&self, method: CachedMethod, key: &str, fetch: F) -> Result<T>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let ttl = self.ttl(method);
+        let cache_key = (method, key.to_string());
+        if let Some(entry) = self.entries.get(&cache_key) {
+            let (fetched_at, value) = entry.value();
+            if fetched_at.elapsed() < ttl {
+                if let Some(value) = value.downcast_ref::<T>() {
+                    return Ok(value.clone());
+                }
+            }
+        }
+        let value = fetch().await?;
+        if !ttl.is_zero() {
+            if self.entries.len() >= RPC_CACHE_PURGE_THRESHOLD {
+                self.purge_expired();
+            }
+            self.entries.insert(cache_key, (Instant::now(), Arc::new(value.clone())));
+        }
+        Ok(value)
+    }
+
+    pub fn purge_expired(&self) {
+        self.entries.retain(|(method, _), (fetched_at, _)| fetched_at.elapsed() < self.ttl(*method));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+static RPC_CACHE: OnceLock<ResponseCache> = OnceLock::new();
+
+// To call once at startup, the default TTLs apply otherwise
+pub fn init_rpc_cache(cache: ResponseCache) {
+    for method in [CachedMethod::TokenSupply, CachedMethod::MintInfo, CachedMethod::TokenLargestAccounts, CachedMethod::EpochInfo, CachedMethod::PoolMetadata, CachedMethod::TokenAnalytics] {
+        info!("🗃️  {:?} responses cached for {:?}", method, cache.ttl(method));
+    }
+    let _ = RPC_CACHE.set(cache);
+}
+
+pub fn rpc_cache() -> &'static ResponseCache {
+    RPC_CACHE.get_or_init(ResponseCache::default)
+}
+
+pub async fn cached_token_supply(rpc_client: &RpcClient, mint: &Pubkey) -> Result<UiTokenAmount> {
+    rpc_cache().get_or_fetch(CachedMethod::TokenSupply, &mint.to_string(), || async { Ok(rpc_client.get_token_supply(mint).await?) }).await
+}
+
+pub async fn cached_mint_account(rpc_client: &RpcClient, mint: &Pubkey) -> Result<Account> {
+    rpc_cache().get_or_fetch(CachedMethod::MintInfo, &mint.to_string(), || async { Ok(rpc_client.get_account(mint).await?) }).await
+}
+
+pub async fn cached_token_largest_accounts(rpc_client: &RpcClient, mint: &Pubkey) -> Result<Vec<RpcTokenAccountBalance>> {
+    rpc_cache().get_or_fetch(CachedMethod::TokenLargestAccounts, &mint.to_string(), || async { Ok(rpc_client.get_token_largest_accounts(mint).await?) }).await
+}
+
+pub async fn cached_epoch_info(rpc_client: &RpcClient) -> Result<EpochInfo> {
+    rpc_cache().get_or_fetch(CachedMethod::EpochInfo, &rpc_client.url(), || async { Ok(rpc_client.get_epoch_info().await?) }).await
+}
+
+// GET of a JSON document, throttled like every other request to the host
+pub async fn cached_http_json<T: DeserializeOwned + Clone + Send + Sync + 'static>(url: &str) -> Result<T> {
+    rpc_cache()
+        .get_or_fetch(CachedMethod::PoolMetadata, url, || async {
+            throttle(url).await;
+            Ok(reqwest::get(url).await?.json::<T>().await?)
+        })
+        .await
+}
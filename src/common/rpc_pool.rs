@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+
+use log::{error, info};
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+// RPC clients created once per endpoint and commitment, shared by every module: the HTTP
+// connections (TCP, TLS, HTTP/2 keep-alive) are reused instead of set up again on each call
+#[derive(Default)]
+pub struct RpcPool {
+    blocking: Mutex<HashMap<(String, CommitmentConfig), Arc<RpcClient>>>,
+    nonblocking: Mutex<HashMap<(String, CommitmentConfig), Arc<NonblockingRpcClient>>>,
+}
+
+impl RpcPool {
+    pub fn blocking(&self, url: &str, commitment: CommitmentConfig) -> Arc<RpcClient> {
+        self.blocking
+            .lock()
+            .unwrap()
+            .entry((url.to_string(), commitment))
+            .or_insert_with(|| Arc::new(RpcClient::new_with_commitment(url.to_string(), commitment)))
+            .clone()
+    }
+
+    pub fn nonblocking(&self, url: &str, commitment: CommitmentConfig) -> Arc<NonblockingRpcClient> {
+        self.nonblocking
+            .lock()
+            .unwrap()
+            .entry((url.to_string(), commitment))
+            .or_insert_with(|| Arc::new(NonblockingRpcClient::new_with_commitment(url.to_string(), commitment)))
+            .clone()
+    }
+}
+
+static RPC_POOL: OnceLock<RpcPool> = OnceLock::new();
+
+pub fn rpc_pool() -> &'static RpcPool {
+    RPC_POOL.get_or_init(RpcPool::default)
+}
+
+pub fn blocking_client(url: &str) -> Arc<RpcClient> {
+    rpc_pool().blocking(url, CommitmentConfig::default())
+}
+
+pub fn blocking_client_with_commitment(url: &str, commitment: CommitmentConfig) -> Arc<RpcClient> {
+    rpc_pool().blocking(url, commitment)
+}
+
+pub fn nonblocking_client(url: &str) -> Arc<NonblockingRpcClient> {
+    rpc_pool().nonblocking(url, CommitmentConfig::default())
+}
+
+pub fn nonblocking_client_with_commitment(url: &str, commitment: CommitmentConfig) -> Arc<NonblockingRpcClient> {
+    rpc_pool().nonblocking(url, commitment)
+}
+
+// One getVersion per endpoint and client at startup, so the first quote or send of the session
+// does not pay the connection setup
+pub async fn warm_up_rpc_clients(urls: &[String], commitments: &[CommitmentConfig]) {
+    for url in urls.iter().filter(|url| !url.is_empty()) {
+        for commitment in commitments {
+            let started_at = Instant::now();
+            if let Err(e) = rpc_pool().nonblocking(url, *commitment).get_version().await {
+                error!("❌ RPC warm-up failed on {}: {}", url, e);
+                continue;
+            }
+            let blocking = rpc_pool().blocking(url, *commitment);
+            match tokio::task::spawn_blocking(move || blocking.get_version()).await {
+                Ok(Ok(_)) => info!("🔥 RPC connections to {} warmed up in {:?}", url, started_at.elapsed()),
+                Ok(Err(e)) => error!("❌ RPC warm-up failed on {}: {}", url, e),
+                Err(e) => error!("❌ RPC warm-up task failed on {}: {}", url, e),
+            }
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use futures::stream::{FuturesUnordered, StreamExt};
+use log::info;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use crate::common::circuit_breaker::CircuitBreaker;
+use crate::common::rpc_pool::nonblocking_client_with_commitment;
+
+// Weight of the last round trip in the latency average of an endpoint
+pub static RACE_LATENCY_SMOOTHING: f64 = 0.2;
+
+#[derive(Debug, Clone, Default)]
+pub struct EndpointLatency {
+    // Exponential moving average of the races won: the losers are dropped before they answer
+    pub average: Duration,
+    pub races: u64,
+    pub wins: u64,
+    pub failures: u64,
+}
+
+impl EndpointLatency {
+    fn observe_win(&mut self, elapsed: Duration) {
+        self.average = if self.wins == 0 { elapsed } else { self.average.mul_f64(1.0 - RACE_LATENCY_SMOOTHING) + elapsed.mul_f64(RACE_LATENCY_SMOOTHING) };
+        self.wins += 1;
+        self.races += 1;
+    }
+
+    fn observe_loss(&mut self) {
+        self.races += 1;
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.races == 0 { 0.0 } else { self.wins as f64 / self.races as f64 }
+    }
+}
+
+// Latency-critical reads sent to several healthy endpoints of a breaker at once, the first success wins.
+// Endpoints are raced by win rate, the one measure every contender gets: it picks which ones go when
+// there are more than the fanout, the never raced ones first so that they get a sample
+#[derive(Debug)]
+pub struct RpcRacer {
+    pub fanout: usize,
+    latencies: Mutex<HashMap<String, EndpointLatency>>,
+}
+
+impl RpcRacer {
+    pub fn new(fanout: usize) -> Self {
+        RpcRacer { fanout: fanout.max(1), latencies: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn latencies(&self) -> HashMap<String, EndpointLatency> {
+        self.latencies.lock().unwrap().clone()
+    }
+
+    // Endpoints to race: `preferred` first when it is the only candidate (outside the breaker, or no fanout),
+    // otherwise the fanout fastest healthy endpoints
+    fn contenders(&self, breaker: &CircuitBreaker, preferred: &str) -> Vec<String> {
+        let healthy = breaker.healthy_urls();
+        if self.fanout == 1 || !healthy.iter().any(|url| url == preferred) {
+            return vec![preferred.to_string()];
+        }
+        let latencies = self.latencies.lock().unwrap();
+        let mut contenders = healthy;
+        contenders.sort_by(|a, b| match (latencies.get(a).filter(|latency| latency.races > 0), latencies.get(b).filter(|latency| latency.races > 0)) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (Some(a), Some(b)) => b.win_rate().total_cmp(&a.win_rate()).then(a.average.cmp(&b.average)),
+        });
+        contenders.truncate(self.fanout);
+        contenders
+    }
+
+    // First successful response among the contenders, the slower calls are dropped.
+    // The outcome of every contender that answers counts toward its breaker, the preferred one's included
+    pub async fn race<T, F, Fut>(&self, breaker: &CircuitBreaker, preferred: &str, commitment: CommitmentConfig, call: F) -> Result<T, ClientError>
+    where
+        F: Fn(Arc<NonblockingRpcClient>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let contenders = self.contenders(breaker, preferred);
+        let started_at = Instant::now();
+        let mut calls: FuturesUnordered<_> = contenders
+            .iter()
+            .map(|url| {
+                let future = call(nonblocking_client_with_commitment(url, commitment));
+                async move { (url, future.await) }
+            })
+            .collect();
+        let mut failed: Vec<&String> = Vec::new();
+        let mut last_error: Option<ClientError> = None;
+        while let Some((url, result)) = calls.next().await {
+            match result {
+                Ok(value) => {
+                    let elapsed = started_at.elapsed();
+                    breaker.record_success(url);
+                    let mut latencies = self.latencies.lock().unwrap();
+                    for contender in contenders.iter().filter(|contender| !failed.contains(contender)) {
+                        let latency = latencies.entry(contender.clone()).or_default();
+                        if contender == url {
+                            latency.observe_win(elapsed);
+                        } else {
+                            latency.observe_loss();
+                        }
+                    }
+                    return Ok(value);
+                }
+                Err(e) => {
+                    breaker.record_failure(url);
+                    failed.push(url);
+                    let mut latencies = self.latencies.lock().unwrap();
+                    let latency = latencies.entry(url.clone()).or_default();
+                    latency.failures += 1;
+                    latency.observe_loss();
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| ClientError::from(ClientErrorKind::Custom("no healthy RPC endpoint".to_string()))))
+    }
+}
+
+static RPC_RACER: OnceLock<RpcRacer> = OnceLock::new();
+
+// To call once at startup, reads go to the current endpoint only otherwise
+pub fn init_rpc_racer(fanout: usize) {
+    info!("🏁 Latency-critical reads raced across {} RPC endpoints", fanout);
+    let _ = RPC_RACER.set(RpcRacer::new(fanout));
+}
+
+pub fn rpc_racer() -> &'static RpcRacer {
+    RPC_RACER.get_or_init(|| RpcRacer::new(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(urls: &[&str]) -> CircuitBreaker {
+        CircuitBreaker::new("test", urls.iter().map(|url| url.to_string()).collect(), 3)
+    }
+
+    #[test]
+    fn only_the_preferred_endpoint_without_fanout_or_outside_the_breaker() {
+        let breaker = breaker(&["a", "b"]);
+        assert_eq!(RpcRacer::new(1).contenders(&breaker, "a"), vec!["a".to_string()]);
+        assert_eq!(RpcRacer::new(2).contenders(&breaker, "c"), vec!["c".to_string()]);
+    }
+
+    #[test]
+    fn best_win_rates_raced_first_the_unraced_ones_before_them() {
+        let racer = RpcRacer::new(2);
+        {
+            let mut latencies = racer.latencies.lock().unwrap();
+            let a = latencies.entry("a".to_string()).or_default();
+            a.observe_win(Duration::from_millis(10));
+            (0..3).for_each(|_| a.observe_loss());
+            let b = latencies.entry("b".to_string()).or_default();
+            (0..3).for_each(|_| b.observe_win(Duration::from_millis(30)));
+            b.observe_loss();
+        }
+        assert_eq!(racer.contenders(&breaker(&["a", "b", "c"]), "a"), vec!["c".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn only_the_races_won_are_timed() {
+        let mut latency = EndpointLatency::default();
+        latency.observe_win(Duration::from_millis(10));
+        latency.observe_loss();
+        assert_eq!((latency.average, latency.races, latency.wins), (Duration::from_millis(10), 2, 1));
+        assert_eq!(latency.win_rate(), 0.5);
+    }
+}
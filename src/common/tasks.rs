@@ -0,0 +1,43 @@
+use std::future::Future;
+
+use tokio::task::{JoinHandle, JoinSet};
+
+// tokio::spawn naming the task for tokio-console. Task names need the tokio_unstable cfg:
+// RUSTFLAGS="--cfg tokio_unstable" cargo run --features console
+#[track_caller]
+pub fn spawn_named<F>(name: &str, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    #[cfg(tokio_unstable)]
+    return tokio::task::Builder::new().name(name).spawn(future).expect("Task not spawned");
+    #[cfg(not(tokio_unstable))]
+    {
+        let _ = name;
+        tokio::spawn(future)
+    }
+}
+
+// Same, in a JoinSet
+#[track_caller]
+pub fn spawn_named_in<T, F>(set: &mut JoinSet<T>, name: &str, future: F)
+where
+    T: Send + 'static,
+    F: Future<Output = T> + Send + 'static,
+{
+    #[cfg(tokio_unstable)]
+    set.build_task().name(name).spawn(future).expect("Task not spawned");
+    #[cfg(not(tokio_unstable))]
+    {
+        let _ = name;
+        set.spawn(future);
+    }
+}
+
+// Runtime diagnostics (tasks, polls, wakers) served to tokio-console on 127.0.0.1:6669
+#[cfg(feature = "console")]
+pub fn init_console() {
+    console_subscriber::init();
+    log::info!("🩺 tokio-console subscriber listening on 127.0.0.1:6669");
+}
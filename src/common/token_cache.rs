@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::Path;
+use std::sync::{OnceLock, RwLock};
+
+use anyhow::Result;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+pub static TOKEN_INFOS_FILE: &str = "src/common/cache/token_infos.json";
+
+// What never changes about a mint, the symbol stays with the caller
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MintInfos {
+    pub decimals: u8,
+}
+
+// Mint infos keyed by mint, in memory and on disk, so overlapping token groups and restarts
+// only ever fetch a mint once
+#[derive(Debug)]
+pub struct TokenInfoCache {
+    file_path: String,
+    mints: RwLock<HashMap<String, MintInfos>>,
+}
+
+impl TokenInfoCache {
+    pub fn load(file_path: &str) -> Self {
+        let mints = match read_mints(file_path) {
+            Ok(mints) => mints,
+            Err(_) => HashMap::new(),
+        };
+        info!("🪙 {} mints in the token infos cache", mints.len());
+        TokenInfoCache { file_path: file_path.to_string(), mints: RwLock::new(mints) }
+    }
+
+    pub fn get(&self, mint: &str) -> Option<MintInfos> {
+        self.mints.read().unwrap().get(mint).copied()
+    }
+
+    // The mints of the list not cached yet
+    pub fn missing<'a>(&self, mints: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        let cached = self.mints.read().unwrap();
+        let mut missing: Vec<String> = mints.into_iter().filter(|mint| !cached.contains_key(*mint)).map(str::to_string).collect();
+        missing.sort();
+        missing.dedup();
+        missing
+    }
+
+    // New mints are written to disk right away, a failed write only costs a refetch after restart
+    pub fn insert_all(&self, fetched: impl IntoIterator<Item = (String, MintInfos)>) {
+        let snapshot = {
+            let mut mints = self.mints.write().unwrap();
+            mints.extend(fetched);
+            mints.clone()
+        };
+        if let Err(e) = write_mints(&self.file_path, &snapshot) {
+            error!("❌ Unable to write the token infos cache {}: {:?}", self.file_path, e);
+        }
+    }
+}
+
+static TOKEN_INFO_CACHE: OnceLock<TokenInfoCache> = OnceLock::new();
+
+pub fn token_info_cache() -> &'static TokenInfoCache {
+    TOKEN_INFO_CACHE.get_or_init(|| TokenInfoCache::load(TOKEN_INFOS_FILE))
+}
+
+fn read_mints(file_path: &str) -> Result<HashMap<String, MintInfos>> {
+    let file = File::open(file_path)?;
+    Ok(serde_json::from_reader(file)?)
+}
+
+fn write_mints(file_path: &str, mints: &HashMap<String, MintInfos>) -> Result<()> {
+    if let Some(parent) = Path::new(file_path).parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = File::create(file_path)?;
+    serde_json::to_writer(file, mints)?;
+    Ok(())
+}
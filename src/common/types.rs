@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::arbitrage::types::TokenInArb;
+use crate::arbitrage::types::{with_bridge_tokens, TokenInArb};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InputVec {
@@ -9,4 +9,62 @@ pub struct InputVec {
     pub include_2hop: bool,
     pub numbers_of_best_paths: usize,
     pub get_fresh_pools_bool: bool,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub preset: StrategyPreset,
+    // Time spent on this group by the rotation strategy before moving to the next one
+    #[serde(default)]
+    pub time_budget_secs: Option<u64>,
+    // Tokens allowed as intermediate hops only (ex: SOL -> token -> USDC -> SOL)
+    #[serde(default)]
+    pub bridge_tokens: Vec<TokenInArb>,
+}
+
+impl InputVec {
+    // Tokens to arb followed by the bridge tokens not already listed
+    pub fn all_tokens(&self) -> Vec<TokenInArb> {
+        with_bridge_tokens(&self.tokens_to_arb, &self.bridge_tokens)
+    }
+}
+
+// Preset used by an InputVec, each one comes with its own sizing and profit threshold
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StrategyPreset {
+    #[default]
+    MemeTokens,
+    StableLst,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PresetParams {
+    pub simulation_amount: u64,
+    // Minimum simulated profit (in lamports of the base token) to send a transaction
+    pub min_profit_threshold: f64,
+    pub restrict_sol_usdc: bool,
+}
+
+impl StrategyPreset {
+    pub const ALL: [StrategyPreset; 2] = [StrategyPreset::MemeTokens, StrategyPreset::StableLst];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StrategyPreset::MemeTokens => "meme_tokens",
+            StrategyPreset::StableLst => "stable_lst",
+        }
+    }
+
+    pub fn params(&self) -> PresetParams {
+        match self {
+            StrategyPreset::MemeTokens => PresetParams {
+                simulation_amount: 3_500_000_000, // 3.5 SOL
+                min_profit_threshold: 20_000_000.0, // 0.02 SOL
+                restrict_sol_usdc: true,
+            },
+            // Deep pools with a small spread: bigger size, lower margin accepted
+            StrategyPreset::StableLst => PresetParams {
+                simulation_amount: 20_000_000_000, // 20 SOL
+                min_profit_threshold: 2_000_000.0, // 0.002 SOL
+                restrict_sol_usdc: false,
+            },
+        }
+    }
+}
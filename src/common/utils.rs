@@ -13,7 +13,10 @@ use std::io::{BufWriter, Write};
 use crate::{arbitrage::types::{SwapPathResult, TokenInArb, TokenInfos}, common::constants::{
     Env, PROJECT_NAME
 }};
-use solana_client::rpc_client::RpcClient;
+use crate::common::prices::token_prices;
+use crate::common::rate_limiter::throttle;
+use crate::common::rpc_pool::blocking_client;
+use crate::common::token_cache::{token_info_cache, MintInfos};
 
 // Function to format our console logs
 pub fn setup_logger() -> Result<(), fern::InitError> {
@@ -72,11 +75,21 @@ pub fn setup_logger() -> Result<(), fern::InitError> {
         .chain(fern::log_file("logs\\errors.log")?)
         .level(log::LevelFilter::Error);
 
-    base_config
+    base_config = base_config
         .chain(file_config)
         .chain(errors_config)
-        .chain(stdout_config)
-        .apply()?;
+        .chain(stdout_config);
+    // No-op until init_error_reporting
+    #[cfg(feature = "sentry")]
+    {
+        base_config = base_config.chain(
+            fern::Dispatch::new()
+                .level(log::LevelFilter::Error)
+                .level_for(PROJECT_NAME, LevelFilter::Info)
+                .chain(crate::monitoring::error_reporting::sentry_logger()),
+        );
+    }
+    base_config.apply()?;
     Ok(())
 }
 
@@ -125,29 +138,41 @@ pub fn from_Pubkey(pubkey: Pubkey) -> String {
     return pubkey_vec;
 }
 
+// Decimals from the token infos cache (only the mints never seen are fetched) and last USD price of the tokens
 pub async fn get_tokens_infos(tokens: Vec<TokenInArb>) -> HashMap<String, TokenInfos> {
-    let env = Env::new();
-    let rpc_client = RpcClient::new(env.rpc_url);
-
-    let mut pubkeys_str: Vec<String> = Vec::new();
-    let mut pubkeys: Vec<Pubkey> = Vec::new();
-    for token in tokens.clone() {
-        pubkeys_str.push(token.address.clone());
-        pubkeys.push(from_str(token.address.clone().as_str()).unwrap());
+    let cache = token_info_cache();
+    let missing = cache.missing(tokens.iter().map(|token| token.address.as_str()));
+    if !missing.is_empty() {
+        let env = Env::new();
+        let rpc_client = blocking_client(&env.rpc_url);
+        let mut fetched: Vec<(String, MintInfos)> = Vec::new();
+        for chunk in missing.chunks(100) {
+            let pubkeys: Vec<Pubkey> = chunk.iter().map(|mint| from_str(mint).unwrap()).collect();
+            throttle(&rpc_client.url()).await;
+            let batch_results = rpc_client.get_multiple_accounts(&pubkeys).unwrap();
+            for (mint, account) in chunk.iter().zip(batch_results) {
+                let account = account.unwrap();
+                let mint_layout = MintLayout::try_from_slice(&account.data).unwrap();
+                fetched.push((mint.clone(), MintInfos { decimals: mint_layout.decimals }));
+            }
+        }
+        info!("🪙 {} mints fetched", fetched.len());
+        cache.insert_all(fetched);
     }
-    let batch_results = rpc_client.get_multiple_accounts(&pubkeys).unwrap();
 
+    // Priced on the next refresh when not watched yet
+    let prices = token_prices();
+    if let Some(prices) = prices {
+        prices.watch(tokens.iter().map(|token| token.address.as_str()));
+    }
     let mut tokens_infos: HashMap<String, TokenInfos> = HashMap::new();
-
-    for (j, account) in batch_results.iter().enumerate() {
-        let account = account.clone().unwrap();
-        let mint_layout = MintLayout::try_from_slice(&account.data).unwrap();
-
-        let symbol = tokens.iter().find(|r| *r.address == pubkeys_str[j]).expect("Symbol token not found");
-        tokens_infos.insert(pubkeys_str[j].clone(), TokenInfos{
-            address: pubkeys_str[j].clone(),
-            decimals: mint_layout.decimals,
-            symbol: symbol.clone().symbol
+    for token in tokens.iter() {
+        let Some(mint_infos) = cache.get(&token.address) else { continue };
+        tokens_infos.insert(token.address.clone(), TokenInfos {
+            address: token.address.clone(),
+            decimals: mint_infos.decimals,
+            symbol: token.symbol.clone(),
+            usd_price: prices.and_then(|prices| prices.usd_price(&token.address)),
         });
     }
     return tokens_infos;
@@ -155,6 +180,7 @@ pub async fn get_tokens_infos(tokens: Vec<TokenInArb>) -> HashMap<String, TokenI
 }
 
 pub async fn make_request(req_url: String) -> Result<reqwest::Response, Error> {
+    throttle(&req_url).await;
     reqwest::get(req_url).await
 }
 
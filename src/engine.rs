@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures::stream::{self, Stream};
+use log::{error, info};
+use solana_sdk::signer::Signer;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::arbitrage::opportunity::{Opportunity, OpportunityQueue};
+use crate::arbitrage::plugins::{builtin_plugins, PluginContext, PluginRegistry};
+use crate::arbitrage::registry::{Strategy, StrategyEvent, StrategyRegistry, StrategyStates};
+use crate::common::tasks::spawn_named;
+use crate::common::types::InputVec;
+use crate::markets::state::MarketState;
+use crate::monitoring::events::{persisted_events, subscribe, TimedEvent};
+use crate::monitoring::notifier::{run_notifier, Notifiers};
+use crate::monitoring::writer::run_database_writer;
+use crate::transactions::signer::{init_wallet_signer, wallet_signer, SignerHandle};
+
+// Consumer of the bot events, alongside the notifier and the database writer
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    fn name(&self) -> String;
+
+    async fn handle(&self, event: &TimedEvent) -> Result<()>;
+}
+
+pub async fn run_event_sink(sink: Arc<dyn EventSink>) {
+    let mut events = subscribe();
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                if let Err(e) = sink.handle(&event).await {
+                    error!("❌ Event sink {} failed on {}: {:?}", sink.name(), event.event.kind(), e);
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => error!("Event sink {} lagged, {} events skipped", sink.name(), skipped),
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+// Everything main.rs wires by hand, for programs embedding the bot:
+//
+//     let markets = Arc::new(MarketState::new());
+//     let engine = ArbitrageEngine::builder()
+//         .with_signer(signer)
+//         .with_markets(markets.clone())
+//         .with_strategy(Box::new(MassiveStrategy::new(inputs, false, true, best_paths_file).with_market_state(markets)))
+//         .with_sink(Arc::new(my_sink))
+//         .build()?;
+//     let mut opportunities = Box::pin(engine.opportunities());
+//     tokio::spawn(engine.run());
+//     while let Some(opportunity) = opportunities.next().await { ... }
+//
+// The shared services (rate limiter, breakers, caches...) keep their defaults unless their init_* was called before
+pub struct ArbitrageEngineBuilder {
+    signer: Option<SignerHandle>,
+    markets: Arc<MarketState>,
+    preload_markets: Option<bool>,
+    opportunity_queue: Arc<OpportunityQueue>,
+    registry: StrategyRegistry,
+    plugins: PluginRegistry,
+    inputs: Vec<InputVec>,
+    sinks: Vec<Arc<dyn EventSink>>,
+    notifiers: Option<Notifiers>,
+    database_writer: bool,
+}
+
+impl ArbitrageEngineBuilder {
+    // The signer of the env (SIGNER_URL or PAYER_KEYPAIR_PATH) without it
+    pub fn with_signer(mut self, signer: SignerHandle) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    // Pools shared by the strategies, to pass to them as well
+    pub fn with_markets(mut self, markets: Arc<MarketState>) -> Self {
+        self.markets = markets;
+        self
+    }
+
+    // Load the pools before the strategies start rather than on the first strategy init
+    pub fn with_preloaded_markets(mut self, fetch_new_pools: bool) -> Self {
+        self.preload_markets = Some(fetch_new_pools);
+        self
+    }
+
+    // Queue the strategies push their opportunities to, and the source of opportunities()
+    pub fn with_opportunity_queue(mut self, queue: Arc<OpportunityQueue>) -> Self {
+        self.opportunity_queue = queue;
+        self
+    }
+
+    pub fn with_strategy(mut self, strategy: Box<dyn Strategy>) -> Self {
+        self.registry.register(strategy);
+        self
+    }
+
+    // Plugins available to with_plugin_strategy, the built-in ones otherwise
+    pub fn with_plugins(mut self, plugins: PluginRegistry) -> Self {
+        self.plugins = plugins;
+        self
+    }
+
+    // Token groups handed to the plugin strategies
+    pub fn with_inputs(mut self, inputs: Vec<InputVec>) -> Self {
+        self.inputs = inputs;
+        self
+    }
+
+    // Strategy created by a plugin, with the markets, queue and inputs of the engine
+    pub fn with_plugin_strategy(mut self, plugin: &str, config: &HashMap<String, String>) -> Result<Self> {
+        let ctx = PluginContext {
+            markets: self.markets.clone(),
+            opportunity_queue: self.opportunity_queue.clone(),
+            events: self.registry.events(),
+            inputs: self.inputs.clone(),
+        };
+        let strategy = self.plugins.create(plugin, &ctx, config)?;
+        self.registry.register(strategy);
+        Ok(self)
+    }
+
+    pub fn with_sink(mut self, sink: Arc<dyn EventSink>) -> Self {
+        info!("📥 Events sent to {}", sink.name());
+        self.sinks.push(sink);
+        self
+    }
+
+    pub fn with_notifiers(mut self, notifiers: Notifiers) -> Self {
+        self.notifiers = Some(notifiers);
+        self
+    }
+
+    // Persist the trades, PnL and submissions, the storage must be initialized
+    pub fn with_database_writer(mut self) -> Self {
+        self.database_writer = true;
+        self
+    }
+
+    pub fn markets(&self) -> Arc<MarketState> {
+        self.markets.clone()
+    }
+
+    // To pass to the strategies (with_opportunity_queue), their opportunities are not streamed otherwise
+    pub fn opportunity_queue(&self) -> Arc<OpportunityQueue> {
+        self.opportunity_queue.clone()
+    }
+
+    // To feed the strategies before the engine runs, e.g. from a custom pool stream
+    pub fn events(&self) -> broadcast::Sender<StrategyEvent> {
+        self.registry.events()
+    }
+
+    // Status, pause and parameters of the registered strategies
+    pub fn strategies(&self) -> StrategyStates {
+        self.registry.states()
+    }
+
+    pub fn build(self) -> Result<ArbitrageEngine> {
+        let signer = match self.signer {
+            Some(signer) => {
+                init_wallet_signer(signer.clone());
+                // The transaction builders sign with the global signer
+                if wallet_signer().pubkey() != signer.pubkey() {
+                    return Err(anyhow!("Another wallet signer ({}) is already in use", wallet_signer().pubkey()));
+                }
+                signer
+            }
+            None => wallet_signer(),
+        };
+        Ok(ArbitrageEngine {
+            signer,
+            markets: self.markets,
+            preload_markets: self.preload_markets,
+            opportunity_queue: self.opportunity_queue,
+            registry: self.registry,
+            sinks: self.sinks,
+            notifiers: self.notifiers,
+            database_writer: self.database_writer,
+        })
+    }
+}
+
+pub struct ArbitrageEngine {
+    signer: SignerHandle,
+    markets: Arc<MarketState>,
+    preload_markets: Option<bool>,
+    opportunity_queue: Arc<OpportunityQueue>,
+    registry: StrategyRegistry,
+    sinks: Vec<Arc<dyn EventSink>>,
+    notifiers: Option<Notifiers>,
+    database_writer: bool,
+}
+
+impl ArbitrageEngine {
+    pub fn builder() -> ArbitrageEngineBuilder {
+        ArbitrageEngineBuilder {
+            signer: None,
+            markets: Arc::new(MarketState::new()),
+            preload_markets: None,
+            opportunity_queue: Arc::new(OpportunityQueue::new(64, Duration::from_millis(1500))),
+            registry: StrategyRegistry::new(),
+            plugins: builtin_plugins(),
+            inputs: Vec::new(),
+            sinks: Vec::new(),
+            notifiers: None,
+            database_writer: false,
+        }
+    }
+
+    pub fn signer(&self) -> SignerHandle {
+        self.signer.clone()
+    }
+
+    pub fn markets(&self) -> Arc<MarketState> {
+        self.markets.clone()
+    }
+
+    // Opportunities found by the strategies from now on, to filter and execute outside of the crate.
+    // Taken before run(); a consumer too slow skips the ones it lagged behind
+    pub fn opportunities(&self) -> impl Stream<Item = Opportunity> + Send + 'static {
+        stream::unfold(self.opportunity_queue.subscribe(), |mut found| async move {
+            loop {
+                match found.recv().await {
+                    Ok(opportunity) => return Some((opportunity, found)),
+                    Err(RecvError::Lagged(skipped)) => error!("Opportunities stream lagged, {} opportunities skipped", skipped),
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    // Send StrategyEvent::Shutdown to stop every strategy, Pause/Resume/SetParam to control one
+    pub fn events(&self) -> broadcast::Sender<StrategyEvent> {
+        self.registry.events()
+    }
+
+    pub fn strategies(&self) -> StrategyStates {
+        self.registry.states()
+    }
+
+    // Until every strategy is done
+    pub async fn run(self) -> Result<()> {
+        info!("🚂 Engine starting {} strategies for {}", self.registry.len(), self.signer.pubkey());
+        for sink in self.sinks {
+            spawn_named("event_sink", run_event_sink(sink));
+        }
+        if let Some(notifiers) = self.notifiers {
+            spawn_named("notifier", run_notifier(notifiers));
+        }
+        if let Some(persisted) = self.database_writer.then(persisted_events).flatten() {
+            spawn_named("database_writer", run_database_writer(persisted));
+        }
+        if let Some(fetch_new_pools) = self.preload_markets {
+            self.markets.ensure_loaded(fetch_new_pools).await;
+        }
+        self.registry.run_all().await
+    }
+}
@@ -4,28 +4,202 @@ pub mod strategies;
 pub mod markets;
 pub mod transactions {
     // pub mod raydium_swap; // Disabled due to missing raydium_amm dependency
+    pub mod audit;
+    pub mod blockhash;
+    pub mod confirmation;
     pub mod create_transaction;
+    pub mod errors;
+    pub mod in_flight;
     pub mod meteoradlmm_swap;
     pub mod orca_whirlpool_swap;
+    pub mod presigned;
+    pub mod private_send;
+    pub mod signer;
+    pub mod sweep;
+    pub mod templates;
     pub mod util;
+    pub mod verify;
+    pub mod warmup;
 }
 pub mod data;
+pub mod risk;
+pub mod monitoring;
+pub mod storage;
+pub mod engine;
+pub mod localnet;
+#[cfg(feature = "python")]
+pub mod python;
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use solana_sdk::native_token::LAMPORTS_PER_SOL;
     use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::{Keypair, Signer};
     use crate::{
-        arbitrage::types::{SwapPathResult, SwapRouteSimulation, TokenInArb},
+        arbitrage::backtest::{run_backtest, BacktestConfig, FillAssumptions, FillOutcome, PoolStateSnapshot},
+        arbitrage::path_files::read_paths,
+        arbitrage::simulate::simulate_paths_local,
+        arbitrage::streams::get_fresh_accounts_states,
+        arbitrage::types::{Route, SwapPath, SwapPathResult, SwapPathSelected, SwapRouteSimulation, TokenInArb, TokenInfos},
+        common::constants::{SOL_MINT, USDC_MINT},
+        common::types::StrategyPreset,
         common::utils::from_str,
-        markets::types::DexLabel,
+        localnet::{LocalValidator, LOCALNET_FIXTURES_DIR},
+        markets::pyth::{PYTH_SOL_USD, PYTH_USDC_USD},
+        markets::types::{DexLabel, Market},
+        transactions::signer::{init_wallet_signer, LocalSigner, SignerHandle},
         transactions::create_transaction::{
-            create_ata_extendlut_transaction, write_lut_for_market, ChainType, SendOrSimulate
+            create_and_send_swap_transaction, create_ata_extendlut_transaction, write_lut_for_market, ChainType, SendOrSimulate
         }
     };
     // mod transactions {
     //     pub mod raydium_swap; // Disabled due to missing raydium_amm
     // }
 
+    // Whirlpool account with just what the local quote reads: tick spacing, fee rate, liquidity, sqrt price,
+    // current tick, mint A. The prices of the tests all fall in the tick array starting at TICK_ARRAY_START
+    fn whirlpool_data(mint_a: &Pubkey) -> Vec<u8> {
+        whirlpool_data_at(mint_a, 7_143_265_591_698_411_110)
+    }
+
+    const TICK_ARRAY_START: i32 = -22_528;
+
+    fn whirlpool_data_at(mint_a: &Pubkey, sqrt_price_x64: u128) -> Vec<u8> {
+        let mut data = vec![0u8; 653];
+        data[41..43].copy_from_slice(&64u16.to_le_bytes());
+        data[45..47].copy_from_slice(&3_000u16.to_le_bytes());
+        data[49..65].copy_from_slice(&120_000_000_000_000u128.to_le_bytes());
+        data[65..81].copy_from_slice(&sqrt_price_x64.to_le_bytes());
+        data[81..85].copy_from_slice(&(-18_976i32).to_le_bytes());
+        data[101..133].copy_from_slice(mint_a.as_ref());
+        data
+    }
+
+    // Tick array of the whirlpool without any initialized tick, the swap stays in the current range
+    fn tick_array_data(whirlpool: &str) -> Vec<u8> {
+        let mut data = vec![0u8; 9988];
+        data[8..12].copy_from_slice(&TICK_ARRAY_START.to_le_bytes());
+        data[9956..9988].copy_from_slice(from_str(whirlpool).unwrap().as_ref());
+        data
+    }
+
+    // Pool and tick array of each whirlpool, addressed as the account source finds them
+    fn whirlpool_accounts(pools: &[(&str, Vec<u8>)]) -> HashMap<String, Vec<u8>> {
+        pools
+            .iter()
+            .flat_map(|(pool, data)| [(pool.to_string(), data.clone()), (Pubkey::new_unique().to_string(), tick_array_data(pool))])
+            .collect()
+    }
+
+    // PriceUpdateV2 account of a price in cents, without confidence interval
+    fn pyth_price_data(cents: i64, publish_time: i64) -> Vec<u8> {
+        let mut data = vec![0u8; 133];
+        data[40] = 1;
+        data[73..81].copy_from_slice(&cents.to_le_bytes());
+        data[89..93].copy_from_slice(&(-2i32).to_le_bytes());
+        data[93..101].copy_from_slice(&publish_time.to_le_bytes());
+        data
+    }
+
+    // SOL -> USDC -> SOL through two whirlpools, returned with their addresses
+    fn backtest_path(sol: &Pubkey, usdc: &Pubkey) -> (SwapPathSelected, String, String) {
+        let (sol, usdc) = (*sol, *usdc);
+        let (pool_1, pool_2) = (Pubkey::new_unique().to_string(), Pubkey::new_unique().to_string());
+        let market = |id: &str| Market {
+            tokenMintA: sol.to_string(),
+            tokenVaultA: Pubkey::new_unique().to_string(),
+            tokenMintB: usdc.to_string(),
+            tokenVaultB: Pubkey::new_unique().to_string(),
+            dexLabel: DexLabel::ORCA_WHIRLPOOLS,
+            fee: 3_000,
+            id: id.to_string(),
+            account_data: None,
+            liquidity: None,
+        };
+        let route = |id: u32, pool: &str, token_in: &Pubkey, token_out: &Pubkey| Route {
+            id,
+            dex: DexLabel::ORCA_WHIRLPOOLS,
+            pool_address: pool.to_string(),
+            token_0to1: *token_in == sol,
+            tokenIn: token_in.to_string(),
+            tokenOut: token_out.to_string(),
+            fee: 3_000,
+        };
+        let path = SwapPathSelected {
+            result: 0.0,
+            path: SwapPath { hops: 2, paths: vec![route(1, &pool_1, &sol, &usdc), route(2, &pool_2, &usdc, &sol)], id_paths: vec![1, 2] },
+            markets: vec![market(&pool_1), market(&pool_2)],
+            preset: StrategyPreset::default(),
+        };
+        (path, pool_1, pool_2)
+    }
+
+    // A 2% price gap between two whirlpools quoted on the first snapshot, closed by the fill snapshot:
+    // the trade reverts on its min amount out and only costs its fee
+    #[test]
+    fn backtest_reverts_when_the_gap_closes_before_the_fill() {
+        let sol = Pubkey::new_unique();
+        let (path, pool_1, pool_2) = backtest_path(&sol, &Pubkey::new_unique());
+        let snapshot = |slot: u64, pool_2_sqrt_price: u128| PoolStateSnapshot {
+            timestamp: slot as i64 * 400,
+            slot,
+            accounts: whirlpool_accounts(&[(&pool_1, whirlpool_data(&sol)), (&pool_2, whirlpool_data_at(&sol, pool_2_sqrt_price))]),
+        };
+        let snapshots = vec![snapshot(1, 7_071_472_155_589_075_968), snapshot(2, 7_143_265_591_698_411_110), snapshot(3, 7_143_265_591_698_411_110)];
+        let config = BacktestConfig::default().with_amount(1_000_000_000).with_min_profit(0.0).with_fill(FillAssumptions::default());
+
+        let report = run_backtest(&snapshots, &[path], &config);
+        assert_eq!(report.quoted, 3);
+        assert_eq!(report.below_threshold, 2);
+        assert_eq!(report.trades.len(), 1);
+        let trade = &report.trades[0];
+        assert!(trade.expected_profit > 0.0);
+        assert_eq!((trade.slot, trade.fill_slot, trade.outcome), (1, 2, FillOutcome::Reverted));
+        assert_eq!(report.pnl, -config.fill.cost_lamports);
+    }
+
+    // The same gap on every snapshot: filled once, then skipped while the pools stay in the state of the fill
+    #[test]
+    fn backtest_fills_a_persistent_gap_once() {
+        let sol = Pubkey::new_unique();
+        let (path, pool_1, pool_2) = backtest_path(&sol, &Pubkey::new_unique());
+        let accounts = whirlpool_accounts(&[(&pool_1, whirlpool_data(&sol)), (&pool_2, whirlpool_data_at(&sol, 7_071_472_155_589_075_968))]);
+        let snapshots: Vec<PoolStateSnapshot> = (1..=4).map(|slot| PoolStateSnapshot { timestamp: slot as i64 * 400, slot, accounts: accounts.clone() }).collect();
+        let config = BacktestConfig::default().with_amount(1_000_000_000).with_min_profit(0.0).with_fill(FillAssumptions::default());
+
+        let report = run_backtest(&snapshots, &[path], &config);
+        assert_eq!((report.quoted, report.consumed), (1, 3));
+        assert_eq!(report.trades.len(), 1);
+        assert_eq!((report.trades[0].slot, report.trades[0].fill_slot, report.trades[0].outcome), (1, 2, FillOutcome::Filled));
+    }
+
+    // The persistent gap between pools quoting SOL around 150 USDC, with the Pyth prices recorded alongside:
+    // rejected on every snapshot with SOL at 100 USD, filled once with SOL at 150 USD
+    #[test]
+    fn backtest_checks_the_quotes_against_the_recorded_oracle_prices() {
+        let sol = from_str(SOL_MINT).unwrap();
+        let (path, pool_1, pool_2) = backtest_path(&sol, &from_str(USDC_MINT).unwrap());
+        let snapshots = |sol_usd_cents: i64| -> Vec<PoolStateSnapshot> {
+            let mut accounts = whirlpool_accounts(&[(&pool_1, whirlpool_data(&sol)), (&pool_2, whirlpool_data_at(&sol, 7_071_472_155_589_075_968))]);
+            accounts.insert(PYTH_SOL_USD.to_string(), pyth_price_data(sol_usd_cents, 0));
+            accounts.insert(PYTH_USDC_USD.to_string(), pyth_price_data(100, 0));
+            (1..=4).map(|slot| PoolStateSnapshot { timestamp: slot as i64 * 400, slot, accounts: accounts.clone() }).collect()
+        };
+        let tokens_infos = [(SOL_MINT, 9), (USDC_MINT, 6)]
+            .into_iter()
+            .map(|(mint, decimals)| (mint.to_string(), TokenInfos { address: mint.to_string(), decimals, symbol: mint.to_string(), usd_price: None }))
+            .collect();
+        let config = BacktestConfig::default().with_amount(1_000_000_000).with_min_profit(0.0).with_tokens_infos(tokens_infos);
+
+        let rejected = run_backtest(&snapshots(10_000), &[path.clone()], &config);
+        assert_eq!((rejected.sanity_rejected, rejected.trades.len()), (4, 0));
+        let filled = run_backtest(&snapshots(15_000), &[path], &config);
+        assert_eq!((filled.sanity_rejected, filled.trades.len()), (0, 1));
+    }
+
     #[test]
     fn write_in_write_lut_for_market() {
         let market: Pubkey = Pubkey::new_unique();
@@ -107,4 +281,48 @@ mod tests {
             tokens
         ).await;       
     }
-}
\ No newline at end of file
+
+    // End to end on solana-test-validator with the fixtures of `localnet-record`: the fixture paths quoted
+    // locally from the validator accounts, then sent through the swap send path
+    #[tokio::test]
+    #[ignore = "needs solana-test-validator and the fixtures recorded in fixtures/localnet"]
+    async fn localnet_quote_and_send_fixture_paths() {
+        let validator = LocalValidator::start(LOCALNET_FIXTURES_DIR).await.unwrap();
+        // Every read and send of the bot goes to the validator
+        std::env::set_var("RPC_URL", validator.rpc_url());
+        std::env::set_var("RPC_URL_TX", validator.rpc_url());
+        let payer = Keypair::new();
+        validator.airdrop(&payer.pubkey(), 100).await.unwrap();
+
+        let paths = read_paths(&format!("{}/paths.json", LOCALNET_FIXTURES_DIR)).unwrap().value;
+        let mut mints: Vec<Pubkey> = paths.iter().flat_map(|path| path.path.paths.iter()).map(|route| from_str(&route.tokenOut).unwrap()).collect();
+        mints.sort();
+        mints.dedup();
+        validator.create_token_accounts(&payer, &mints, 10 * LAMPORTS_PER_SOL).await.unwrap();
+        init_wallet_signer(SignerHandle::new(Arc::new(LocalSigner::new(payer))));
+
+        let markets: HashMap<String, Market> = paths.iter().flat_map(|path| path.markets.iter()).map(|market| (market.id.clone(), market.clone())).collect();
+        get_fresh_accounts_states(markets).await;
+        let amount_in = LAMPORTS_PER_SOL / 10;
+        for (path, quote) in paths.iter().zip(simulate_paths_local(amount_in, &paths)) {
+            let (route_simulations, result) = quote.expect("Fixture path not quoted locally");
+            let last = route_simulations.last().unwrap().clone();
+            let spr = SwapPathResult {
+                path_id: path.path.id_paths[0],
+                hops: path.path.hops,
+                tokens_path: String::new(),
+                token_in: route_simulations[0].token_in.clone(),
+                token_in_symbol: String::new(),
+                token_out: last.token_out.clone(),
+                token_out_symbol: String::new(),
+                amount_in,
+                estimated_amount_out: last.estimated_amount_out,
+                estimated_min_amount_out: last.estimated_min_amount_out,
+                route_simulations,
+                result,
+            };
+            let sent = create_and_send_swap_transaction(SendOrSimulate::Send, ChainType::Localnet, spr, 0).await;
+            assert!(matches!(sent, Ok(true)), "Path {:?} not landed: {:?}", path.path.id_paths, sent);
+        }
+    }
+}
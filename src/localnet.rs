@@ -0,0 +1,180 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use log::{error, info};
+use anchor_spl::token::spl_token;
+use serde::{Deserialize, Serialize};
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_sdk::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::arbitrage::path_files::{read_paths, write_paths};
+use crate::arbitrage::types::{SwapPathSelected, VecSwapPathSelected};
+use crate::common::rpc_pool::nonblocking_client;
+use crate::transactions::create_transaction::{construct_transaction, get_lut_address_for_market, LOCALNET_RPC_URL};
+use crate::transactions::warmup::placeholder_path_result;
+
+// Recorded by `localnet-record`, loaded by LocalValidator::start:
+//     paths.json              best paths file of the fixture pools
+//     accounts/<address>.json pools, vaults, tick arrays, mints and lookup tables, as `solana account --output json` writes them
+//     programs/<program>.so   the DEX programs the swaps go through
+pub static LOCALNET_FIXTURES_DIR: &str = "fixtures/localnet";
+pub static LOCALNET_STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+// Account file of the fixtures, the format solana-test-validator --account reads
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FixtureAccount {
+    pub pubkey: String,
+    pub account: UiAccount,
+}
+
+// Accounts the swaps of the paths read: every account of their instructions, and their lookup tables
+async fn path_accounts(paths: &[SwapPathSelected]) -> Result<(BTreeSet<Pubkey>, BTreeSet<Pubkey>)> {
+    let (mut accounts, mut programs) = (BTreeSet::new(), BTreeSet::new());
+    for path in paths {
+        let instructions = construct_transaction(placeholder_path_result(&path.path, 0)).await;
+        if instructions.is_empty() {
+            return Err(anyhow!("No swap instructions for the path {:?}", path.path.id_paths));
+        }
+        for details in instructions {
+            programs.insert(details.instruction.program_id);
+            accounts.extend(details.instruction.accounts.iter().filter(|meta| !meta.is_signer).map(|meta| meta.pubkey));
+            if let Some(market) = details.market {
+                if let (true, Some(lut_address)) = get_lut_address_for_market(market.address, false)? {
+                    accounts.insert(lut_address);
+                }
+            }
+        }
+    }
+    Ok((accounts, programs))
+}
+
+// Snapshot of the accounts and programs of the paths from an RPC (mainnet), into the fixtures directory.
+// The accounts missing on chain (the payer token accounts) are left to the test
+pub async fn record_fixtures(rpc_url: &str, paths_file: &str, dir: &str) -> Result<()> {
+    let paths = read_paths(paths_file)?.value;
+    let (accounts, programs) = path_accounts(&paths).await?;
+    let client = nonblocking_client(rpc_url);
+    fs::create_dir_all(Path::new(dir).join("accounts"))?;
+    fs::create_dir_all(Path::new(dir).join("programs"))?;
+
+    let accounts: Vec<Pubkey> = accounts.into_iter().collect();
+    let mut recorded = 0;
+    for batch in accounts.chunks(100) {
+        for (pubkey, account) in batch.iter().zip(client.get_multiple_accounts(batch).await?) {
+            // Programs and sysvars come with the validator or from programs/
+            let Some(account) = account.filter(|account| !account.executable && !programs.contains(pubkey)) else { continue };
+            let fixture = FixtureAccount { pubkey: pubkey.to_string(), account: UiAccount::encode(pubkey, &account, UiAccountEncoding::Base64, None, None) };
+            fs::write(Path::new(dir).join("accounts").join(format!("{}.json", pubkey)), serde_json::to_string_pretty(&fixture)?)?;
+            recorded += 1;
+        }
+    }
+
+    for program in programs.iter() {
+        let account = client.get_account(program).await?;
+        // Upgradeable programs keep their code in the program data account, after its header
+        if account.owner != bpf_loader_upgradeable::id() {
+            continue;
+        }
+        let UpgradeableLoaderState::Program { programdata_address } = bincode::deserialize(&account.data)? else { continue };
+        let program_data = client.get_account(&programdata_address).await?;
+        let code = program_data.data.get(UpgradeableLoaderState::size_of_programdata_metadata()..).unwrap_or_default();
+        fs::write(Path::new(dir).join("programs").join(format!("{}.so", program)), code)?;
+        info!("🧪 Program {} recorded, {} bytes", program, code.len());
+    }
+    info!("🧪 {} accounts of {} paths recorded in {}", recorded, paths.len(), dir);
+    write_paths(&Path::new(dir).join("paths.json").to_string_lossy(), &VecSwapPathSelected { value: paths })?;
+    Ok(())
+}
+
+// solana-test-validator on LOCALNET_RPC_URL with the fixtures loaded, killed on drop
+pub struct LocalValidator {
+    child: Child,
+    ledger: PathBuf,
+}
+
+impl LocalValidator {
+    pub async fn start(fixtures_dir: &str) -> Result<Self> {
+        let ledger = std::env::temp_dir().join(format!("mev-localnet-{}", std::process::id()));
+        let mut command = Command::new("solana-test-validator");
+        command.arg("--reset").arg("--quiet").arg("--ledger").arg(&ledger);
+        for entry in fs::read_dir(Path::new(fixtures_dir).join("programs")).with_context(|| format!("reading the fixtures of {}", fixtures_dir))? {
+            let file = entry?.path();
+            let Some(program) = file.file_stem().and_then(|stem| stem.to_str()) else { continue };
+            command.arg("--bpf-program").arg(program).arg(&file);
+        }
+        for entry in fs::read_dir(Path::new(fixtures_dir).join("accounts"))? {
+            let file = entry?.path();
+            let Some(address) = file.file_stem().and_then(|stem| stem.to_str()) else { continue };
+            command.arg("--account").arg(address).arg(&file);
+        }
+        let child = command.stdout(Stdio::null()).stderr(Stdio::inherit()).spawn().context("solana-test-validator not found in the PATH")?;
+        let validator = LocalValidator { child, ledger };
+
+        let started_at = Instant::now();
+        while nonblocking_client(LOCALNET_RPC_URL).get_health().await.is_err() {
+            if started_at.elapsed() > LOCALNET_STARTUP_TIMEOUT {
+                return Err(anyhow!("solana-test-validator not healthy after {:?}", LOCALNET_STARTUP_TIMEOUT));
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        info!("🧪 Localnet up on {} in {:?}", LOCALNET_RPC_URL, started_at.elapsed());
+        Ok(validator)
+    }
+
+    pub fn rpc_url(&self) -> &'static str {
+        LOCALNET_RPC_URL
+    }
+
+    // SOL from the validator faucet, confirmed
+    pub async fn airdrop(&self, pubkey: &Pubkey, sol: u64) -> Result<Signature> {
+        let client = nonblocking_client(LOCALNET_RPC_URL);
+        let signature = client.request_airdrop(pubkey, sol * LAMPORTS_PER_SOL).await?;
+        let started_at = Instant::now();
+        while !client.confirm_transaction(&signature).await? {
+            if started_at.elapsed() > LOCALNET_STARTUP_TIMEOUT {
+                return Err(anyhow!("Airdrop to {} not confirmed", pubkey));
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        Ok(signature)
+    }
+
+    // Token accounts of the payer for the mints of the paths, with `wrapped_sol` lamports of wSOL to swap
+    pub async fn create_token_accounts(&self, payer: &Keypair, mints: &[Pubkey], wrapped_sol: u64) -> Result<Signature> {
+        let mut instructions: Vec<Instruction> = mints
+            .iter()
+            .map(|mint| create_associated_token_account_idempotent(&payer.pubkey(), &payer.pubkey(), mint, &spl_token::id()))
+            .collect();
+        let wsol_ata = get_associated_token_address(&payer.pubkey(), &spl_token::native_mint::id());
+        if !mints.contains(&spl_token::native_mint::id()) {
+            instructions.push(create_associated_token_account_idempotent(&payer.pubkey(), &payer.pubkey(), &spl_token::native_mint::id(), &spl_token::id()));
+        }
+        instructions.push(system_instruction::transfer(&payer.pubkey(), &wsol_ata, wrapped_sol));
+        instructions.push(spl_token::instruction::sync_native(&spl_token::id(), &wsol_ata)?);
+        let client = nonblocking_client(LOCALNET_RPC_URL);
+        let transaction = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[payer], client.get_latest_blockhash().await?);
+        Ok(client.send_and_confirm_transaction(&transaction).await?)
+    }
+}
+
+impl Drop for LocalValidator {
+    fn drop(&mut self) {
+        if let Err(e) = self.child.kill() {
+            error!("❌ solana-test-validator not stopped: {:?}", e);
+        }
+        let _ = self.child.wait();
+        let _ = fs::remove_dir_all(&self.ledger);
+    }
+}
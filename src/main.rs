@@ -1,53 +1,295 @@
 use std::collections::HashMap;
-use std::fs::{File, OpenOptions};
-use std::io::BufWriter;
+use std::sync::Arc;
+use std::time::Duration;
 use anyhow::Result;
-use futures::FutureExt;
-use log::{error, info};
-use tokio::task::JoinSet;
-use MEV_Bot_Solana::arbitrage::strategies::{
-    optimism_tx_strategy,
-    run_arbitrage_strategy,
-    sorted_interesting_path_strategy,
-};
-use MEV_Bot_Solana::common::database::insert_vec_swap_path_selected_collection;
+use chrono::NaiveDate;
+use log::{error, info, warn};
+use tokio::sync::RwLock;
+use MEV_Bot_Solana::arbitrage::backtest::{backtest_files, record_snapshots, BacktestConfig, FillAssumptions};
+use MEV_Bot_Solana::arbitrage::claims::ClaimsRegistry;
+use MEV_Bot_Solana::arbitrage::cooldown::PathCooldowns;
+use MEV_Bot_Solana::arbitrage::experiments::{run_experiment_reports, Experiment, ExperimentArm};
+use MEV_Bot_Solana::arbitrage::discovery::{discover_tokens, discovered_inputs, run_discovery, DiscoveryFilters};
+use MEV_Bot_Solana::arbitrage::opportunity::{run_executor_pool, ExecutorContext, OpportunityQueue};
+use MEV_Bot_Solana::arbitrage::jupiter_routes::{export_jupiter_file, import_jupiter_file};
+use MEV_Bot_Solana::arbitrage::live_ranking::LiveRanking;
+use MEV_Bot_Solana::arbitrage::ranking::{run_path_stats_writer, PathRanking, PATH_STATS_FILE};
+use MEV_Bot_Solana::arbitrage::path_files::{init_paths_format, read_paths, PathsFormat};
+use MEV_Bot_Solana::arbitrage::presets::{stable_lst_inputs, StrategyPreset};
+use MEV_Bot_Solana::arbitrage::token_health::TokenHealth;
+use MEV_Bot_Solana::arbitrage::submissions::{run_submission_slot_refresh, SubmissionGuard};
+use MEV_Bot_Solana::arbitrage::streams::{run_pool_refresher, PoolRefresher};
+use MEV_Bot_Solana::arbitrage::strategies::{BestPathStrategy, MassiveStrategy, OptimismStrategy, RotationStrategy};
+use MEV_Bot_Solana::arbitrage::types::TokenInArb;
+use MEV_Bot_Solana::common::birdeye::BirdeyeClient;
+use MEV_Bot_Solana::engine::ArbitrageEngine;
+use MEV_Bot_Solana::localnet::{record_fixtures, LocalValidator, LOCALNET_FIXTURES_DIR};
+use MEV_Bot_Solana::common::dexscreener::DexScreenerClient;
+use MEV_Bot_Solana::common::feeds::{feeds, init_feeds, BackoffPolicy, FeedKind, FeedsConfig};
+use MEV_Bot_Solana::common::circuit_breaker::{init_circuit_breakers, quote_breaker, run_recovery_probe, send_breaker};
+use MEV_Bot_Solana::common::rpc_race::init_rpc_racer;
+use MEV_Bot_Solana::common::constants::{Env, JITOSOL_MINT, SOL_MINT, USDC_MINT, USDT_MINT};
+use MEV_Bot_Solana::common::debug::DebugConsole;
+use MEV_Bot_Solana::common::tasks::spawn_named;
+use MEV_Bot_Solana::common::rpc_pool::{nonblocking_client, warm_up_rpc_clients};
+use MEV_Bot_Solana::common::rate_limiter::{init_rate_limiter, RateLimiterConfig};
 use MEV_Bot_Solana::common::types::InputVec;
+use MEV_Bot_Solana::common::compute::init_compute_pool;
+use MEV_Bot_Solana::common::prices::{init_token_prices, run_price_refresh, JupiterPriceProvider, TokenPrices};
+use MEV_Bot_Solana::common::rpc_cache::{init_rpc_cache, CachedMethod, ResponseCache};
+use MEV_Bot_Solana::markets::coverage::check_pool_coverage;
 use MEV_Bot_Solana::markets::pools::load_all_pools;
-use MEV_Bot_Solana::transactions::create_transaction::{
-    create_ata_extendlut_transaction,
-    ChainType,
-    SendOrSimulate,
-};
-use MEV_Bot_Solana::{
-    common::constants::Env,
-    common::utils::{from_str, get_tokens_infos, setup_logger},
-    transactions::create_transaction::create_and_send_swap_transaction,
-};
-use MEV_Bot_Solana::arbitrage::types::{
-    SwapPathResult,
-    SwapPathSelected,
-    TokenInArb,
-    TokenInfos,
-    VecSwapPathSelected,
-};
-use rust_socketio::{asynchronous::{Client, ClientBuilder}, Payload};
+use MEV_Bot_Solana::markets::ingest::{init_account_updates, run_account_update_applier};
+use MEV_Bot_Solana::markets::pyth::{default_pyth_feeds, init_pyth_oracle, run_pyth_oracle, PythOracle};
+use MEV_Bot_Solana::markets::pubsub::{run_pubsub, PubsubSubscriptions};
+use MEV_Bot_Solana::common::utils::{from_str, setup_logger};
+use MEV_Bot_Solana::markets::state::MarketState;
+use MEV_Bot_Solana::monitoring::api::{run_control_api, ApiState};
+use MEV_Bot_Solana::monitoring::bench::{run_bench, BenchConfig};
+use MEV_Bot_Solana::monitoring::competitors::{run_competitor_tracker, CompetitorTracker};
+use MEV_Bot_Solana::monitoring::dashboard::{run_dashboard_state, DashboardState};
+use MEV_Bot_Solana::monitoring::daily_summary::{build_daily_summary, run_daily_summary};
+use MEV_Bot_Solana::monitoring::dex_stats::{run_dex_stats, DexStats};
+use MEV_Bot_Solana::monitoring::error_reporting::init_error_reporting;
+use MEV_Bot_Solana::monitoring::heartbeat::{run_heartbeat, HeartbeatConfig};
+use MEV_Bot_Solana::monitoring::landing::run_landing_analyzer;
+use MEV_Bot_Solana::monitoring::notifier::{run_notifier, DiscordNotifier, Notifiers, NotifyFlags, SlackNotifier, TelegramNotifier};
+use MEV_Bot_Solana::monitoring::sheets::GoogleSheetsExporter;
+use MEV_Bot_Solana::monitoring::events::persisted_events;
+use MEV_Bot_Solana::monitoring::writer::run_database_writer;
+use MEV_Bot_Solana::storage::export::{export_swap_path_results, export_trades, ExportFormat};
+use MEV_Bot_Solana::storage::migrations::{check_schema, run_migrations};
+use MEV_Bot_Solana::storage::query::{parse_flags, query_best_paths, query_swap_path_results, PathQuery, BEST_PATHS_COLLECTION, SWAP_PATH_RESULTS_COLLECTION};
+use MEV_Bot_Solana::storage::{connect_storage_from_env, init_storage};
+use MEV_Bot_Solana::monitoring::pnl::PnlTracker;
+use MEV_Bot_Solana::transactions::audit::{init_audit_log, verify_audit_log, AUDIT_LOG_FILE};
+use MEV_Bot_Solana::transactions::blockhash::run_blockhash_prefetcher;
+use MEV_Bot_Solana::transactions::confirmation::{init_confirmation_policy, ConfirmationPolicy};
+use MEV_Bot_Solana::transactions::create_transaction::{ChainType, SendOrSimulate};
+use MEV_Bot_Solana::transactions::in_flight::init_in_flight_limiter;
+use MEV_Bot_Solana::transactions::presigned::{init_presigned, run_presigner, PresignedCache, TipLevel};
+use MEV_Bot_Solana::transactions::private_send::{init_send_mode, SendMode};
+use MEV_Bot_Solana::transactions::signer::{init_wallet_signer, signer_from_env, wallet_signer};
+use MEV_Bot_Solana::transactions::sweep::{run_profit_sweep, SweepConfig};
+use MEV_Bot_Solana::transactions::verify::{TradeVerifier, CALIBRATION_FILE};
+use MEV_Bot_Solana::transactions::warmup::WarmupConfig;
+use MEV_Bot_Solana::risk::authority_monitor::{run_authority_monitor, AuthorityMonitor};
+use MEV_Bot_Solana::risk::balance::{run_balance_monitor, BalanceMonitor};
+use MEV_Bot_Solana::risk::capital::{run_capital_refresh, CapitalManager};
+use MEV_Bot_Solana::risk::congestion::{CongestionMonitor, CongestionPolicy};
+use MEV_Bot_Solana::arbitrage::research::{init_research, ResearchConfig};
+use MEV_Bot_Solana::risk::fee_budget::{init_fee_budget, run_fee_budget_writer, FeeBudget, FEE_BUDGET_FILE};
+use MEV_Bot_Solana::risk::honeypot::{HoneypotDetector, HONEYPOT_BLACKLIST_FILE};
+use MEV_Bot_Solana::risk::exposure::{run_exposure_refresh, ExposureLimiter};
+use MEV_Bot_Solana::risk::kill_switch::{rearm, run_kill_switch_sync, KillSwitch, KILL_SWITCH_FILE};
+use MEV_Bot_Solana::risk::sanity::SanityBounds;
+use MEV_Bot_Solana::risk::safety::{SafetyPolicy, TokenSafetyChecker};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signer;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Options
-    let simulation_amount = 3_500_000_000; // 3.5 SOL
     let massive_strategy = true;
     let best_strategy = true;
     let optimism_strategy = true;
+    let stable_lst_strategy = true;
+    // Cycle through the token groups instead of running them all at once
+    let rotation_strategy = false;
+    let rotation_time_budget = Duration::from_secs(10 * 60);
+    // Strategies created by plugin name (arbitrage::plugins) with their config,
+    // e.g. ("rotation", HashMap::from([("time_budget_secs".to_string(), "600".to_string())]))
+    let plugin_strategies: Vec<(&str, HashMap<String, String>)> = Vec::new();
+
+    // Executor pool options
+    let executor_pool = true;
+    let max_in_flight_txs = 4;
+    // Unconfirmed transactions at the same time, all senders included; further sends are queued
+    let max_unconfirmed_txs = 4;
+    let opportunity_max_age = Duration::from_millis(1500);
+    // A pool set isn't submitted again within N slots, even across restarts (~60s)
+    let submission_slot_window = 150;
+    // Failed paths are retried after 5s, 10s, 20s... up to 10 minutes
+    let path_cooldown_base = Duration::from_secs(5);
+    let path_cooldown_max = Duration::from_secs(10 * 60);
+    // Tokens are disabled after N unprofitable executions in a row, for the quarantine duration
+    let token_max_consecutive_losses = 5;
+    let token_quarantine = Duration::from_secs(60 * 60);
+    // A venue failing more than dex_max_failure_rate of its last dex_stats_window swaps is left out of routing for dex_quarantine
+    let dex_stats_window = 50;
+    let dex_min_samples = 10;
+    let dex_max_failure_rate = 0.5;
+    let dex_quarantine = Duration::from_secs(30 * 60);
+
+    // A/B experiment on the executor pool parameters, results persisted in the "experiments" collection
+    let run_experiment = false;
+    let experiment_arms = (
+        ExperimentArm { name: "threshold-20M".to_string(), min_profit_threshold: 20_000_000.0, max_opportunity_age_ms: 1500 },
+        ExperimentArm { name: "threshold-10M".to_string(), min_profit_threshold: 10_000_000.0, max_opportunity_age_ms: 800 },
+    );
+
+    // Max loss over a rolling 24h before halting all sending (re-arm with `cargo run -- rearm`)
+    let max_daily_loss = 1_000_000_000.0; // 1 SOL
+    // Same in USD, converted at the SOL price when the bot starts (None: max_daily_loss)
+    let max_daily_loss_usd: Option<f64> = None;
+    // Fees + tips spent per UTC day before stopping, priority fees are capped past 80% of it
+    let daily_fee_budget = 500_000_000; // 0.5 SOL
+    let fee_budget_throttle_ratio = 0.8;
+
+    // SOL/USDC/USDT prices from the Pyth price accounts (followed on WSS_RPC_URL, polled without it): a hop quoted
+    // further than this from the oracle price (+ 3 confidence intervals) is rejected as stale pool data
+    let pyth_oracle_check = true;
+    let pyth_max_deviation = 0.05;
+    let pyth_max_age = Duration::from_secs(60);
+
+    // USD prices of SOL and the traded tokens from the Jupiter price API (JUPITER_API_KEY optional),
+    // valuing the non-SOL inventory and converting the USD thresholds
+    let price_refresh_interval = Duration::from_secs(30);
+    let price_max_age = Duration::from_secs(2 * 60);
+
+    // Max SOL value held in any single non-base token
+    let max_token_exposure = 2_000_000_000; // 2 SOL
+
+    // Native SOL kept for rent and fees, and balance alert thresholds (lamports)
+    let balance_reserve = 50_000_000; // 0.05 SOL
+    let balance_alert_thresholds = vec![500_000_000, 1_000_000_000, 5_000_000_000];
+
+    // Sweep everything above the working balance to COLD_WALLET_ADDRESS, off by default
+    let profit_sweep = false;
+    let sweep_working_balance = 10_000_000_000; // 10 SOL
+    let sweep_interval = Duration::from_secs(6 * 60 * 60);
+
+    // Raise the priority fees and skip low-EV opportunities when our sends expire, drop or confirm slowly
+    let congestion_policy = CongestionPolicy::default();
+
+    // Send only through Jito bundles (tip in lamports) or PRIVATE_RPC_URL, never on the public mempool
+    let send_mode = SendMode::JitoBundle { tip_lamports: 100_000 };
+
+    // Path math on its own thread pool (0 = half the cores), away from the tokio IO runtime
+    let compute_threads = 0;
+    let max_pending_compute_jobs = 4;
+
+    // Data feeds (WSS_RPC_URL, GEYSER_URL, SHREDSTREAM_URL, BLOCK_ENGINE_URL) reconnected
+    // 500ms, 1s, 2s... up to 30s after a failure, forever
+    let feed_backoff = BackoffPolicy::default();
+    // Pools of the best paths, their swaps and the slots followed with accountSubscribe/logsSubscribe/slotSubscribe
+    // on WSS_RPC_URL, resubscribed after every disconnect (the pool refresher keeps polling as a fallback)
+    let pubsub_ingestion = true;
+    // Pending account updates of the subscriptions, coalesced by account, the oldest dropped past this
+    let account_update_queue_capacity = 4096;
+    // Swaps through our pools read from the shredstream proxy at SHREDSTREAM_URL, ahead of the RPC,
+    // and pushed to the strategies: best_paths quotes the paths through them again (build with the shredstream feature)
+    #[cfg(feature = "shredstream")]
+    let shredstream_ingestion = true;
+    // Best paths re-ranked as their pools update, only the paths through the updated pool are quoted again
+    let incremental_reranking = true;
+
+    // Idempotent RPC/HTTP responses (mint info, supplies, largest holders, epoch, pool metadata) served
+    // from memory, the other methods keep their default TTL
+    let rpc_cache_ttls = [(CachedMethod::MintInfo, Duration::from_secs(10 * 60)), (CachedMethod::EpochInfo, Duration::from_secs(30))];
+
+    // Recent blockhash refreshed in the background every slot, never fetched while building a swap
+    let blockhash_prefetch_interval = Duration::from_millis(400);
+    // Opportunity swaps signed ahead at these multiples of the base priority fee and tip (empty: signed at send time),
+    // the executor sends the variant matching the congestion
+    let presigned_tip_multipliers: Vec<u64> = vec![1, 2, 5];
+
+    // Commitment a trade must reach before it counts as landed (finalized: slower, immune to skipped slots)
+    let confirmation_commitment = CommitmentConfig::confirmed();
+
+    // Compare the confirmed balance deltas with the simulation, flag trades deviating more than 30%
+    let verify_trades = true;
+    let verification_tolerance = 0.3;
+
+    // Requests per second per host for the RPC and the DEX/token APIs, to stay under the 429 limits
+    let rate_limits = RateLimiterConfig::default();
+
+    // Failover to the next endpoint of RPC_URL_FALLBACKS after N RPC failures in a row
+    let rpc_failure_threshold = 3;
+    let rpc_recovery_probe_interval = Duration::from_secs(15);
+    // Open the pooled RPC connections before the first quote or send
+    let rpc_warm_up = true;
+    // Swap simulation and lookup table reads sent to the N fastest healthy send endpoints, first answer wins (1 = current endpoint only)
+    let rpc_race_fanout = 2;
+
+    // Telegram (TELEGRAM_BOT_TOKEN + TELEGRAM_CHAT_ID), Discord (DISCORD_WEBHOOK_URL) and Slack (SLACK_WEBHOOK_URL) alerts, per event type
+    let notify_flags = NotifyFlags::default();
+    // End-of-day report at midnight UTC, saved in the "daily_summaries" collection and notified
+    let daily_summary = true;
+
+    // Research mode: every opportunity above research_min_profit is recorded with the reason it was not sent,
+    // research_dry_run never sends at all
+    let research_mode = false;
+    let research_min_profit = 1_000_000.0;
+    let research_dry_run = false;
+
+    // Block position and competitors touching the same pools earlier in the block, for every landed transaction
+    let landing_analytics = true;
+    // Heartbeats POSTed to HEARTBEAT_URL (any endpoint or a healthchecks.io ping URL), stalled without pool refresh
+    let heartbeat_interval = Duration::from_secs(60);
+    let heartbeat_stall_after = Duration::from_secs(5 * 60);
+    // One block sampled per interval to fingerprint the arbitragers on our pools (GET /competitors)
+    let competitor_scan_interval = Duration::from_secs(10);
+
+    // HTTP control API: dashboard (GET /), Prometheus scraping (GET /metrics) and PnL (GET /pnl, /pnl/daily, /pnl/weekly)
+    // Localhost only, another interface requires CONTROL_API_TOKEN
+    let control_api = true;
+    let control_api_addr = "127.0.0.1:9100";
+    // gRPC control API (mev.Control): start/stop and parameters of the strategies, stats, opportunities and events streams
+    // (build with the grpc feature). Localhost only as well, another interface requires CONTROL_API_TOKEN
+    #[cfg(feature = "grpc")]
+    let grpc_api_addr = Some("127.0.0.1:9101");
+
+    // Capital allocation per strategy (fraction of SOL + wSOL balance), only for the strategies pushing to the
+    // opportunity queue: the massive strategy never sends. Plugins reserve under the name of their strategy
+    let capital_shares = HashMap::from([
+        ("best_paths".to_string(), 0.6),
+        ("rotation".to_string(), 0.3),
+        ("optimism".to_string(), 0.1),
+    ]);
 
     // Massive strategy options
     let fetch_new_pools = false;
     let restrict_sol_usdc = true;
+    let max_concurrent_inputs = 4;
+    // Re-run the massive strategy on the same tokens and hot-swap the best paths (None: run once)
+    let best_paths_refresh_interval: Option<Duration> = Some(Duration::from_secs(30 * 60));
+
+    // Check mint/freeze authorities, Token-2022 extensions and holders before trading a token
+    let token_safety_checks = true;
+
+    // Automatic target-token discovery (Birdeye if BIRDEYE_API_KEY is set, DexScreener otherwise)
+    let auto_discovery = false;
+    let discovery_interval = Duration::from_secs(30 * 60);
 
     // Best strategy options
+    // Buy+sell round trip simulation of every new token, 0.01 SOL probe, blacklisted above 30% lost
+    let honeypot_detection = true;
+    let honeypot_probe_amount = 10_000_000;
+    let honeypot_max_loss_ratio = 0.3;
+    // Quarantine a token and sell it on a freeze or a mint of more than 10% of the supply
+    let authority_monitoring = true;
+    let authority_quarantine = Duration::from_secs(7 * 24 * 60 * 60);
+    let authority_max_supply_increase = 0.1;
+    // Account metas and instruction data of the swaps compiled with each path set, amounts patched in at execution
+    let instruction_templates = true;
+    // Create missing ATAs and extend the LUT (WARMUP_LUT_ADDRESS, a table the wallet owns) before looping on a new path set.
+    // Skipped without WARMUP_LUT_ADDRESS, simulated without LIVE_TRADING
+    let warmup_before_live = true;
+    // Best paths files written as bincode (.bin), the JSON files already written stay readable
+    let best_paths_format = PathsFormat::Binary;
     let path_best_strategy = "best_paths_selected/ultra_strategies/0-SOL-SOLLY-1-SOL-SPIKE-2-SOL-AMC-GME.json".to_string();
 
-    // Optimism tx path
+    // Only the pools between the configured tokens and bridge tokens are loaded, the rest on demand
+    let scoped_pool_loading = true;
+
+    // Pools of the loaded best paths refreshed in batches of 100 accounts (getMultipleAccounts)
+    let pool_refresh_interval = Duration::from_secs(2);
+
+    // Quotes above 20% instant profit are re-quoted on fresh pool data, then rejected
+    let sanity_bounds = SanityBounds::new(0.2).with_max_oracle_deviation(pyth_max_deviation);
+
+    // Optimism tx path (a single JSON file or a directory of them), replayed through the executor pool
     let optimism_path = "optimism_transactions/11-6-2024-SOL-SOLLY-SOL-0.json".to_string();
 
     let mut inputs_vec = vec![
@@ -66,6 +308,9 @@ async fn main() -> Result<()> {
             include_2hop: true,
             numbers_of_best_paths: 4,
             get_fresh_pools_bool: false,
+            preset: StrategyPreset::MemeTokens,
+            time_budget_secs: None,
+            bridge_tokens: Vec::new(),
         },
         InputVec {
             tokens_to_arb: vec![
@@ -82,6 +327,9 @@ async fn main() -> Result<()> {
             include_2hop: true,
             numbers_of_best_paths: 4,
             get_fresh_pools_bool: false,
+            preset: StrategyPreset::MemeTokens,
+            time_budget_secs: None,
+            bridge_tokens: Vec::new(),
         },
         InputVec {
             tokens_to_arb: vec![
@@ -98,6 +346,9 @@ async fn main() -> Result<()> {
             include_2hop: true,
             numbers_of_best_paths: 2,
             get_fresh_pools_bool: false,
+            preset: StrategyPreset::MemeTokens,
+            time_budget_secs: None,
+            bridge_tokens: Vec::new(),
         },
         InputVec {
             tokens_to_arb: vec![
@@ -118,122 +369,551 @@ async fn main() -> Result<()> {
             include_2hop: true,
             numbers_of_best_paths: 4,
             get_fresh_pools_bool: false,
+            preset: StrategyPreset::MemeTokens,
+            time_budget_secs: None,
+            bridge_tokens: Vec::new(),
         },
     ];
 
+    if stable_lst_strategy {
+        inputs_vec.extend(stable_lst_inputs());
+    }
+
+    // Intermediate hops allowed through these tokens, even when not listed in the InputVec
+    let use_bridge_tokens = false;
+    let bridge_tokens = vec![
+        TokenInArb { address: USDC_MINT.into(), symbol: "USDC".into() },
+        TokenInArb { address: USDT_MINT.into(), symbol: "USDT".into() },
+        TokenInArb { address: JITOSOL_MINT.into(), symbol: "JitoSOL".into() },
+    ];
+    if use_bridge_tokens {
+        for input in inputs_vec.iter_mut().filter(|input| input.bridge_tokens.is_empty()) {
+            input.bridge_tokens = bridge_tokens.clone();
+        }
+    }
+
+    // Pending schema migrations applied at startup, false refuses to start until `migrate` is run
+    let auto_migrate = true;
+
     dotenv::dotenv().ok();
     setup_logger()?;
+    #[cfg(feature = "console")]
+    MEV_Bot_Solana::common::tasks::init_console();
+    init_error_reporting();
+
+    // Operator commands
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("rearm") => return rearm(KILL_SWITCH_FILE),
+        // Check the hash chain of the signed transactions log
+        Some("audit-verify") => {
+            let entries = verify_audit_log(AUDIT_LOG_FILE)?;
+            info!("🧾 Audit log intact, {} entries", entries);
+            return Ok(());
+        }
+        // query results|best-paths [--collection name] [--token SOL] [--since YYYY-MM-DD] [--until YYYY-MM-DD] [--min-profit lamports] [--limit n]
+        Some("query") => {
+            init_storage(connect_storage_from_env().await?);
+            let flags = parse_flags(args.get(3..).unwrap_or(&[]))?;
+            let query = PathQuery::from_flags(&flags)?;
+            match args.get(2).map(|arg| arg.as_str()) {
+                Some("results") => {
+                    let collection = flags.get("collection").map(|c| c.as_str()).unwrap_or(SWAP_PATH_RESULTS_COLLECTION);
+                    for stored in query_swap_path_results(collection, &query).await? {
+                        println!("{}", serde_json::to_string(&stored)?);
+                    }
+                }
+                Some("best-paths") => {
+                    let collection = flags.get("collection").map(|c| c.as_str()).unwrap_or(BEST_PATHS_COLLECTION);
+                    for stored in query_best_paths(collection, &query).await? {
+                        println!("{}", serde_json::to_string(&stored)?);
+                    }
+                }
+                _ => error!("Usage: query results|best-paths [--token SOL] [--since YYYY-MM-DD] [--until YYYY-MM-DD] [--min-profit lamports] [--limit n]"),
+            }
+            return Ok(());
+        }
+        // export results|trades --output file [--format csv|parquet] [--collection name] [--since YYYY-MM-DD] [--until YYYY-MM-DD]
+        Some("export") => {
+            init_storage(connect_storage_from_env().await?);
+            let flags = parse_flags(args.get(3..).unwrap_or(&[]))?;
+            let query = PathQuery::from_flags(&flags)?;
+            let format = ExportFormat::parse(flags.get("format").map(|f| f.as_str()).unwrap_or("csv"))?;
+            let Some(output) = flags.get("output") else {
+                error!("Usage: export results|trades --output file [--format csv|parquet] [--since YYYY-MM-DD] [--until YYYY-MM-DD]");
+                return Ok(());
+            };
+            let rows = match args.get(2).map(|arg| arg.as_str()) {
+                Some("results") => {
+                    let collection = flags.get("collection").map(|c| c.as_str()).unwrap_or(SWAP_PATH_RESULTS_COLLECTION);
+                    export_swap_path_results(collection, &query, format, output).await?
+                }
+                Some("trades") => export_trades(&query, format, output).await?,
+                _ => {
+                    error!("Usage: export results|trades --output file [--format csv|parquet] [--since YYYY-MM-DD] [--until YYYY-MM-DD]");
+                    return Ok(());
+                }
+            };
+            info!("📤 {} rows exported to {}", rows, output);
+            return Ok(());
+        }
+        // sheets-export YYYY-MM-DD: the daily summary of a past day appended to the Google Sheet, to backfill it
+        Some("sheets-export") => {
+            let (Some(day), Some(sheets)) = (args.get(2), GoogleSheetsExporter::from_env()?) else {
+                error!("Usage: sheets-export YYYY-MM-DD, with GOOGLE_SERVICE_ACCOUNT_FILE and GOOGLE_SHEET_ID set");
+                return Ok(());
+            };
+            init_storage(connect_storage_from_env().await?);
+            let summary = build_daily_summary(NaiveDate::parse_from_str(day, "%Y-%m-%d")?, Vec::new()).await?;
+            sheets.append_summary(&summary).await?;
+            return Ok(());
+        }
+        // Interactive console over the cached pools: pools, pool state, quotes and route accounts
+        Some("debug") => {
+            DebugConsole::load(&Env::new().rpc_url).await?.run().await?;
+            return Ok(());
+        }
+        // paths show <file>: a best paths file, binary or JSON, printed as JSON
+        // paths jupiter-import <quote.json> <file>: a Jupiter quote response as a best paths file
+        // paths jupiter-export <file> <amount> <quotes.json>: the paths quoted locally, as Jupiter quote responses
+        Some("paths") => {
+            match (args.get(2).map(|arg| arg.as_str()), args.get(3), args.get(4), args.get(5)) {
+                (Some("show"), Some(file), _, _) => println!("{}", serde_json::to_string_pretty(&read_paths(file)?)?),
+                (Some("jupiter-import"), Some(quote_file), Some(file), _) => import_jupiter_file(quote_file, file).await?,
+                (Some("jupiter-export"), Some(file), Some(amount), Some(quotes_file)) => export_jupiter_file(file, amount.parse()?, quotes_file).await?,
+                _ => error!("Usage: paths show <file> | paths jupiter-import <quote.json> <file> | paths jupiter-export <file> <amount> <quotes.json>"),
+            }
+            return Ok(());
+        }
+        // backtest record <paths file> <snapshots.jsonl> [--interval-ms 2000] [--count 100]: pool states of the paths recorded over time
+        // backtest run <paths file> <snapshots.jsonl> [--amount lamports] [--min-profit lamports] [--max-profit-ratio r] [--latency snapshots]
+        //     [--land-rate r] [--cost lamports] [--output report.json]: the paths quoted and filled on the recorded states, hypothetical PnL.
+        //     Amount and min profit default to those of the preset of each path
+        Some("backtest") => {
+            let flags = parse_flags(args.get(5..).unwrap_or(&[]))?;
+            match (args.get(2).map(|arg| arg.as_str()), args.get(3), args.get(4)) {
+                (Some("record"), Some(paths_file), Some(snapshots_file)) => {
+                    let interval = Duration::from_millis(flags.get("interval-ms").map(|ms| ms.parse()).transpose()?.unwrap_or(2_000));
+                    let count = flags.get("count").map(|count| count.parse()).transpose()?.unwrap_or(100);
+                    record_snapshots(paths_file, snapshots_file, interval, count).await?;
+                }
+                (Some("run"), Some(paths_file), Some(snapshots_file)) => {
+                    let mut config = BacktestConfig::default();
+                    if let Some(amount) = flags.get("amount") {
+                        config = config.with_amount(amount.parse()?);
+                    }
+                    if let Some(min_profit) = flags.get("min-profit") {
+                        config = config.with_min_profit(min_profit.parse()?);
+                    }
+                    if let Some(max_profit_ratio) = flags.get("max-profit-ratio") {
+                        config = config.with_sanity_bounds(SanityBounds::new(max_profit_ratio.parse()?));
+                    }
+                    let mut fill = FillAssumptions::default();
+                    if let Some(latency) = flags.get("latency") {
+                        fill.latency_snapshots = latency.parse()?;
+                    }
+                    if let Some(land_rate) = flags.get("land-rate") {
+                        fill.land_rate = land_rate.parse()?;
+                    }
+                    if let Some(cost) = flags.get("cost") {
+                        fill.cost_lamports = cost.parse()?;
+                    }
+                    backtest_files(paths_file, snapshots_file, &config.with_fill(fill), flags.get("output").map(|output| output.as_str())).await?;
+                }
+                _ => error!("Usage: backtest record <paths file> <snapshots.jsonl> [--interval-ms n] [--count n] | backtest run <paths file> <snapshots.jsonl> [--amount n] [--min-profit n] [--latency n] [--land-rate r] [--cost n] [--output file]"),
+            }
+            return Ok(());
+        }
+        // localnet-record <paths file> [dir]: the pools, vaults, lookup tables and DEX programs of the paths
+        // snapshotted from RPC_URL as localnet fixtures (fixtures/localnet by default)
+        Some("localnet-record") => {
+            let Some(file) = args.get(2) else {
+                error!("Usage: localnet-record <paths file> [dir]");
+                return Ok(());
+            };
+            let dir = args.get(3).map(|dir| dir.as_str()).unwrap_or(LOCALNET_FIXTURES_DIR);
+            record_fixtures(&Env::new().rpc_url, file, dir).await?;
+            return Ok(());
+        }
+        // localnet [dir]: solana-test-validator with the fixtures loaded until Ctrl-C, to run the bot against it
+        Some("localnet") => {
+            let validator = LocalValidator::start(args.get(2).map(|dir| dir.as_str()).unwrap_or(LOCALNET_FIXTURES_DIR)).await?;
+            info!("🧪 Set RPC_URL and RPC_URL_TX to {} to run against it", validator.rpc_url());
+            tokio::signal::ctrl_c().await?;
+            return Ok(());
+        }
+        // bench [--samples n]: quote throughput, build + sign time and round trips of every configured endpoint,
+        // to compare VPS locations
+        Some("bench") => {
+            let flags = parse_flags(args.get(2..).unwrap_or(&[]))?;
+            let mut config = BenchConfig::default();
+            if let Some(samples) = flags.get("samples") {
+                config.rpc_samples = samples.parse()?;
+            }
+            let mut rpc_urls = quote_breaker().urls();
+            rpc_urls.extend(send_breaker().urls());
+            rpc_urls.sort();
+            rpc_urls.dedup();
+            run_bench(&rpc_urls, &config).await?.print();
+            return Ok(());
+        }
+        // coverage [--mints mint1,mint2] [--min-liquidity usd]: live DexScreener pairs of the mints (the tokens to arb by default)
+        // missing from the cached pool lists, and the venues without loader
+        Some("coverage") => {
+            let flags = parse_flags(args.get(2..).unwrap_or(&[]))?;
+            let mut mints: Vec<String> = match flags.get("mints") {
+                Some(mints) => mints.split(',').map(str::to_string).collect(),
+                None => inputs_vec.iter().flat_map(|input| input.all_tokens()).map(|token| token.address).collect(),
+            };
+            mints.sort();
+            mints.dedup();
+            let min_liquidity_usd = flags.get("min-liquidity").map(|usd| usd.parse()).transpose()?.unwrap_or(1_000.0);
+            let dexs = load_all_pools(false).await;
+            check_pool_coverage(&DexScreenerClient::new(), &mints, &dexs, min_liquidity_usd).await?.print();
+            return Ok(());
+        }
+        // Bring the database schema up to date
+        Some("migrate") => {
+            let storage = connect_storage_from_env().await?;
+            let applied = run_migrations(storage.as_ref()).await?;
+            info!("🗄️ {} migrations applied on {}", applied, storage.name());
+            return Ok(());
+        }
+        _ => {}
+    }
 
     info!("Starting MEV_Bot_Solana");
+    // DATABASE_URL picks the backend: mongodb:// (default), postgres:// or sqlite:
+    let storage = connect_storage_from_env().await?;
+    if auto_migrate {
+        run_migrations(storage.as_ref()).await?;
+    } else {
+        check_schema(storage.as_ref()).await?;
+    }
+    init_storage(storage);
+    init_rate_limiter(rate_limits);
+    init_circuit_breakers(rpc_failure_threshold);
+    init_rpc_racer(rpc_race_fanout);
+    init_feeds(FeedsConfig::from_env().with_backoff(feed_backoff));
+    if rpc_warm_up {
+        let mut rpc_urls = quote_breaker().urls();
+        rpc_urls.extend(send_breaker().urls());
+        rpc_urls.sort();
+        rpc_urls.dedup();
+        warm_up_rpc_clients(&rpc_urls, &[CommitmentConfig::default(), confirmation_commitment]).await;
+    }
+    init_send_mode(send_mode);
+    init_compute_pool(compute_threads, max_pending_compute_jobs);
+    init_rpc_cache(rpc_cache_ttls.into_iter().fold(ResponseCache::new(), |cache, (method, ttl)| cache.with_ttl(method, ttl)));
+    let token_prices = init_token_prices(TokenPrices::new(Arc::new(JupiterPriceProvider::from_env()), price_max_age));
+    if let Err(e) = token_prices.refresh().await {
+        error!("❌ Unable to fetch the token prices: {:?}", e);
+    }
+    spawn_named("price_refresh", run_price_refresh(token_prices, price_refresh_interval));
+    if pyth_oracle_check {
+        let oracle = init_pyth_oracle(PythOracle::new(default_pyth_feeds(), pyth_max_age));
+        spawn_named("pyth_oracle", run_pyth_oracle(oracle, Env::new().rpc_url, Duration::from_secs(2)));
+    }
+    init_account_updates(account_update_queue_capacity);
+    let live_ranking = incremental_reranking.then(|| Arc::new(LiveRanking::new()));
+    spawn_named("account_update_applier", run_account_update_applier(live_ranking.clone()));
+    init_paths_format(best_paths_format);
+    init_in_flight_limiter(max_unconfirmed_txs);
+    init_fee_budget(FeeBudget::load(FEE_BUDGET_FILE, daily_fee_budget, fee_budget_throttle_ratio));
+    spawn_named("fee_budget_writer", run_fee_budget_writer(Duration::from_secs(10)));
+    if research_mode {
+        init_research(ResearchConfig { min_profit: research_min_profit, dry_run: research_dry_run });
+    }
+    init_audit_log(AUDIT_LOG_FILE);
+    let mut notifiers = Notifiers::new(notify_flags);
+    if let Some(telegram) = TelegramNotifier::from_env() {
+        notifiers = notifiers.with_notifier(Arc::new(telegram));
+    }
+    if let Some(discord) = DiscordNotifier::from_env() {
+        notifiers = notifiers.with_notifier(Arc::new(discord));
+    }
+    if let Some(slack) = SlackNotifier::from_env() {
+        notifiers = notifiers.with_notifier(Arc::new(slack));
+    }
+    // Notifier and database writer hang off the event bus
+    spawn_named("notifier", run_notifier(notifiers));
+    if let Some(persisted) = persisted_events() {
+        spawn_named("database_writer", run_database_writer(persisted));
+    }
+    if daily_summary {
+        spawn_named("daily_summary", run_daily_summary());
+    }
+    init_confirmation_policy(ConfirmationPolicy { commitment: confirmation_commitment, ..ConfirmationPolicy::default() });
+    spawn_named("quote_recovery_probe", run_recovery_probe(quote_breaker(), rpc_recovery_probe_interval));
+    spawn_named("send_recovery_probe", run_recovery_probe(send_breaker(), rpc_recovery_probe_interval));
+    spawn_named("blockhash_prefetcher", run_blockhash_prefetcher(CommitmentConfig::confirmed(), blockhash_prefetch_interval));
+    if !presigned_tip_multipliers.is_empty() {
+        let presigned = init_presigned(PresignedCache::new(TipLevel::scaled(&presigned_tip_multipliers), CommitmentConfig::confirmed()));
+        spawn_named("presigner", run_presigner(presigned, blockhash_prefetch_interval / 4));
+    }
     info!("⚠️ New fresh pools fetched on METEORA and RAYDIUM are excluded because they often have low liquidity");
     info!("⚠️ Liquidity is fetched from API and may be outdated on Raydium Pool");
 
-    let mut set: JoinSet<()> = JoinSet::new();
-    let tokens_to_arb: Vec<_> = inputs_vec.clone().into_iter().flat_map(|input| input.tokens_to_arb).collect();
+    let tokens_to_arb: Vec<_> = inputs_vec.iter().flat_map(|input| input.all_tokens()).collect();
 
-    info!("Open Socket.IO channel...");
     let env = Env::new();
-    
-    let callback = |payload: Payload, _: Client| {
-        async move {
-            match payload {
-                Payload::Text(data) => println!("Received: {:?}", data),
-                Payload::Binary(data) => println!("Received bytes: {:#?}", data),
+
+    info!("🪙 Tokens: {:?}", tokens_to_arb);
+    // Best paths file shared between the massive strategy (writer) and the best paths strategy (reader)
+    let best_paths_file = Arc::new(RwLock::new(if massive_strategy { None } else { Some(path_best_strategy) }));
+
+    init_wallet_signer(signer_from_env()?);
+    let payer = wallet_signer();
+    let capital_manager = Arc::new(CapitalManager::new(capital_shares));
+    // First balance known before any executor reserves capital
+    if let Err(e) = capital_manager.refresh_balance(&nonblocking_client(&env.rpc_url), &payer.pubkey()).await {
+        error!("❌ Initial capital refresh failed: {:?}", e);
+    }
+    spawn_named("capital_refresh", run_capital_refresh(capital_manager.clone(), env.rpc_url.clone(), payer.pubkey(), Duration::from_secs(30)));
+
+    let max_daily_loss = match max_daily_loss_usd.and_then(|usd| token_prices.lamports_for_usd(usd)) {
+        Some(lamports) => lamports as f64,
+        None => max_daily_loss,
+    };
+    let kill_switch = Arc::new(KillSwitch::load(KILL_SWITCH_FILE, max_daily_loss));
+    spawn_named("kill_switch_sync", run_kill_switch_sync(kill_switch.clone(), Duration::from_secs(5)));
+
+    if profit_sweep && !env.cold_wallet_address.is_empty() {
+        let sweep_config = SweepConfig {
+            cold_wallet: from_str(&env.cold_wallet_address)?,
+            working_balance: sweep_working_balance,
+            min_sweep: 100_000_000,
+            fee_reserve: balance_reserve,
+            simulate_or_send: SendOrSimulate::Send,
+        };
+        spawn_named("profit_sweep", run_profit_sweep(sweep_config, sweep_interval));
+    }
+
+    let balance_monitor = Arc::new(BalanceMonitor::new(balance_reserve, balance_alert_thresholds));
+    spawn_named("balance_monitor", run_balance_monitor(balance_monitor.clone(), env.rpc_url.clone(), payer.pubkey(), Duration::from_secs(15)));
+
+    let exposure_limiter = Arc::new(ExposureLimiter::new(SOL_MINT, max_token_exposure));
+    spawn_named("exposure_refresh", run_exposure_refresh(exposure_limiter.clone(), env.rpc_url.clone(), payer.pubkey(), Duration::from_secs(30)));
+
+    let pnl_tracker = Arc::new(match PnlTracker::load(exposure_limiter.clone()).await {
+        Ok(tracker) => tracker,
+        Err(e) => {
+            error!("❌ Unable to reload the PnL history: {:?}", e);
+            PnlTracker::new(exposure_limiter.clone())
+        }
+    });
+    if landing_analytics {
+        spawn_named("landing_analyzer", run_landing_analyzer(env.rpc_url.clone()));
+    }
+    let competitor_tracker = Arc::new(CompetitorTracker::new());
+    let dex_stats = Arc::new(DexStats::new(dex_stats_window, dex_min_samples, dex_max_failure_rate, dex_quarantine));
+    spawn_named("dex_stats", run_dex_stats(dex_stats.clone(), env.rpc_url.clone()));
+    spawn_named("competitor_tracker", run_competitor_tracker(competitor_tracker.clone(), env.rpc_url.clone(), competitor_scan_interval));
+    if control_api {
+        let addr = control_api_addr.parse()?;
+        let dashboard = Arc::new(DashboardState::new());
+        spawn_named("dashboard_state", run_dashboard_state(dashboard.clone()));
+        let state = ApiState { pnl: Some(pnl_tracker.clone()), dashboard: Some(dashboard), competitors: Some(competitor_tracker.clone()), dex_stats: Some(dex_stats.clone()), token: (!env.control_api_token.is_empty()).then(|| env.control_api_token.clone()) };
+        spawn_named("control_api", async move {
+            if let Err(e) = run_control_api(addr, state).await {
+                error!("❌ Control API stopped: {:?}", e);
             }
+        });
+    }
+
+    let claims_registry = Arc::new(ClaimsRegistry::new(Duration::from_secs(30)));
+    let submission_guard = Arc::new(match SubmissionGuard::load(submission_slot_window, &env.rpc_url).await {
+        Ok(guard) => guard,
+        Err(e) => {
+            error!("❌ Unable to reload the recent submissions: {:?}", e);
+            SubmissionGuard::new(submission_slot_window)
         }
-        .boxed()
+    });
+    spawn_named("submission_slot_refresh", run_submission_slot_refresh(submission_guard.clone(), env.rpc_url.clone(), Duration::from_secs(2)));
+    let path_ranking = Arc::new(PathRanking::load(PATH_STATS_FILE));
+    spawn_named("path_stats_writer", run_path_stats_writer(path_ranking.clone(), Duration::from_secs(10)));
+    let path_cooldowns = Arc::new(PathCooldowns::new(path_cooldown_base, path_cooldown_max));
+    let token_health = Arc::new(TokenHealth::new(token_max_consecutive_losses, token_quarantine));
+    let opportunity_queue = Arc::new(OpportunityQueue::new(64, opportunity_max_age));
+    // Real funds only on an explicit LIVE_TRADING=true, a fresh checkout simulates
+    let executor_mode = if env.live_trading == "true" {
+        info!("💸 LIVE_TRADING set, the executor pool sends real transactions");
+        SendOrSimulate::Send
+    } else {
+        info!("🧪 LIVE_TRADING not set, the executor pool only simulates");
+        SendOrSimulate::Simulate
     };
-    
-    let socket = ClientBuilder::new("wss://lively-shy-smoke.solana-mainnet.quiknode.pro/xxx")
-        .namespace("/")
-        .on("connection", callback)
-        .on("error", |err, _| async move { error!("Socket.IO error: {}", err) }.boxed())
-        .on("orca_quote", callback)
-        .on("orca_quote_res", callback)
-        .connect()
-        .await?;
+    if !env.heartbeat_url.is_empty() {
+        let heartbeat_config = HeartbeatConfig { url: env.heartbeat_url.clone(), interval: heartbeat_interval, stall_after: heartbeat_stall_after };
+        spawn_named("heartbeat", run_heartbeat(heartbeat_config, executor_pool.then(|| opportunity_queue.clone())));
+    }
+    if executor_pool {
+        let experiment = if run_experiment {
+            let experiment = Arc::new(Experiment::new("executor-thresholds", experiment_arms.0, experiment_arms.1));
+            spawn_named("experiment_reports", run_experiment_reports(experiment.clone(), Duration::from_secs(15 * 60)));
+            Some(experiment)
+        } else {
+            None
+        };
+        let executor_ctx = Arc::new(ExecutorContext {
+            max_in_flight: max_in_flight_txs,
+            capital: capital_manager.clone(),
+            path_ranking: path_ranking.clone(),
+            cooldowns: path_cooldowns.clone(),
+            token_health: token_health.clone(),
+            dex_stats: dex_stats.clone(),
+            experiment,
+            kill_switch: kill_switch.clone(),
+            exposure: exposure_limiter.clone(),
+            balance: balance_monitor.clone(),
+            congestion: Arc::new(CongestionMonitor::new(congestion_policy)),
+            pnl: pnl_tracker.clone(),
+            claims: claims_registry.clone(),
+            submissions: submission_guard.clone(),
+            verifier: if verify_trades {
+                Some(Arc::new(TradeVerifier::new(env.rpc_url.clone(), payer.pubkey(), verification_tolerance, CALIBRATION_FILE)))
+            } else {
+                None
+            },
+            chain: ChainType::Mainnet,
+            simulate_or_send: executor_mode,
+        });
+        spawn_named("executor_pool", run_executor_pool(opportunity_queue.clone(), executor_ctx));
+    }
 
+    // Pools loaded once for the massive and rotation strategies
+    let mut market_state = MarketState::new();
+    if scoped_pool_loading {
+        market_state = market_state.with_scope(tokens_to_arb.iter().filter_map(|token| from_str(&token.address).ok()).collect());
+    }
+    let market_state = Arc::new(market_state);
+    let mut engine = ArbitrageEngine::builder()
+        .with_signer(payer.clone())
+        .with_markets(market_state.clone())
+        .with_opportunity_queue(opportunity_queue.clone())
+        .with_inputs(inputs_vec.clone());
+    // Daily summaries appended to a Google Sheet as well (GOOGLE_SERVICE_ACCOUNT_FILE + GOOGLE_SHEET_ID)
+    if daily_summary {
+        if let Some(sheets) = GoogleSheetsExporter::from_env()? {
+            engine = engine.with_sink(Arc::new(sheets));
+        }
+    }
+    let pubsub = Arc::new(PubsubSubscriptions::new(confirmation_commitment));
+    if pubsub_ingestion && feeds().endpoint(FeedKind::AccountWs).is_some() {
+        spawn_named("pubsub", run_pubsub(pubsub.clone(), engine.events()));
+    }
+    #[cfg(feature = "shredstream")]
+    if shredstream_ingestion && feeds().endpoint(FeedKind::Shredstream).is_some() {
+        spawn_named("shredstream", MEV_Bot_Solana::markets::shredstream::run_shredstream(engine.events()));
+    }
     if massive_strategy {
-        info!("🏊 Fetching pools...");
-        let dexs = load_all_pools(fetch_new_pools).await;
-        info!("🏊 Loaded {} dexs", dexs.len());
-        
-        info!("🪙 Tokens: {:?}", tokens_to_arb);
-        info!("📈 Starting arbitrage...");
-        let mut vec_best_paths = Vec::new();
-        for input_iter in inputs_vec.clone() {
-            let tokens_infos = get_tokens_infos(input_iter.tokens_to_arb.clone()).await;
-
-            let result = run_arbitrage_strategy(
-                simulation_amount,
-                input_iter.get_fresh_pools_bool,
-                restrict_sol_usdc,
-                input_iter.include_1hop,
-                input_iter.include_2hop,
-                input_iter.numbers_of_best_paths,
-                dexs.clone(),
-                input_iter.tokens_to_arb.clone(),
-                tokens_infos.clone(),
-            )
-            .await?;
-            let (path_for_best_strategy, _) = result;
-            vec_best_paths.push(path_for_best_strategy);
-        }
-        if inputs_vec.len() > 1 {
-            let mut vec_to_ultra_strategy: Vec<_> = Vec::new();
-            let mut ultra_strategy_name = String::new();
-            for (index, path) in vec_best_paths.iter().enumerate() {
-                let name_parts: Vec<_> = path.split('/').collect();
-                let name: Vec<_> = name_parts[1].split('.').collect();
-                ultra_strategy_name = if index == 0 {
-                    format!("{}-{}", index, name[0])
-                } else {
-                    format!("{}-{}-{}", ultra_strategy_name, index, name[0])
-                };
-
-                let file = File::open(path)?;
-                let paths_vec: VecSwapPathSelected = serde_json::from_reader(file)?;
-                vec_to_ultra_strategy.extend(paths_vec.value);
+        let mut strategy = MassiveStrategy::new(inputs_vec.clone(), fetch_new_pools, restrict_sol_usdc, best_paths_file.clone())
+            .with_market_state(market_state.clone())
+            .with_max_concurrent_inputs(max_concurrent_inputs)
+            .with_token_health(token_health.clone());
+        if token_safety_checks {
+            let mut safety_checker = TokenSafetyChecker::new(env.rpc_url.clone(), SafetyPolicy::default(), Duration::from_secs(6 * 60 * 60));
+            // Liquidity and holder figures from Birdeye when BIRDEYE_API_KEY is set
+            if let Some(birdeye) = BirdeyeClient::from_env() {
+                safety_checker = safety_checker.with_birdeye(birdeye);
             }
-            let path = format!("best_paths_selected/ultra_strategies/{}.json", ultra_strategy_name);
-            let file = File::create(&path).map_err(|e| error!("Failed to create file {}: {}", path, e))?;
-            let mut writer = BufWriter::new(file);
-        
-            let content = VecSwapPathSelected { value: vec_to_ultra_strategy };
-            serde_json::to_writer(&writer, &content)?;
-            writer.flush()?;
-            info!("Written to {}", path);
-
-            insert_vec_swap_path_selected_collection("ultra_strategies", content)
-                .await
-                .map_err(|e| {
-                    error!("Failed to insert to ultra_strategies: {}", e);
-                    e
-                })?;
-
-            path_best_strategy = path;
+            strategy = strategy.with_safety_checker(Arc::new(safety_checker));
         }
-
-        if best_strategy {
-            let tokens_infos = get_tokens_infos(tokens_to_arb.clone()).await;
-            sorted_interesting_path_strategy(simulation_amount, path_best_strategy.clone(), tokens_to_arb.clone(), tokens_infos.clone())
-                .await?;
+        if let Some(refresh_interval) = best_paths_refresh_interval {
+            strategy = strategy.with_refresh_interval(refresh_interval);
         }
+        if auto_discovery {
+            let filters = DiscoveryFilters::default();
+            let discovered_inputs = Arc::new(RwLock::new(match discover_tokens(&filters).await {
+                Ok(tokens) => discovered_inputs(&tokens),
+                Err(e) => {
+                    error!("❌ Token discovery failed: {:?}", e);
+                    Vec::new()
+                }
+            }));
+            spawn_named("discovery", run_discovery(filters, discovered_inputs.clone(), discovery_interval));
+            strategy = strategy.with_discovered_inputs(discovered_inputs);
+        }
+        engine = engine.with_strategy(Box::new(strategy));
+    }
+    if best_strategy {
+        let mut pool_refresher = PoolRefresher::new();
+        if pubsub_ingestion {
+            pool_refresher = pool_refresher.with_subscriptions(pubsub.clone());
+        }
+        let pool_refresher = Arc::new(pool_refresher);
+        spawn_named("pool_refresher", run_pool_refresher(pool_refresher.clone(), pool_refresh_interval));
+        let mut strategy = BestPathStrategy::new(tokens_to_arb.clone(), best_paths_file.clone())
+            .with_pool_refresher(pool_refresher)
+            .with_sanity_bounds(sanity_bounds.clone())
+            .with_path_ranking(path_ranking.clone())
+            .with_cooldowns(path_cooldowns.clone())
+            .with_token_health(token_health.clone());
+        if executor_pool {
+            strategy = strategy.with_opportunity_queue(opportunity_queue.clone());
+        }
+        if let Some(live_ranking) = &live_ranking {
+            strategy = strategy.with_live_ranking(live_ranking.clone());
+        }
+        if honeypot_detection {
+            strategy = strategy.with_honeypot_detector(Arc::new(HoneypotDetector::load(HONEYPOT_BLACKLIST_FILE, honeypot_probe_amount, honeypot_max_loss_ratio)));
+        }
+        if authority_monitoring {
+            let authority_monitor = Arc::new(AuthorityMonitor::new(
+                token_health.clone(),
+                authority_quarantine,
+                authority_max_supply_increase,
+                // Exits are allowed even with the kill switch tripped
+                SendOrSimulate::Send,
+                payer.pubkey(),
+            ));
+            spawn_named("authority_monitor", run_authority_monitor(authority_monitor.clone()));
+            strategy = strategy.with_authority_monitor(authority_monitor);
+        }
+        if instruction_templates {
+            strategy = strategy.with_instruction_templates();
+        }
+        if warmup_before_live && env.warmup_lut_address.is_empty() {
+            warn!("⚠️ WARMUP_LUT_ADDRESS not set, no warm-up of the new path sets");
+        } else if warmup_before_live {
+            strategy = strategy.with_warmup(WarmupConfig {
+                chain: ChainType::Mainnet,
+                simulate_or_send: executor_mode,
+                lut_address: from_str(&env.warmup_lut_address)?,
+                // Only shapes the instruction data of the warm-up transactions
+                amount_in: StrategyPreset::default().params().simulation_amount,
+            });
+        }
+        engine = engine.with_strategy(Box::new(strategy));
     }
-    
-    if best_strategy && !massive_strategy {
-        let tokens_infos = get_tokens_infos(tokens_to_arb.clone()).await;
-        sorted_interesting_path_strategy(simulation_amount, path_best_strategy.clone(), tokens_to_arb.clone(), tokens_infos.clone())
-            .await?;
+    if rotation_strategy {
+        let mut strategy = RotationStrategy::new(inputs_vec.clone(), fetch_new_pools, restrict_sol_usdc, rotation_time_budget)
+            .with_market_state(market_state.clone())
+            .with_sanity_bounds(sanity_bounds.clone());
+        if executor_pool {
+            strategy = strategy.with_opportunity_queue(opportunity_queue.clone());
+        }
+        engine = engine.with_strategy(Box::new(strategy));
+    }
+    // Replayed through the executor pool, nothing to send them without it
+    if optimism_strategy && executor_pool {
+        engine = engine.with_strategy(Box::new(OptimismStrategy::new(optimism_path, opportunity_queue.clone()).with_max_concurrent(4)));
     }
-    
-    if optimism_strategy {
-        optimism_tx_strategy(optimism_path)?;
+    for (plugin, config) in &plugin_strategies {
+        engine = engine.with_plugin_strategy(plugin, config)?;
     }
-    
-    while let Some(res) = set.join_next().await {
-        info!("{:?}", res);
+    #[cfg(feature = "grpc")]
+    if let Some(grpc_api_addr) = grpc_api_addr {
+        use MEV_Bot_Solana::monitoring::grpc::{run_grpc_api, GrpcState};
+        let addr = grpc_api_addr.parse()?;
+        let state = GrpcState { strategies: engine.strategies(), events: engine.events(), opportunities: opportunity_queue.clone(), pnl: Some(pnl_tracker.clone()), token: (!env.control_api_token.is_empty()).then(|| env.control_api_token.clone()) };
+        spawn_named("grpc_api", async move {
+            if let Err(e) = run_grpc_api(addr, state).await {
+                error!("❌ gRPC API stopped: {:?}", e);
+            }
+        });
     }
+    engine.build()?.run().await?;
 
     println!("End");
     Ok(())
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use log::{error, info};
+use serde::Serialize;
+
+use crate::common::dexscreener::{DexScreenerClient, DexScreenerPair};
+use crate::markets::types::{Dex, DexLabel};
+
+// Loader which should know a DexScreener pair, None for the venues without one (Raydium CPMM, Meteora dynamic pools, Phoenix...)
+pub fn loader_for_pair(pair: &DexScreenerPair) -> Option<DexLabel> {
+    let has_label = |label: &str| pair.labels.iter().any(|pair_label| pair_label.eq_ignore_ascii_case(label));
+    match pair.dex_id.as_str() {
+        "raydium" if has_label("CLMM") => Some(DexLabel::RAYDIUM_CLMM),
+        "raydium" if has_label("CPMM") => None,
+        "raydium" => Some(DexLabel::RAYDIUM),
+        "orca" if has_label("wp") => Some(DexLabel::ORCA_WHIRLPOOLS),
+        "orca" => Some(DexLabel::ORCA),
+        "meteora" if has_label("DLMM") => Some(DexLabel::METEORA),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverageGap {
+    pub pair_address: String,
+    pub dex_id: String,
+    pub labels: Vec<String>,
+    pub base_symbol: String,
+    pub quote_symbol: String,
+    pub liquidity_usd: f64,
+    pub volume_24h_usd: f64,
+}
+
+impl From<&DexScreenerPair> for CoverageGap {
+    fn from(pair: &DexScreenerPair) -> Self {
+        CoverageGap {
+            pair_address: pair.pair_address.clone(),
+            dex_id: pair.dex_id.clone(),
+            labels: pair.labels.clone(),
+            base_symbol: pair.base_token.symbol.clone(),
+            quote_symbol: pair.quote_token.symbol.clone(),
+            liquidity_usd: pair.liquidity_usd(),
+            volume_24h_usd: pair.volume_24h_usd(),
+        }
+    }
+}
+
+// Live pairs of a mint against the pools loaded by load_all_pools
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MintCoverage {
+    pub mint: String,
+    pub live_pairs: usize,
+    pub covered_pairs: usize,
+    // On a DEX we load, but missing from its pool list: a loader bug or a stale cache
+    pub missed: Vec<CoverageGap>,
+    // On a venue without a loader
+    pub unsupported: Vec<CoverageGap>,
+    pub covered_liquidity_usd: f64,
+    pub total_liquidity_usd: f64,
+}
+
+impl MintCoverage {
+    // Share of the live liquidity in pools we load, 1 without any
+    pub fn liquidity_coverage(&self) -> f64 {
+        if self.total_liquidity_usd > 0.0 { self.covered_liquidity_usd / self.total_liquidity_usd } else { 1.0 }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CoverageReport {
+    pub mints: Vec<MintCoverage>,
+}
+
+impl CoverageReport {
+    // Uncovered liquidity of every venue, deepest first
+    pub fn unsupported_venues(&self) -> Vec<(String, f64)> {
+        let mut venues: HashMap<String, f64> = HashMap::new();
+        for gap in self.mints.iter().flat_map(|coverage| coverage.unsupported.iter()) {
+            let venue = if gap.labels.is_empty() { gap.dex_id.clone() } else { format!("{} {}", gap.dex_id, gap.labels.join("/")) };
+            *venues.entry(venue).or_default() += gap.liquidity_usd;
+        }
+        let mut venues: Vec<(String, f64)> = venues.into_iter().collect();
+        venues.sort_by(|a, b| b.1.total_cmp(&a.1));
+        venues
+    }
+
+    pub fn print(&self) {
+        for coverage in &self.mints {
+            println!(
+                "{}: {}/{} live pairs loaded, {:.1}% of ${:.0} liquidity",
+                coverage.mint,
+                coverage.covered_pairs,
+                coverage.live_pairs,
+                coverage.liquidity_coverage() * 100.0,
+                coverage.total_liquidity_usd
+            );
+            for gap in &coverage.missed {
+                println!("  missed      {} {}/{} on {} {:?}, ${:.0} liquidity", gap.pair_address, gap.base_symbol, gap.quote_symbol, gap.dex_id, gap.labels, gap.liquidity_usd);
+            }
+            for gap in &coverage.unsupported {
+                println!("  unsupported {} {}/{} on {} {:?}, ${:.0} liquidity", gap.pair_address, gap.base_symbol, gap.quote_symbol, gap.dex_id, gap.labels, gap.liquidity_usd);
+            }
+        }
+        for (venue, liquidity_usd) in self.unsupported_venues() {
+            println!("Venue without loader: {}, ${:.0} liquidity", venue, liquidity_usd);
+        }
+    }
+}
+
+// Cross-check of the live pairs of a mint with the loaded pools, by pool address
+pub fn mint_coverage(mint: &str, pairs: &[DexScreenerPair], dexs: &[Dex]) -> MintCoverage {
+    let loaded: HashMap<&str, &DexLabel> = dexs
+        .iter()
+        .flat_map(|dex| dex.pairToMarkets.values().flatten())
+        .map(|market| (market.id.as_str(), &market.dexLabel))
+        .collect();
+    let mut coverage = MintCoverage { mint: mint.to_string(), live_pairs: pairs.len(), ..MintCoverage::default() };
+    for pair in pairs {
+        let liquidity_usd = pair.liquidity_usd();
+        coverage.total_liquidity_usd += liquidity_usd;
+        if loaded.contains_key(pair.pair_address.as_str()) {
+            coverage.covered_pairs += 1;
+            coverage.covered_liquidity_usd += liquidity_usd;
+            continue;
+        }
+        match loader_for_pair(pair) {
+            Some(_) => coverage.missed.push(CoverageGap::from(pair)),
+            None => coverage.unsupported.push(CoverageGap::from(pair)),
+        }
+    }
+    coverage.missed.sort_by(|a, b| b.liquidity_usd.total_cmp(&a.liquidity_usd));
+    coverage.unsupported.sort_by(|a, b| b.liquidity_usd.total_cmp(&a.liquidity_usd));
+    coverage
+}
+
+// Pools our loaders missed for each mint, pairs under `min_liquidity_usd` left out
+pub async fn check_pool_coverage(dexscreener: &DexScreenerClient, mints: &[String], dexs: &[Dex], min_liquidity_usd: f64) -> Result<CoverageReport> {
+    let mut report = CoverageReport::default();
+    for mint in mints {
+        let pairs: Vec<DexScreenerPair> = match dexscreener.token_pairs(mint).await {
+            Ok(pairs) => pairs.into_iter().filter(|pair| pair.liquidity_usd() >= min_liquidity_usd).collect(),
+            Err(e) => {
+                error!("❌ DexScreener pairs of {} not fetched: {:?}", mint, e);
+                continue;
+            }
+        };
+        let coverage = mint_coverage(mint, &pairs, dexs);
+        info!(
+            "🧭 {}: {}/{} live pairs loaded, {} missed, {} on venues without loader",
+            mint,
+            coverage.covered_pairs,
+            coverage.live_pairs,
+            coverage.missed.len(),
+            coverage.unsupported.len()
+        );
+        report.mints.push(coverage);
+    }
+    Ok(report)
+}
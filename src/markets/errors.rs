@@ -0,0 +1,44 @@
+use solana_program::program_error::ProgramError;
+use thiserror::Error;
+
+// Why a route could not be quoted: a failed request is worth retrying, a pool rejecting the swap
+// (empty reserves, tick out of range...) will fail the same way next time
+#[derive(Debug, Error)]
+pub enum QuoteError {
+    #[error("no token infos for {0}")]
+    UnknownToken(String),
+    #[error("no account data for pool {0}")]
+    MissingAccountData(String),
+    #[error("pool state not decoded: {0}")]
+    PoolState(#[from] ProgramError),
+    #[error("simulator request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("swap rejected by the pool: {0}")]
+    Rejected(String),
+    #[error("unexpected simulator response: {0}")]
+    UnexpectedResponse(String),
+}
+
+impl QuoteError {
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, QuoteError::Request(_))
+    }
+
+    // The pool itself is at fault, its paths can be set aside
+    pub fn is_pool_error(&self) -> bool {
+        matches!(self, QuoteError::PoolState(_) | QuoteError::MissingAccountData(_) | QuoteError::Rejected(_))
+    }
+}
+
+// Why a DEX pools list could not be refreshed from its API
+#[derive(Debug, Error)]
+pub enum PoolLoadError {
+    #[error("pools request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("pools API answered {0}")]
+    Status(reqwest::StatusCode),
+    #[error("pools list not decoded: {0}")]
+    Decode(#[from] serde_json::Error),
+    #[error("pools cache not written: {0}")]
+    Cache(#[from] std::io::Error),
+}
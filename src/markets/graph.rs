@@ -0,0 +1,82 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use solana_program::pubkey::Pubkey;
+
+use crate::common::utils::from_str;
+use crate::markets::types::{Dex, DexLabel, Market};
+
+// A pool seen from one of its mints
+#[derive(Debug, Clone)]
+pub struct PoolRef {
+    pub dex: DexLabel,
+    pub address: String,
+    // Mint on the other side of the pool
+    pub other_mint: Pubkey,
+    pub market: Arc<Market>,
+}
+
+// Pools indexed by mint, built once from the loaded dexs and extended as new pools are found,
+// so finding the pools of a token set never rescans every dex
+#[derive(Debug, Default)]
+pub struct TokenGraph {
+    edges: HashMap<Pubkey, Vec<PoolRef>>,
+    pools: HashSet<String>,
+}
+
+impl TokenGraph {
+    pub fn new() -> Self {
+        TokenGraph::default()
+    }
+
+    pub fn from_dexs(dexs: &[Dex]) -> Self {
+        let mut graph = TokenGraph::new();
+        for market in dexs.iter().flat_map(|dex| dex.pairToMarkets.values()).flatten() {
+            graph.add_market(market);
+        }
+        graph
+    }
+
+    // False when the pool is already in the graph or its mints can't be parsed
+    pub fn add_market(&mut self, market: &Market) -> bool {
+        if self.pools.contains(&market.id) {
+            return false;
+        }
+        let (Ok(mint_a), Ok(mint_b)) = (from_str(&market.tokenMintA), from_str(&market.tokenMintB)) else { return false };
+        let market = Arc::new(market.clone());
+        self.edges.entry(mint_a).or_default().push(PoolRef { dex: market.dexLabel.clone(), address: market.id.clone(), other_mint: mint_b, market: market.clone() });
+        self.edges.entry(mint_b).or_default().push(PoolRef { dex: market.dexLabel.clone(), address: market.id.clone(), other_mint: mint_a, market: market.clone() });
+        self.pools.insert(market.id.clone());
+        true
+    }
+
+    pub fn pools_of(&self, mint: &Pubkey) -> &[PoolRef] {
+        self.edges.get(mint).map(|pools| pools.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn pools_between<'a>(&'a self, mint_a: &Pubkey, mint_b: &'a Pubkey) -> impl Iterator<Item = &'a PoolRef> {
+        self.pools_of(mint_a).iter().filter(move |pool| &pool.other_mint == mint_b)
+    }
+
+    // Pools with both mints in the set
+    pub fn markets_within(&self, mints: &HashSet<Pubkey>) -> Vec<Arc<Market>> {
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut markets: Vec<Arc<Market>> = Vec::new();
+        for mint in mints {
+            for pool in self.pools_of(mint) {
+                if mints.contains(&pool.other_mint) && seen.insert(pool.address.as_str()) {
+                    markets.push(pool.market.clone());
+                }
+            }
+        }
+        markets
+    }
+
+    pub fn pools_len(&self) -> usize {
+        self.pools.len()
+    }
+
+    pub fn mints_len(&self) -> usize {
+        self.edges.len()
+    }
+}
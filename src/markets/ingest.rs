@@ -0,0 +1,138 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tokio::sync::Notify;
+
+use crate::arbitrage::live_ranking::LiveRanking;
+use crate::markets::pool_cache::pool_cache;
+use crate::monitoring::metrics::metrics;
+
+pub static DEFAULT_UPDATE_QUEUE_CAPACITY: usize = 4096;
+
+#[derive(Debug, Default)]
+struct PendingUpdates {
+    // Accounts in arrival order of their first pending update
+    order: VecDeque<String>,
+    data: HashMap<String, Vec<u8>>,
+}
+
+// Account updates from the subscriptions, waiting to be applied to the pool cache.
+// Bounded and coalesced by account: a new update of a pending account replaces its data in place,
+// so a flood on a hot pool collapses into its latest state; when full the oldest account is dropped
+#[derive(Debug)]
+pub struct UpdateQueue {
+    capacity: usize,
+    pending: Mutex<PendingUpdates>,
+    ready: Notify,
+}
+
+impl UpdateQueue {
+    pub fn new(capacity: usize) -> Self {
+        UpdateQueue { capacity: capacity.max(1), pending: Mutex::new(PendingUpdates::default()), ready: Notify::new() }
+    }
+
+    // Never blocks the subscription
+    pub fn push(&self, address: String, data: Vec<u8>) {
+        {
+            let mut pending = self.pending.lock().unwrap();
+            if let Some(existing) = pending.data.get_mut(&address) {
+                *existing = data;
+                metrics().account_updates_coalesced.inc();
+            } else {
+                if pending.order.len() >= self.capacity {
+                    if let Some(oldest) = pending.order.pop_front() {
+                        pending.data.remove(&oldest);
+                        metrics().account_updates_dropped.inc();
+                    }
+                }
+                pending.order.push_back(address.clone());
+                pending.data.insert(address, data);
+            }
+        }
+        self.ready.notify_one();
+    }
+
+    pub fn try_pop(&self) -> Option<(String, Vec<u8>)> {
+        let mut pending = self.pending.lock().unwrap();
+        let address = pending.order.pop_front()?;
+        let data = pending.data.remove(&address)?;
+        Some((address, data))
+    }
+
+    pub async fn pop(&self) -> (String, Vec<u8>) {
+        loop {
+            if let Some(update) = self.try_pop() {
+                return update;
+            }
+            self.ready.notified().await;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.lock().unwrap().order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+static ACCOUNT_UPDATES: OnceLock<UpdateQueue> = OnceLock::new();
+
+// To call once at startup, before the subscriptions
+pub fn init_account_updates(capacity: usize) {
+    let _ = ACCOUNT_UPDATES.set(UpdateQueue::new(capacity));
+}
+
+pub fn account_updates() -> &'static UpdateQueue {
+    ACCOUNT_UPDATES.get_or_init(|| UpdateQueue::new(DEFAULT_UPDATE_QUEUE_CAPACITY))
+}
+
+// Single consumer of the subscriptions: the pending updates applied to the pool cache,
+// then the paths through the updated account re-scored
+pub async fn run_account_update_applier(live_ranking: Option<Arc<LiveRanking>>) {
+    loop {
+        let (address, data) = account_updates().pop().await;
+        pool_cache().insert(&address, data);
+        if let Some(live_ranking) = &live_ranking {
+            live_ranking.on_account_update(&address);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn updates_of_a_pending_account_are_coalesced() {
+        let queue = UpdateQueue::new(8);
+        queue.push("a".to_string(), vec![1]);
+        queue.push("b".to_string(), vec![2]);
+        queue.push("a".to_string(), vec![3]);
+        assert_eq!(queue.len(), 2);
+        // Still in the arrival order of its first update, with the latest data
+        assert_eq!(queue.try_pop(), Some(("a".to_string(), vec![3])));
+        assert_eq!(queue.try_pop(), Some(("b".to_string(), vec![2])));
+        assert_eq!(queue.try_pop(), None);
+    }
+
+    #[test]
+    fn a_full_queue_drops_the_oldest_account() {
+        let queue = UpdateQueue::new(2);
+        for (address, data) in [("a", 1), ("b", 2), ("c", 3)] {
+            queue.push(address.to_string(), vec![data]);
+        }
+        assert_eq!(queue.try_pop(), Some(("b".to_string(), vec![2])));
+        assert_eq!(queue.try_pop(), Some(("c".to_string(), vec![3])));
+        assert!(queue.is_empty());
+    }
+
+    #[tokio::test]
+    async fn pop_waits_for_a_push() {
+        let queue = Arc::new(UpdateQueue::new(4));
+        let pusher = queue.clone();
+        tokio::spawn(async move { pusher.push("a".to_string(), vec![1]) });
+        assert_eq!(queue.pop().await, ("a".to_string(), vec![1]));
+    }
+}
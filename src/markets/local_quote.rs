@@ -0,0 +1,90 @@
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+use dashmap::DashMap;
+use smallvec::{smallvec, SmallVec};
+use solana_sdk::bs58;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::arbitrage::types::Route;
+use crate::common::utils::from_str;
+use crate::markets::pool_cache::{pool_cache, AccountSource};
+use crate::markets::types::{DexLabel, Market};
+use crate::markets::zero_copy::whirlpool;
+use quote_core::quote::{decode_address, quote_pool, raydium_vaults, whirlpool_tick_array_starts, Hop, PoolKind};
+
+pub static WHIRLPOOL_PROGRAM: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
+
+static TICK_ARRAY_ADDRESSES: OnceLock<DashMap<([u8; 32], i32), [u8; 32]>> = OnceLock::new();
+
+// The quote math itself is in quote-core, the same code the wasm build runs
+pub use quote_core::quote::LOCAL_QUOTE_SLIPPAGE_BPS;
+
+// Amount out and min amount out from the pool cache, None when the DEX has no local math
+// or the cache misses one of the accounts
+pub fn quote_local(route: &Route, market: &Market, amount_in: u64) -> Option<(u64, u64)> {
+    quote_local_in(pool_cache(), route, market, amount_in)
+}
+
+// Same quote from a given account source, a pool snapshot for batch quoting.
+// Only the source is read: the data loaded with the market is too old to quote from
+pub fn quote_local_in(accounts: &impl AccountSource, route: &Route, market: &Market, amount_in: u64) -> Option<(u64, u64)> {
+    let hop = Hop { kind: pool_kind(&route.dex)?, pool: &market.id, token_in: &route.tokenIn };
+    quote_pool(accounts, &hop, amount_in)
+}
+
+// Whirlpool tick array PDA, derived once per whirlpool and start tick
+pub fn tick_array_address(whirlpool: [u8; 32], start_tick_index: i32) -> [u8; 32] {
+    let addresses = TICK_ARRAY_ADDRESSES.get_or_init(DashMap::new);
+    if let Some(address) = addresses.get(&(whirlpool, start_tick_index)) {
+        return *address;
+    }
+    let seeds: [&[u8]; 3] = [b"tick_array", &whirlpool, start_tick_index.to_string().as_bytes()];
+    let (address, _) = Pubkey::find_program_address(&seeds, &from_str(WHIRLPOOL_PROGRAM).unwrap());
+    addresses.insert((whirlpool, start_tick_index), address.to_bytes());
+    address.to_bytes()
+}
+
+// Tick arrays a swap of the whirlpool may go through, both ways from its current tick
+pub fn whirlpool_tick_arrays(pool: &str, pool_data: &[u8]) -> Vec<String> {
+    let (Some(whirlpool_address), Some(pool_state)) = (decode_address(pool), whirlpool(pool_data)) else { return Vec::new() };
+    let mut starts: Vec<i32> = [true, false]
+        .into_iter()
+        .filter_map(|a_to_b| whirlpool_tick_array_starts(pool_state.tick_current_index(), pool_state.tick_spacing(), a_to_b))
+        .flatten()
+        .collect();
+    starts.sort_unstable();
+    starts.dedup();
+    starts.into_iter().map(|start| bs58::encode(tick_array_address(whirlpool_address, start)).into_string()).collect()
+}
+
+pub fn pool_kind(dex: &DexLabel) -> Option<PoolKind> {
+    match dex {
+        DexLabel::RAYDIUM => Some(PoolKind::RaydiumAmm),
+        DexLabel::ORCA_WHIRLPOOLS => Some(PoolKind::Whirlpool),
+        _ => None,
+    }
+}
+
+// Accounts a local quote of the market reads, what a snapshot has to hold
+pub fn quoted_accounts(market: &Market) -> SmallVec<[Cow<'_, str>; 3]> {
+    let mut addresses: SmallVec<[Cow<'_, str>; 3]> = smallvec![Cow::Borrowed(market.id.as_str())];
+    if market.dexLabel == DexLabel::ORCA_WHIRLPOOLS {
+        let tick_arrays = match pool_cache().read(&market.id, |data| whirlpool_tick_arrays(&market.id, data)) {
+            Some(tick_arrays) => tick_arrays,
+            None => market.account_data.as_deref().map(|data| whirlpool_tick_arrays(&market.id, data)).unwrap_or_default(),
+        };
+        addresses.extend(tick_arrays.into_iter().map(Cow::Owned));
+    }
+    if market.dexLabel == DexLabel::RAYDIUM {
+        let vaults = match pool_cache().read(&market.id, raydium_vaults) {
+            Some(vaults) => vaults,
+            None => market.account_data.as_deref().and_then(raydium_vaults),
+        };
+        if let Some((coin_vault, pc_vault)) = vaults {
+            addresses.push(Cow::Owned(bs58::encode(coin_vault).into_string()));
+            addresses.push(Cow::Owned(bs58::encode(pc_vault).into_string()));
+        }
+    }
+    addresses
+}
@@ -1,8 +1,10 @@
 use crate::arbitrage::types::{Route, TokenInfos};
+use crate::markets::errors::{PoolLoadError, QuoteError};
 use crate::markets::types::{Dex, DexLabel, Market, PoolItem, SimulationError, SimulationRes};
 use crate::markets::utils::toPairString;
 use crate::common::debug::print_json_segment;
 use crate::common::utils::{from_Pubkey, from_str, make_request};
+use crate::common::rate_limiter::throttle;
 use crate::common::constants::Env;
 
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -79,7 +81,8 @@ impl MeteoraDEX {
     }
 }
 
-pub async fn fetch_data_meteora() -> Result<(), Box<dyn std::error::Error>> {
+pub async fn fetch_data_meteora() -> Result<(), PoolLoadError> {
+    throttle("https://dlmm-api.meteora.ag/pair/all").await;
     let response = get("https://dlmm-api.meteora.ag/pair/all").await?;
     // info!("response: {:?}", response);
     // info!("response-status: {:?}", response.status().is_success());
@@ -104,10 +107,12 @@ pub async fn fetch_data_meteora() -> Result<(), Box<dyn std::error::Error>> {
                 let result = print_json_segment("src/markets/cache/meteora-markets-raw.json", 3426919 - 100 as u64, 2000);
                 // raw_file.write_all(data.as_bytes())?;
                 // info!("Raw data written to 'meteora-markets-raw.json' for inspection.");
+                return Err(PoolLoadError::Decode(e));
             }
         }
     } else {
         error!("Fetch of 'meteora-markets.json' not successful: {}", response.status());
+        return Err(PoolLoadError::Status(response.status()));
     }
     Ok(())
 }
@@ -131,6 +136,7 @@ pub async fn fetch_new_meteora_pools(rpc_client: &RpcClient, token: String, on_t
         RpcFilterType::DataSize(904), 
     ]);
     
+    throttle(&rpc_client.url()).await;
     let accounts = rpc_client.get_program_accounts_with_config(
         &from_str(&meteora_program).unwrap(),
         RpcProgramAccountsConfig {
@@ -170,13 +176,13 @@ pub async fn fetch_new_meteora_pools(rpc_client: &RpcClient, token: String, on_t
 
 // Simulate one route 
 // I want to get the data of the market i'm interested in this route
-pub async fn simulate_route_meteora(printing_amt: bool, amount_in: u64, route: Route, market: Market, tokens_infos: HashMap<String, TokenInfos>) -> Result<(String, String), Box<dyn std::error::Error>> {
+pub async fn simulate_route_meteora(printing_amt: bool, amount_in: u64, route: Route, market: Market, tokens_infos: &HashMap<String, TokenInfos>) -> Result<(String, String), QuoteError> {
     // println!("account_data: {:?}", &market.account_data.clone().unwrap());
     // println!("market: {:?}", market.clone());
     // let meteora_data = AccountData::try_from_slice(&market.account_data.expect("Account data problem // METEORA")).expect("Account data not fit bytes length");
 
-    let token0 = tokens_infos.get(&market.tokenMintA).unwrap();
-    let token1 = tokens_infos.get(&market.tokenMintB).unwrap();
+    let token0 = tokens_infos.get(&market.tokenMintA).ok_or_else(|| QuoteError::UnknownToken(market.tokenMintA.clone()))?;
+    let token1 = tokens_infos.get(&market.tokenMintB).ok_or_else(|| QuoteError::UnknownToken(market.tokenMintB.clone()))?;
 
     let amount_in_uint = amount_in as u64;
 
@@ -207,15 +213,9 @@ pub async fn simulate_route_meteora(printing_amt: bool, amount_in: u64, route: R
         return Ok((json_value.estimatedAmountOut, json_value.estimatedMinAmountOut.unwrap_or_default()))
     } else if let Ok(error_value) = serde_json::from_str::<SimulationError>(&res_text) {
         // println!("ERROR Value: {:?}", error_value.error);
-        Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            error_value.error,
-        )))
+        Err(QuoteError::Rejected(error_value.error))
     } else {
-        Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "Unexpected response format",
-        )))
+        Err(QuoteError::UnexpectedResponse(res_text))
     }
 }
 
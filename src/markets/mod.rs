@@ -5,4 +5,16 @@ pub mod orca_whirpools;
 pub mod meteora;
 pub mod types;
 pub mod utils;
+pub mod errors;
 pub mod pools;
+pub mod pool_cache;
+pub mod local_quote;
+pub mod state;
+pub mod zero_copy;
+pub mod graph;
+pub mod ingest;
+pub mod pubsub;
+#[cfg(feature = "shredstream")]
+pub mod shredstream;
+pub mod pyth;
+pub mod coverage;
@@ -1,13 +1,17 @@
 use crate::common::constants::Env;
+use crate::common::feeds::{feeds, FeedKind};
+use crate::markets::errors::PoolLoadError;
 use crate::markets::types::{Dex, DexLabel, Market, PoolItem};
 use crate::markets::utils::toPairString;
 use crate::common::utils::{from_str, from_Pubkey};
+use crate::common::rate_limiter::{throttle, throttle_blocking};
 use std::collections::HashMap;
 use std::{fs, fs::File};
 use std::io::Write;
 use serde::{Deserialize, Serialize};
 use reqwest::get;
-use log::info;
+use log::{info, error, Level};
+use crate::log_every_n;
 use solana_client::rpc_client::RpcClient;
 use solana_client::rpc_config::RpcAccountInfoConfig;
 use solana_program::pubkey::Pubkey;
@@ -15,7 +19,7 @@ use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::program_error::ProgramError;
 use solana_account_decoder::{UiAccountData, UiAccountEncoding};
 use solana_pubsub_client::pubsub_client::PubsubClient;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 #[derive(Debug)]
 pub struct OrcaDex {
@@ -48,6 +52,7 @@ impl OrcaDex {
             let maxLength = std::cmp::min(i + 100, pubkeys_vec.len());
             let batch = &pubkeys_vec[(i..maxLength)];
 
+            throttle_blocking(&rpc_client.url());
             let batch_results = rpc_client.get_multiple_accounts(&batch).unwrap();
             for j in batch_results {
                 let account = j.unwrap();
@@ -99,7 +104,8 @@ impl OrcaDex {
     }
   }
 
-pub async fn fetch_data_orca() -> Result<(), Box<dyn std::error::Error>> {
+pub async fn fetch_data_orca() -> Result<(), PoolLoadError> {
+    throttle("https://api.orca.so/allPools").await;
     let response = get("https://api.orca.so/allPools").await?;
     // info!("response: {:?}", response);
     // info!("response-status: {:?}", response.status().is_success());
@@ -110,17 +116,17 @@ pub async fn fetch_data_orca() -> Result<(), Box<dyn std::error::Error>> {
         file.write_all(serde_json::to_string(&json)?.as_bytes())?;
         info!("Data written to 'orca-markets.json' successfully.");
     } else {
-        info!("Fetch of 'orca-markets.json' not successful: {}", response.status());
+        error!("Fetch of 'orca-markets.json' not successful: {}", response.status());
+        return Err(PoolLoadError::Status(response.status()));
     }
     Ok(())
 }
 
 pub async fn stream_orca(account: Pubkey) -> Result<()> {
-    let env = Env::new();
-    let url = env.wss_rpc_url.as_str();
+    let url = feeds().url(FeedKind::AccountWs).ok_or_else(|| anyhow!("WSS_RPC_URL not set"))?;
     let (mut account_subscription_client, account_subscription_receiver) =
     PubsubClient::account_subscribe(
-        url,
+        &url,
         &account,
         Some(RpcAccountInfoConfig {
             encoding: Some(UiAccountEncoding::JsonParsed),
@@ -137,8 +143,7 @@ pub async fn stream_orca(account: Pubkey) -> Result<()> {
                 let bytes_slice = UiAccountData::decode(&data).unwrap();
                 // println!("account subscription data response: {:?}", data);
                 let account_data = unpack_from_slice(bytes_slice.as_slice());
-                println!("Orca Pool updated: {:?}", account);
-                println!("Data: {:?}", account_data.unwrap());
+                log_every_n!(Level::Info, 100, "Orca Pool updated: {:?} // Data: {:?}", account, account_data);
 
             }
             Err(e) => {
@@ -1,15 +1,21 @@
 use crate::arbitrage::types::{Route, TokenInfos};
 use crate::common::constants::Env;
+use crate::common::feeds::{feeds, FeedKind};
+use crate::markets::errors::{PoolLoadError, QuoteError};
+use crate::markets::ingest::account_updates;
 use crate::markets::types::{Dex, DexLabel, Market, PoolItem, SimulationRes};
+use crate::markets::zero_copy::whirlpool;
 use crate::markets::utils::toPairString;
 use crate::common::utils::{from_Pubkey, from_str, make_request};
+use crate::common::rate_limiter::{throttle, throttle_blocking};
 use std::collections::HashMap;
 use std::{fs, fs::File};
 use std::io::Write;
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 use reqwest::get;
-use log::{info, error};
+use log::{info, error, Level};
+use crate::log_every_n;
 use solana_account_decoder::{UiAccountData, UiAccountEncoding};
 use solana_client::rpc_client::RpcClient;
 use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
@@ -18,7 +24,7 @@ use solana_program::pubkey::Pubkey;
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::program_error::ProgramError;
 use solana_pubsub_client::pubsub_client::PubsubClient;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 use super::types::SimulationError;
 
@@ -53,6 +59,7 @@ impl OrcaDexWhirpools {
             let maxLength = std::cmp::min(i + 100, pubkeys_vec.len());
             let batch = &pubkeys_vec[(i..maxLength)];
             
+            throttle_blocking(&rpc_client.url());
             let batch_results = rpc_client.get_multiple_accounts(&batch).unwrap();
             for (j, account) in batch_results.iter().enumerate() {
                 let account = account.clone().unwrap();
@@ -133,6 +140,7 @@ pub async fn fetch_new_orca_whirpools(rpc_client: &RpcClient, token: String, on_
         RpcFilterType::DataSize(653),  //data.len == 653 for Whirpool account
     ]);
     
+    throttle(&rpc_client.url()).await;
     let accounts = rpc_client.get_program_accounts_with_config(
         &from_str(&orca_program).unwrap(),
         RpcProgramAccountsConfig {
@@ -165,7 +173,8 @@ pub async fn fetch_new_orca_whirpools(rpc_client: &RpcClient, token: String, on_
     return new_markets;
 }
 
-pub async fn fetch_data_orca_whirpools() -> Result<(), Box<dyn std::error::Error>> {
+pub async fn fetch_data_orca_whirpools() -> Result<(), PoolLoadError> {
+    throttle("https://api.mainnet.orca.so/v1/whirlpool/list").await;
     let response = get("https://api.mainnet.orca.so/v1/whirlpool/list").await?;
     // info!("response: {:?}", response);
     // info!("response-status: {:?}", response.status().is_success());
@@ -177,16 +186,16 @@ pub async fn fetch_data_orca_whirpools() -> Result<(), Box<dyn std::error::Error
         info!("Data written to 'orca_whirpools-markets.json' successfully.");
     } else {
         error!("Fetch of 'orca_whirpools-markets.json' not successful: {}", response.status());
+        return Err(PoolLoadError::Status(response.status()));
     }
     Ok(())
 }
 
 pub async fn stream_orca_whirpools(account: Pubkey) -> Result<()> {
-    let env = Env::new();
-    let url = env.wss_rpc_url.as_str();
+    let url = feeds().url(FeedKind::AccountWs).ok_or_else(|| anyhow!("WSS_RPC_URL not set"))?;
     let (mut account_subscription_client, account_subscription_receiver) =
     PubsubClient::account_subscribe(
-        url,
+        &url,
         &account,
         Some(RpcAccountInfoConfig {
             encoding: Some(UiAccountEncoding::JsonParsed),
@@ -200,11 +209,11 @@ pub async fn stream_orca_whirpools(account: Pubkey) -> Result<()> {
         match account_subscription_receiver.recv() {
             Ok(response) => {
                 let data = response.value.data;
-                let bytes_slice = UiAccountData::decode(&data).unwrap();
-                // println!("account subscription data response: {:?}", data);
-                let account_data = unpack_from_slice(bytes_slice.as_slice());
-                println!("Orca Whirpools Pool updated: {:?}", account);
-                println!("Data: {:?}", account_data.unwrap());
+                let Some(bytes_slice) = UiAccountData::decode(&data) else { continue };
+                if let Some(pool) = whirlpool(&bytes_slice) {
+                    log_every_n!(Level::Info, 100, "Orca Whirpools Pool updated: {:?} // Liquidity: {} Sqrt price: {}", account, pool.liquidity(), pool.sqrt_price());
+                }
+                account_updates().push(from_Pubkey(account), bytes_slice);
 
             }
             Err(e) => {
@@ -218,12 +227,13 @@ pub async fn stream_orca_whirpools(account: Pubkey) -> Result<()> {
 }
 
 // Simulate one route 
-pub async fn simulate_route_orca_whirpools(printing_amt: bool, amount_in: u64, route: Route, market: Market, tokens_infos: HashMap<String, TokenInfos>) -> Result<(String, String), Box<dyn std::error::Error>> {
+pub async fn simulate_route_orca_whirpools(printing_amt: bool, amount_in: u64, route: Route, market: Market, tokens_infos: &HashMap<String, TokenInfos>) -> Result<(String, String), QuoteError> {
     // I want to get the data of the market i'm interested in this route
-    let whirpool_data = unpack_from_slice(market.account_data.expect("No account data provided").as_slice()).unwrap();
+    let account_data = market.account_data.as_ref().ok_or_else(|| QuoteError::MissingAccountData(market.id.clone()))?;
+    let whirpool_data = unpack_from_slice(account_data.as_slice())?;
 
-    let token_0 = tokens_infos.get(&market.tokenMintA).unwrap();
-    let token_1 = tokens_infos.get(&market.tokenMintB).unwrap();
+    let token_0 = tokens_infos.get(&market.tokenMintA).ok_or_else(|| QuoteError::UnknownToken(market.tokenMintA.clone()))?;
+    let token_1 = tokens_infos.get(&market.tokenMintB).ok_or_else(|| QuoteError::UnknownToken(market.tokenMintB.clone()))?;
     let mut params: String = String::new();
     if route.token_0to1 {
         params = format!(
@@ -277,15 +287,9 @@ pub async fn simulate_route_orca_whirpools(printing_amt: bool, amount_in: u64, r
         Ok((json_value.estimatedAmountOut, json_value.estimatedMinAmountOut.unwrap_or_default()))
     } else if let Ok(error_value) = serde_json::from_str::<SimulationError>(&res_text) {
         // println!("ERROR Value: {:?}", error_value.error);
-        Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            error_value.error,
-        )))
+        Err(QuoteError::Rejected(error_value.error))
     } else {
-        Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "Unexpected response format",
-        )))
+        Err(QuoteError::UnexpectedResponse(res_text))
     }
 }
 
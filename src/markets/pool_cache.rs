@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use dashmap::DashMap;
+
+// Attempts at a snapshot no write lands in the middle of, the last one is kept otherwise
+pub static SNAPSHOT_ATTEMPTS: usize = 3;
+
+use crate::markets::local_quote::tick_array_address;
+
+// Source of the local quotes, implemented by the live cache and its snapshots
+pub use quote_core::quote::AccountSource;
+
+// Latest data of the accounts read by the local quotes (pool states, vaults), keyed by address.
+// Filled by the markets refresh and the streams, so quoting never waits on the network.
+// Sharded: the writers and the strategy readers only ever contend on the same shard
+#[derive(Debug, Default)]
+pub struct PoolCache {
+    accounts: DashMap<String, Arc<[u8]>>,
+    // Bumped on every write, tells a snapshot whether the cache moved while it was taken
+    generation: AtomicU64,
+}
+
+impl PoolCache {
+    pub fn insert(&self, address: &str, data: Vec<u8>) {
+        self.accounts.insert(address.to_string(), Arc::from(data));
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+
+    pub fn get(&self, address: &str) -> Option<Arc<[u8]>> {
+        self.accounts.get(address).map(|data| data.clone())
+    }
+
+    pub fn contains(&self, address: &str) -> bool {
+        self.accounts.contains_key(address)
+    }
+
+    // Borrow the data in place, without copying it
+    pub fn read<R>(&self, address: &str, f: impl FnOnce(&[u8]) -> R) -> Option<R> {
+        self.accounts.get(address).map(|data| f(&data))
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    // The accounts as they were at a single point in time, for batch quoting: every hop of every
+    // path is priced from the same state. Missing accounts are left out
+    pub fn snapshot<'a>(&self, addresses: impl IntoIterator<Item = &'a str>) -> PoolSnapshot {
+        let addresses: Vec<&str> = addresses.into_iter().collect();
+        let mut snapshot = PoolSnapshot::default();
+        for _ in 0..SNAPSHOT_ATTEMPTS {
+            let generation = self.generation();
+            snapshot = PoolSnapshot {
+                generation,
+                accounts: addresses.iter().filter_map(|address| Some((address.to_string(), self.get(address)?))).collect(),
+            };
+            if self.generation() == generation {
+                break;
+            }
+        }
+        snapshot
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+}
+
+impl AccountSource for PoolCache {
+    fn read_account<R>(&self, address: &str, f: impl FnOnce(&[u8]) -> R) -> Option<R> {
+        self.read(address, f)
+    }
+
+    fn tick_array_address(&self, whirlpool: [u8; 32], start_tick_index: i32) -> Option<[u8; 32]> {
+        Some(tick_array_address(whirlpool, start_tick_index))
+    }
+}
+
+// Immutable copy of some cache accounts, the data itself is shared with the cache
+#[derive(Debug, Clone, Default)]
+pub struct PoolSnapshot {
+    pub generation: u64,
+    accounts: HashMap<String, Arc<[u8]>>,
+}
+
+impl PoolSnapshot {
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+}
+
+impl AccountSource for PoolSnapshot {
+    fn read_account<R>(&self, address: &str, f: impl FnOnce(&[u8]) -> R) -> Option<R> {
+        self.accounts.get(address).map(|data| f(data))
+    }
+
+    fn tick_array_address(&self, whirlpool: [u8; 32], start_tick_index: i32) -> Option<[u8; 32]> {
+        Some(tick_array_address(whirlpool, start_tick_index))
+    }
+}
+
+static POOL_CACHE: OnceLock<PoolCache> = OnceLock::new();
+
+pub fn pool_cache() -> &'static PoolCache {
+    POOL_CACHE.get_or_init(PoolCache::default)
+}
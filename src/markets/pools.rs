@@ -6,23 +6,32 @@ use super::orca::{OrcaDex, fetch_data_orca};
 use super::orca_whirpools::{OrcaDexWhirpools, fetch_data_orca_whirpools};
 use super::raydium::{fetch_data_raydium, RaydiumDEX};
 
+use std::collections::HashSet;
 use strum::IntoEnumIterator;
 use std::fs::File;
 use std::io::Write;
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use reqwest::get;
-use log::info;
+use log::{error, info};
 use solana_client::rpc_client::RpcClient;
 
 
 pub async fn load_all_pools(refecth_api: bool) -> Vec<Dex> {
     if refecth_api {
-        fetch_data_raydium_clmm().await;
-        fetch_data_orca().await;
-        fetch_data_orca_whirpools().await;
-        fetch_data_raydium().await;
-        fetch_data_meteora().await;
+        // A failed refresh keeps the cached list of that DEX
+        let refreshes = [
+            (DexLabel::RAYDIUM_CLMM, fetch_data_raydium_clmm().await),
+            (DexLabel::ORCA, fetch_data_orca().await),
+            (DexLabel::ORCA_WHIRLPOOLS, fetch_data_orca_whirpools().await),
+            (DexLabel::RAYDIUM, fetch_data_raydium().await),
+            (DexLabel::METEORA, fetch_data_meteora().await),
+        ];
+        for (dex, refresh) in refreshes {
+            if let Err(e) = refresh {
+                error!("❌ {} pools not refreshed: {}", dex.str(), e);
+            }
+        }
     }
 
     let mut dex1 = Dex::new(DexLabel::RAYDIUM_CLMM);
@@ -48,3 +57,15 @@ pub async fn load_all_pools(refecth_api: bool) -> Vec<Dex> {
 
 }
 
+// Keep only the pools trading two mints of the scope. Returns how many pools were kept
+pub fn retain_scoped(dexs: &mut [Dex], mints: &HashSet<String>) -> usize {
+    let mut kept = 0;
+    for dex in dexs.iter_mut() {
+        dex.pairToMarkets.retain(|_, markets| {
+            markets.retain(|market| mints.contains(&market.tokenMintA) && mints.contains(&market.tokenMintB));
+            kept += markets.len();
+            !markets.is_empty()
+        });
+    }
+    kept
+}
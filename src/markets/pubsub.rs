@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use futures::stream::{select_all, BoxStream, SelectAll, StreamExt};
+use log::{debug, info};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::{PubsubClient, UnsubscribeFn};
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::{broadcast, Notify};
+
+use crate::arbitrage::registry::StrategyEvent;
+use crate::common::feeds::{feeds, FeedEndpoint, FeedKind};
+use crate::common::utils::from_str;
+use crate::markets::ingest::account_updates;
+
+enum PubsubUpdate {
+    Account { address: String, data: Option<Vec<u8>> },
+    Logs { mention: String, signature: String, slot: u64, failed: bool },
+    Slot(u64),
+}
+
+// Accounts, log mentions and slots followed on the RPC WebSocket (WSS_RPC_URL).
+// The whole set is subscribed again on every new connection, only its changes on the live one
+#[derive(Debug)]
+pub struct PubsubSubscriptions {
+    pub commitment: CommitmentConfig,
+    accounts: RwLock<HashSet<String>>,
+    log_mentions: RwLock<HashSet<String>>,
+    slot: AtomicU64,
+    changed: Notify,
+}
+
+impl PubsubSubscriptions {
+    pub fn new(commitment: CommitmentConfig) -> Self {
+        PubsubSubscriptions {
+            commitment,
+            accounts: RwLock::new(HashSet::new()),
+            log_mentions: RwLock::new(HashSet::new()),
+            slot: AtomicU64::new(0),
+            changed: Notify::new(),
+        }
+    }
+
+    // Replace the subscribed accounts, their updates go to the pool cache
+    pub fn watch_accounts(&self, accounts: &[String]) {
+        if replace(&self.accounts, accounts) {
+            self.changed.notify_one();
+        }
+    }
+
+    // Replace the log mentions: successful transactions mentioning one of them are pushed to the strategies as observed swaps
+    pub fn watch_log_mentions(&self, mentions: &[String]) {
+        if replace(&self.log_mentions, mentions) {
+            self.changed.notify_one();
+        }
+    }
+
+    pub fn accounts(&self) -> Vec<String> {
+        self.accounts.read().unwrap().iter().cloned().collect()
+    }
+
+    // Last slot notified, 0 before the first notification
+    pub fn current_slot(&self) -> u64 {
+        self.slot.load(Ordering::Relaxed)
+    }
+
+    async fn session(&self, endpoint: FeedEndpoint, events: broadcast::Sender<StrategyEvent>) -> Result<()> {
+        let client = PubsubClient::new(&endpoint.authenticated_url()).await?;
+        let (slots, unsubscribe_slots) = client.slot_subscribe().await?;
+        let mut updates = select_all(vec![slots.map(|slot| PubsubUpdate::Slot(slot.slot)).boxed()]);
+        let mut accounts = HashMap::new();
+        let mut log_mentions = HashMap::new();
+        self.sync(&client, &mut updates, &mut accounts, &mut log_mentions).await?;
+        info!("📡 Subscribed to {} accounts, {} log mentions and the slots on {}", accounts.len(), log_mentions.len(), endpoint.url);
+
+        loop {
+            let changed = tokio::select! {
+                update = updates.next() => {
+                    // Every subscription closed by the server: the connection is reopened and everything resubscribed
+                    let Some(update) = update else { break };
+                    self.apply(update, &events);
+                    false
+                }
+                _ = self.changed.notified() => true,
+            };
+            if changed {
+                self.sync(&client, &mut updates, &mut accounts, &mut log_mentions).await?;
+                debug!("📡 Subscriptions changed, now {} accounts and {} log mentions", accounts.len(), log_mentions.len());
+            }
+        }
+        drop(updates);
+        for unsubscribe in accounts.into_values().chain(log_mentions.into_values()) {
+            unsubscribe().await;
+        }
+        unsubscribe_slots().await;
+        let _ = client.shutdown().await;
+        Ok(())
+    }
+
+    // Subscribe what was added to the watched sets and unsubscribe what was removed, on the live connection.
+    // An unsubscribed stream ends and leaves `updates` on its own
+    async fn sync<'a>(
+        &self,
+        client: &'a PubsubClient,
+        updates: &mut SelectAll<BoxStream<'a, PubsubUpdate>>,
+        accounts: &mut HashMap<String, UnsubscribeFn>,
+        log_mentions: &mut HashMap<String, UnsubscribeFn>,
+    ) -> Result<()> {
+        let watched_accounts = self.accounts.read().unwrap().clone();
+        let watched_mentions = self.log_mentions.read().unwrap().clone();
+        for unsubscribe in drain_unwatched(accounts, &watched_accounts).into_iter().chain(drain_unwatched(log_mentions, &watched_mentions)) {
+            unsubscribe().await;
+        }
+        for address in watched_accounts {
+            if accounts.contains_key(&address) {
+                continue;
+            }
+            let Ok(pubkey) = from_str(&address) else { continue };
+            let config = RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.commitment),
+                ..RpcAccountInfoConfig::default()
+            };
+            let (stream, unsubscribe) = client.account_subscribe(&pubkey, Some(config)).await?;
+            let account = address.clone();
+            updates.push(stream.map(move |update| PubsubUpdate::Account { address: account.clone(), data: update.value.data.decode() }).boxed());
+            accounts.insert(address, unsubscribe);
+        }
+        for mention in watched_mentions {
+            if log_mentions.contains_key(&mention) {
+                continue;
+            }
+            let config = RpcTransactionLogsConfig { commitment: Some(self.commitment) };
+            let (logs, unsubscribe) = client.logs_subscribe(RpcTransactionLogsFilter::Mentions(vec![mention.clone()]), config).await?;
+            let pool = mention.clone();
+            updates.push(logs.map(move |log| PubsubUpdate::Logs { mention: pool.clone(), signature: log.value.signature, slot: log.context.slot, failed: log.value.err.is_some() }).boxed());
+            log_mentions.insert(mention, unsubscribe);
+        }
+        Ok(())
+    }
+
+    fn apply(&self, update: PubsubUpdate, events: &broadcast::Sender<StrategyEvent>) {
+        match update {
+            PubsubUpdate::Account { address, data: Some(data) } => account_updates().push(address, data),
+            PubsubUpdate::Account { data: None, .. } => {}
+            PubsubUpdate::Logs { mention, signature, slot, failed: false } => {
+                // The best paths through the pool are quoted again. No strategy listening is not an error
+                let _ = events.send(StrategyEvent::SwapObserved { signature, slot, pools: vec![mention] });
+            }
+            PubsubUpdate::Logs { failed: true, .. } => {}
+            PubsubUpdate::Slot(slot) => {
+                self.slot.fetch_max(slot, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+// Unsubscribe functions of the subscriptions no longer watched, removed from `subscribed`
+fn drain_unwatched<T>(subscribed: &mut HashMap<String, T>, watched: &HashSet<String>) -> Vec<T> {
+    let unwatched: Vec<String> = subscribed.keys().filter(|key| !watched.contains(*key)).cloned().collect();
+    unwatched.into_iter().filter_map(|key| subscribed.remove(&key)).collect()
+}
+
+fn replace(set: &RwLock<HashSet<String>>, values: &[String]) -> bool {
+    let values: HashSet<String> = values.iter().cloned().collect();
+    let mut current = set.write().unwrap();
+    if *current == values {
+        return false;
+    }
+    *current = values;
+    true
+}
+
+// Native Solana subscriptions kept open by the feed manager, with the account WebSocket backoff
+pub async fn run_pubsub(subscriptions: Arc<PubsubSubscriptions>, events: broadcast::Sender<StrategyEvent>) {
+    feeds().run(FeedKind::AccountWs, |endpoint| subscriptions.session(endpoint, events.clone())).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_reports_a_change_only() {
+        let set = RwLock::new(HashSet::new());
+        assert!(replace(&set, &["a".to_string(), "b".to_string()]));
+        assert!(!replace(&set, &["b".to_string(), "a".to_string()]));
+        assert!(replace(&set, &["a".to_string()]));
+        assert_eq!(*set.read().unwrap(), HashSet::from(["a".to_string()]));
+    }
+
+    #[test]
+    fn only_the_unwatched_subscriptions_drained() {
+        let mut subscribed = HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)]);
+        let drained = drain_unwatched(&mut subscribed, &HashSet::from(["b".to_string(), "c".to_string()]));
+        assert_eq!(drained, vec![1]);
+        assert_eq!(subscribed, HashMap::from([("b".to_string(), 2)]));
+    }
+}
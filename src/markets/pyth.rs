@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use futures::stream::{select_all, StreamExt};
+use log::{error, info};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::RpcAccountInfoConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::arbitrage::types::{SwapRouteSimulation, TokenInfos};
+use crate::common::constants::{SOL_MINT, USDC_MINT, USDT_MINT};
+use crate::common::feeds::{feeds, FeedEndpoint, FeedKind};
+use crate::common::rpc_pool::nonblocking_client;
+use crate::common::utils::from_str;
+
+// Sponsored PriceUpdateV2 accounts of the Pyth receiver program (shard 0), updated every few slots
+pub static PYTH_SOL_USD: &str = "7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE";
+pub static PYTH_USDC_USD: &str = "Dpw1EAVrSB1ibxiDQyTAW6Zip3J4Btk2x4SgApQCeFbX";
+pub static PYTH_USDT_USD: &str = "HT2PLQBcG5EiCcNSaMHAjSgd9F98ecpATbk4Sk5oYuM";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OraclePrice {
+    // USD per whole token
+    pub price: f64,
+    // One standard deviation, in USD
+    pub confidence: f64,
+    // Unix seconds
+    pub publish_time: i64,
+    pub posted_slot: u64,
+}
+
+impl OraclePrice {
+    // PriceUpdateV2: discriminator, write authority, verification level (Partial { num_signatures } or Full),
+    // then the price message and the posted slot
+    pub fn decode(data: &[u8]) -> Option<OraclePrice> {
+        let mut offset = 8 + 32;
+        offset += match *data.get(offset)? {
+            0 => 2,
+            1 => 1,
+            _ => return None,
+        };
+        // Feed id
+        offset += 32;
+        let read_i64 = |at: usize| -> Option<i64> { Some(i64::from_le_bytes(data.get(at..at + 8)?.try_into().ok()?)) };
+        let price = read_i64(offset)?;
+        let conf = read_i64(offset + 8)? as u64;
+        let exponent = i32::from_le_bytes(data.get(offset + 16..offset + 20)?.try_into().ok()?);
+        let publish_time = read_i64(offset + 20)?;
+        // prev_publish_time, ema_price, ema_conf
+        let posted_slot = read_i64(offset + 52)? as u64;
+        let scale = 10f64.powi(exponent);
+        Some(OraclePrice { price: price as f64 * scale, confidence: conf as f64 * scale, publish_time, posted_slot })
+    }
+
+    pub fn age_secs(&self) -> i64 {
+        Utc::now().timestamp() - self.publish_time
+    }
+
+    // Confidence as a share of the price
+    pub fn confidence_ratio(&self) -> f64 {
+        if self.price > 0.0 { self.confidence / self.price } else { f64::INFINITY }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PythFeed {
+    pub mint: String,
+    pub account: Pubkey,
+}
+
+// SOL, USDC and USDT against USD
+pub fn default_pyth_feeds() -> Vec<PythFeed> {
+    [(SOL_MINT, PYTH_SOL_USD), (USDC_MINT, PYTH_USDC_USD), (USDT_MINT, PYTH_USDT_USD)]
+        .into_iter()
+        .filter_map(|(mint, account)| Some(PythFeed { mint: mint.to_string(), account: from_str(account).ok()? }))
+        .collect()
+}
+
+// Oracle prices of the feed mints, read from their price accounts on-chain.
+// Sanity check of the pool-implied prices, and the price source of the oracle-priced venues
+#[derive(Debug)]
+pub struct PythOracle {
+    pub feeds: Vec<PythFeed>,
+    // Older prices are not served
+    pub max_age: Duration,
+    // Unix seconds the prices are served as of, now when None
+    as_of: Option<i64>,
+    prices: RwLock<HashMap<String, OraclePrice>>,
+}
+
+impl PythOracle {
+    pub fn new(feeds: Vec<PythFeed>, max_age: Duration) -> Self {
+        PythOracle { feeds, max_age, as_of: None, prices: RwLock::new(HashMap::new()) }
+    }
+
+    // The prices of recorded price accounts, as of the time they were recorded at (unix seconds)
+    pub fn recorded(feeds: Vec<PythFeed>, max_age: Duration, as_of: i64, accounts: &HashMap<String, Vec<u8>>) -> Self {
+        let oracle = PythOracle { feeds, max_age, as_of: Some(as_of), prices: RwLock::new(HashMap::new()) };
+        for feed in oracle.feeds.iter() {
+            if let Some(data) = accounts.get(&feed.account.to_string()) {
+                oracle.update(&feed.account, data);
+            }
+        }
+        oracle
+    }
+
+    pub fn price(&self, mint: &str) -> Option<OraclePrice> {
+        let age = |price: &OraclePrice| match self.as_of {
+            Some(as_of) => as_of - price.publish_time,
+            None => price.age_secs(),
+        };
+        self.prices.read().unwrap().get(mint).copied().filter(|price| age(price) <= self.max_age.as_secs() as i64)
+    }
+
+    // Whole tokens of quote_mint per whole token of base_mint, and the combined confidence ratio
+    pub fn relative_price(&self, base_mint: &str, quote_mint: &str) -> Option<(f64, f64)> {
+        let base = self.price(base_mint)?;
+        let quote = self.price(quote_mint)?;
+        if quote.price <= 0.0 {
+            return None;
+        }
+        Some((base.price / quote.price, base.confidence_ratio() + quote.confidence_ratio()))
+    }
+
+    // Relative gap between the price a route quoted and the oracle one, None when a side has no oracle price
+    pub fn route_deviation(&self, route: &SwapRouteSimulation, tokens_infos: &HashMap<String, TokenInfos>) -> Option<(f64, f64)> {
+        let (oracle_price, confidence_ratio) = self.relative_price(&route.token_in, &route.token_out)?;
+        let decimals_in = tokens_infos.get(&route.token_in)?.decimals as i32;
+        let decimals_out = tokens_infos.get(&route.token_out)?.decimals as i32;
+        let amount_out = route.estimated_amount_out.parse::<f64>().ok()?;
+        if route.amount_in == 0 {
+            return None;
+        }
+        let implied_price = (amount_out / 10f64.powi(decimals_out)) / (route.amount_in as f64 / 10f64.powi(decimals_in));
+        Some(((implied_price / oracle_price - 1.0).abs(), confidence_ratio))
+    }
+
+    fn update(&self, account: &Pubkey, data: &[u8]) {
+        let Some(feed) = self.feeds.iter().find(|feed| feed.account == *account) else { return };
+        let Some(price) = OraclePrice::decode(data) else {
+            error!("❌ Pyth price account {} not decoded", account);
+            return;
+        };
+        let mut prices = self.prices.write().unwrap();
+        // Notifications may arrive out of order
+        if !prices.get(&feed.mint).is_some_and(|previous| previous.posted_slot > price.posted_slot) {
+            prices.insert(feed.mint.clone(), price);
+        }
+    }
+
+    pub async fn refresh(&self, rpc_url: &str) -> Result<()> {
+        let accounts: Vec<Pubkey> = self.feeds.iter().map(|feed| feed.account).collect();
+        let fetched = nonblocking_client(rpc_url).get_multiple_accounts(&accounts).await?;
+        for (account, fetched) in accounts.iter().zip(fetched) {
+            if let Some(fetched) = fetched {
+                self.update(account, &fetched.data);
+            }
+        }
+        Ok(())
+    }
+
+    async fn subscribe(&self, endpoint: FeedEndpoint) -> Result<()> {
+        let client = PubsubClient::new(&endpoint.authenticated_url()).await?;
+        let mut streams = Vec::new();
+        for feed in &self.feeds {
+            let config = RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..RpcAccountInfoConfig::default()
+            };
+            let (updates, _unsubscribe) = client.account_subscribe(&feed.account, Some(config)).await?;
+            let account = feed.account;
+            streams.push(updates.map(move |update| (account, update.value.data.decode())).boxed());
+        }
+        info!("🔮 Subscribed to {} Pyth price accounts", streams.len());
+        let mut updates = select_all(streams);
+        while let Some((account, data)) = updates.next().await {
+            if let Some(data) = data {
+                self.update(&account, &data);
+            }
+        }
+        Ok(())
+    }
+}
+
+static PYTH_ORACLE: OnceLock<PythOracle> = OnceLock::new();
+
+// To call once at startup, no oracle check otherwise
+pub fn init_pyth_oracle(oracle: PythOracle) -> &'static PythOracle {
+    PYTH_ORACLE.get_or_init(|| oracle)
+}
+
+pub fn pyth_oracle() -> Option<&'static PythOracle> {
+    PYTH_ORACLE.get()
+}
+
+// Price accounts fetched once, then followed on the account WebSocket (polled every interval without one)
+pub async fn run_pyth_oracle(oracle: &'static PythOracle, rpc_url: String, poll_interval: Duration) {
+    if let Err(e) = oracle.refresh(&rpc_url).await {
+        error!("❌ Pyth prices not fetched: {:?}", e);
+    }
+    if feeds().endpoint(FeedKind::AccountWs).is_some() {
+        feeds().run(FeedKind::AccountWs, |endpoint| oracle.subscribe(endpoint)).await;
+        return;
+    }
+    let mut ticker = tokio::time::interval(poll_interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        if let Err(e) = oracle.refresh(&rpc_url).await {
+            error!("❌ Pyth prices not fetched: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // PriceUpdateV2 with a Full verification level: 150.25 ± 0.05 USD (exponent -2)
+    fn price_update(verification_level: &[u8]) -> Vec<u8> {
+        let mut data = vec![0u8; 8 + 32];
+        data.extend_from_slice(verification_level);
+        data.extend_from_slice(&[0; 32]);
+        data.extend_from_slice(&15_025i64.to_le_bytes());
+        data.extend_from_slice(&5u64.to_le_bytes());
+        data.extend_from_slice(&(-2i32).to_le_bytes());
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        data.extend_from_slice(&[0; 24]);
+        data.extend_from_slice(&250_000_000u64.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn decode_a_fully_verified_price() {
+        let price = OraclePrice::decode(&price_update(&[1])).unwrap();
+        assert!((price.price - 150.25).abs() < 1e-9);
+        assert!((price.confidence - 0.05).abs() < 1e-9);
+        assert_eq!((price.publish_time, price.posted_slot), (1_700_000_000, 250_000_000));
+    }
+
+    #[test]
+    fn decode_a_partially_verified_price() {
+        let full = OraclePrice::decode(&price_update(&[1])).unwrap();
+        assert_eq!(OraclePrice::decode(&price_update(&[0, 3])), Some(full));
+    }
+
+    #[test]
+    fn unknown_verification_level_or_short_account() {
+        assert_eq!(OraclePrice::decode(&price_update(&[2])), None);
+        assert_eq!(OraclePrice::decode(&price_update(&[1])[..100]), None);
+    }
+
+    #[test]
+    fn recorded_prices_served_as_of_their_recording() {
+        let accounts = HashMap::from([(PYTH_SOL_USD.to_string(), price_update(&[1]))]);
+        let oracle = |as_of: i64| PythOracle::recorded(default_pyth_feeds(), Duration::from_secs(60), as_of, &accounts);
+        assert!(oracle(1_700_000_030).price(SOL_MINT).is_some());
+        assert_eq!(oracle(1_700_000_100).price(SOL_MINT), None);
+        assert_eq!(oracle(1_700_000_030).price(USDC_MINT), None);
+    }
+}
@@ -1,9 +1,14 @@
 use crate::arbitrage::types::{Route, TokenInfos};
+use crate::markets::errors::{PoolLoadError, QuoteError};
+use crate::markets::ingest::account_updates;
 use crate::markets::types::{Dex, DexLabel, Market, PoolItem, SimulationRes};
+use crate::markets::zero_copy::raydium_amm;
 use crate::markets::utils::toPairString;
 use crate::common::debug::print_json_segment;
 use crate::common::utils::{from_Pubkey, from_str, make_request};
+use crate::common::rate_limiter::throttle;
 use crate::common::constants::Env;
+use crate::common::feeds::{feeds, FeedKind};
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_client::rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType};
@@ -14,12 +19,13 @@ use serde::{Deserialize, Deserializer, de, Serialize};
 use serde_json::Value;
 use reqwest::get;
 use std::io::{BufWriter, Write};
-use log::{info, error};
+use log::{info, error, Level};
+use crate::log_every_n;
 use solana_account_decoder::{UiAccountData, UiAccountEncoding};
 use solana_program::pubkey::Pubkey;
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_pubsub_client::pubsub_client::PubsubClient;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use solana_client::rpc_client::RpcClient;
 use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
 
@@ -97,7 +103,8 @@ impl RaydiumDEX {
 //     }
 //     Ok(())
 // }
-pub async fn fetch_data_raydium() -> Result<(), Box<dyn std::error::Error>> {
+pub async fn fetch_data_raydium() -> Result<(), PoolLoadError> {
+    throttle("https://api.raydium.io/v2/main/pairs").await;
     let response = get("https://api.raydium.io/v2/main/pairs").await?;
     // info!("response: {:?}", response);
     // info!("response-status: {:?}", response.status().is_success());
@@ -119,10 +126,12 @@ pub async fn fetch_data_raydium() -> Result<(), Box<dyn std::error::Error>> {
                 let result = print_json_segment("src/markets/cache/raydium-markets.json", 21174733 - 1000 as u64, 2000);
                 // raw_file.write_all(data.as_bytes())?;
                 // info!("Raw data written to 'raydium-markets-raw.json' for inspection.");
+                return Err(PoolLoadError::Decode(e));
             }
         }
     } else {
         error!("Fetch of 'raydium-markets.json' not successful: {}", response.status());
+        return Err(PoolLoadError::Status(response.status()));
     }
     Ok(())
 }
@@ -145,6 +154,7 @@ pub async fn fetch_new_raydium_pools(rpc_client: &RpcClient, token: String, on_t
         RpcFilterType::DataSize(752), 
     ]);
     
+    throttle(&rpc_client.url()).await;
     let accounts = rpc_client.get_program_accounts_with_config(
         &from_str(&raydium_program).unwrap(),
         RpcProgramAccountsConfig {
@@ -180,11 +190,10 @@ pub async fn fetch_new_raydium_pools(rpc_client: &RpcClient, token: String, on_t
 }
 
 pub async fn stream_raydium(account: Pubkey) -> Result<()> {
-    let env = Env::new();
-    let url = env.wss_rpc_url.as_str();
+    let url = feeds().url(FeedKind::AccountWs).ok_or_else(|| anyhow!("WSS_RPC_URL not set"))?;
     let (mut account_subscription_client, account_subscription_receiver) =
     PubsubClient::account_subscribe(
-        url,
+        &url,
         &account,
         Some(RpcAccountInfoConfig {
             encoding: Some(UiAccountEncoding::JsonParsed),
@@ -198,11 +207,11 @@ pub async fn stream_raydium(account: Pubkey) -> Result<()> {
         match account_subscription_receiver.recv() {
             Ok(response) => {
                 let data = response.value.data;
-                let bytes_slice = UiAccountData::decode(&data).unwrap();
-                println!("account subscription data response: {:?}", data);
-                // let account_data = unpack_from_slice(bytes_slice.as_slice());
-                // println!("Raydium CLMM Pool updated: {:?}", account);
-                // println!("Data: {:?}", account_data.unwrap());
+                let Some(bytes_slice) = UiAccountData::decode(&data) else { continue };
+                if let Some(amm) = raydium_amm(&bytes_slice) {
+                    log_every_n!(Level::Info, 100, "Raydium Pool updated: {:?} // Need take pnl: {:?}", account, amm.need_take_pnl());
+                }
+                account_updates().push(from_Pubkey(account), bytes_slice);
 
             }
             Err(e) => {
@@ -217,13 +226,13 @@ pub async fn stream_raydium(account: Pubkey) -> Result<()> {
 
 // Simulate one route 
 // I want to get the data of the market i'm interested in this route
-pub async fn simulate_route_raydium(printing_amt: bool, amount_in: u64, route: Route, market: Market, tokens_infos: HashMap<String, TokenInfos>) -> Result<(String, String), Box<dyn std::error::Error>> {
+pub async fn simulate_route_raydium(printing_amt: bool, amount_in: u64, route: Route, market: Market, tokens_infos: &HashMap<String, TokenInfos>) -> Result<(String, String), QuoteError> {
     // println!("account_data: {:?}", &market.account_data.clone().unwrap());
     // println!("market: {:?}", market.clone());
     // let raydium_data = AmmInfo::try_from_slice(&market.account_data.unwrap()).unwrap();
     // println!("raydium_data: {:?}", raydium_data);
-    let token0 = tokens_infos.get(&market.tokenMintA).unwrap();
-    let token1 = tokens_infos.get(&market.tokenMintB).unwrap();
+    let token0 = tokens_infos.get(&market.tokenMintA).ok_or_else(|| QuoteError::UnknownToken(market.tokenMintA.clone()))?;
+    let token1 = tokens_infos.get(&market.tokenMintB).ok_or_else(|| QuoteError::UnknownToken(market.tokenMintB.clone()))?;
     let mut params: String = String::new();
 
     let amount_in_uint = amount_in as u64;
@@ -270,15 +279,9 @@ pub async fn simulate_route_raydium(printing_amt: bool, amount_in: u64, route: R
         Ok((json_value.estimatedAmountOut,json_value.estimatedMinAmountOut.unwrap_or_default()))
     } else if let Ok(error_value) = serde_json::from_str::<SimulationError>(&res_text) {
         // println!("ERROR Value: {:?}", error_value.error);
-        Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            error_value.error,
-        )))
+        Err(QuoteError::Rejected(error_value.error))
     } else {
-        Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "Unexpected response format",
-        )))
+        Err(QuoteError::UnexpectedResponse(res_text))
     }
 
 
@@ -1,3 +1,4 @@
+use crate::markets::errors::PoolLoadError;
 use crate::markets::types::{Dex, DexLabel, Market, PoolItem};
 use crate::markets::utils::toPairString;
 use tokio::net::TcpStream;
@@ -7,16 +8,18 @@ use std::io;
 use std::io::Write;
 use serde::{Deserialize, Serialize};
 use reqwest::get;
-use log::{info, error};
+use log::{info, error, Level};
+use crate::log_every_n;
 use solana_account_decoder::{UiAccountData, UiAccountEncoding};
 use solana_program::pubkey::Pubkey;
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_pubsub_client::pubsub_client::PubsubClient;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use solana_client::rpc_client::RpcClient;
 use solana_client::rpc_config::RpcAccountInfoConfig;
 
-use crate::common::constants::Env;
+use crate::common::feeds::{feeds, FeedKind};
+use crate::common::rate_limiter::throttle;
 
 #[derive(Debug)]
 pub struct RaydiumClmmDEX {
@@ -70,7 +73,8 @@ impl RaydiumClmmDEX {
     }
   }
 
-pub async fn fetch_data_raydium_clmm() -> Result<(), Box<dyn std::error::Error>> {
+pub async fn fetch_data_raydium_clmm() -> Result<(), PoolLoadError> {
+    throttle("https://api.raydium.io/v2/ammV3/ammPools").await;
     let response = get("https://api.raydium.io/v2/ammV3/ammPools").await?;
     // info!("response: {:?}", response);
     // info!("response-status: {:?}", response.status().is_success());
@@ -82,16 +86,16 @@ pub async fn fetch_data_raydium_clmm() -> Result<(), Box<dyn std::error::Error>>
         info!("Data written to 'raydiumclmm-markets.json' successfully.");
     } else {
         error!("Fetch of 'raydiumclmm-markets.json' not successful: {}", response.status());
+        return Err(PoolLoadError::Status(response.status()));
     }
     Ok(())
 }
 
 pub async fn stream_raydium_clmm(account: Pubkey) -> Result<()> {
-    let env = Env::new();
-    let url = env.wss_rpc_url.as_str();
+    let url = feeds().url(FeedKind::AccountWs).ok_or_else(|| anyhow!("WSS_RPC_URL not set"))?;
     let (mut account_subscription_client, account_subscription_receiver) =
     PubsubClient::account_subscribe(
-        url,
+        &url,
         &account,
         Some(RpcAccountInfoConfig {
             encoding: Some(UiAccountEncoding::JsonParsed),
@@ -106,7 +110,7 @@ pub async fn stream_raydium_clmm(account: Pubkey) -> Result<()> {
             Ok(response) => {
                 let data = response.value.data;
                 let bytes_slice = UiAccountData::decode(&data).unwrap();
-                println!("account subscription data response: {:?}", data);
+                log_every_n!(Level::Info, 100, "Raydium CLMM Pool updated: {:?} // Data: {:?}", account, data);
                 // let account_data = unpack_from_slice(bytes_slice.as_slice());
                 // println!("Raydium CLMM Pool updated: {:?}", account);
                 // println!("Data: {:?}", account_data.unwrap());
@@ -0,0 +1,70 @@
+use anyhow::{anyhow, Result};
+use log::{error, info};
+use solana_entry::entry::Entry;
+use tokio::sync::broadcast;
+use tonic::codec::ProstCodec;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::metadata::{AsciiMetadataKey, AsciiMetadataValue};
+use tonic::transport::Endpoint;
+
+use crate::arbitrage::registry::StrategyEvent;
+use crate::common::feeds::{feeds, FeedEndpoint, FeedKind};
+use crate::common::utils::from_Pubkey;
+use crate::markets::pool_cache::pool_cache;
+
+static SUBSCRIBE_ENTRIES: &str = "/shredstream.ShredstreamProxy/SubscribeEntries";
+
+// shredstream.proto of the Jito shredstream proxy
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SubscribeEntriesRequest {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct EntriesMessage {
+    #[prost(uint64, tag = "1")]
+    pub slot: u64,
+    // bincode Vec<solana_entry::entry::Entry>
+    #[prost(bytes = "vec", tag = "2")]
+    pub entries: Vec<u8>,
+}
+
+// Transactions of the entries reassembled from shreds by the proxy, a few hundred milliseconds
+// before the RPC confirms them. Those touching a pool of the cache are pushed to the strategies.
+// Only the static account keys are read: a pool reached through a lookup table is missed.
+// Reconnected by the feed manager with the shredstream backoff
+pub async fn run_shredstream(events: broadcast::Sender<StrategyEvent>) {
+    feeds().run(FeedKind::Shredstream, |endpoint| subscribe_entries(endpoint, events.clone())).await;
+}
+
+async fn subscribe_entries(endpoint: FeedEndpoint, events: broadcast::Sender<StrategyEvent>) -> Result<()> {
+    let channel = Endpoint::from_shared(endpoint.url.clone())?.tcp_nodelay(true).connect().await?;
+    let mut client = tonic::client::Grpc::new(channel);
+    client.ready().await.map_err(|e| anyhow!("Shredstream proxy not ready: {}", e))?;
+    let mut request = tonic::Request::new(SubscribeEntriesRequest {});
+    if let Some((name, token)) = endpoint.auth_header() {
+        request.metadata_mut().insert(AsciiMetadataKey::from_bytes(name.as_bytes())?, AsciiMetadataValue::try_from(token)?);
+    }
+    let mut stream = client
+        .server_streaming(request, PathAndQuery::from_static(SUBSCRIBE_ENTRIES), ProstCodec::<SubscribeEntriesRequest, EntriesMessage>::default())
+        .await?
+        .into_inner();
+    info!("🛰️ Subscribed to the shredstream entries of {}", endpoint.url);
+    while let Some(message) = stream.message().await? {
+        let entries: Vec<Entry> = match bincode::deserialize(&message.entries) {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!("❌ Shredstream entries of slot {} not decoded: {}", message.slot, e);
+                continue;
+            }
+        };
+        for transaction in entries.iter().flat_map(|entry| entry.transactions.iter()) {
+            let pools: Vec<String> = transaction.message.static_account_keys().iter().map(|key| from_Pubkey(*key)).filter(|address| pool_cache().contains(address)).collect();
+            if pools.is_empty() {
+                continue;
+            }
+            let Some(signature) = transaction.signatures.first() else { continue };
+            // No strategy listening is not an error
+            let _ = events.send(StrategyEvent::SwapObserved { signature: signature.to_string(), slot: message.slot, pools });
+        }
+    }
+    Ok(())
+}
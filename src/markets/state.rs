@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+use log::info;
+use tokio::sync::Mutex;
+
+use solana_program::pubkey::Pubkey;
+
+use crate::markets::graph::TokenGraph;
+use crate::common::utils::from_Pubkey;
+use crate::markets::pools::{load_all_pools, retain_scoped};
+use crate::markets::types::{Dex, Market};
+
+// Pools of every DEX, loaded once and shared by all the strategies.
+// A reload swaps the whole set: readers keep the snapshot they already hold
+#[derive(Debug, Default)]
+pub struct MarketState {
+    dexs: RwLock<Arc<Vec<Dex>>>,
+    // Pools by mint, rebuilt on reload and extended with the pools found afterwards
+    graph: RwLock<TokenGraph>,
+    // Held while loading, so strategies initialized together share the same load
+    loading: Mutex<bool>,
+    // Mints the loaded pools are restricted to, every pool when None
+    scope: RwLock<Option<HashSet<Pubkey>>>,
+}
+
+impl MarketState {
+    pub fn new() -> Self {
+        MarketState::default()
+    }
+
+    // Load only the pools between the configured mints (bridge tokens included): the pool lists are
+    // still read in full but the rest is never indexed. Asking for another mint loads everything
+    pub fn with_scope(self, mints: HashSet<Pubkey>) -> Self {
+        *self.scope.write().unwrap() = Some(mints);
+        self
+    }
+
+    // Every mint of the set is within the loaded pools
+    pub fn covers(&self, mints: &HashSet<Pubkey>) -> bool {
+        self.scope.read().unwrap().as_ref().map_or(true, |scope| mints.is_subset(scope))
+    }
+
+    pub fn dexs(&self) -> Arc<Vec<Dex>> {
+        self.dexs.read().unwrap().clone()
+    }
+
+    pub fn replace(&self, dexs: Vec<Dex>) {
+        let graph = TokenGraph::from_dexs(&dexs);
+        info!("🕸️  Token graph: {} pools over {} mints", graph.pools_len(), graph.mints_len());
+        *self.graph.write().unwrap() = graph;
+        *self.dexs.write().unwrap() = Arc::new(dexs);
+    }
+
+    // Pools trading two mints of the set
+    pub fn markets_within(&self, mints: &HashSet<Pubkey>) -> Vec<Market> {
+        self.graph.read().unwrap().markets_within(mints).into_iter().map(|market| (*market).clone()).collect()
+    }
+
+    // Newly found pools, returns how many were not known yet
+    pub fn add_markets<'a>(&self, markets: impl IntoIterator<Item = &'a Market>) -> usize {
+        let mut graph = self.graph.write().unwrap();
+        markets.into_iter().filter(|market| graph.add_market(market)).count()
+    }
+
+    // Pools loaded on first use only
+    pub async fn ensure_loaded(&self, fetch_new_pools: bool) -> Arc<Vec<Dex>> {
+        let mut loaded = self.loading.lock().await;
+        if !*loaded {
+            self.reload_locked(fetch_new_pools).await;
+            *loaded = true;
+        }
+        self.dexs()
+    }
+
+    // Full load on demand, when a token group goes beyond the scope
+    pub async fn ensure_covered(&self, mints: &HashSet<Pubkey>) {
+        if self.covers(mints) {
+            return;
+        }
+        let mut loaded = self.loading.lock().await;
+        if self.covers(mints) {
+            return;
+        }
+        info!("🏊 Tokens outside of the loaded scope, loading every pool");
+        *self.scope.write().unwrap() = None;
+        self.reload_locked(false).await;
+        *loaded = true;
+    }
+
+    pub async fn reload(&self, fetch_new_pools: bool) -> Arc<Vec<Dex>> {
+        let mut loaded = self.loading.lock().await;
+        self.reload_locked(fetch_new_pools).await;
+        *loaded = true;
+        self.dexs()
+    }
+
+    async fn reload_locked(&self, fetch_new_pools: bool) {
+        info!("🏊 Fetching pools...");
+        let mut dexs = load_all_pools(fetch_new_pools).await;
+        let scope: Option<HashSet<String>> = self.scope.read().unwrap().as_ref().map(|scope| scope.iter().map(|mint| from_Pubkey(*mint)).collect());
+        if let Some(scope) = scope {
+            let kept = retain_scoped(&mut dexs, &scope);
+            info!("🏊 Scoped to {} mints, {} pools kept", scope.len(), kept);
+        }
+        self.replace(dexs);
+        info!("🏊 Loaded {} dexs", self.dexs().len());
+    }
+}
@@ -0,0 +1,2 @@
+// The pool account views live in quote-core, with the local quote math
+pub use quote_core::layouts::*;
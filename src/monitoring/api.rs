@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{Html, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use log::{error, info};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::monitoring::competitors::{CompetitorReport, CompetitorTracker};
+use crate::monitoring::dex_stats::{DexStats, VenueReport};
+use crate::monitoring::dashboard::{DashboardState, DashboardView, DASHBOARD_HTML};
+use crate::monitoring::events::subscribe;
+use crate::monitoring::metrics::metrics_handler;
+use crate::monitoring::pnl::{PnlAggregate, PnlSnapshot, PnlTracker};
+
+// What the control API reads from, the routes of a missing subsystem answer 404
+#[derive(Clone, Default)]
+pub struct ApiState {
+    pub pnl: Option<Arc<PnlTracker>>,
+    pub dashboard: Option<Arc<DashboardState>>,
+    pub competitors: Option<Arc<CompetitorTracker>>,
+    pub dex_stats: Option<Arc<DexStats>>,
+    // Every route requires `Authorization: Bearer <token>` (or ?token= for the browser) when set
+    pub token: Option<String>,
+}
+
+pub fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/", get(dashboard_page))
+        .route("/dashboard/state", get(dashboard_state))
+        .route("/metrics", get(metrics_handler))
+        .route("/pnl", get(pnl_snapshot))
+        .route("/pnl/daily", get(pnl_daily))
+        .route("/pnl/weekly", get(pnl_weekly))
+        .route("/competitors", get(competitors_report))
+        .route("/dex-stats", get(dex_stats_report))
+        .route("/ws/events", get(events_stream))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+async fn require_token(State(state): State<ApiState>, Query(params): Query<HashMap<String, String>>, request: Request, next: Next) -> Result<Response, StatusCode> {
+    let Some(token) = &state.token else {
+        return Ok(next.run(request).await);
+    };
+    let bearer = request.headers().get(header::AUTHORIZATION).and_then(|value| value.to_str().ok()).and_then(|value| value.strip_prefix("Bearer "));
+    if bearer.or(params.get("token").map(String::as_str)) != Some(token.as_str()) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(next.run(request).await)
+}
+
+// HTTP control API: GET / (dashboard), /dashboard/state, /metrics (Prometheus), /pnl, /pnl/daily, /pnl/weekly
+// /competitors, /dex-stats and the /ws/events WebSocket
+pub async fn run_control_api(addr: SocketAddr, state: ApiState) -> Result<()> {
+    if !addr.ip().is_loopback() && state.token.is_none() {
+        return Err(anyhow!("CONTROL_API_TOKEN is required to serve the control API on {}", addr));
+    }
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("📈 Control API served on http://{}", addr);
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
+
+async fn dashboard_page() -> Html<&'static str> {
+    Html(DASHBOARD_HTML)
+}
+
+async fn dashboard_state(State(state): State<ApiState>) -> Result<Json<DashboardView>, StatusCode> {
+    let dashboard = state.dashboard.ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(dashboard.view()))
+}
+
+async fn pnl_snapshot(State(state): State<ApiState>) -> Result<Json<PnlSnapshot>, StatusCode> {
+    let pnl = state.pnl.ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(pnl.snapshot()))
+}
+
+async fn pnl_daily(State(state): State<ApiState>) -> Result<Json<Vec<PnlAggregate>>, StatusCode> {
+    let pnl = state.pnl.ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(pnl.daily()))
+}
+
+async fn pnl_weekly(State(state): State<ApiState>) -> Result<Json<Vec<PnlAggregate>>, StatusCode> {
+    let pnl = state.pnl.ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(pnl.weekly()))
+}
+
+async fn competitors_report(State(state): State<ApiState>) -> Result<Json<Vec<CompetitorReport>>, StatusCode> {
+    let competitors = state.competitors.ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(competitors.report()))
+}
+
+async fn dex_stats_report(State(state): State<ApiState>) -> Result<Json<Vec<VenueReport>>, StatusCode> {
+    let dex_stats = state.dex_stats.ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(dex_stats.report()))
+}
+
+// One JSON text message per bot event, ?types=opportunity_found,tx_landed keeps only those types
+async fn events_stream(ws: WebSocketUpgrade, Query(params): Query<HashMap<String, String>>) -> Response {
+    let types: Option<Vec<String>> = params.get("types").map(|types| types.split(',').map(|kind| kind.trim().to_string()).collect());
+    ws.on_upgrade(move |socket| forward_events(socket, types))
+}
+
+async fn forward_events(mut socket: WebSocket, types: Option<Vec<String>>) {
+    let mut events = subscribe();
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            // A slow client misses events rather than slowing the bot down
+            Err(RecvError::Lagged(skipped)) => {
+                error!("WebSocket client lagged, {} events skipped", skipped);
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+        if types.as_ref().is_some_and(|types| !types.iter().any(|kind| kind == event.event.kind())) {
+            continue;
+        }
+        let Ok(text) = serde_json::to_string(&event) else { continue };
+        if socket.send(Message::Text(text)).await.is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use rayon::prelude::*;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::{v0, VersionedMessage};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::common::maths::{clmm_swap, constant_product_out, FeeMultiplier, TickCrossing};
+use crate::common::rpc_pool::nonblocking_client_with_commitment;
+
+// SOL/USDC-like pools, same shapes as benches/quote_math.rs
+const SQRT_PRICE_X64: u128 = 7_143_265_591_698_411_110;
+const LIQUIDITY: u128 = 120_000_000_000_000;
+const AMOUNT_IN: u64 = 50_000_000_000;
+
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    // Round trips per endpoint and measure
+    pub rpc_samples: usize,
+    pub quote_duration: Duration,
+    pub sign_iterations: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig { rpc_samples: 20, quote_duration: Duration::from_secs(2), sign_iterations: 2_000 }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub errors: usize,
+    pub min: Duration,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub max: Duration,
+}
+
+impl LatencyStats {
+    pub fn from_samples(mut samples: Vec<Duration>, errors: usize) -> Self {
+        if samples.is_empty() {
+            return LatencyStats { errors, ..LatencyStats::default() };
+        }
+        samples.sort();
+        let at = |ratio: f64| samples[((samples.len() - 1) as f64 * ratio).round() as usize];
+        LatencyStats { samples: samples.len(), errors, min: samples[0], p50: at(0.5), p90: at(0.9), max: samples[samples.len() - 1] }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EndpointReport {
+    pub url: String,
+    pub round_trip: LatencyStats,
+    pub blockhash: LatencyStats,
+    pub simulate: LatencyStats,
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    // 3-hop paths quoted per second (constant product, CLMM crossing 4 ticks, constant product)
+    pub quotes_per_sec_single: f64,
+    pub quotes_per_sec_parallel: f64,
+    pub threads: usize,
+    // Compile + sign of a swap-sized v0 transaction
+    pub build_sign: Duration,
+    pub endpoints: Vec<EndpointReport>,
+}
+
+impl BenchReport {
+    pub fn print(&self) {
+        println!("Quote engine: {:.0} paths/s on 1 thread, {:.0} paths/s on {} threads", self.quotes_per_sec_single, self.quotes_per_sec_parallel, self.threads);
+        println!("Build + sign: {:?} per transaction", self.build_sign);
+        println!("{:<48} {:<10} {:>8} {:>8} {:>8} {:>8} {:>7}", "Endpoint", "Call", "min", "p50", "p90", "max", "errors");
+        for endpoint in &self.endpoints {
+            for (call, stats) in [("getSlot", &endpoint.round_trip), ("blockhash", &endpoint.blockhash), ("simulate", &endpoint.simulate)] {
+                println!(
+                    "{:<48} {:<10} {:>8} {:>8} {:>8} {:>8} {:>7}",
+                    endpoint.url,
+                    call,
+                    millis(stats.min),
+                    millis(stats.p50),
+                    millis(stats.p90),
+                    millis(stats.max),
+                    stats.errors
+                );
+            }
+        }
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn quote_path(fee: FeeMultiplier, ticks: &[TickCrossing]) -> Option<u64> {
+    let out = constant_product_out(black_box(AMOUNT_IN), 8_000_000_000_000, 1_200_000_000_000, fee)?;
+    let out = clmm_swap(out, LIQUIDITY, SQRT_PRICE_X64, fee, true, ticks)?;
+    constant_product_out(out, 1_200_000_000_000, 8_000_000_000_000, fee)
+}
+
+// Quotes per second over the duration, on this thread then on every core
+pub fn bench_quotes(duration: Duration) -> (f64, f64, usize) {
+    let Some(fee) = FeeMultiplier::from_fee_rate(3_000) else { return (0.0, 0.0, 0) };
+    let ticks: Vec<TickCrossing> = (1..=4)
+        .map(|i| TickCrossing { sqrt_price_x64: (SQRT_PRICE_X64 as f64 * 0.9975f64.powi(i)) as u128, liquidity_net: 10_000_000_000_000 })
+        .collect();
+    let started_at = Instant::now();
+    let mut quotes = 0u64;
+    while started_at.elapsed() < duration {
+        for _ in 0..1_000 {
+            black_box(quote_path(fee, &ticks));
+        }
+        quotes += 1_000;
+    }
+    let single = quotes as f64 / started_at.elapsed().as_secs_f64();
+
+    let threads = rayon::current_num_threads();
+    let started_at = Instant::now();
+    let quotes: u64 = (0..threads)
+        .into_par_iter()
+        .map(|_| {
+            let mut quotes = 0u64;
+            while started_at.elapsed() < duration {
+                for _ in 0..1_000 {
+                    black_box(quote_path(fee, &ticks));
+                }
+                quotes += 1_000;
+            }
+            quotes
+        })
+        .sum();
+    (single, quotes as f64 / started_at.elapsed().as_secs_f64(), threads)
+}
+
+fn bench_transaction(payer: &Keypair, blockhash: Hash) -> Result<VersionedTransaction> {
+    // Budget instructions, a few transfers for the swap instructions and the tip
+    let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_price(100), ComputeBudgetInstruction::set_compute_unit_limit(200_000)];
+    instructions.extend((0..4).map(|_| system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1)));
+    Ok(VersionedTransaction::try_new(VersionedMessage::V0(v0::Message::try_compile(&payer.pubkey(), &instructions, &[], blockhash)?), &[payer])?)
+}
+
+// Mean time to compile and sign a transaction
+pub fn bench_build_sign(iterations: usize) -> Result<Duration> {
+    let payer = Keypair::new();
+    let started_at = Instant::now();
+    for _ in 0..iterations {
+        black_box(bench_transaction(&payer, Hash::new_unique())?);
+    }
+    Ok(started_at.elapsed() / iterations.max(1) as u32)
+}
+
+// getSlot, getLatestBlockhash and simulateTransaction round trips, one after the other
+pub async fn bench_endpoint(url: &str, samples: usize) -> EndpointReport {
+    let commitment = CommitmentConfig::confirmed();
+    let rpc_client = nonblocking_client_with_commitment(url, commitment);
+    let (mut round_trip, mut blockhash, mut simulate) = (Vec::new(), Vec::new(), Vec::new());
+    let (mut round_trip_errors, mut blockhash_errors, mut simulate_errors) = (0, 0, 0);
+    // Unfunded payer: the simulation is rejected, after a full round trip through the bank
+    let payer = Keypair::new();
+    let config = RpcSimulateTransactionConfig { sig_verify: false, replace_recent_blockhash: true, commitment: Some(commitment), ..RpcSimulateTransactionConfig::default() };
+    for _ in 0..samples {
+        let started_at = Instant::now();
+        match rpc_client.get_slot().await {
+            Ok(_) => round_trip.push(started_at.elapsed()),
+            Err(_) => round_trip_errors += 1,
+        }
+        let started_at = Instant::now();
+        match rpc_client.get_latest_blockhash_with_commitment(commitment).await {
+            Ok(_) => blockhash.push(started_at.elapsed()),
+            Err(_) => blockhash_errors += 1,
+        }
+        let Ok(transaction) = bench_transaction(&payer, Hash::new_unique()) else {
+            simulate_errors += 1;
+            continue;
+        };
+        let started_at = Instant::now();
+        match rpc_client.simulate_transaction_with_config(&transaction, config.clone()).await {
+            Ok(_) => simulate.push(started_at.elapsed()),
+            Err(_) => simulate_errors += 1,
+        }
+    }
+    EndpointReport {
+        url: url.to_string(),
+        round_trip: LatencyStats::from_samples(round_trip, round_trip_errors),
+        blockhash: LatencyStats::from_samples(blockhash, blockhash_errors),
+        simulate: LatencyStats::from_samples(simulate, simulate_errors),
+    }
+}
+
+// Everything the bot waits on, measured from this machine: compare the reports of candidate VPS locations
+pub async fn run_bench(urls: &[String], config: &BenchConfig) -> Result<BenchReport> {
+    let quote_duration = config.quote_duration;
+    let (quotes_per_sec_single, quotes_per_sec_parallel, threads) = tokio::task::spawn_blocking(move || bench_quotes(quote_duration)).await?;
+    let build_sign = bench_build_sign(config.sign_iterations)?;
+    let mut endpoints = Vec::new();
+    for url in urls {
+        endpoints.push(bench_endpoint(url, config.rpc_samples).await);
+    }
+    Ok(BenchReport { quotes_per_sec_single, quotes_per_sec_parallel, threads, build_sign, endpoints })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latency_percentiles_of_the_samples() {
+        let samples = (1..=10).rev().map(Duration::from_millis).collect();
+        let stats = LatencyStats::from_samples(samples, 2);
+        assert_eq!((stats.samples, stats.errors), (10, 2));
+        assert_eq!((stats.min, stats.max), (Duration::from_millis(1), Duration::from_millis(10)));
+        assert_eq!((stats.p50, stats.p90), (Duration::from_millis(6), Duration::from_millis(9)));
+    }
+
+    #[test]
+    fn no_sample_only_errors() {
+        let stats = LatencyStats::from_samples(Vec::new(), 3);
+        assert_eq!((stats.samples, stats.errors, stats.max), (0, 3, Duration::ZERO));
+    }
+}
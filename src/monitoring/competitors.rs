@@ -0,0 +1,155 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use chrono::Utc;
+use log::{debug, error};
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::common::database::TradeStatus;
+use crate::common::rpc_pool::nonblocking_client;
+use crate::monitoring::events::{subscribe, BotEvent};
+use crate::monitoring::landing::{analyze_landing, block_transactions, BlockTransaction};
+
+static COMPUTE_BUDGET_PROGRAM: &str = "ComputeBudget111111111111111111111111111111";
+
+// A recurring arbitrager: who signs and which program routes the swaps
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct CompetitorFingerprint {
+    pub signer: String,
+    pub program_id: String,
+}
+
+impl CompetitorFingerprint {
+    fn of(transaction: &BlockTransaction) -> Self {
+        let program_id = transaction.program_ids.iter().find(|program_id| program_id.as_str() != COMPUTE_BUDGET_PROGRAM).cloned().unwrap_or_default();
+        CompetitorFingerprint { signer: transaction.fee_payer.clone(), program_id }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CompetitorStats {
+    // Arbitrage-like transactions seen on the tracked pools
+    pub transactions: u64,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    pub pools: HashMap<String, u64>,
+    // Ahead of one of our trades on the same pools
+    pub beat_us: u64,
+    // Tokens paths of the trades lost to it
+    pub lost_paths: HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompetitorReport {
+    #[serde(flatten)]
+    pub fingerprint: CompetitorFingerprint,
+    #[serde(flatten)]
+    pub stats: CompetitorStats,
+}
+
+// Arbitragers active on the pools of the loaded paths
+#[derive(Debug, Default)]
+pub struct CompetitorTracker {
+    tracked_pools: RwLock<HashMap<String, HashSet<String>>>,
+    competitors: Mutex<HashMap<CompetitorFingerprint, CompetitorStats>>,
+}
+
+impl CompetitorTracker {
+    pub fn new() -> Self {
+        CompetitorTracker::default()
+    }
+
+    pub fn track_pools(&self, strategy: &str, pools: Vec<String>) {
+        self.tracked_pools.write().unwrap().insert(strategy.to_string(), pools.into_iter().collect());
+    }
+
+    fn pools(&self) -> HashSet<String> {
+        self.tracked_pools.read().unwrap().values().flatten().cloned().collect()
+    }
+
+    // Successful transactions swapping through two tracked pools or more
+    pub fn observe_block(&self, transactions: &[BlockTransaction]) {
+        let pools = self.pools();
+        let now = Utc::now().timestamp();
+        let mut competitors = self.competitors.lock().unwrap();
+        for transaction in transactions.iter().filter(|transaction| transaction.succeeded) {
+            let touched: Vec<&String> = transaction.accounts.iter().filter(|account| pools.contains(*account)).collect();
+            if touched.len() < 2 {
+                continue;
+            }
+            let stats = competitors.entry(CompetitorFingerprint::of(transaction)).or_insert_with(|| CompetitorStats { first_seen: now, ..CompetitorStats::default() });
+            stats.transactions += 1;
+            stats.last_seen = now;
+            for pool in touched {
+                *stats.pools.entry(pool.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    pub fn record_beaten(&self, tokens_path: &str, competitors_before: &[BlockTransaction]) {
+        let now = Utc::now().timestamp();
+        let mut competitors = self.competitors.lock().unwrap();
+        for transaction in competitors_before.iter().filter(|transaction| transaction.succeeded) {
+            let stats = competitors.entry(CompetitorFingerprint::of(transaction)).or_insert_with(|| CompetitorStats { first_seen: now, ..CompetitorStats::default() });
+            stats.beat_us += 1;
+            stats.last_seen = now;
+            *stats.lost_paths.entry(tokens_path.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    // Who we lose to the most first
+    pub fn report(&self) -> Vec<CompetitorReport> {
+        let mut report: Vec<CompetitorReport> = self
+            .competitors
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(fingerprint, stats)| CompetitorReport { fingerprint: fingerprint.clone(), stats: stats.clone() })
+            .collect();
+        report.sort_by(|a, b| b.stats.beat_us.cmp(&a.stats.beat_us).then(b.stats.transactions.cmp(&a.stats.transactions)));
+        report
+    }
+}
+
+// Tracked pools and lost trades from the event bus, plus one block sampled every scan_interval
+pub async fn run_competitor_tracker(tracker: Arc<CompetitorTracker>, rpc_url: String, scan_interval: Duration) {
+    let rpc_client = nonblocking_client(&rpc_url);
+    let mut events = subscribe();
+    let mut scan = tokio::time::interval(scan_interval);
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(timed) => match timed.event {
+                    BotEvent::PathsLoaded { strategy, pools, .. } => tracker.track_pools(&strategy, pools),
+                    // Landed but reverted, most often because someone took the opportunity first
+                    BotEvent::TradeRecorded { trade } if trade.status == TradeStatus::Failed => {
+                        let Some(slot) = trade.slot else { continue };
+                        let pools: Vec<String> = trade.hops.iter().map(|hop| hop.pool_address.clone()).collect();
+                        match analyze_landing(&rpc_client, &trade.signature, slot, &pools).await {
+                            Ok(landing) => tracker.record_beaten(&trade.tokens_path, &landing.competitors_before),
+                            Err(e) => error!("❌ Competitor analysis of {} failed: {:?}", trade.signature, e),
+                        }
+                    }
+                    _ => {}
+                },
+                Err(RecvError::Lagged(skipped)) => error!("Competitor tracker lagged, {} events skipped", skipped),
+                Err(RecvError::Closed) => break,
+            },
+            _ = scan.tick() => {
+                let slot = match rpc_client.get_slot().await {
+                    Ok(slot) => slot,
+                    Err(e) => {
+                        error!("❌ Competitor scan slot failed: {:?}", e);
+                        continue;
+                    }
+                };
+                match block_transactions(&rpc_client, slot).await {
+                    Ok(transactions) => tracker.observe_block(&transactions),
+                    Err(e) => debug!("Competitor scan of slot {} failed: {:?}", slot, e),
+                }
+            }
+        }
+    }
+}
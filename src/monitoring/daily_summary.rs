@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{NaiveDate, Utc};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::common::database::{find_pnl_records_since, find_trades_since, TradeStatus};
+use crate::monitoring::events::{publish, subscribe, BotEvent};
+use crate::monitoring::notifier::{notify, NotifyEvent};
+use crate::monitoring::pnl::PNL_COLLECTION;
+
+pub static DAILY_SUMMARIES_COLLECTION: &str = "daily_summaries";
+pub static DAILY_SUMMARY_TOP_PATHS: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathSummary {
+    pub tokens_path: String,
+    pub trades: u64,
+    pub net_pnl: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Incident {
+    pub at: String,
+    pub event: NotifyEvent,
+    pub title: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailySummary {
+    // YYYY-MM-DD, UTC
+    pub day: String,
+    pub trades: u64,
+    pub landed: u64,
+    pub failed: u64,
+    // Profitable trades over the trades with a PnL
+    pub win_rate: f64,
+    // Lamports of the base token, before and after fees and tips
+    pub gross_pnl: f64,
+    pub net_pnl: f64,
+    pub fees_lamports: u64,
+    pub tips_lamports: u64,
+    pub top_paths: Vec<PathSummary>,
+    pub incidents: Vec<Incident>,
+}
+
+impl DailySummary {
+    pub fn message(&self) -> String {
+        let mut message = format!(
+            "{} trades ({} landed, {} failed), win rate {:.0}%\nPnL gross {:.0} / net {:.0} lamports, fees {} + tips {} lamports",
+            self.trades,
+            self.landed,
+            self.failed,
+            self.win_rate * 100.0,
+            self.gross_pnl,
+            self.net_pnl,
+            self.fees_lamports,
+            self.tips_lamports
+        );
+        for path in self.top_paths.iter() {
+            message.push_str(&format!("\n• {}: {:.0} lamports over {} trades", path.tokens_path, path.net_pnl, path.trades));
+        }
+        if !self.incidents.is_empty() {
+            message.push_str(&format!("\n{} incidents", self.incidents.len()));
+            for incident in self.incidents.iter() {
+                message.push_str(&format!("\n• {} {}", incident.at, incident.title));
+            }
+        }
+        message
+    }
+}
+
+// From the trades ledger and the PnL records of that day
+pub async fn build_daily_summary(day: NaiveDate, incidents: Vec<Incident>) -> Result<DailySummary> {
+    let since = day.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp();
+    let until = since + 24 * 60 * 60;
+    let trades: Vec<_> = find_trades_since(since).await?.into_iter().filter(|trade| trade.timestamp < until).collect();
+    let pnl_records: Vec<_> = find_pnl_records_since(PNL_COLLECTION, since).await?.into_iter().filter(|record| record.timestamp < until).collect();
+
+    let fees_lamports = trades.iter().map(|trade| trade.fee_lamports).sum::<u64>();
+    let tips_lamports = trades.iter().map(|trade| trade.tip_lamports).sum::<u64>();
+    // The PnL records are net of fees
+    let net_pnl = pnl_records.iter().map(|record| record.realized_profit).sum::<f64>();
+    let wins = pnl_records.iter().filter(|record| record.realized_profit > 0.0).count();
+
+    let mut paths: HashMap<String, PathSummary> = HashMap::new();
+    for record in pnl_records.iter() {
+        let path = paths.entry(record.tokens_path.clone()).or_insert(PathSummary { tokens_path: record.tokens_path.clone(), trades: 0, net_pnl: 0.0 });
+        path.trades += 1;
+        path.net_pnl += record.realized_profit;
+    }
+    let mut top_paths: Vec<PathSummary> = paths.into_values().collect();
+    top_paths.sort_by(|a, b| b.net_pnl.total_cmp(&a.net_pnl));
+    top_paths.truncate(DAILY_SUMMARY_TOP_PATHS);
+
+    Ok(DailySummary {
+        day: day.format("%Y-%m-%d").to_string(),
+        trades: trades.len() as u64,
+        landed: trades.iter().filter(|trade| trade.status == TradeStatus::Landed).count() as u64,
+        failed: trades.iter().filter(|trade| trade.status != TradeStatus::Landed).count() as u64,
+        win_rate: wins as f64 / pnl_records.len().max(1) as f64,
+        gross_pnl: net_pnl + (fees_lamports + tips_lamports) as f64,
+        net_pnl,
+        fees_lamports,
+        tips_lamports,
+        top_paths,
+        incidents,
+    })
+}
+
+fn until_next_midnight() -> Duration {
+    let now = Utc::now();
+    let midnight = (now.date_naive() + chrono::Days::new(1)).and_hms_opt(0, 0, 0).unwrap().and_utc();
+    (midnight - now).to_std().unwrap_or(Duration::from_secs(60))
+}
+
+// Collect the day's alerts from the event bus, then report the day at midnight UTC
+pub async fn run_daily_summary() {
+    let mut events = subscribe();
+    let mut incidents: Vec<Incident> = Vec::new();
+    let mut day = Utc::now().date_naive();
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(timed) => {
+                    if let BotEvent::Alert { event, title, message } = timed.event {
+                        if event != NotifyEvent::DailySummary {
+                            incidents.push(Incident { at: timed.timestamp, event, title, message });
+                        }
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => error!("Daily summary lagged, {} events skipped", skipped),
+                Err(RecvError::Closed) => break,
+            },
+            _ = tokio::time::sleep(until_next_midnight()) => {
+                match build_daily_summary(day, std::mem::take(&mut incidents)).await {
+                    Ok(summary) => {
+                        info!("📋 Daily summary {}: {} trades, net PnL {:.0}", summary.day, summary.trades, summary.net_pnl);
+                        notify(NotifyEvent::DailySummary, &format!("Daily summary {}", summary.day), &summary.message());
+                        publish(BotEvent::DailySummaryReady { summary });
+                    }
+                    Err(e) => error!("❌ Daily summary of {} failed: {:?}", day, e),
+                }
+                day = Utc::now().date_naive();
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use log::error;
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::monitoring::events::{subscribe, BotEvent, TimedEvent};
+
+pub static DASHBOARD_HISTORY: usize = 50;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OpportunityView {
+    pub timestamp: String,
+    pub strategy: String,
+    pub tokens_path: String,
+    pub expected_profit: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeView {
+    pub timestamp: String,
+    pub strategy: String,
+    pub tokens_path: String,
+    pub expected_profit: f64,
+    pub realized_profit: Option<f64>,
+    pub landed: bool,
+}
+
+// What the dashboard page shows, rebuilt from the event bus
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DashboardView {
+    pub strategies: BTreeMap<String, String>,
+    pub active_paths: BTreeMap<String, usize>,
+    pub loaded_pools: BTreeMap<String, Vec<String>>,
+    pub recent_opportunities: VecDeque<OpportunityView>,
+    pub recent_trades: VecDeque<TradeView>,
+    pub sol_balance: u64,
+    pub wsol_balance: u64,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct DashboardState {
+    view: Mutex<DashboardView>,
+}
+
+impl DashboardState {
+    pub fn new() -> Self {
+        DashboardState::default()
+    }
+
+    pub fn view(&self) -> DashboardView {
+        self.view.lock().unwrap().clone()
+    }
+
+    pub fn apply(&self, timed: TimedEvent) {
+        let mut view = self.view.lock().unwrap();
+        match timed.event {
+            BotEvent::OpportunityFound { strategy, tokens_path, expected_profit, .. } => {
+                push_capped(&mut view.recent_opportunities, OpportunityView { timestamp: timed.timestamp.clone(), strategy, tokens_path, expected_profit });
+            }
+            BotEvent::TradeExecuted { strategy, tokens_path, expected_profit, realized_profit, landed, .. } => {
+                push_capped(&mut view.recent_trades, TradeView { timestamp: timed.timestamp.clone(), strategy, tokens_path, expected_profit, realized_profit, landed });
+            }
+            BotEvent::PathsLoaded { strategy, paths, pools } => {
+                view.active_paths.insert(strategy.clone(), paths);
+                view.loaded_pools.insert(strategy, pools);
+            }
+            BotEvent::StrategyStatus { strategy, status } => {
+                view.strategies.insert(strategy, status);
+            }
+            BotEvent::BalanceUpdate { sol_lamports, wsol_lamports } => {
+                view.sol_balance = sol_lamports;
+                view.wsol_balance = wsol_lamports;
+            }
+            BotEvent::TxSent { .. }
+            | BotEvent::TxLanded { .. }
+            | BotEvent::PnlUpdate { .. }
+            | BotEvent::TradeRecorded { .. }
+            | BotEvent::PnlRecorded { .. }
+            | BotEvent::SubmissionRecorded { .. }
+            | BotEvent::ResearchRecorded { .. }
+            | BotEvent::LandingAnalyzed { .. }
+            | BotEvent::DailySummaryReady { .. }
+            | BotEvent::Alert { .. } => {}
+        }
+        view.updated_at = Some(timed.timestamp);
+    }
+}
+
+fn push_capped<T>(items: &mut VecDeque<T>, item: T) {
+    items.push_front(item);
+    items.truncate(DASHBOARD_HISTORY);
+}
+
+pub async fn run_dashboard_state(state: Arc<DashboardState>) {
+    let mut events = subscribe();
+    loop {
+        match events.recv().await {
+            Ok(event) => state.apply(event),
+            Err(RecvError::Lagged(skipped)) => error!("Dashboard lagged, {} events skipped", skipped),
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+// Single page polling /dashboard/state
+pub static DASHBOARD_HTML: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>MEV_Bot_Solana</title>
+<style>
+body { font-family: monospace; background: #111; color: #ddd; margin: 2em; }
+h2 { color: #9cf; margin-top: 1.5em; }
+table { border-collapse: collapse; }
+td, th { padding: 2px 12px; text-align: left; border-bottom: 1px solid #333; }
+.ok { color: #6c6; } .ko { color: #e66; }
+</style>
+</head>
+<body>
+<h1>MEV_Bot_Solana</h1>
+<div id="content">Loading...</div>
+<script>
+const lamports = (value) => value == null ? "-" : (value / 1e9).toFixed(6);
+const rows = (items, cells) => items.map((item) => "<tr>" + cells(item).map((cell) => "<td>" + cell + "</td>").join("") + "</tr>").join("");
+async function refresh() {
+  const state = await (await fetch("/dashboard/state")).json();
+  document.getElementById("content").innerHTML =
+    "<h2>Wallet</h2><p>SOL " + lamports(state.sol_balance) + " / wSOL " + lamports(state.wsol_balance) + "</p>" +
+    "<h2>Strategies</h2><table><tr><th>Strategy</th><th>Status</th><th>Paths</th><th>Pools</th></tr>" +
+    rows(Object.keys(state.strategies), (name) => [name, state.strategies[name], state.active_paths[name] ?? 0, (state.loaded_pools[name] ?? []).length]) + "</table>" +
+    "<h2>Recent trades</h2><table><tr><th>Time</th><th>Strategy</th><th>Path</th><th>Simulated</th><th>Realized</th><th>Landed</th></tr>" +
+    rows(state.recent_trades, (trade) => [trade.timestamp, trade.strategy, trade.tokens_path, lamports(trade.expected_profit), lamports(trade.realized_profit), trade.landed ? "<span class='ok'>yes</span>" : "<span class='ko'>no</span>"]) + "</table>" +
+    "<h2>Recent opportunities</h2><table><tr><th>Time</th><th>Strategy</th><th>Path</th><th>Simulated</th></tr>" +
+    rows(state.recent_opportunities, (opportunity) => [opportunity.timestamp, opportunity.strategy, opportunity.tokens_path, lamports(opportunity.expected_profit)]) + "</table>";
+}
+refresh();
+setInterval(refresh, 2000);
+</script>
+</body>
+</html>
+"#;
@@ -0,0 +1,174 @@
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use log::{error, info};
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::UiTransactionEncoding;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::common::database::TradeStatus;
+use crate::common::rpc_pool::nonblocking_client;
+use crate::markets::types::DexLabel;
+use crate::monitoring::events::{subscribe, BotEvent};
+
+#[derive(Debug, Clone, Default)]
+struct VenueState {
+    // Latest swap outcomes, true when the transaction succeeded
+    outcomes: VecDeque<bool>,
+    swaps: u64,
+    failed: u64,
+    quote_samples: u64,
+    quote_error_sum: f64,
+    cu_samples: u64,
+    cu_consumed_sum: u64,
+    slippage_samples: u64,
+    slippage_sum: f64,
+    quarantined_until: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VenueReport {
+    pub dex: DexLabel,
+    pub swaps: u64,
+    pub failure_rate: f64,
+    // Over the rolling window, what the quarantine is decided on
+    pub recent_failure_rate: f64,
+    // 1 - mean |realized - expected| / |expected| of the verified trades
+    pub quote_accuracy: Option<f64>,
+    // Per hop, a transaction's consumption is split evenly between its hops
+    pub avg_compute_units: Option<f64>,
+    // Output shortfall against the quote, as a fraction of the quoted output
+    pub avg_slippage: Option<f64>,
+    pub quarantined: bool,
+}
+
+// Execution quality per venue, and the venues taken out of routing while they keep failing
+#[derive(Debug)]
+pub struct DexStats {
+    pub window: usize,
+    pub min_samples: usize,
+    pub max_failure_rate: f64,
+    pub quarantine: Duration,
+    venues: Mutex<HashMap<DexLabel, VenueState>>,
+}
+
+impl DexStats {
+    pub fn new(window: usize, min_samples: usize, max_failure_rate: f64, quarantine: Duration) -> Self {
+        DexStats { window: window.max(1), min_samples, max_failure_rate, quarantine, venues: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn record_swap(&self, dexes: &[DexLabel], succeeded: bool, compute_units: Option<u64>) {
+        let mut venues = self.venues.lock().unwrap();
+        for dex in dexes {
+            let venue = venues.entry(dex.clone()).or_default();
+            venue.swaps += 1;
+            venue.failed += !succeeded as u64;
+            venue.outcomes.push_back(succeeded);
+            if venue.outcomes.len() > self.window {
+                venue.outcomes.pop_front();
+            }
+            if let Some(compute_units) = compute_units {
+                venue.cu_samples += 1;
+                venue.cu_consumed_sum += compute_units / dexes.len() as u64;
+            }
+            let failures = venue.outcomes.iter().filter(|succeeded| !**succeeded).count();
+            if venue.quarantined_until.is_none() && venue.outcomes.len() >= self.min_samples && failures as f64 / venue.outcomes.len() as f64 > self.max_failure_rate {
+                venue.quarantined_until = Some(Instant::now() + self.quarantine);
+                info!("🚫 {} deprioritized for {:?}: {} failed swaps out of the last {}", dex.str(), self.quarantine, failures, venue.outcomes.len());
+            }
+        }
+    }
+
+    pub fn record_quote(&self, dexes: &[DexLabel], amount_in: u64, expected_profit: f64, realized_profit: f64) {
+        let quote_error = (realized_profit - expected_profit).abs() / expected_profit.abs().max(1.0);
+        let expected_out = amount_in as f64 + expected_profit;
+        let slippage = if expected_out > 0.0 { (expected_profit - realized_profit) / expected_out } else { 0.0 };
+        let mut venues = self.venues.lock().unwrap();
+        for dex in dexes {
+            let venue = venues.entry(dex.clone()).or_default();
+            venue.quote_samples += 1;
+            venue.quote_error_sum += quote_error;
+            venue.slippage_samples += 1;
+            venue.slippage_sum += slippage;
+        }
+    }
+
+    // False while the venue is quarantined, its failure window starts over afterwards
+    pub fn is_venue_active(&self, dex: &DexLabel) -> bool {
+        let mut venues = self.venues.lock().unwrap();
+        let Some(venue) = venues.get_mut(dex) else { return true };
+        match venue.quarantined_until {
+            Some(until) if Instant::now() < until => false,
+            Some(_) => {
+                info!("✅ {} back in routing after quarantine", dex.str());
+                venue.quarantined_until = None;
+                venue.outcomes.clear();
+                true
+            }
+            None => true,
+        }
+    }
+
+    pub fn report(&self) -> Vec<VenueReport> {
+        let now = Instant::now();
+        let venues = self.venues.lock().unwrap();
+        let mut report: Vec<VenueReport> = venues
+            .iter()
+            .map(|(dex, venue)| VenueReport {
+                dex: dex.clone(),
+                swaps: venue.swaps,
+                failure_rate: venue.failed as f64 / venue.swaps.max(1) as f64,
+                recent_failure_rate: venue.outcomes.iter().filter(|succeeded| !**succeeded).count() as f64 / venue.outcomes.len().max(1) as f64,
+                quote_accuracy: (venue.quote_samples > 0).then(|| 1.0 - venue.quote_error_sum / venue.quote_samples as f64),
+                avg_compute_units: (venue.cu_samples > 0).then(|| venue.cu_consumed_sum as f64 / venue.cu_samples as f64),
+                avg_slippage: (venue.slippage_samples > 0).then(|| venue.slippage_sum / venue.slippage_samples as f64),
+                quarantined: venue.quarantined_until.is_some_and(|until| now < until),
+            })
+            .collect();
+        report.sort_by(|a, b| b.swaps.cmp(&a.swaps));
+        report
+    }
+}
+
+async fn compute_units_consumed(rpc_client: &RpcClient, signature: &str) -> Option<u64> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Json),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    let transaction = rpc_client.get_transaction_with_config(&Signature::from_str(signature).ok()?, config).await.ok()?;
+    match transaction.transaction.meta?.compute_units_consumed {
+        OptionSerializer::Some(compute_units) => Some(compute_units),
+        _ => None,
+    }
+}
+
+// Landed and failed swaps from the ledger events, quote accuracy from the verified trades
+pub async fn run_dex_stats(stats: Arc<DexStats>, rpc_url: String) {
+    let rpc_client = nonblocking_client(&rpc_url);
+    let mut events = subscribe();
+    loop {
+        match events.recv().await {
+            Ok(timed) => match timed.event {
+                BotEvent::TradeRecorded { trade } if matches!(trade.status, TradeStatus::Landed | TradeStatus::Failed) => {
+                    let dexes: Vec<DexLabel> = trade.hops.iter().map(|hop| hop.dex.clone()).collect();
+                    let compute_units = compute_units_consumed(&rpc_client, &trade.signature).await;
+                    stats.record_swap(&dexes, trade.status == TradeStatus::Landed, compute_units);
+                }
+                BotEvent::TradeExecuted { dexes, amount_in, expected_profit, realized_profit: Some(realized_profit), .. } => {
+                    stats.record_quote(&dexes, amount_in, expected_profit, realized_profit);
+                }
+                _ => {}
+            },
+            Err(RecvError::Lagged(skipped)) => error!("DEX stats lagged, {} events skipped", skipped),
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
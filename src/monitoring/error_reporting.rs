@@ -0,0 +1,80 @@
+use std::fmt::Display;
+#[cfg(feature = "sentry")]
+use std::sync::OnceLock;
+
+use log::{error, info};
+
+use crate::arbitrage::types::SwapPathResult;
+use crate::common::constants::Env;
+
+// Where an error happened, attached to the reported event as tags
+#[derive(Debug, Clone, Default)]
+pub struct ErrorContext {
+    pub strategy: Option<String>,
+    pub path_id: Option<u32>,
+    pub tokens_path: Option<String>,
+}
+
+impl ErrorContext {
+    pub fn for_path(strategy: &str, spr: &SwapPathResult) -> Self {
+        ErrorContext { strategy: Some(strategy.to_string()), path_id: Some(spr.path_id), tokens_path: Some(spr.tokens_path.clone()) }
+    }
+}
+
+#[cfg(feature = "sentry")]
+static SENTRY_GUARD: OnceLock<sentry::ClientInitGuard> = OnceLock::new();
+
+// SENTRY_DSN, with the sentry feature: panics and error-level logs are reported from then on.
+// Returns false when not configured
+pub fn init_error_reporting() -> bool {
+    let env = Env::new();
+    if env.sentry_dsn.is_empty() {
+        return false;
+    }
+    #[cfg(feature = "sentry")]
+    {
+        let guard = sentry::init((
+            env.sentry_dsn.as_str(),
+            sentry::ClientOptions { release: sentry::release_name!(), attach_stacktrace: true, ..Default::default() },
+        ));
+        let enabled = guard.is_enabled();
+        let _ = SENTRY_GUARD.set(guard);
+        info!("🛰️ Error reporting to Sentry enabled");
+        enabled
+    }
+    #[cfg(not(feature = "sentry"))]
+    {
+        info!("🛰️ SENTRY_DSN set but the bot is built without the sentry feature");
+        false
+    }
+}
+
+// Error-level logs become events, lower levels breadcrumbs; chained to the logger
+#[cfg(feature = "sentry")]
+pub fn sentry_logger() -> Box<dyn log::Log> {
+    Box::new(sentry_log::SentryLogger::new())
+}
+
+// error! with the strategy/path context attached to the reported event
+pub fn report_error(context: &ErrorContext, message: &dyn Display) {
+    #[cfg(feature = "sentry")]
+    sentry::with_scope(
+        |scope| {
+            if let Some(strategy) = &context.strategy {
+                scope.set_tag("strategy", strategy);
+            }
+            if let Some(path_id) = context.path_id {
+                scope.set_tag("path_id", path_id);
+            }
+            if let Some(tokens_path) = &context.tokens_path {
+                scope.set_tag("tokens_path", tokens_path);
+            }
+        },
+        || error!("{}", message),
+    );
+    #[cfg(not(feature = "sentry"))]
+    {
+        let _ = context;
+        error!("{}", message);
+    }
+}
@@ -0,0 +1,167 @@
+use std::sync::OnceLock;
+
+use chrono::Utc;
+use log::error;
+use serde::Serialize;
+use tokio::sync::{broadcast, mpsc};
+
+use crate::arbitrage::research::ResearchRecord;
+use crate::arbitrage::submissions::SubmissionFingerprint;
+use crate::common::database::TradeRecord;
+use crate::markets::types::DexLabel;
+use crate::monitoring::daily_summary::DailySummary;
+use crate::monitoring::landing::LandingRecord;
+use crate::monitoring::notifier::NotifyEvent;
+use crate::monitoring::pnl::PnlRecord;
+
+// Everything observable about the bot, published once and consumed by whoever subscribes
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotEvent {
+    OpportunityFound {
+        strategy: String,
+        tokens_path: String,
+        pools: Vec<String>,
+        expected_profit: f64,
+    },
+    TxSent {
+        signature: String,
+        strategy: String,
+        tokens_path: String,
+        fee_lamports: u64,
+        tip_lamports: u64,
+    },
+    TxLanded {
+        signature: String,
+        slot: u64,
+        pools: Vec<String>,
+    },
+    TradeExecuted {
+        strategy: String,
+        tokens_path: String,
+        expected_profit: f64,
+        // From the confirmed balance deltas, when verified
+        realized_profit: Option<f64>,
+        landed: bool,
+        amount_in: u64,
+        dexes: Vec<DexLabel>,
+    },
+    PathsLoaded {
+        strategy: String,
+        paths: usize,
+        pools: Vec<String>,
+    },
+    StrategyStatus {
+        strategy: String,
+        status: String,
+    },
+    PnlUpdate {
+        realized_today: f64,
+        realized_week: f64,
+        unrealized: f64,
+    },
+    BalanceUpdate {
+        sol_lamports: u64,
+        wsol_lamports: u64,
+    },
+    // Persisted by the database writer
+    TradeRecorded {
+        trade: TradeRecord,
+    },
+    PnlRecorded {
+        record: PnlRecord,
+    },
+    SubmissionRecorded {
+        fingerprint: SubmissionFingerprint,
+    },
+    ResearchRecorded {
+        record: ResearchRecord,
+    },
+    LandingAnalyzed {
+        record: LandingRecord,
+    },
+    DailySummaryReady {
+        summary: DailySummary,
+    },
+    // Dispatched by the notifier
+    Alert {
+        event: NotifyEvent,
+        title: String,
+        message: String,
+    },
+}
+
+impl BotEvent {
+    // Value of the "type" field
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BotEvent::OpportunityFound { .. } => "opportunity_found",
+            BotEvent::TxSent { .. } => "tx_sent",
+            BotEvent::TxLanded { .. } => "tx_landed",
+            BotEvent::TradeExecuted { .. } => "trade_executed",
+            BotEvent::PathsLoaded { .. } => "paths_loaded",
+            BotEvent::StrategyStatus { .. } => "strategy_status",
+            BotEvent::PnlUpdate { .. } => "pnl_update",
+            BotEvent::BalanceUpdate { .. } => "balance_update",
+            BotEvent::TradeRecorded { .. } => "trade_recorded",
+            BotEvent::PnlRecorded { .. } => "pnl_recorded",
+            BotEvent::SubmissionRecorded { .. } => "submission_recorded",
+            BotEvent::ResearchRecorded { .. } => "research_recorded",
+            BotEvent::LandingAnalyzed { .. } => "landing_analyzed",
+            BotEvent::DailySummaryReady { .. } => "daily_summary",
+            BotEvent::Alert { .. } => "alert",
+        }
+    }
+
+    // Records the database writer has to store, whatever the dashboards and notifiers miss
+    pub fn is_persisted(&self) -> bool {
+        matches!(
+            self,
+            BotEvent::TradeRecorded { .. }
+                | BotEvent::PnlRecorded { .. }
+                | BotEvent::SubmissionRecorded { .. }
+                | BotEvent::ResearchRecorded { .. }
+                | BotEvent::LandingAnalyzed { .. }
+                | BotEvent::DailySummaryReady { .. }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimedEvent {
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub event: BotEvent,
+}
+
+static EVENT_BUS: OnceLock<broadcast::Sender<TimedEvent>> = OnceLock::new();
+
+fn event_bus() -> &'static broadcast::Sender<TimedEvent> {
+    EVENT_BUS.get_or_init(|| broadcast::channel(4096).0)
+}
+
+// Records queued for the database writer, apart from the lossy bus
+pub static PERSIST_QUEUE_CAPACITY: usize = 65_536;
+static PERSIST_QUEUE: OnceLock<mpsc::Sender<BotEvent>> = OnceLock::new();
+
+// Never blocks: without subscriber the event is dropped, a slow subscriber lags.
+// The persisted records also go to the writer queue, only lost once it is full
+pub fn publish(event: BotEvent) {
+    if let Some(queue) = PERSIST_QUEUE.get().filter(|_| event.is_persisted()) {
+        if queue.try_send(event.clone()).is_err() {
+            error!("❌ Persistence queue full or closed, {} record dropped", event.kind());
+        }
+    }
+    let _ = event_bus().send(TimedEvent { timestamp: Utc::now().to_rfc3339(), event });
+}
+
+// To call once, by the database writer: the records are queued from then on
+pub fn persisted_events() -> Option<mpsc::Receiver<BotEvent>> {
+    let (sender, receiver) = mpsc::channel(PERSIST_QUEUE_CAPACITY);
+    PERSIST_QUEUE.set(sender).ok()?;
+    Some(receiver)
+}
+
+pub fn subscribe() -> broadcast::Receiver<TimedEvent> {
+    event_bus().subscribe()
+}
@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use futures::stream::{self, BoxStream, Stream};
+use log::{error, info};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tonic::body::BoxBody;
+use tonic::codec::ProstCodec;
+use tonic::codegen::{empty_body, http, Body, BoxFuture, Service, StdError};
+use tonic::server::{Grpc, NamedService, ServerStreamingService, UnaryService};
+use tonic::service::interceptor::InterceptedService;
+use tonic::{Request, Response, Status};
+
+use crate::arbitrage::opportunity::{Opportunity, OpportunityQueue};
+use crate::arbitrage::registry::{StrategyEvent, StrategyStates};
+use crate::monitoring::events::{subscribe, TimedEvent};
+use crate::monitoring::metrics::metrics;
+use crate::monitoring::pnl::PnlTracker;
+
+// mev.proto, the messages are written by hand like the shredstream ones:
+//
+// service Control {
+//   rpc ListStrategies(Empty) returns (StrategyList);
+//   rpc StartStrategy(StrategyRequest) returns (Ack);
+//   rpc StopStrategy(StrategyRequest) returns (Ack);
+//   rpc SetParam(SetParamRequest) returns (Ack);
+//   rpc GetStats(Empty) returns (Stats);
+//   rpc StreamOpportunities(Empty) returns (stream OpportunityMessage);
+//   rpc StreamEvents(Empty) returns (stream EventMessage);
+// }
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Empty {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StrategyRequest {
+    #[prost(string, tag = "1")]
+    pub name: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SetParamRequest {
+    #[prost(string, tag = "1")]
+    pub strategy: String,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(string, tag = "3")]
+    pub value: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Ack {
+    #[prost(string, tag = "1")]
+    pub message: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StrategyInfo {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub status: String,
+    #[prost(bool, tag = "3")]
+    pub paused: bool,
+    #[prost(map = "string, string", tag = "4")]
+    pub params: HashMap<String, String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StrategyList {
+    #[prost(message, repeated, tag = "1")]
+    pub strategies: Vec<StrategyInfo>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Stats {
+    #[prost(uint64, tag = "1")]
+    pub opportunities_found: u64,
+    #[prost(uint64, tag = "2")]
+    pub quotes: u64,
+    #[prost(uint64, tag = "3")]
+    pub txs_sent: u64,
+    #[prost(uint64, tag = "4")]
+    pub txs_landed: u64,
+    #[prost(uint64, tag = "5")]
+    pub txs_failed: u64,
+    // Lamports
+    #[prost(double, tag = "6")]
+    pub profit: f64,
+    #[prost(double, tag = "7")]
+    pub realized_today: f64,
+    #[prost(double, tag = "8")]
+    pub realized_week: f64,
+    #[prost(double, tag = "9")]
+    pub unrealized: f64,
+    #[prost(uint64, tag = "10")]
+    pub queued_opportunities: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OpportunityMessage {
+    #[prost(string, tag = "1")]
+    pub strategy: String,
+    #[prost(string, tag = "2")]
+    pub tokens_path: String,
+    #[prost(string, repeated, tag = "3")]
+    pub pools: Vec<String>,
+    #[prost(uint64, tag = "4")]
+    pub amount_in: u64,
+    #[prost(double, tag = "5")]
+    pub expected_profit: f64,
+    // Since the detection, when sent
+    #[prost(uint64, tag = "6")]
+    pub age_ms: u64,
+}
+
+impl From<Opportunity> for OpportunityMessage {
+    fn from(opportunity: Opportunity) -> Self {
+        OpportunityMessage {
+            pools: opportunity.pools_key(),
+            strategy: opportunity.strategy,
+            tokens_path: opportunity.swap_path_result.tokens_path,
+            amount_in: opportunity.swap_path_result.amount_in,
+            expected_profit: opportunity.expected_profit,
+            age_ms: Instant::now().saturating_duration_since(opportunity.detected_at).as_millis() as u64,
+        }
+    }
+}
+
+// A bot event as the control API WebSocket sends it
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct EventMessage {
+    #[prost(string, tag = "1")]
+    pub kind: String,
+    #[prost(string, tag = "2")]
+    pub timestamp: String,
+    #[prost(string, tag = "3")]
+    pub json: String,
+}
+
+impl From<TimedEvent> for EventMessage {
+    fn from(timed: TimedEvent) -> Self {
+        EventMessage { kind: timed.event.kind().to_string(), json: serde_json::to_string(&timed).unwrap_or_default(), timestamp: timed.timestamp }
+    }
+}
+
+// What the gRPC service controls and reads from
+#[derive(Clone)]
+pub struct GrpcState {
+    pub strategies: StrategyStates,
+    pub events: broadcast::Sender<StrategyEvent>,
+    pub opportunities: Arc<OpportunityQueue>,
+    pub pnl: Option<Arc<PnlTracker>>,
+    // Every call requires the `authorization: Bearer <token>` metadata when set
+    pub token: Option<String>,
+}
+
+impl GrpcState {
+    fn list_strategies(&self) -> StrategyList {
+        let mut strategies: Vec<StrategyInfo> = self
+            .strategies
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(name, state)| StrategyInfo { name: name.clone(), status: state.status.clone(), paused: state.paused, params: state.params.clone() })
+            .collect();
+        strategies.sort_by(|a, b| a.name.cmp(&b.name));
+        StrategyList { strategies }
+    }
+
+    fn control(&self, strategy: &str, event: StrategyEvent) -> Result<Ack, Status> {
+        if !self.strategies.read().unwrap().contains_key(strategy) {
+            return Err(Status::not_found(format!("No strategy {}", strategy)));
+        }
+        self.events.send(event).map_err(|_| Status::unavailable("Strategies not running"))?;
+        Ok(Ack { message: format!("Sent to {}", strategy) })
+    }
+
+    fn stats(&self) -> Stats {
+        let pnl = self.pnl.as_ref().map(|pnl| pnl.snapshot());
+        Stats {
+            opportunities_found: metrics().opportunities_found.get(),
+            quotes: metrics().quotes.get(),
+            txs_sent: metrics().txs_sent.get(),
+            txs_landed: metrics().txs_landed.get(),
+            txs_failed: metrics().txs_failed.get(),
+            profit: metrics().profit_lamports.get(),
+            realized_today: pnl.as_ref().map_or(0.0, |pnl| pnl.realized_today),
+            realized_week: pnl.as_ref().map_or(0.0, |pnl| pnl.realized_week),
+            unrealized: pnl.as_ref().map_or(0.0, |pnl| pnl.unrealized),
+            queued_opportunities: self.opportunities.len() as u64,
+        }
+    }
+
+    fn opportunities_stream(&self) -> BoxStream<'static, Result<OpportunityMessage, Status>> {
+        receiver_stream(self.opportunities.subscribe())
+    }
+
+    fn events_stream(&self) -> BoxStream<'static, Result<EventMessage, Status>> {
+        receiver_stream(subscribe())
+    }
+}
+
+// Messages from now on, a lagging client skips what it missed
+fn receiver_stream<T, M>(receiver: broadcast::Receiver<T>) -> BoxStream<'static, Result<M, Status>>
+where
+    T: Clone + Send + 'static,
+    M: From<T> + Send + 'static,
+{
+    Box::pin(stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(item) => return Some((Ok(M::from(item)), receiver)),
+                Err(RecvError::Lagged(skipped)) => error!("gRPC stream lagged, {} messages skipped", skipped),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }))
+}
+
+// Closure as a tonic unary or server streaming handler
+struct Handler<F>(F);
+
+impl<Req, Res, F, Fut> UnaryService<Req> for Handler<F>
+where
+    F: FnMut(Request<Req>) -> Fut,
+    Fut: Future<Output = Result<Response<Res>, Status>>,
+{
+    type Response = Res;
+    type Future = Fut;
+
+    fn call(&mut self, request: Request<Req>) -> Fut {
+        (self.0)(request)
+    }
+}
+
+struct StreamHandler<F>(F);
+
+impl<Req, Res, S, F, Fut> ServerStreamingService<Req> for StreamHandler<F>
+where
+    F: FnMut(Request<Req>) -> Fut,
+    Fut: Future<Output = Result<Response<S>, Status>>,
+    S: Stream<Item = Result<Res, Status>> + Send + 'static,
+{
+    type Response = Res;
+    type ResponseStream = S;
+    type Future = Fut;
+
+    fn call(&mut self, request: Request<Req>) -> Fut {
+        (self.0)(request)
+    }
+}
+
+// The tonic service, routed by method path
+#[derive(Clone)]
+pub struct ControlServer {
+    state: GrpcState,
+}
+
+impl ControlServer {
+    pub fn new(state: GrpcState) -> Self {
+        ControlServer { state }
+    }
+}
+
+impl NamedService for ControlServer {
+    const NAME: &'static str = "mev.Control";
+}
+
+impl<B> Service<http::Request<B>> for ControlServer
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let state = self.state.clone();
+        Box::pin(async move {
+            let method = request.uri().path().strip_prefix(&format!("/{}/", Self::NAME)).unwrap_or_default().to_string();
+            let response = match method.as_str() {
+                "ListStrategies" => {
+                    let handler = Handler(|_: Request<Empty>| futures::future::ready(Ok(Response::new(state.list_strategies()))));
+                    Grpc::new(ProstCodec::<StrategyList, Empty>::default()).unary(handler, request).await
+                }
+                "StartStrategy" | "StopStrategy" => {
+                    let start = method == "StartStrategy";
+                    let handler = Handler(|request: Request<StrategyRequest>| {
+                        let name = request.into_inner().name;
+                        let event = if start { StrategyEvent::Resume { strategy: name.clone() } } else { StrategyEvent::Pause { strategy: name.clone() } };
+                        futures::future::ready(state.control(&name, event).map(Response::new))
+                    });
+                    Grpc::new(ProstCodec::<Ack, StrategyRequest>::default()).unary(handler, request).await
+                }
+                "SetParam" => {
+                    let handler = Handler(|request: Request<SetParamRequest>| {
+                        let SetParamRequest { strategy, name, value } = request.into_inner();
+                        futures::future::ready(state.control(&strategy, StrategyEvent::SetParam { strategy: strategy.clone(), name, value }).map(Response::new))
+                    });
+                    Grpc::new(ProstCodec::<Ack, SetParamRequest>::default()).unary(handler, request).await
+                }
+                "GetStats" => {
+                    let handler = Handler(|_: Request<Empty>| futures::future::ready(Ok(Response::new(state.stats()))));
+                    Grpc::new(ProstCodec::<Stats, Empty>::default()).unary(handler, request).await
+                }
+                "StreamOpportunities" => {
+                    let handler = StreamHandler(|_: Request<Empty>| futures::future::ready(Ok(Response::new(state.opportunities_stream()))));
+                    Grpc::new(ProstCodec::<OpportunityMessage, Empty>::default()).server_streaming(handler, request).await
+                }
+                "StreamEvents" => {
+                    let handler = StreamHandler(|_: Request<Empty>| futures::future::ready(Ok(Response::new(state.events_stream()))));
+                    Grpc::new(ProstCodec::<EventMessage, Empty>::default()).server_streaming(handler, request).await
+                }
+                _ => {
+                    let mut response = http::Response::new(empty_body());
+                    let headers = response.headers_mut();
+                    headers.insert(Status::GRPC_STATUS, (tonic::Code::Unimplemented as i32).into());
+                    headers.insert(http::header::CONTENT_TYPE, tonic::metadata::GRPC_CONTENT_TYPE);
+                    response
+                }
+            };
+            Ok(response)
+        })
+    }
+}
+
+fn authorize(token: Option<&str>, request: Request<()>) -> Result<Request<()>, Status> {
+    let Some(token) = token else {
+        return Ok(request);
+    };
+    let bearer = request.metadata().get("authorization").and_then(|value| value.to_str().ok()).and_then(|value| value.strip_prefix("Bearer "));
+    if bearer != Some(token) {
+        return Err(Status::unauthenticated("invalid or missing control API token"));
+    }
+    Ok(request)
+}
+
+// gRPC control and telemetry (mev.Control), for an operator UI or an orchestrator of several bots
+pub async fn run_grpc_api(addr: SocketAddr, state: GrpcState) -> Result<()> {
+    if !addr.ip().is_loopback() && state.token.is_none() {
+        return Err(anyhow!("CONTROL_API_TOKEN is required to serve the gRPC control API on {}", addr));
+    }
+    let token = state.token.clone();
+    let service = InterceptedService::new(ControlServer::new(state), move |request| authorize(token.as_deref(), request));
+    info!("🛰️ gRPC control API served on {}", addr);
+    tonic::transport::Server::builder().add_service(service).serve(addr).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(authorization: Option<&str>) -> Request<()> {
+        let mut request = Request::new(());
+        if let Some(authorization) = authorization {
+            request.metadata_mut().insert("authorization", authorization.parse().unwrap());
+        }
+        request
+    }
+
+    #[test]
+    fn calls_need_the_bearer_token_when_set() {
+        assert!(authorize(None, request(None)).is_ok());
+        assert!(authorize(Some("secret"), request(Some("Bearer secret"))).is_ok());
+        assert_eq!(authorize(Some("secret"), request(None)).unwrap_err().code(), tonic::Code::Unauthenticated);
+        assert_eq!(authorize(Some("secret"), request(Some("Bearer other"))).unwrap_err().code(), tonic::Code::Unauthenticated);
+    }
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use log::{error, warn};
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::arbitrage::opportunity::OpportunityQueue;
+use crate::arbitrage::streams::last_pool_refresh;
+use crate::common::constants::PROJECT_NAME;
+use crate::monitoring::events::{subscribe, BotEvent};
+use crate::transactions::in_flight::in_flight_limiter;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Heartbeat {
+    pub bot: String,
+    // "ok" or "stalled"
+    pub status: String,
+    pub uptime_secs: u64,
+    pub last_pool_refresh_secs: Option<u64>,
+    pub last_trade_secs: Option<u64>,
+    pub opportunity_queue: usize,
+    pub in_flight_txs: usize,
+    pub waiting_txs: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct HeartbeatConfig {
+    // Any endpoint taking a JSON POST, or a healthchecks.io ping URL
+    pub url: String,
+    pub interval: Duration,
+    // Without pool refresh for that long the bot is reported stalled
+    pub stall_after: Duration,
+}
+
+// POST a heartbeat every interval; a stalled bot pings {url}/fail so healthchecks.io alerts right away
+pub async fn run_heartbeat(config: HeartbeatConfig, queue: Option<Arc<OpportunityQueue>>) {
+    let client = reqwest::Client::new();
+    let started_at = Instant::now();
+    let mut last_trade: Option<Instant> = None;
+    let mut events = subscribe();
+    let mut ticker = tokio::time::interval(config.interval);
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(timed) => {
+                    if let BotEvent::TradeExecuted { .. } = timed.event {
+                        last_trade = Some(Instant::now());
+                    }
+                }
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            },
+            _ = ticker.tick() => {
+                let last_pool_refresh = last_pool_refresh().map(|at| at.elapsed());
+                let stalled = last_pool_refresh.map_or(started_at.elapsed() > config.stall_after, |elapsed| elapsed > config.stall_after);
+                let heartbeat = Heartbeat {
+                    bot: PROJECT_NAME.to_string(),
+                    status: if stalled { "stalled" } else { "ok" }.to_string(),
+                    uptime_secs: started_at.elapsed().as_secs(),
+                    last_pool_refresh_secs: last_pool_refresh.map(|elapsed| elapsed.as_secs()),
+                    last_trade_secs: last_trade.map(|at| at.elapsed().as_secs()),
+                    opportunity_queue: queue.as_ref().map_or(0, |queue| queue.len()),
+                    in_flight_txs: in_flight_limiter().in_flight(),
+                    waiting_txs: in_flight_limiter().waiting(),
+                };
+                if stalled {
+                    warn!("💓 No pool refresh for {:?}, reporting the bot stalled", last_pool_refresh.unwrap_or(started_at.elapsed()));
+                }
+                let url = if stalled { format!("{}/fail", config.url.trim_end_matches('/')) } else { config.url.clone() };
+                match client.post(&url).timeout(Duration::from_secs(10)).json(&heartbeat).send().await {
+                    Ok(response) if !response.status().is_success() => error!("❌ Heartbeat rejected: {}", response.status()),
+                    Ok(_) => {}
+                    Err(e) => error!("❌ Heartbeat failed: {:?}", e),
+                }
+            }
+        }
+    }
+}
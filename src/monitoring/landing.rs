@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcBlockConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{EncodedTransactionWithStatusMeta, TransactionDetails, UiTransactionEncoding};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::common::rpc_pool::nonblocking_client;
+use crate::monitoring::events::{publish, subscribe, BotEvent};
+
+pub static LANDINGS_COLLECTION: &str = "landings";
+
+// A transaction of a block, with every account it loads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockTransaction {
+    pub signature: String,
+    // Index in the block
+    pub position: usize,
+    pub fee_payer: String,
+    pub program_ids: Vec<String>,
+    pub accounts: Vec<String>,
+    pub succeeded: bool,
+}
+
+impl BlockTransaction {
+    fn from_encoded(position: usize, encoded: &EncodedTransactionWithStatusMeta) -> Option<Self> {
+        let transaction = encoded.transaction.decode()?;
+        let keys = transaction.message.static_account_keys();
+        let program_ids = transaction.message.instructions().iter().filter_map(|instruction| keys.get(instruction.program_id_index as usize)).map(|key| key.to_string()).collect();
+        let mut accounts: Vec<String> = keys.iter().map(|key| key.to_string()).collect();
+        if let Some(meta) = &encoded.meta {
+            if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+                accounts.extend(loaded.writable.iter().chain(loaded.readonly.iter()).cloned());
+            }
+        }
+        Some(BlockTransaction {
+            signature: transaction.signatures.first()?.to_string(),
+            position,
+            fee_payer: keys.first()?.to_string(),
+            program_ids,
+            accounts,
+            succeeded: encoded.meta.as_ref().is_some_and(|meta| meta.err.is_none()),
+        })
+    }
+
+    pub fn touches_any(&self, pools: &HashSet<&str>) -> bool {
+        self.accounts.iter().any(|account| pools.contains(account.as_str()))
+    }
+}
+
+// Every transaction of the block, in block order
+pub async fn block_transactions(rpc_client: &RpcClient, slot: u64) -> Result<Vec<BlockTransaction>> {
+    let config = RpcBlockConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        transaction_details: Some(TransactionDetails::Full),
+        rewards: Some(false),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    let block = rpc_client.get_block_with_config(slot, config).await?;
+    let transactions = block.transactions.ok_or_else(|| anyhow!("No transactions in block {}", slot))?;
+    Ok(transactions.iter().enumerate().filter_map(|(position, encoded)| BlockTransaction::from_encoded(position, encoded)).collect())
+}
+
+// Where a landed transaction of ours ended up in its block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LandingRecord {
+    pub signature: String,
+    pub slot: u64,
+    pub position: usize,
+    pub block_transactions: usize,
+    // Other signers touching the same pools earlier in the block
+    pub competitors_before: Vec<BlockTransaction>,
+    pub beaten: bool,
+}
+
+pub async fn analyze_landing(rpc_client: &RpcClient, signature: &str, slot: u64, pools: &[String]) -> Result<LandingRecord> {
+    let transactions = block_transactions(rpc_client, slot).await?;
+    let ours = transactions.iter().find(|transaction| transaction.signature == signature).ok_or_else(|| anyhow!("{} not found in block {}", signature, slot))?;
+    let pools: HashSet<&str> = pools.iter().map(|pool| pool.as_str()).collect();
+    let competitors_before: Vec<BlockTransaction> = transactions
+        .iter()
+        .take(ours.position)
+        .filter(|transaction| transaction.fee_payer != ours.fee_payer && transaction.touches_any(&pools))
+        .cloned()
+        .collect();
+    Ok(LandingRecord {
+        signature: signature.to_string(),
+        slot,
+        position: ours.position,
+        block_transactions: transactions.len(),
+        beaten: competitors_before.iter().any(|transaction| transaction.succeeded),
+        competitors_before,
+    })
+}
+
+// Analyze every landed transaction from the event bus, the records are persisted by the database writer
+pub async fn run_landing_analyzer(rpc_url: String) {
+    let rpc_client = nonblocking_client(&rpc_url);
+    let mut events = subscribe();
+    loop {
+        let (signature, slot, pools) = match events.recv().await {
+            Ok(timed) => match timed.event {
+                BotEvent::TxLanded { signature, slot, pools } => (signature, slot, pools),
+                _ => continue,
+            },
+            Err(RecvError::Lagged(skipped)) => {
+                error!("Landing analyzer lagged, {} events skipped", skipped);
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+        // A confirmed block can take a moment to be served by the RPC
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        match analyze_landing(&rpc_client, &signature, slot, &pools).await {
+            Ok(record) => {
+                info!("🎯 {} landed at position {}/{} in slot {}, {} competitors before", signature, record.position, record.block_transactions, slot, record.competitors_before.len());
+                publish(BotEvent::LandingAnalyzed { record });
+            }
+            Err(e) => error!("❌ Landing analysis of {} failed: {:?}", signature, e),
+        }
+    }
+}
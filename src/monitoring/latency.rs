@@ -0,0 +1,82 @@
+use std::time::Instant;
+
+use crate::monitoring::metrics::metrics;
+
+// Pipeline stages of an opportunity, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    // Pool accounts fetched from the chain
+    PoolUpdate,
+    // Path simulated
+    Quote,
+    // Picked by the executor pool
+    Dequeue,
+    // Instructions built and priced (simulation included)
+    Build,
+    Sign,
+    // Handed to the sender (TPU, bundle or relay)
+    Send,
+    // Confirmed at the configured commitment
+    Land,
+}
+
+impl Stage {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Stage::PoolUpdate => "pool_update",
+            Stage::Quote => "quote",
+            Stage::Dequeue => "dequeue",
+            Stage::Build => "build",
+            Stage::Sign => "sign",
+            Stage::Send => "send",
+            Stage::Land => "land",
+        }
+    }
+}
+
+// Carried along with an opportunity, each stage is exported as the time since the previous one
+#[derive(Debug, Clone, Default)]
+pub struct StageTimestamps {
+    marks: Vec<(Stage, Instant)>,
+}
+
+impl StageTimestamps {
+    pub fn new(pool_updated_at: Instant) -> Self {
+        StageTimestamps { marks: vec![(Stage::PoolUpdate, pool_updated_at)] }
+    }
+
+    pub fn mark(&mut self, stage: Stage) {
+        self.marks.push((stage, Instant::now()));
+    }
+
+    pub fn at(&self, stage: Stage) -> Option<Instant> {
+        self.marks.iter().find(|(marked, _)| *marked == stage).map(|(_, at)| *at)
+    }
+
+    // To call once the opportunity is done, the stages not reached are left out
+    pub fn observe(&self) {
+        for window in self.marks.windows(2) {
+            let elapsed = window[1].1.saturating_duration_since(window[0].1);
+            metrics().stage_latency.with_label_values(&[window[1].0.label()]).observe(elapsed.as_secs_f64());
+        }
+        if let (Some((_, first)), Some((_, last))) = (self.marks.first(), self.marks.last()) {
+            metrics().stage_latency.with_label_values(&["total"]).observe(last.saturating_duration_since(*first).as_secs_f64());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_marked_in_order() {
+        let pool_updated_at = Instant::now();
+        let mut stages = StageTimestamps::new(pool_updated_at);
+        stages.mark(Stage::Quote);
+        stages.mark(Stage::Send);
+        assert_eq!(stages.at(Stage::PoolUpdate), Some(pool_updated_at));
+        assert!(stages.at(Stage::Send) >= stages.at(Stage::Quote));
+        assert_eq!(stages.at(Stage::Land), None);
+    }
+}
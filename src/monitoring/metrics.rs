@@ -0,0 +1,90 @@
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use log::error;
+use prometheus::{Encoder, Gauge, Histogram, HistogramOpts, HistogramVec, IntCounter, Registry, TextEncoder};
+
+// Everything exported on /metrics, quotes/sec and landing rates are left to PromQL rate()
+pub struct Metrics {
+    pub registry: Registry,
+    pub opportunities_found: IntCounter,
+    pub quotes: IntCounter,
+    pub txs_sent: IntCounter,
+    pub txs_landed: IntCounter,
+    pub txs_failed: IntCounter,
+    // Cumulative, realized when verified, expected otherwise
+    pub profit_lamports: Gauge,
+    pub tip_spend_lamports: IntCounter,
+    pub fee_spend_lamports: IntCounter,
+    pub quote_latency: Histogram,
+    pub pool_refresh_duration: Histogram,
+    pub pool_last_refresh: Gauge,
+    // Subscription updates merged into a pending update of the same account, or dropped on a full queue
+    pub account_updates_coalesced: IntCounter,
+    pub account_updates_dropped: IntCounter,
+    // Per pipeline stage, labelled by stage (see monitoring::latency)
+    pub stage_latency: HistogramVec,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new_custom(Some("mev_bot".to_string()), None)?;
+        let latency_buckets = vec![0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+        let metrics = Metrics {
+            opportunities_found: IntCounter::new("opportunities_found_total", "Profitable paths found by the strategies")?,
+            quotes: IntCounter::new("quotes_total", "Path simulations")?,
+            txs_sent: IntCounter::new("txs_sent_total", "Swap transactions sent")?,
+            txs_landed: IntCounter::new("txs_landed_total", "Swap transactions confirmed")?,
+            txs_failed: IntCounter::new("txs_failed_total", "Swap transactions not landed: failed, expired, dropped or blocked before sending")?,
+            profit_lamports: Gauge::new("profit_lamports", "Cumulative profit of the landed trades")?,
+            tip_spend_lamports: IntCounter::new("tip_spend_lamports_total", "Jito tips paid")?,
+            fee_spend_lamports: IntCounter::new("fee_spend_lamports_total", "Base and priority fees paid, tips included")?,
+            quote_latency: Histogram::with_opts(HistogramOpts::new("quote_latency_seconds", "Duration of a path simulation").buckets(latency_buckets.clone()))?,
+            pool_refresh_duration: Histogram::with_opts(HistogramOpts::new("pool_refresh_duration_seconds", "Duration of a pool accounts refresh").buckets(vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]))?,
+            pool_last_refresh: Gauge::new("pool_last_refresh_timestamp_seconds", "Unix time of the last pool accounts refresh")?,
+            account_updates_coalesced: IntCounter::new("account_updates_coalesced_total", "Account updates replacing a pending update of the same account")?,
+            account_updates_dropped: IntCounter::new("account_updates_dropped_total", "Pending account updates dropped on a full ingestion queue")?,
+            stage_latency: HistogramVec::new(HistogramOpts::new("stage_latency_seconds", "Time spent reaching a pipeline stage from the previous one").buckets(latency_buckets), &["stage"])?,
+            registry,
+        };
+        metrics.registry.register(Box::new(metrics.opportunities_found.clone()))?;
+        metrics.registry.register(Box::new(metrics.quotes.clone()))?;
+        metrics.registry.register(Box::new(metrics.txs_sent.clone()))?;
+        metrics.registry.register(Box::new(metrics.txs_landed.clone()))?;
+        metrics.registry.register(Box::new(metrics.txs_failed.clone()))?;
+        metrics.registry.register(Box::new(metrics.profit_lamports.clone()))?;
+        metrics.registry.register(Box::new(metrics.tip_spend_lamports.clone()))?;
+        metrics.registry.register(Box::new(metrics.fee_spend_lamports.clone()))?;
+        metrics.registry.register(Box::new(metrics.quote_latency.clone()))?;
+        metrics.registry.register(Box::new(metrics.pool_refresh_duration.clone()))?;
+        metrics.registry.register(Box::new(metrics.pool_last_refresh.clone()))?;
+        metrics.registry.register(Box::new(metrics.account_updates_coalesced.clone()))?;
+        metrics.registry.register(Box::new(metrics.account_updates_dropped.clone()))?;
+        metrics.registry.register(Box::new(metrics.stage_latency.clone()))?;
+        Ok(metrics)
+    }
+
+    // Prometheus text format
+    pub fn encode(&self) -> Result<String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+pub fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(|| Metrics::new().expect("Invalid metrics definition"))
+}
+
+// Body of GET /metrics
+pub async fn metrics_handler() -> String {
+    match metrics().encode() {
+        Ok(text) => text,
+        Err(e) => {
+            error!("❌ Unable to encode the metrics: {:?}", e);
+            String::new()
+        }
+    }
+}
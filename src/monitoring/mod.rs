@@ -0,0 +1,18 @@
+pub mod metrics;
+pub mod latency;
+pub mod pnl;
+pub mod api;
+pub mod notifier;
+pub mod events;
+pub mod dashboard;
+pub mod writer;
+pub mod landing;
+pub mod competitors;
+pub mod heartbeat;
+pub mod dex_stats;
+pub mod daily_summary;
+pub mod error_reporting;
+pub mod bench;
+pub mod sheets;
+#[cfg(feature = "grpc")]
+pub mod grpc;
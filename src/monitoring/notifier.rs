@@ -0,0 +1,311 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::common::constants::{Env, PROJECT_NAME};
+use crate::common::tasks::spawn_named;
+use crate::monitoring::events::{publish, subscribe, BotEvent};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyEvent {
+    TradeLanded,
+    LossIncurred,
+    KillSwitchTripped,
+    RpcFailover,
+    LowBalance,
+    DailySummary,
+}
+
+impl NotifyEvent {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            NotifyEvent::TradeLanded => "💸",
+            NotifyEvent::LossIncurred => "📉",
+            NotifyEvent::KillSwitchTripped => "🛑",
+            NotifyEvent::RpcFailover => "🔌",
+            NotifyEvent::LowBalance => "🚨",
+            NotifyEvent::DailySummary => "📋",
+        }
+    }
+
+    // Discord embed and Slack attachment color
+    pub fn color(&self) -> u32 {
+        match self {
+            NotifyEvent::TradeLanded => 0x2ecc71,
+            NotifyEvent::LossIncurred => 0xe67e22,
+            NotifyEvent::KillSwitchTripped => 0xe74c3c,
+            NotifyEvent::RpcFailover => 0xf1c40f,
+            NotifyEvent::LowBalance => 0xe74c3c,
+            NotifyEvent::DailySummary => 0x3498db,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub event: NotifyEvent,
+    pub title: String,
+    pub message: String,
+}
+
+// A channel the operator is alerted on
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    fn name(&self) -> String;
+
+    async fn send(&self, notification: &Notification) -> Result<()>;
+}
+
+// Bot API sendMessage to a single chat
+pub struct TelegramNotifier {
+    bot_token: String,
+    chat_id: String,
+    client: reqwest::Client,
+}
+
+impl TelegramNotifier {
+    pub fn new(bot_token: &str, chat_id: &str) -> Self {
+        TelegramNotifier {
+            bot_token: bot_token.to_string(),
+            chat_id: chat_id.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    // TELEGRAM_BOT_TOKEN + TELEGRAM_CHAT_ID, None when not configured
+    pub fn from_env() -> Option<Self> {
+        let env = Env::new();
+        if env.telegram_bot_token.is_empty() || env.telegram_chat_id.is_empty() {
+            return None;
+        }
+        Some(TelegramNotifier::new(&env.telegram_bot_token, &env.telegram_chat_id))
+    }
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    fn name(&self) -> String {
+        "telegram".to_string()
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let body = json!({
+            "chat_id": self.chat_id,
+            "text": format!("{} {}\n{}", notification.event.emoji(), notification.title, notification.message),
+            "disable_web_page_preview": true,
+        });
+        let response = self.client.post(format!("https://api.telegram.org/bot{}/sendMessage", self.bot_token)).json(&body).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Telegram sendMessage failed: {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+// Embeds posted on a channel webhook
+pub struct DiscordNotifier {
+    webhook_url: String,
+    client: reqwest::Client,
+}
+
+impl DiscordNotifier {
+    pub fn new(webhook_url: &str) -> Self {
+        DiscordNotifier {
+            webhook_url: webhook_url.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    // DISCORD_WEBHOOK_URL, None when not configured
+    pub fn from_env() -> Option<Self> {
+        let env = Env::new();
+        if env.discord_webhook_url.is_empty() {
+            return None;
+        }
+        Some(DiscordNotifier::new(&env.discord_webhook_url))
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> String {
+        "discord".to_string()
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let body = json!({
+            "username": PROJECT_NAME,
+            "embeds": [{
+                "title": format!("{} {}", notification.event.emoji(), notification.title),
+                "description": notification.message,
+                "color": notification.event.color(),
+                "fields": [{ "name": "Event", "value": format!("{:?}", notification.event), "inline": true }],
+                "timestamp": Utc::now().to_rfc3339(),
+            }],
+        });
+        let response = self.client.post(&self.webhook_url).json(&body).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Discord webhook failed: {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+// Incoming webhook of a Slack channel, the event color as the attachment bar
+pub struct SlackNotifier {
+    webhook_url: String,
+    client: reqwest::Client,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: &str) -> Self {
+        SlackNotifier {
+            webhook_url: webhook_url.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    // SLACK_WEBHOOK_URL, None when not configured
+    pub fn from_env() -> Option<Self> {
+        let env = Env::new();
+        if env.slack_webhook_url.is_empty() {
+            return None;
+        }
+        Some(SlackNotifier::new(&env.slack_webhook_url))
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn name(&self) -> String {
+        "slack".to_string()
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let title = format!("{} {}", notification.event.emoji(), notification.title);
+        let body = json!({
+            // Shown in the desktop and mobile notifications
+            "text": title,
+            "attachments": [{
+                "color": format!("#{:06x}", notification.event.color()),
+                "blocks": [
+                    { "type": "section", "text": { "type": "mrkdwn", "text": format!("*{}*\n{}", title, notification.message) } },
+                    { "type": "context", "elements": [{ "type": "mrkdwn", "text": format!("{} · {:?} · {}", PROJECT_NAME, notification.event, Utc::now().to_rfc3339()) }] },
+                ],
+            }],
+        });
+        let response = self.client.post(&self.webhook_url).json(&body).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Slack webhook failed: {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+// Which events are sent, everything is on by default
+#[derive(Debug, Clone)]
+pub struct NotifyFlags {
+    pub trade_landed: bool,
+    pub loss_incurred: bool,
+    pub kill_switch_tripped: bool,
+    pub rpc_failover: bool,
+    pub low_balance: bool,
+    pub daily_summary: bool,
+}
+
+impl Default for NotifyFlags {
+    fn default() -> Self {
+        NotifyFlags {
+            trade_landed: true,
+            loss_incurred: true,
+            kill_switch_tripped: true,
+            rpc_failover: true,
+            low_balance: true,
+            daily_summary: true,
+        }
+    }
+}
+
+impl NotifyFlags {
+    pub fn is_enabled(&self, event: NotifyEvent) -> bool {
+        match event {
+            NotifyEvent::TradeLanded => self.trade_landed,
+            NotifyEvent::LossIncurred => self.loss_incurred,
+            NotifyEvent::KillSwitchTripped => self.kill_switch_tripped,
+            NotifyEvent::RpcFailover => self.rpc_failover,
+            NotifyEvent::LowBalance => self.low_balance,
+            NotifyEvent::DailySummary => self.daily_summary,
+        }
+    }
+}
+
+pub struct Notifiers {
+    pub flags: NotifyFlags,
+    notifiers: Vec<Arc<dyn Notifier>>,
+}
+
+impl Notifiers {
+    pub fn new(flags: NotifyFlags) -> Self {
+        Notifiers { flags, notifiers: Vec::new() }
+    }
+
+    pub fn with_notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        info!("📣 Notifications sent to {}", notifier.name());
+        self.notifiers.push(notifier);
+        self
+    }
+
+    fn dispatch(&self, notification: Notification) {
+        if !self.flags.is_enabled(notification.event) {
+            return;
+        }
+        for notifier in self.notifiers.iter().cloned() {
+            let notification = notification.clone();
+            spawn_named("notify", async move {
+                if let Err(e) = notifier.send(&notification).await {
+                    error!("❌ {} notification failed: {:?}", notifier.name(), e);
+                }
+            });
+        }
+    }
+}
+
+// Fire and forget: the alert goes through the event bus, it never delays or fails the caller
+pub fn notify(event: NotifyEvent, title: &str, message: &str) {
+    publish(BotEvent::Alert { event, title: title.to_string(), message: message.to_string() });
+}
+
+// Alerts and landed trades from the event bus, sent to every notifier
+pub async fn run_notifier(notifiers: Notifiers) {
+    let mut events = subscribe();
+    loop {
+        let notification = match events.recv().await {
+            Ok(timed) => match timed.event {
+                BotEvent::Alert { event, title, message } => Notification { event, title, message },
+                BotEvent::TradeExecuted { strategy, tokens_path, expected_profit, realized_profit, landed: true, .. } => {
+                    let profit = realized_profit.unwrap_or(expected_profit);
+                    let message = format!("{} via {}: {} lamports (expected {})", tokens_path, strategy, profit, expected_profit);
+                    if profit >= 0.0 {
+                        Notification { event: NotifyEvent::TradeLanded, title: "Trade landed".to_string(), message }
+                    } else {
+                        Notification { event: NotifyEvent::LossIncurred, title: "Trade landed at a loss".to_string(), message }
+                    }
+                }
+                _ => continue,
+            },
+            Err(RecvError::Lagged(skipped)) => {
+                error!("Notifier lagged, {} events skipped", skipped);
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+        notifiers.dispatch(notification);
+    }
+}
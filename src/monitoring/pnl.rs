@@ -0,0 +1,148 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::arbitrage::types::SwapPathResult;
+use crate::common::database::find_pnl_records_since;
+use crate::common::prices::token_prices;
+use crate::monitoring::events::{publish, BotEvent};
+use crate::risk::exposure::ExposureLimiter;
+
+pub static PNL_COLLECTION: &str = "pnl";
+// History kept in memory for the aggregates
+pub static PNL_HISTORY_DAYS: i64 = 8 * 7;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PnlRecord {
+    // Unix seconds
+    pub timestamp: i64,
+    pub strategy: String,
+    pub path_id: u32,
+    pub tokens_path: String,
+    pub expected_profit: f64,
+    // Lamports of the base token, fees included; the base fee alone for a failed trade
+    pub realized_profit: f64,
+    pub landed: bool,
+    // Realized profit read from the confirmed balance deltas, expected profit otherwise
+    pub verified: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PnlAggregate {
+    // YYYY-MM-DD or YYYY-Www
+    pub period: String,
+    pub trades: u64,
+    pub landed: u64,
+    pub realized_profit: f64,
+    pub expected_profit: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PnlSnapshot {
+    pub realized_today: f64,
+    pub realized_week: f64,
+    // SOL value of the stranded token inventory at mark price
+    pub unrealized: f64,
+    pub inventory: HashMap<String, u64>,
+    // Same at the SOL price of the price provider, None without it
+    pub realized_today_usd: Option<f64>,
+    pub unrealized_usd: Option<f64>,
+}
+
+// Realized PnL of every executed trade (persisted in the "pnl" collection) and unrealized PnL
+// of the tokens left in the wallet, valued at the price provider prices (the exposure limiter ones without them)
+#[derive(Debug)]
+pub struct PnlTracker {
+    exposure: Arc<ExposureLimiter>,
+    records: Mutex<Vec<PnlRecord>>,
+}
+
+impl PnlTracker {
+    pub fn new(exposure: Arc<ExposureLimiter>) -> Self {
+        PnlTracker { exposure, records: Mutex::new(Vec::new()) }
+    }
+
+    // Reload the recent history from the database
+    pub async fn load(exposure: Arc<ExposureLimiter>) -> Result<Self> {
+        let tracker = PnlTracker::new(exposure);
+        let since = Utc::now().timestamp() - PNL_HISTORY_DAYS * 24 * 60 * 60;
+        let records = find_pnl_records_since(PNL_COLLECTION, since).await?;
+        info!("💰 {} trades reloaded in the PnL tracker", records.len());
+        *tracker.records.lock().unwrap() = records;
+        Ok(tracker)
+    }
+
+    pub fn record_trade(&self, strategy: &str, spr: &SwapPathResult, expected_profit: f64, realized_profit: f64, landed: bool, verified: bool) {
+        let record = PnlRecord {
+            timestamp: Utc::now().timestamp(),
+            strategy: strategy.to_string(),
+            path_id: spr.path_id,
+            tokens_path: spr.tokens_path.clone(),
+            expected_profit,
+            realized_profit,
+            landed,
+            verified,
+        };
+        {
+            let mut records = self.records.lock().unwrap();
+            let since = record.timestamp - PNL_HISTORY_DAYS * 24 * 60 * 60;
+            records.retain(|record| record.timestamp >= since);
+            records.push(record.clone());
+        }
+        publish(BotEvent::PnlRecorded { record });
+        let snapshot = self.snapshot();
+        publish(BotEvent::PnlUpdate { realized_today: snapshot.realized_today, realized_week: snapshot.realized_week, unrealized: snapshot.unrealized });
+    }
+
+    pub fn daily(&self) -> Vec<PnlAggregate> {
+        self.aggregate(|at| at.format("%Y-%m-%d").to_string())
+    }
+
+    pub fn weekly(&self) -> Vec<PnlAggregate> {
+        self.aggregate(|at| at.format("%G-W%V").to_string())
+    }
+
+    fn aggregate(&self, period: impl Fn(DateTime<Utc>) -> String) -> Vec<PnlAggregate> {
+        let mut aggregates: BTreeMap<String, PnlAggregate> = BTreeMap::new();
+        for record in self.records.lock().unwrap().iter() {
+            let Some(at) = DateTime::from_timestamp(record.timestamp, 0) else { continue };
+            let key = period(at);
+            let aggregate = aggregates.entry(key.clone()).or_insert(PnlAggregate { period: key, ..PnlAggregate::default() });
+            aggregate.trades += 1;
+            aggregate.landed += record.landed as u64;
+            aggregate.realized_profit += record.realized_profit;
+            aggregate.expected_profit += record.expected_profit;
+        }
+        aggregates.into_values().collect()
+    }
+
+    pub fn snapshot(&self) -> PnlSnapshot {
+        let now = Utc::now();
+        let today = now.format("%Y-%m-%d").to_string();
+        let week = now.format("%G-W%V").to_string();
+        let prices = token_prices();
+        let inventory: HashMap<String, u64> = self
+            .exposure
+            .holdings()
+            .into_iter()
+            .map(|(mint, amount)| {
+                let value = prices.and_then(|prices| prices.lamports_value(&mint, amount)).unwrap_or_else(|| self.exposure.exposure(&mint));
+                (mint, value)
+            })
+            .collect();
+        let realized_today = self.daily().iter().find(|aggregate| aggregate.period == today).map(|aggregate| aggregate.realized_profit).unwrap_or(0.0);
+        let unrealized = inventory.values().sum::<u64>() as f64;
+        PnlSnapshot {
+            realized_today,
+            realized_week: self.weekly().iter().find(|aggregate| aggregate.period == week).map(|aggregate| aggregate.realized_profit).unwrap_or(0.0),
+            unrealized,
+            inventory,
+            realized_today_usd: prices.and_then(|prices| prices.lamports_to_usd(realized_today)),
+            unrealized_usd: prices.and_then(|prices| prices.lamports_to_usd(unrealized)),
+        }
+    }
+}
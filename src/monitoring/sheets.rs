@@ -0,0 +1,206 @@
+use std::fs;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::common::constants::Env;
+use crate::engine::EventSink;
+use crate::monitoring::daily_summary::DailySummary;
+use crate::monitoring::events::{BotEvent, TimedEvent};
+
+pub static SHEETS_API_URL: &str = "https://sheets.googleapis.com/v4/spreadsheets";
+pub static SHEETS_SCOPE: &str = "https://www.googleapis.com/auth/spreadsheets";
+// Tab the rows are appended to, created by hand in the sheet
+pub static SHEETS_DEFAULT_TAB: &str = "Daily PnL";
+
+const HEADER: [&str; 11] = ["Day", "Trades", "Landed", "Failed", "Win rate", "Gross PnL (SOL)", "Net PnL (SOL)", "Fees (SOL)", "Tips (SOL)", "Best path", "Incidents"];
+
+// The JSON key file of a Google Cloud service account, the sheet being shared with its email.
+// Not Debug, the private key would end up in the logs
+#[derive(Clone, Deserialize)]
+pub struct ServiceAccountKey {
+    pub client_email: String,
+    pub private_key: String,
+    pub token_uri: String,
+}
+
+#[derive(Serialize)]
+struct Claims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: i64,
+    exp: i64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+fn sol(lamports: f64) -> f64 {
+    lamports / LAMPORTS_PER_SOL as f64
+}
+
+// One row per daily summary, in SOL for the readers of the sheet
+pub fn summary_row(summary: &DailySummary) -> Vec<Value> {
+    vec![
+        json!(summary.day),
+        json!(summary.trades),
+        json!(summary.landed),
+        json!(summary.failed),
+        json!(summary.win_rate),
+        json!(sol(summary.gross_pnl)),
+        json!(sol(summary.net_pnl)),
+        json!(sol(summary.fees_lamports as f64)),
+        json!(sol(summary.tips_lamports as f64)),
+        json!(summary.top_paths.first().map(|path| path.tokens_path.clone()).unwrap_or_default()),
+        json!(summary.incidents.len()),
+    ]
+}
+
+// Appends the daily summaries to a Google Sheet, for the stakeholders without database access
+pub struct GoogleSheetsExporter {
+    key: ServiceAccountKey,
+    spreadsheet_id: String,
+    tab: String,
+    client: reqwest::Client,
+    // Access token and its expiry
+    token: Mutex<Option<(String, Instant)>>,
+    header_checked: OnceCell<()>,
+}
+
+impl GoogleSheetsExporter {
+    pub fn new(key: ServiceAccountKey, spreadsheet_id: &str, tab: &str) -> Self {
+        GoogleSheetsExporter {
+            key,
+            spreadsheet_id: spreadsheet_id.to_string(),
+            tab: tab.to_string(),
+            client: reqwest::Client::new(),
+            token: Mutex::new(None),
+            header_checked: OnceCell::new(),
+        }
+    }
+
+    // GOOGLE_SERVICE_ACCOUNT_FILE + GOOGLE_SHEET_ID (+ GOOGLE_SHEET_TAB), None when not configured
+    pub fn from_env() -> Result<Option<Self>> {
+        let env = Env::new();
+        if env.google_service_account_file.is_empty() || env.google_sheet_id.is_empty() {
+            return Ok(None);
+        }
+        let key: ServiceAccountKey = serde_json::from_str(
+            &fs::read_to_string(&env.google_service_account_file).with_context(|| format!("reading {}", env.google_service_account_file))?,
+        )?;
+        let tab = if env.google_sheet_tab.is_empty() { SHEETS_DEFAULT_TAB } else { env.google_sheet_tab.as_str() };
+        Ok(Some(GoogleSheetsExporter::new(key, &env.google_sheet_id, tab)))
+    }
+
+    // OAuth token of the service account (JWT bearer grant), renewed a minute before it expires
+    async fn access_token(&self) -> Result<String> {
+        let mut token = self.token.lock().await;
+        if let Some((access_token, expires_at)) = token.as_ref() {
+            if Instant::now() + Duration::from_secs(60) < *expires_at {
+                return Ok(access_token.clone());
+            }
+        }
+        let now = Utc::now().timestamp();
+        let claims = Claims { iss: &self.key.client_email, scope: SHEETS_SCOPE, aud: &self.key.token_uri, iat: now, exp: now + 3600 };
+        let assertion = encode(&Header::new(Algorithm::RS256), &claims, &EncodingKey::from_rsa_pem(self.key.private_key.as_bytes())?)?;
+        let response = self
+            .client
+            .post(&self.key.token_uri)
+            .form(&[("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"), ("assertion", assertion.as_str())])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Google token exchange failed: {} {}", response.status(), response.text().await.unwrap_or_default()));
+        }
+        let response: TokenResponse = response.json().await?;
+        *token = Some((response.access_token.clone(), Instant::now() + Duration::from_secs(response.expires_in)));
+        Ok(response.access_token)
+    }
+
+    // A1 notation of the tab in the URL path, `suffix` being the method (`:append`) if any.
+    // The path segment is percent-encoded by the URL itself, whatever the tab name
+    fn range_url(&self, range: &str, suffix: &str) -> Result<reqwest::Url> {
+        let mut url = reqwest::Url::parse(SHEETS_API_URL)?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow!("{} cannot be a base URL", SHEETS_API_URL))?
+            .extend([self.spreadsheet_id.as_str(), "values", &format!("'{}'!{}{}", self.tab, range, suffix)]);
+        Ok(url)
+    }
+
+    async fn append_rows(&self, rows: Vec<Vec<Value>>) -> Result<()> {
+        let response = self
+            .client
+            .post(self.range_url("A1", ":append")?)
+            .query(&[("valueInputOption", "USER_ENTERED"), ("insertDataOption", "INSERT_ROWS")])
+            .bearer_auth(self.access_token().await?)
+            .json(&json!({ "values": rows }))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Sheets append failed: {} {}", response.status(), response.text().await.unwrap_or_default()));
+        }
+        Ok(())
+    }
+
+    // Header written on an empty tab
+    async fn ensure_header(&self) -> Result<()> {
+        let response = self.client.get(self.range_url("A1:A1", "")?).bearer_auth(self.access_token().await?).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Sheets read failed: {} {}", response.status(), response.text().await.unwrap_or_default()));
+        }
+        let values: Value = response.json().await?;
+        if values.get("values").is_none() {
+            self.append_rows(vec![HEADER.iter().map(|column| json!(column)).collect()]).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn append_summary(&self, summary: &DailySummary) -> Result<()> {
+        self.header_checked.get_or_try_init(|| self.ensure_header()).await?;
+        self.append_rows(vec![summary_row(summary)]).await?;
+        info!("📗 Daily summary {} appended to the Google Sheet", summary.day);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventSink for GoogleSheetsExporter {
+    fn name(&self) -> String {
+        "google_sheets".to_string()
+    }
+
+    async fn handle(&self, event: &TimedEvent) -> Result<()> {
+        match &event.event {
+            BotEvent::DailySummaryReady { summary } => self.append_summary(summary).await,
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exporter(tab: &str) -> GoogleSheetsExporter {
+        let key = ServiceAccountKey { client_email: String::new(), private_key: String::new(), token_uri: String::new() };
+        GoogleSheetsExporter::new(key, "sheet-id", tab)
+    }
+
+    #[test]
+    fn tab_name_encoded_in_a_single_path_segment() {
+        let url = exporter("PnL #1 / 100%?").range_url("A1", ":append").unwrap();
+        assert_eq!(url.as_str(), "https://sheets.googleapis.com/v4/spreadsheets/sheet-id/values/'PnL%20%231%20%2F%20100%25%3F'!A1:append");
+    }
+}
@@ -0,0 +1,29 @@
+use log::error;
+use tokio::sync::mpsc;
+
+use crate::arbitrage::research::RESEARCH_COLLECTION;
+use crate::arbitrage::submissions::SUBMISSIONS_COLLECTION;
+use crate::common::database::{insert_daily_summary, insert_landing_record, insert_pnl_record, insert_research_record, insert_submission_fingerprint, insert_trade};
+use crate::monitoring::events::BotEvent;
+use crate::monitoring::daily_summary::DAILY_SUMMARIES_COLLECTION;
+use crate::monitoring::landing::LANDINGS_COLLECTION;
+use crate::monitoring::pnl::PNL_COLLECTION;
+
+// Single place the hot path records are written from, nothing is persisted without it.
+// Fed by its own bounded queue (events::persisted_events), the broadcast bus is for the dashboards and notifiers
+pub async fn run_database_writer(mut events: mpsc::Receiver<BotEvent>) {
+    while let Some(event) = events.recv().await {
+        let result = match event {
+            BotEvent::TradeRecorded { trade } => insert_trade(trade).await,
+            BotEvent::PnlRecorded { record } => insert_pnl_record(PNL_COLLECTION, record).await,
+            BotEvent::SubmissionRecorded { fingerprint } => insert_submission_fingerprint(SUBMISSIONS_COLLECTION, fingerprint).await,
+            BotEvent::ResearchRecorded { record } => insert_research_record(RESEARCH_COLLECTION, record).await,
+            BotEvent::LandingAnalyzed { record } => insert_landing_record(LANDINGS_COLLECTION, record).await,
+            BotEvent::DailySummaryReady { summary } => insert_daily_summary(DAILY_SUMMARIES_COLLECTION, summary).await,
+            _ => continue,
+        };
+        if let Err(e) = result {
+            error!("❌ Database writer failed: {:?}", e);
+        }
+    }
+}
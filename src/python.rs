@@ -0,0 +1,198 @@
+use std::sync::OnceLock;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use tokio::runtime::Runtime;
+
+use crate::arbitrage::path_files::read_paths;
+use crate::arbitrage::simulate::{simulate_path_local, simulate_paths_local};
+use crate::arbitrage::types::{Route, SwapPath, SwapRouteSimulation};
+use crate::common::maths::{constant_product_out, FeeMultiplier};
+use crate::common::utils::{from_Pubkey, from_str};
+use crate::common::rpc_pool::nonblocking_client;
+use crate::markets::local_quote::{quote_local, quoted_accounts};
+use crate::markets::pool_cache::pool_cache;
+use crate::markets::pools::load_all_pools;
+use crate::markets::types::Market;
+
+// Python module mev_bot_solana (python feature): pool loading, local quotes and path simulation
+// with the code the bot runs. Built with
+//     cargo rustc --release --lib --features python,pyo3/extension-module --crate-type cdylib
+// and the library copied as mev_bot_solana.so (.pyd on Windows) next to the notebook
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+fn runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| Runtime::new().expect("Tokio runtime not started"))
+}
+
+#[pyclass(name = "Pool", module = "mev_bot_solana")]
+#[derive(Clone)]
+pub struct PyPool {
+    market: Market,
+}
+
+#[pymethods]
+impl PyPool {
+    #[getter]
+    fn id(&self) -> String {
+        self.market.id.clone()
+    }
+
+    #[getter]
+    fn dex(&self) -> String {
+        self.market.dexLabel.str()
+    }
+
+    #[getter]
+    fn mint_a(&self) -> String {
+        self.market.tokenMintA.clone()
+    }
+
+    #[getter]
+    fn mint_b(&self) -> String {
+        self.market.tokenMintB.clone()
+    }
+
+    #[getter]
+    fn fee(&self) -> u64 {
+        self.market.fee
+    }
+
+    #[getter]
+    fn liquidity(&self) -> Option<u64> {
+        self.market.liquidity
+    }
+
+    // Accounts its quote reads, to fetch or to set
+    fn quoted_accounts(&self) -> Vec<String> {
+        quoted_accounts(&self.market).into_iter().map(|address| address.into_owned()).collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Pool({} {} {}/{})", self.market.dexLabel.str(), self.market.id, self.market.tokenMintA, self.market.tokenMintB)
+    }
+}
+
+#[pyclass(name = "PathQuote", module = "mev_bot_solana", get_all)]
+#[derive(Clone)]
+pub struct PyPathQuote {
+    // Amount out minus amount in, in the raw units of the first token
+    pub profit: f64,
+    pub pools: Vec<String>,
+    pub amounts_in: Vec<u64>,
+    pub amounts_out: Vec<u64>,
+}
+
+impl PyPathQuote {
+    fn new(simulations: &[SwapRouteSimulation], profit: f64) -> Self {
+        PyPathQuote {
+            profit,
+            pools: simulations.iter().map(|simulation| simulation.pool_address.clone()).collect(),
+            amounts_in: simulations.iter().map(|simulation| simulation.amount_in).collect(),
+            amounts_out: simulations.iter().map(|simulation| simulation.estimated_amount_out.parse().unwrap_or(0)).collect(),
+        }
+    }
+}
+
+fn route(id: u32, market: &Market, token_in: &str) -> PyResult<Route> {
+    let token_0to1 = match token_in {
+        _ if token_in == market.tokenMintA => true,
+        _ if token_in == market.tokenMintB => false,
+        _ => return Err(PyValueError::new_err(format!("{} is not traded by pool {}", token_in, market.id))),
+    };
+    Ok(Route {
+        id,
+        dex: market.dexLabel.clone(),
+        pool_address: market.id.clone(),
+        token_0to1,
+        tokenIn: token_in.to_string(),
+        tokenOut: if token_0to1 { market.tokenMintB.clone() } else { market.tokenMintA.clone() },
+        fee: market.fee,
+    })
+}
+
+// Pools of every DEX from the cached lists, refreshed from the DEX APIs with refetch
+#[pyfunction]
+#[pyo3(signature = (refetch = false))]
+fn load_pools(py: Python<'_>, refetch: bool) -> Vec<PyPool> {
+    let dexs = py.allow_threads(|| runtime().block_on(load_all_pools(refetch)));
+    dexs.into_iter().flat_map(|dex| dex.pairToMarkets.into_values().flatten()).map(|market| PyPool { market }).collect()
+}
+
+// Account data the quotes read, e.g. from a historical snapshot to backtest
+#[pyfunction]
+fn set_account(address: &str, data: Vec<u8>) {
+    pool_cache().insert(address, data);
+}
+
+// Current data of the accounts from the RPC, returns how many were found
+#[pyfunction]
+fn fetch_accounts(py: Python<'_>, rpc_url: &str, addresses: Vec<String>) -> anyhow::Result<usize> {
+    py.allow_threads(|| {
+        runtime().block_on(async {
+            let pubkeys = addresses.iter().map(|address| from_str(address)).collect::<Result<Vec<_>, _>>()?;
+            let mut found = 0;
+            for chunk in pubkeys.chunks(100) {
+                let accounts = nonblocking_client(rpc_url).get_multiple_accounts(chunk).await?;
+                for (pubkey, account) in chunk.iter().zip(accounts) {
+                    if let Some(account) = account {
+                        pool_cache().insert(&from_Pubkey(*pubkey), account.data);
+                        found += 1;
+                    }
+                }
+            }
+            Ok(found)
+        })
+    })
+}
+
+// Amount out and min amount out of a swap, None without local math for the DEX or account data
+#[pyfunction]
+fn quote(pool: &PyPool, token_in: &str, amount_in: u64) -> PyResult<Option<(u64, u64)>> {
+    Ok(quote_local(&route(0, &pool.market, token_in)?, &pool.market, amount_in))
+}
+
+// The pools swapped in order from token_in, each hop selling what the previous one bought
+#[pyfunction]
+fn simulate_path(pools: Vec<PyPool>, token_in: &str, amount_in: u64) -> PyResult<Option<PyPathQuote>> {
+    let markets: Vec<Market> = pools.into_iter().map(|pool| pool.market).collect();
+    let mut routes = Vec::with_capacity(markets.len());
+    let mut token = token_in.to_string();
+    for (index, market) in markets.iter().enumerate() {
+        let route = route(index as u32, market, &token)?;
+        token = route.tokenOut.clone();
+        routes.push(route);
+    }
+    let path = SwapPath { hops: routes.len() as u8, id_paths: routes.iter().map(|route| route.id).collect(), paths: routes };
+    Ok(simulate_path_local(pool_cache(), amount_in, &path, &markets).map(|(simulations, profit)| PyPathQuote::new(&simulations, profit)))
+}
+
+// Every path of a best paths file (binary or JSON), quoted like the strategies do
+#[pyfunction]
+fn simulate_paths_file(py: Python<'_>, file: &str, amount_in: u64) -> anyhow::Result<Vec<Option<PyPathQuote>>> {
+    let paths = read_paths(file)?.value;
+    let quotes = py.allow_threads(|| simulate_paths_local(amount_in, &paths));
+    Ok(quotes.into_iter().map(|quote| quote.map(|(simulations, profit)| PyPathQuote::new(&simulations, profit))).collect())
+}
+
+// Constant product swap of the Raydium AMM math
+#[pyfunction]
+fn constant_product_amount_out(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_numerator: u64, fee_denominator: u64) -> Option<u64> {
+    constant_product_out(amount_in, reserve_in, reserve_out, FeeMultiplier::from_ratio(fee_numerator, fee_denominator)?)
+}
+
+#[pymodule]
+#[pyo3(name = "mev_bot_solana")]
+fn mev_bot_solana(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyPool>()?;
+    m.add_class::<PyPathQuote>()?;
+    m.add_function(wrap_pyfunction!(load_pools, m)?)?;
+    m.add_function(wrap_pyfunction!(set_account, m)?)?;
+    m.add_function(wrap_pyfunction!(fetch_accounts, m)?)?;
+    m.add_function(wrap_pyfunction!(quote, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate_path, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate_paths_file, m)?)?;
+    m.add_function(wrap_pyfunction!(constant_product_amount_out, m)?)?;
+    Ok(())
+}
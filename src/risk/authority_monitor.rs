@@ -0,0 +1,275 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use anchor_spl::token::spl_token;
+use anyhow::Result;
+use futures::stream::{select_all, BoxStream, StreamExt};
+use log::{debug, error, info, warn};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcAccountInfoConfig;
+use solana_program::program_pack::Pack;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+use tokio::sync::Notify;
+
+use crate::arbitrage::token_health::TokenHealth;
+use crate::arbitrage::types::{Route, SwapPathResult, SwapPathSelected, SwapRouteSimulation};
+use crate::common::constants::{Env, SOL_MINT, USDC_MINT, USDT_MINT};
+use crate::common::feeds::{feeds, FeedEndpoint, FeedKind};
+use crate::common::utils::from_str;
+use crate::markets::local_quote::quote_local;
+use crate::markets::types::Market;
+use crate::transactions::create_transaction::{create_and_send_swap_transaction, ChainType, SendOrSimulate, BASE_COMPUTE_UNIT_PRICE};
+
+enum AuthorityUpdate {
+    Supply { mint: String, supply: Option<u64> },
+    Frozen { mint: String },
+}
+
+// Watch the mints of the traded tokens: our own token account frozen by the freeze authority,
+// or a large mint by the mint authority, quarantines the token right away. After a mint the
+// wallet balance is sold, a frozen account can't be.
+pub struct AuthorityMonitor {
+    pub token_health: Arc<TokenHealth>,
+    pub quarantine: Duration,
+    // Supply increase (ratio of the previous supply) considered as a dilution attack
+    pub max_supply_increase_ratio: f64,
+    // Share of the quoted output given up to get out of a diluted token
+    pub dump_slippage_bps: u64,
+    pub simulate_or_send: SendOrSimulate,
+    // Authorities trusted, never watched
+    pub trusted_mints: HashSet<String>,
+    wallet: Pubkey,
+    // Mint -> route selling it for SOL and its market, from the loaded paths
+    exit_routes: Mutex<HashMap<String, (Route, Market)>>,
+    mints: RwLock<HashSet<String>>,
+    changed: Notify,
+}
+
+impl AuthorityMonitor {
+    pub fn new(token_health: Arc<TokenHealth>, quarantine: Duration, max_supply_increase_ratio: f64, simulate_or_send: SendOrSimulate, wallet: Pubkey) -> Self {
+        AuthorityMonitor {
+            token_health,
+            quarantine,
+            max_supply_increase_ratio,
+            dump_slippage_bps: 300,
+            simulate_or_send,
+            trusted_mints: HashSet::from([SOL_MINT.to_string(), USDC_MINT.to_string(), USDT_MINT.to_string()]),
+            wallet,
+            exit_routes: Mutex::new(HashMap::new()),
+            mints: RwLock::new(HashSet::new()),
+            changed: Notify::new(),
+        }
+    }
+
+    // Add the mints of the paths to the watched set, the session resubscribes when it grows
+    pub fn watch_paths(&self, paths: &[SwapPathSelected]) {
+        let mut added = false;
+        for path in paths {
+            for route in &path.path.paths {
+                let market = path.markets.iter().find(|market| market.id == route.pool_address);
+                if let (Some((mint, exit_route)), Some(market)) = (exit_route(route), market) {
+                    self.exit_routes.lock().unwrap().entry(mint).or_insert((exit_route, market.clone()));
+                }
+                for mint in [&route.tokenIn, &route.tokenOut] {
+                    if !self.trusted_mints.contains(mint) {
+                        added |= self.mints.write().unwrap().insert(mint.clone());
+                    }
+                }
+            }
+        }
+        if added {
+            self.changed.notify_one();
+        }
+    }
+
+    // One connection for every watched mint: the mint account and our token account of it
+    async fn session(&self, endpoint: FeedEndpoint) -> Result<()> {
+        let mints: Vec<(String, Pubkey)> = self.mints.read().unwrap().iter().filter_map(|mint| from_str(mint).ok().map(|pubkey| (mint.clone(), pubkey))).collect();
+        if mints.is_empty() {
+            self.changed.notified().await;
+            return Ok(());
+        }
+        let client = PubsubClient::new(&endpoint.authenticated_url()).await?;
+        let config = || RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..RpcAccountInfoConfig::default()
+        };
+
+        let mut streams: Vec<BoxStream<'_, AuthorityUpdate>> = Vec::new();
+        // Unsubscribed when the session ends, along with the connection
+        let mut unsubscribes = Vec::new();
+        for (mint, mint_pubkey) in &mints {
+            let (updates, unsubscribe) = client.account_subscribe(mint_pubkey, Some(config())).await?;
+            let supply_mint = mint.clone();
+            streams.push(
+                updates
+                    .map(move |update| {
+                        let supply = update
+                            .value
+                            .decode::<Account>()
+                            .filter(|account| account.data.len() >= spl_token::state::Mint::LEN)
+                            .and_then(|account| spl_token::state::Mint::unpack_from_slice(&account.data[..spl_token::state::Mint::LEN]).ok())
+                            .map(|state| state.supply);
+                        AuthorityUpdate::Supply { mint: supply_mint.clone(), supply }
+                    })
+                    .boxed(),
+            );
+            unsubscribes.push(unsubscribe);
+
+            // Freezes of other holders don't concern us, only a freeze of our own account does
+            let token_ata = get_associated_token_address(&self.wallet, mint_pubkey);
+            let (updates, unsubscribe) = client.account_subscribe(&token_ata, Some(config())).await?;
+            let frozen_mint = mint.clone();
+            streams.push(
+                updates
+                    .filter_map(move |update| {
+                        let frozen = update
+                            .value
+                            .decode::<Account>()
+                            .filter(|account| account.data.len() >= spl_token::state::Account::LEN)
+                            .and_then(|account| spl_token::state::Account::unpack_from_slice(&account.data[..spl_token::state::Account::LEN]).ok())
+                            .is_some_and(|state| state.is_frozen());
+                        let mint = frozen_mint.clone();
+                        async move { frozen.then_some(AuthorityUpdate::Frozen { mint }) }
+                    })
+                    .boxed(),
+            );
+            unsubscribes.push(unsubscribe);
+        }
+        info!("👁️ Watching mint/freeze authorities of {} mints on {}", mints.len(), endpoint.url);
+
+        let mut last_supplies: HashMap<String, u64> = HashMap::new();
+        let mut updates = select_all(streams);
+        loop {
+            tokio::select! {
+                update = updates.next() => {
+                    // A subscription closed by the server: the connection is reopened and everything resubscribed
+                    let Some(update) = update else { break };
+                    match update {
+                        AuthorityUpdate::Supply { mint, supply: Some(supply) } => {
+                            if let Some(previous) = last_supplies.insert(mint.clone(), supply) {
+                                let increase = supply.saturating_sub(previous) as f64 / previous.max(1) as f64;
+                                if increase > self.max_supply_increase_ratio {
+                                    self.trip(&mint, &format!("supply minted +{:.1}% ({} -> {})", increase * 100.0, previous, supply)).await;
+                                }
+                            }
+                        }
+                        AuthorityUpdate::Supply { supply: None, .. } => {}
+                        AuthorityUpdate::Frozen { mint } => {
+                            warn!("🧊 Our {} account has been frozen, it can't be sold", mint);
+                            self.token_health.quarantine_for(&mint, self.quarantine, "token account frozen");
+                        }
+                    }
+                }
+                _ = self.changed.notified() => {
+                    debug!("👁️ Watched mints changed, resubscribing");
+                    break;
+                }
+            }
+        }
+        drop(updates);
+        for unsubscribe in unsubscribes {
+            unsubscribe().await;
+        }
+        let _ = client.shutdown().await;
+        Ok(())
+    }
+
+    async fn trip(&self, mint: &str, reason: &str) {
+        warn!("🧊 Authority event on {}: {}", mint, reason);
+        self.token_health.quarantine_for(mint, self.quarantine, reason);
+        if let Err(e) = self.dump_inventory(mint).await {
+            error!("❌ Unable to dump the {} inventory: {:?}", mint, e);
+        }
+    }
+
+    // Sell the whole wallet balance of the token for SOL, with a min out from the local quote:
+    // without a quote nothing is sold
+    async fn dump_inventory(&self, mint: &str) -> Result<()> {
+        let env = Env::new();
+        let rpc_client = RpcClient::new(env.rpc_url);
+        let token_ata = get_associated_token_address(&self.wallet, &from_str(mint)?);
+        let balance = match rpc_client.get_token_account_balance(&token_ata).await {
+            Ok(balance) => balance.amount.parse::<u64>().unwrap_or(0),
+            Err(_) => 0,
+        };
+        if balance == 0 {
+            return Ok(());
+        }
+        let (route, market) = match self.exit_routes.lock().unwrap().get(mint) {
+            Some(exit) => exit.clone(),
+            None => {
+                error!("🧊 {} {} held but no route to SOL is known", balance, mint);
+                return Ok(());
+            }
+        };
+        let min_amount_out = match quote_local(&route, &market, balance) {
+            Some((amount_out, _)) => (amount_out as u128 * (10_000 - self.dump_slippage_bps.min(10_000)) as u128 / 10_000) as u64,
+            None => 0,
+        };
+        if min_amount_out == 0 {
+            error!("🧊 {} {} held but no quote on {}, not sold without a min out", balance, mint, route.pool_address);
+            return Ok(());
+        }
+        let swap = SwapRouteSimulation {
+            id_route: route.id,
+            pool_address: route.pool_address.clone(),
+            dex_label: route.dex.clone(),
+            token_0to1: route.token_0to1,
+            token_in: route.tokenIn.clone(),
+            token_out: route.tokenOut.clone(),
+            amount_in: balance,
+            // The swap instructions take it as their min out
+            estimated_amount_out: min_amount_out.to_string(),
+            estimated_min_amount_out: min_amount_out.to_string(),
+        };
+        let spr = SwapPathResult {
+            path_id: 0,
+            hops: 1,
+            tokens_path: format!("{}-SOL", mint),
+            route_simulations: vec![swap],
+            token_in: mint.to_string(),
+            token_in_symbol: String::new(),
+            token_out: SOL_MINT.to_string(),
+            token_out_symbol: "SOL".to_string(),
+            amount_in: balance,
+            estimated_amount_out: min_amount_out.to_string(),
+            estimated_min_amount_out: min_amount_out.to_string(),
+            result: 0.0,
+        };
+        info!("🧊 Dumping {} {} through {} for {} lamports min", balance, mint, route.pool_address, min_amount_out);
+        let sold = create_and_send_swap_transaction(self.simulate_or_send, ChainType::Mainnet, spr, BASE_COMPUTE_UNIT_PRICE).await?;
+        if !sold {
+            error!("❌ Dump of {} not executed", mint);
+        }
+        Ok(())
+    }
+}
+
+// A route between a token and SOL, oriented to sell the token
+fn exit_route(route: &Route) -> Option<(String, Route)> {
+    if route.tokenOut == SOL_MINT && route.tokenIn != SOL_MINT {
+        return Some((route.tokenIn.clone(), route.clone()));
+    }
+    if route.tokenIn == SOL_MINT && route.tokenOut != SOL_MINT {
+        let mut reversed = route.clone();
+        reversed.tokenIn = route.tokenOut.clone();
+        reversed.tokenOut = route.tokenIn.clone();
+        reversed.token_0to1 = !route.token_0to1;
+        return Some((route.tokenOut.clone(), reversed));
+    }
+    None
+}
+
+// Resubscribed by the feed manager until its attempts are exhausted
+pub async fn run_authority_monitor(monitor: Arc<AuthorityMonitor>) {
+    feeds().run(FeedKind::AccountWs, |endpoint| monitor.session(endpoint)).await;
+    warn!("⚠️ Authority monitor stopped");
+}
@@ -0,0 +1,119 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use log::{error, info, warn};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::common::constants::SOL_MINT;
+use crate::common::rpc_pool::nonblocking_client;
+use crate::common::utils::from_str;
+use crate::monitoring::events::{publish, BotEvent};
+use crate::monitoring::notifier::{notify, NotifyEvent};
+use crate::transactions::confirmation::wait_refresh;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BalanceAlert {
+    // Balance went under a threshold (lamports)
+    Below { threshold: u64, balance: u64 },
+    // Balance came back above a threshold
+    Above { threshold: u64, balance: u64 },
+}
+
+// Track the SOL and wSOL balances of the wallet, refuse to send when a trade would
+// eat the rent + fees reserve, and alert when the balance crosses the thresholds
+#[derive(Debug)]
+pub struct BalanceMonitor {
+    // Native SOL always kept for rent and transaction fees
+    pub reserve_lamports: u64,
+    pub alert_thresholds: Vec<u64>,
+    sol_balance: AtomicU64,
+    wsol_balance: AtomicU64,
+    // u64::MAX until the first refresh
+    last_total: AtomicU64,
+}
+
+impl BalanceMonitor {
+    pub fn new(reserve_lamports: u64, mut alert_thresholds: Vec<u64>) -> Self {
+        alert_thresholds.sort();
+        BalanceMonitor {
+            reserve_lamports,
+            alert_thresholds,
+            sol_balance: AtomicU64::new(0),
+            wsol_balance: AtomicU64::new(0),
+            last_total: AtomicU64::new(u64::MAX),
+        }
+    }
+
+    pub fn sol_balance(&self) -> u64 {
+        self.sol_balance.load(Ordering::Relaxed)
+    }
+
+    pub fn wsol_balance(&self) -> u64 {
+        self.wsol_balance.load(Ordering::Relaxed)
+    }
+
+    // The fees are paid in native SOL, the amount in is spent from SOL + wSOL
+    pub fn can_send(&self, amount_in: u64) -> bool {
+        let sol_balance = self.sol_balance();
+        let total = sol_balance + self.wsol_balance();
+        if sol_balance < self.reserve_lamports || total.saturating_sub(amount_in) < self.reserve_lamports {
+            warn!("🪫 Send refused, balance {} lamports ({} SOL) would go under the {} lamports reserve", total, sol_balance, self.reserve_lamports);
+            return false;
+        }
+        true
+    }
+
+    // Store the new balances and return the thresholds crossed since the previous update
+    pub fn update(&self, sol_balance: u64, wsol_balance: u64) -> Vec<BalanceAlert> {
+        self.sol_balance.store(sol_balance, Ordering::Relaxed);
+        self.wsol_balance.store(wsol_balance, Ordering::Relaxed);
+        let balance = sol_balance + wsol_balance;
+        let previous = self.last_total.swap(balance, Ordering::Relaxed);
+
+        let mut alerts: Vec<BalanceAlert> = Vec::new();
+        for &threshold in &self.alert_thresholds {
+            if balance < threshold && (previous >= threshold || previous == u64::MAX) {
+                alerts.push(BalanceAlert::Below { threshold, balance });
+            } else if balance >= threshold && previous < threshold {
+                alerts.push(BalanceAlert::Above { threshold, balance });
+            }
+        }
+        alerts
+    }
+
+    pub async fn refresh(&self, rpc_client: &RpcClient, wallet: &Pubkey) -> Result<Vec<BalanceAlert>> {
+        let sol_balance = rpc_client.get_balance(wallet).await?;
+        let wsol_ata = get_associated_token_address(wallet, &from_str(SOL_MINT)?);
+        let wsol_balance = match rpc_client.get_token_account_balance(&wsol_ata).await {
+            Ok(balance) => balance.amount.parse::<u64>().unwrap_or(0),
+            Err(_) => 0,
+        };
+        Ok(self.update(sol_balance, wsol_balance))
+    }
+}
+
+pub async fn run_balance_monitor(monitor: Arc<BalanceMonitor>, rpc_url: String, wallet: Pubkey, interval: Duration) {
+    let rpc_client = nonblocking_client(&rpc_url);
+    loop {
+        match monitor.refresh(&rpc_client, &wallet).await {
+            Ok(alerts) => {
+                publish(BotEvent::BalanceUpdate { sol_lamports: monitor.sol_balance(), wsol_lamports: monitor.wsol_balance() });
+                for alert in alerts {
+                    match alert {
+                        BalanceAlert::Below { threshold, balance } => {
+                            error!("🚨 Wallet balance {} lamports went under {} lamports", balance, threshold);
+                            notify(NotifyEvent::LowBalance, "Low wallet balance", &format!("{} lamports, under the {} lamports threshold", balance, threshold));
+                        }
+                        BalanceAlert::Above { threshold, balance } => info!("✅ Wallet balance {} lamports back above {} lamports", balance, threshold),
+                    }
+                }
+            }
+            Err(e) => error!("❌ Balance monitor refresh failed: {:?}", e),
+        }
+        wait_refresh(interval).await;
+    }
+}
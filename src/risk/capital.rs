@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use log::{error, info};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::common::constants::SOL_MINT;
+use crate::common::rpc_pool::nonblocking_client;
+use crate::common::utils::from_str;
+
+#[derive(Debug, Default)]
+struct CapitalState {
+    // SOL + wSOL of the wallet, in lamports
+    total_available: u64,
+    reserved: HashMap<String, u64>,
+}
+
+// Partition the wallet balance between the running strategies so two strategies
+// never try to spend the same lamports at the same time
+#[derive(Debug)]
+pub struct CapitalManager {
+    shares: HashMap<String, f64>,
+    state: Mutex<CapitalState>,
+}
+
+impl CapitalManager {
+    // shares: strategy name -> fraction of the balance (ex: {"best_paths": 0.6, "rotation": 0.3})
+    pub fn new(shares: HashMap<String, f64>) -> Self {
+        let total_shares: f64 = shares.values().sum();
+        if total_shares > 1.0 {
+            error!("⚠️ Capital shares sum to {} (> 1.0), strategies may compete for funds", total_shares);
+        }
+        CapitalManager {
+            shares,
+            state: Mutex::new(CapitalState::default()),
+        }
+    }
+
+    pub fn set_total_available(&self, lamports: u64) {
+        self.state.lock().unwrap().total_available = lamports;
+    }
+
+    pub fn total_available(&self) -> u64 {
+        self.state.lock().unwrap().total_available
+    }
+
+    pub fn allocation(&self, strategy: &str) -> u64 {
+        let share = self.shares.get(strategy).cloned().unwrap_or(0.0);
+        (self.total_available() as f64 * share) as u64
+    }
+
+    pub fn available_for(&self, strategy: &str) -> u64 {
+        let allocation = self.allocation(strategy);
+        let reserved = self.state.lock().unwrap().reserved.get(strategy).cloned().unwrap_or(0);
+        allocation.saturating_sub(reserved)
+    }
+
+    // Reserve `amount` lamports for the strategy, released when the reservation is dropped
+    pub fn try_reserve(self: &Arc<Self>, strategy: &str, amount: u64) -> Option<CapitalReservation> {
+        let allocation = self.allocation(strategy);
+        let mut state = self.state.lock().unwrap();
+        let reserved = state.reserved.entry(strategy.to_string()).or_insert(0);
+        if *reserved + amount > allocation {
+            info!("💰 Not enough capital for {}: {} requested, {}/{} already reserved", strategy, amount, reserved, allocation);
+            return None;
+        }
+        *reserved += amount;
+        Some(CapitalReservation {
+            manager: self.clone(),
+            strategy: strategy.to_string(),
+            amount,
+        })
+    }
+
+    fn release(&self, strategy: &str, amount: u64) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(reserved) = state.reserved.get_mut(strategy) {
+            *reserved = reserved.saturating_sub(amount);
+        }
+    }
+
+    // Fetch SOL + wSOL balances of the wallet
+    pub async fn refresh_balance(&self, rpc_client: &RpcClient, wallet: &Pubkey) -> Result<u64> {
+        let sol_balance = rpc_client.get_balance(wallet).await?;
+        let wsol_ata = get_associated_token_address(wallet, &from_str(SOL_MINT)?);
+        let wsol_balance = match rpc_client.get_token_account_balance(&wsol_ata).await {
+            Ok(balance) => balance.amount.parse::<u64>().unwrap_or(0),
+            Err(_) => 0,
+        };
+        let total = sol_balance + wsol_balance;
+        self.set_total_available(total);
+        Ok(total)
+    }
+}
+
+pub struct CapitalReservation {
+    manager: Arc<CapitalManager>,
+    strategy: String,
+    pub amount: u64,
+}
+
+impl Drop for CapitalReservation {
+    fn drop(&mut self) {
+        self.manager.release(&self.strategy, self.amount);
+    }
+}
+
+pub async fn run_capital_refresh(manager: Arc<CapitalManager>, rpc_url: String, wallet: Pubkey, interval: Duration) {
+    let rpc_client = nonblocking_client(&rpc_url);
+    loop {
+        match manager.refresh_balance(&rpc_client, &wallet).await {
+            Ok(total) => info!("💰 Capital available: {} lamports", total),
+            Err(e) => error!("❌ Capital refresh failed: {:?}", e),
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> Arc<CapitalManager> {
+        let manager = Arc::new(CapitalManager::new(HashMap::from([("best_paths".to_string(), 0.6), ("rotation".to_string(), 0.3)])));
+        manager.set_total_available(10_000_000_000);
+        manager
+    }
+
+    #[test]
+    fn reservations_stay_within_the_share_of_the_strategy() {
+        let manager = manager();
+        let first = manager.try_reserve("best_paths", 4_000_000_000).unwrap();
+        assert_eq!(first.amount, 4_000_000_000);
+        assert!(manager.try_reserve("best_paths", 3_000_000_000).is_none());
+        assert_eq!(manager.available_for("best_paths"), 2_000_000_000);
+        // The other strategies keep their own share
+        assert!(manager.try_reserve("rotation", 3_000_000_000).is_some());
+    }
+
+    #[test]
+    fn a_dropped_reservation_is_released() {
+        let manager = manager();
+        drop(manager.try_reserve("best_paths", 6_000_000_000).unwrap());
+        assert_eq!(manager.available_for("best_paths"), 6_000_000_000);
+        assert!(manager.try_reserve("best_paths", 6_000_000_000).is_some());
+    }
+
+    #[test]
+    fn no_share_no_capital() {
+        let manager = manager();
+        assert_eq!(manager.allocation("massive"), 0);
+        assert!(manager.try_reserve("massive", 1).is_none());
+    }
+}
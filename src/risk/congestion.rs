@@ -0,0 +1,156 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::{info, warn};
+
+use crate::transactions::errors::SendError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SendOutcome {
+    Landed { latency: Duration },
+    // Still unconfirmed when its blockhash expired, after every re-sign
+    Expired,
+    // Sent but not executed
+    Dropped,
+}
+
+impl SendOutcome {
+    // Outcome of a create_and_send_swap_transaction call, None for errors unrelated to the network
+    // (RPC down, instruction building...)
+    pub fn classify(result: &Result<bool, SendError>, latency: Duration) -> Option<SendOutcome> {
+        match result {
+            Ok(true) => Some(SendOutcome::Landed { latency }),
+            Ok(false) => Some(SendOutcome::Dropped),
+            Err(SendError::Expired(_)) => Some(SendOutcome::Expired),
+            Err(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CongestionPolicy {
+    // Number of recent sends considered
+    pub window: usize,
+    // Don't conclude anything under this number of sends
+    pub min_samples: usize,
+    pub max_expired_ratio: f64,
+    pub max_dropped_ratio: f64,
+    pub max_confirmation_latency: Duration,
+    // Priority fee multiplier while congested
+    pub fee_multiplier: u64,
+    // Opportunities under this expected profit (lamports) are skipped while congested
+    pub min_profit_when_congested: f64,
+}
+
+impl Default for CongestionPolicy {
+    fn default() -> Self {
+        CongestionPolicy {
+            window: 50,
+            min_samples: 10,
+            max_expired_ratio: 0.2,
+            max_dropped_ratio: 0.4,
+            max_confirmation_latency: Duration::from_secs(20),
+            fee_multiplier: 5,
+            min_profit_when_congested: 50_000_000.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CongestionStats {
+    pub samples: usize,
+    pub expired_ratio: f64,
+    pub dropped_ratio: f64,
+    pub average_latency: Duration,
+}
+
+// Watch the outcome of our own sends: a rising blockhash expiry or drop rate, or slow
+// confirmations, mean the leaders are congested. Fees are raised and low-EV
+// opportunities skipped until it clears.
+#[derive(Debug)]
+pub struct CongestionMonitor {
+    pub policy: CongestionPolicy,
+    outcomes: Mutex<VecDeque<SendOutcome>>,
+    congested: AtomicBool,
+}
+
+impl CongestionMonitor {
+    pub fn new(policy: CongestionPolicy) -> Self {
+        CongestionMonitor {
+            policy,
+            outcomes: Mutex::new(VecDeque::new()),
+            congested: AtomicBool::new(false),
+        }
+    }
+
+    pub fn record(&self, outcome: SendOutcome) {
+        let stats = {
+            let mut outcomes = self.outcomes.lock().unwrap();
+            outcomes.push_back(outcome);
+            while outcomes.len() > self.policy.window {
+                outcomes.pop_front();
+            }
+            compute_stats(&outcomes)
+        };
+        let congested = stats.samples >= self.policy.min_samples
+            && (stats.expired_ratio > self.policy.max_expired_ratio
+                || stats.dropped_ratio > self.policy.max_dropped_ratio
+                || stats.average_latency > self.policy.max_confirmation_latency);
+        if self.congested.swap(congested, Ordering::Relaxed) != congested {
+            if congested {
+                warn!("🚦 Network congested: {:?}, priority fees x{} and min profit {}", stats, self.policy.fee_multiplier, self.policy.min_profit_when_congested);
+            } else {
+                info!("🚦 Network congestion cleared: {:?}", stats);
+            }
+        }
+    }
+
+    pub fn is_congested(&self) -> bool {
+        self.congested.load(Ordering::Relaxed)
+    }
+
+    pub fn stats(&self) -> CongestionStats {
+        compute_stats(&self.outcomes.lock().unwrap())
+    }
+
+    pub fn compute_unit_price(&self, base: u64) -> u64 {
+        if self.is_congested() {
+            base * self.policy.fee_multiplier
+        } else {
+            base
+        }
+    }
+
+    pub fn accepts(&self, expected_profit: f64) -> bool {
+        !self.is_congested() || expected_profit >= self.policy.min_profit_when_congested
+    }
+}
+
+fn compute_stats(outcomes: &VecDeque<SendOutcome>) -> CongestionStats {
+    let samples = outcomes.len();
+    if samples == 0 {
+        return CongestionStats::default();
+    }
+    let expired = outcomes.iter().filter(|outcome| **outcome == SendOutcome::Expired).count();
+    let dropped = outcomes.iter().filter(|outcome| **outcome == SendOutcome::Dropped).count();
+    let latencies: Vec<Duration> = outcomes
+        .iter()
+        .filter_map(|outcome| match outcome {
+            SendOutcome::Landed { latency } => Some(*latency),
+            _ => None,
+        })
+        .collect();
+    let average_latency = if latencies.is_empty() {
+        Duration::ZERO
+    } else {
+        latencies.iter().sum::<Duration>() / latencies.len() as u32
+    };
+    CongestionStats {
+        samples,
+        expired_ratio: expired as f64 / samples as f64,
+        dropped_ratio: dropped as f64 / samples as f64,
+        average_latency,
+    }
+}
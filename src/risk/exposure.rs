@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anchor_spl::token::spl_token;
+use anyhow::Result;
+use log::{error, info};
+use solana_account_decoder::UiAccountData;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::common::rpc_pool::nonblocking_client;
+use crate::arbitrage::types::SwapPathResult;
+use crate::transactions::confirmation::wait_refresh;
+
+// Cap the SOL value held in any single non-base token. Tokens left in the wallet by
+// partial fills or failed back legs count as exposure. The hops of a path land in one
+// transaction, so a new trade only adds what a filled path leaves in a token, and is
+// refused when that plus the inventory already held would exceed the cap.
+#[derive(Debug)]
+pub struct ExposureLimiter {
+    pub base_mint: String,
+    // Lamports of SOL value
+    pub default_max_exposure: u64,
+    pub max_exposure_overrides: HashMap<String, u64>,
+    // SOL lamports per token unit, learned from the simulated first hops
+    prices: Mutex<HashMap<String, f64>>,
+    holdings: Mutex<HashMap<String, u64>>,
+}
+
+impl ExposureLimiter {
+    pub fn new(base_mint: &str, default_max_exposure: u64) -> Self {
+        ExposureLimiter {
+            base_mint: base_mint.to_string(),
+            default_max_exposure,
+            max_exposure_overrides: HashMap::new(),
+            prices: Mutex::new(HashMap::new()),
+            holdings: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_override(mut self, mint: &str, max_exposure: u64) -> Self {
+        self.max_exposure_overrides.insert(mint.to_string(), max_exposure);
+        self
+    }
+
+    pub fn max_exposure(&self, mint: &str) -> u64 {
+        self.max_exposure_overrides.get(mint).cloned().unwrap_or(self.default_max_exposure)
+    }
+
+    pub fn update_prices(&self, spr: &SwapPathResult) {
+        let mut prices = self.prices.lock().unwrap();
+        for route in spr.route_simulations.iter().filter(|route| route.token_in == self.base_mint) {
+            let amount_out = route.estimated_amount_out.parse::<f64>().unwrap_or(0.0);
+            if amount_out > 0.0 {
+                prices.insert(route.token_out.clone(), route.amount_in as f64 / amount_out);
+            }
+        }
+    }
+
+    // Current SOL value held in the token, 0 when the price is unknown
+    pub fn exposure(&self, mint: &str) -> u64 {
+        let amount = self.holdings.lock().unwrap().get(mint).cloned().unwrap_or(0);
+        let price = self.prices.lock().unwrap().get(mint).cloned().unwrap_or(0.0);
+        (amount as f64 * price) as u64
+    }
+
+    // Raw amount held per non-base token
+    pub fn holdings(&self) -> HashMap<String, u64> {
+        self.holdings.lock().unwrap().clone()
+    }
+
+    // SOL value held per token, tokens with an unknown price are valued 0
+    pub fn exposures(&self) -> HashMap<String, u64> {
+        let mints: Vec<String> = self.holdings.lock().unwrap().keys().cloned().collect();
+        mints.into_iter().map(|mint| (mint.clone(), self.exposure(&mint))).collect()
+    }
+
+    // Tokens received by the path minus the tokens its next hops spend, 0 for a closed round trip
+    fn leftover(spr: &SwapPathResult, mint: &str) -> u64 {
+        let received: u64 = spr.route_simulations.iter().filter(|route| route.token_out == mint).map(|route| route.estimated_amount_out.parse::<u64>().unwrap_or(0)).sum();
+        let spent: u64 = spr.route_simulations.iter().filter(|route| route.token_in == mint).map(|route| route.amount_in).sum();
+        received.saturating_sub(spent)
+    }
+
+    pub fn can_trade(&self, spr: &SwapPathResult) -> bool {
+        self.update_prices(spr);
+        for route in spr.route_simulations.iter().filter(|route| route.token_out != self.base_mint) {
+            let price = self.prices.lock().unwrap().get(&route.token_out).cloned().unwrap_or(0.0);
+            let leftover = (Self::leftover(spr, &route.token_out) as f64 * price) as u64;
+            let exposure = self.exposure(&route.token_out) + leftover;
+            let max_exposure = self.max_exposure(&route.token_out);
+            if exposure > max_exposure {
+                info!("⚖️  Trade refused, {} exposure would reach {}/{} lamports", route.token_out, exposure, max_exposure);
+                return false;
+            }
+        }
+        true
+    }
+
+    pub async fn refresh_holdings(&self, rpc_client: &RpcClient, wallet: &Pubkey) -> Result<()> {
+        let accounts = rpc_client.get_token_accounts_by_owner(wallet, TokenAccountsFilter::ProgramId(spl_token::id())).await?;
+        let mut holdings: HashMap<String, u64> = HashMap::new();
+        for keyed_account in accounts {
+            if let UiAccountData::Json(parsed) = keyed_account.account.data {
+                let info = &parsed.parsed["info"];
+                let mint = info["mint"].as_str().unwrap_or_default().to_string();
+                let amount = info["tokenAmount"]["amount"].as_str().and_then(|amount| amount.parse::<u64>().ok()).unwrap_or(0);
+                if amount > 0 && mint != self.base_mint {
+                    *holdings.entry(mint).or_insert(0) += amount;
+                }
+            }
+        }
+        *self.holdings.lock().unwrap() = holdings;
+        Ok(())
+    }
+}
+
+pub async fn run_exposure_refresh(limiter: Arc<ExposureLimiter>, rpc_url: String, wallet: Pubkey, interval: Duration) {
+    let rpc_client = nonblocking_client(&rpc_url);
+    loop {
+        if let Err(e) = limiter.refresh_holdings(&rpc_client, &wallet).await {
+            error!("❌ Exposure refresh failed: {:?}", e);
+        }
+        wait_refresh(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrage::types::SwapRouteSimulation;
+    use crate::markets::types::DexLabel;
+
+    const SOL: &str = "So11111111111111111111111111111111111111112";
+    const TOKEN: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    fn route(token_in: &str, token_out: &str, amount_in: u64, amount_out: u64) -> SwapRouteSimulation {
+        SwapRouteSimulation {
+            id_route: 0,
+            pool_address: String::new(),
+            dex_label: DexLabel::RAYDIUM,
+            token_0to1: true,
+            token_in: token_in.to_string(),
+            token_out: token_out.to_string(),
+            amount_in,
+            estimated_amount_out: amount_out.to_string(),
+            estimated_min_amount_out: amount_out.to_string(),
+        }
+    }
+
+    fn path(routes: Vec<SwapRouteSimulation>) -> SwapPathResult {
+        let amount_in = routes[0].amount_in;
+        SwapPathResult {
+            path_id: 0,
+            hops: routes.len() as u8,
+            tokens_path: String::new(),
+            route_simulations: routes,
+            token_in: SOL.to_string(),
+            token_in_symbol: String::new(),
+            token_out: SOL.to_string(),
+            token_out_symbol: String::new(),
+            amount_in,
+            estimated_amount_out: "0".to_string(),
+            estimated_min_amount_out: "0".to_string(),
+            result: 0.0,
+        }
+    }
+
+    #[test]
+    fn round_trip_larger_than_the_cap_passes() {
+        let limiter = ExposureLimiter::new(SOL, 2_000_000_000);
+        let spr = path(vec![route(SOL, TOKEN, 3_500_000_000, 7_000_000), route(TOKEN, SOL, 7_000_000, 3_510_000_000)]);
+        assert!(limiter.can_trade(&spr));
+    }
+
+    #[test]
+    fn held_inventory_over_the_cap_refuses() {
+        let limiter = ExposureLimiter::new(SOL, 2_000_000_000);
+        limiter.holdings.lock().unwrap().insert(TOKEN.to_string(), 5_000_000);
+        let spr = path(vec![route(SOL, TOKEN, 3_500_000_000, 7_000_000), route(TOKEN, SOL, 7_000_000, 3_510_000_000)]);
+        // 500 lamports per token unit, 5M units held = 2.5 SOL
+        assert!(!limiter.can_trade(&spr));
+    }
+
+    #[test]
+    fn leftover_of_a_partial_back_leg_counts() {
+        let limiter = ExposureLimiter::new(SOL, 2_000_000_000);
+        let spr = path(vec![route(SOL, TOKEN, 3_500_000_000, 7_000_000), route(TOKEN, SOL, 2_000_000, 1_000_000_000)]);
+        // 5M units left, 2.5 SOL
+        assert!(!limiter.can_trade(&spr));
+    }
+}
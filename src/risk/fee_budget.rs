@@ -0,0 +1,161 @@
+use std::fs::{self, File};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+
+pub static FEE_BUDGET_FILE: &str = "src/risk/cache/fee_budget.json";
+// Base fee of a single-signature transaction
+pub static SIGNATURE_FEE_LAMPORTS: u64 = 5_000;
+pub static DEFAULT_DAILY_FEE_BUDGET: u64 = 500_000_000; // 0.5 SOL
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeeBudgetState {
+    // UTC day, YYYY-MM-DD
+    pub day: String,
+    pub spent_lamports: u64,
+    pub transactions: u64,
+}
+
+// Fees (base fee + priority fee + tip) spent per UTC day, persisted so a restart doesn't reset it.
+// Past throttle_ratio of the budget the priority fee is capped to the base price,
+// once exhausted nothing is sent until the next day.
+#[derive(Debug)]
+pub struct FeeBudget {
+    pub daily_budget_lamports: u64,
+    pub throttle_ratio: f64,
+    file_path: String,
+    state: Mutex<FeeBudgetState>,
+    // Spends since the last write, flushed by run_fee_budget_writer
+    dirty: AtomicBool,
+}
+
+impl FeeBudget {
+    pub fn load(file_path: &str, daily_budget_lamports: u64, throttle_ratio: f64) -> Self {
+        let state = read_state(file_path).unwrap_or_default();
+        if state.day == today() {
+            info!("⛽ Fee budget: {} / {} lamports already spent today", state.spent_lamports, daily_budget_lamports);
+        }
+        FeeBudget {
+            daily_budget_lamports,
+            throttle_ratio,
+            file_path: file_path.to_string(),
+            state: Mutex::new(state),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    pub fn spent_today(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        roll_day(&mut state);
+        state.spent_lamports
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.daily_budget_lamports.saturating_sub(self.spent_today())
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    pub fn is_throttled(&self) -> bool {
+        self.spent_today() as f64 >= self.daily_budget_lamports as f64 * self.throttle_ratio
+    }
+
+    // Priority fee to use for the next transaction
+    pub fn compute_unit_price(&self, requested: u64, base: u64) -> u64 {
+        if requested > base && self.is_throttled() {
+            warn!("⛽ Fee budget {}% spent, priority fee capped to {}", (self.throttle_ratio * 100.0) as u64, base);
+            return base;
+        }
+        requested
+    }
+
+    // Counted when the transaction is sent, whether it lands or not: an overestimate is
+    // safer than a wallet drained by transactions which never confirm
+    pub fn try_spend(&self, fee_lamports: u64) -> bool {
+        let mut state = self.state.lock().unwrap();
+        roll_day(&mut state);
+        if state.spent_lamports + fee_lamports > self.daily_budget_lamports {
+            error!("⛽ Daily fee budget exhausted ({} / {} lamports), transaction not sent", state.spent_lamports, self.daily_budget_lamports);
+            return false;
+        }
+        state.spent_lamports += fee_lamports;
+        state.transactions += 1;
+        self.dirty.store(true, Ordering::Release);
+        true
+    }
+
+    // Written only when a fee was spent since the last write
+    pub fn save_if_dirty(&self) -> Result<bool> {
+        if !self.dirty.swap(false, Ordering::AcqRel) {
+            return Ok(false);
+        }
+        let state = self.state.lock().unwrap().clone();
+        write_state(&self.file_path, &state)?;
+        Ok(true)
+    }
+}
+
+// Base fee + compute_unit_price (micro-lamports) * units + tip
+pub fn transaction_fee(compute_unit_price: u64, compute_units: u64, tip_lamports: u64) -> u64 {
+    SIGNATURE_FEE_LAMPORTS + (compute_unit_price as u128 * compute_units as u128 / 1_000_000) as u64 + tip_lamports
+}
+
+static FEE_BUDGET: OnceLock<FeeBudget> = OnceLock::new();
+
+// To call once at startup, DEFAULT_DAILY_FEE_BUDGET is used otherwise
+pub fn init_fee_budget(fee_budget: FeeBudget) {
+    info!("⛽ Daily fee budget: {} lamports", fee_budget.daily_budget_lamports);
+    let _ = FEE_BUDGET.set(fee_budget);
+}
+
+pub fn fee_budget() -> &'static FeeBudget {
+    FEE_BUDGET.get_or_init(|| FeeBudget::load(FEE_BUDGET_FILE, DEFAULT_DAILY_FEE_BUDGET, 0.8))
+}
+
+// Fee budget state written off the send path, at most once per interval
+pub async fn run_fee_budget_writer(interval: Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+        match tokio::task::spawn_blocking(|| fee_budget().save_if_dirty()).await {
+            Ok(Err(e)) => error!("❌ Unable to persist fee budget state: {:?}", e),
+            Err(e) => error!("❌ Fee budget writer panicked: {:?}", e),
+            Ok(Ok(_)) => {}
+        }
+    }
+}
+
+fn today() -> String {
+    Utc::now().format("%Y-%m-%d").to_string()
+}
+
+fn roll_day(state: &mut FeeBudgetState) {
+    let today = today();
+    if state.day != today {
+        if !state.day.is_empty() {
+            info!("⛽ Fee budget reset, {} lamports spent on {} in {} transactions", state.spent_lamports, state.day, state.transactions);
+        }
+        *state = FeeBudgetState { day: today, ..FeeBudgetState::default() };
+    }
+}
+
+fn read_state(file_path: &str) -> Result<FeeBudgetState> {
+    let file = File::open(file_path)?;
+    Ok(serde_json::from_reader(file)?)
+}
+
+fn write_state(file_path: &str, state: &FeeBudgetState) -> Result<()> {
+    if let Some(parent) = Path::new(file_path).parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, state)?;
+    Ok(())
+}
@@ -0,0 +1,273 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use anchor_spl::token::spl_token;
+use anyhow::{anyhow, Result};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig};
+use solana_program::program_pack::Pack;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::{v0, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::arbitrage::types::{Route, SwapPathResult, SwapPathSelected, SwapRouteSimulation};
+use crate::common::constants::Env;
+use crate::common::utils::from_str;
+use crate::transactions::create_transaction::construct_transaction;
+use crate::transactions::signer::{wallet_signer, SignerHandle};
+
+pub static HONEYPOT_BLACKLIST_FILE: &str = "src/risk/cache/honeypot_blacklist.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct BlacklistFile {
+    value: Vec<String>,
+}
+
+// Buy a small amount of a new token and sell it back in the same simulated transaction:
+// a failing sell or an absurd loss means the token can't be exited, the mint is blacklisted
+pub struct HoneypotDetector {
+    rpc_client: RpcClient,
+    payer: SignerHandle,
+    // Lamports of the base token used for the probe
+    pub probe_amount: u64,
+    // Max share of the probe lost in the round trip (fees + slippage)
+    pub max_loss_ratio: f64,
+    file_path: String,
+    blacklist: Mutex<HashSet<String>>,
+    checked: Mutex<HashSet<String>>,
+}
+
+impl HoneypotDetector {
+    pub fn load(file_path: &str, probe_amount: u64, max_loss_ratio: f64) -> Self {
+        let env = Env::new();
+        let blacklist: HashSet<String> = match File::open(file_path).map_err(anyhow::Error::from).and_then(|file| Ok(serde_json::from_reader::<_, BlacklistFile>(file)?)) {
+            Ok(content) => content.value.into_iter().collect(),
+            Err(_) => HashSet::new(),
+        };
+        info!("🍯 Honeypot blacklist loaded with {} mints", blacklist.len());
+        HoneypotDetector {
+            rpc_client: RpcClient::new(env.rpc_url.clone()),
+            payer: wallet_signer(),
+            probe_amount,
+            max_loss_ratio,
+            file_path: file_path.to_string(),
+            blacklist: Mutex::new(blacklist),
+            checked: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn is_blacklisted(&self, mint: &str) -> bool {
+        self.blacklist.lock().unwrap().contains(mint)
+    }
+
+    pub fn blacklist(&self, mint: &str, reason: &str) {
+        error!("🍯 Mint {} blacklisted: {}", mint, reason);
+        self.blacklist.lock().unwrap().insert(mint.to_string());
+        if let Err(e) = self.save() {
+            error!("❌ Unable to write honeypot blacklist: {:?}", e);
+        }
+    }
+
+    // Check every token bought along the paths once, drop the paths touching a blacklisted mint.
+    // A mint is marked checked only after its probe ran, a failed probe is retried on the next pass
+    pub async fn screen_paths(&self, paths: Vec<SwapPathSelected>) -> Vec<SwapPathSelected> {
+        for path in &paths {
+            let routes = &path.path.paths;
+            let base = match routes.first() {
+                Some(route) => route.tokenIn.clone(),
+                None => continue,
+            };
+            for hop in 0..routes.len() {
+                let token = &routes[hop].tokenOut;
+                if *token == base || self.checked.lock().unwrap().contains(token) || self.is_blacklisted(token) {
+                    continue;
+                }
+                match self.check_hop(&routes[..=hop]).await {
+                    Ok(_) => {
+                        self.checked.lock().unwrap().insert(token.clone());
+                    }
+                    Err(e) => error!("❌ Honeypot check failed for {}: {:?}", token, e),
+                }
+            }
+        }
+        paths
+            .into_iter()
+            .filter(|path| path.path.paths.iter().all(|route| !self.is_blacklisted(&route.tokenOut)))
+            .collect()
+    }
+
+    // Probe the token bought by the last of `routes`: the previous hops only bring the probe to it.
+    // Returns true when the token has been blacklisted
+    pub async fn check_hop(&self, routes: &[Route]) -> Result<bool> {
+        let buy_route = routes.last().ok_or(anyhow!("No route to probe"))?;
+        let token_in = from_str(&buy_route.tokenIn)?;
+        let token_in_ata = get_associated_token_address(&self.payer.pubkey(), &token_in);
+
+        // 1. Buy along the routes, one hop at a time, to know how much each next hop receives
+        let mut buys: Vec<SwapRouteSimulation> = Vec::new();
+        let mut amount_in = self.probe_amount;
+        let mut bought = 0;
+        let mut token_in_after_buy = 0;
+        for (hop, route) in routes.iter().enumerate() {
+            let out_ata = get_associated_token_address(&self.payer.pubkey(), &from_str(&route.tokenOut)?);
+            let out_before = self.token_balance(&out_ata).await;
+            buys.push(self.swap_simulation(route, true, amount_in));
+            let (buy_err, hop_balances) = self.simulate(&buys, &[out_ata, token_in_ata]).await?;
+            if let Some(err) = buy_err {
+                info!("🍯 Probe buy of {} failed, pool skipped: {}", route.tokenOut, err);
+                return Ok(false);
+            }
+            bought = hop_balances[0].saturating_sub(out_before);
+            token_in_after_buy = hop_balances[1];
+            if bought == 0 {
+                // Only the probed token is judged, an earlier hop is probed on its own
+                if hop + 1 < routes.len() {
+                    return Ok(false);
+                }
+                self.blacklist(&buy_route.tokenOut, "nothing received on buy");
+                return Ok(true);
+            }
+            if hop + 1 < routes.len() {
+                amount_in = bought;
+            }
+        }
+
+        // 2. Buys + sell back on the same pool in the same transaction
+        let mut round_trip = buys;
+        round_trip.push(self.swap_simulation(buy_route, false, bought));
+        let (round_trip_err, balances) = self.simulate(&round_trip, &[token_in_ata]).await?;
+        if let Some(err) = round_trip_err {
+            self.blacklist(&buy_route.tokenOut, &format!("sell failed: {}", err));
+            return Ok(true);
+        }
+        let returned = balances[0].saturating_sub(token_in_after_buy);
+        let lost = amount_in.saturating_sub(returned);
+        let loss_ratio = lost as f64 / amount_in as f64;
+        if loss_ratio > self.max_loss_ratio {
+            self.blacklist(&buy_route.tokenOut, &format!("{:.1}% lost on round trip", loss_ratio * 100.0));
+            return Ok(true);
+        }
+        info!("🍯 {} passed the round trip ({:.2}% lost)", buy_route.tokenOut, loss_ratio * 100.0);
+        Ok(false)
+    }
+
+    async fn token_balance(&self, token_account: &Pubkey) -> u64 {
+        match self.rpc_client.get_token_account_balance(token_account).await {
+            Ok(balance) => balance.amount.parse::<u64>().unwrap_or(0),
+            Err(_) => 0,
+        }
+    }
+
+    // Same pool, buy direction or the reverse one
+    fn swap_simulation(&self, buy_route: &Route, is_buy: bool, amount_in: u64) -> SwapRouteSimulation {
+        let (token_in, token_out) = if is_buy {
+            (buy_route.tokenIn.clone(), buy_route.tokenOut.clone())
+        } else {
+            (buy_route.tokenOut.clone(), buy_route.tokenIn.clone())
+        };
+        SwapRouteSimulation {
+            id_route: buy_route.id,
+            pool_address: buy_route.pool_address.clone(),
+            dex_label: buy_route.dex.clone(),
+            token_0to1: if is_buy { buy_route.token_0to1 } else { !buy_route.token_0to1 },
+            token_in,
+            token_out,
+            amount_in,
+            // No slippage protection, the probe measures the real output
+            estimated_amount_out: "0".to_string(),
+            estimated_min_amount_out: "0".to_string(),
+        }
+    }
+
+    // Simulate the swaps and return the error if any, and the token balances of `watched` after the transaction
+    async fn simulate(&self, swaps: &[SwapRouteSimulation], watched: &[Pubkey]) -> Result<(Option<String>, Vec<u64>)> {
+        let first = swaps.first().ok_or(anyhow!("No swap to simulate"))?;
+        let spr = SwapPathResult {
+            path_id: 0,
+            hops: swaps.len() as u8,
+            tokens_path: String::new(),
+            route_simulations: swaps.to_vec(),
+            token_in: first.token_in.clone(),
+            token_in_symbol: String::new(),
+            token_out: first.token_in.clone(),
+            token_out_symbol: String::new(),
+            amount_in: first.amount_in,
+            estimated_amount_out: "0".to_string(),
+            estimated_min_amount_out: "0".to_string(),
+            result: 0.0,
+        };
+        let swap_instructions = construct_transaction(spr).await;
+        if swap_instructions.is_empty() {
+            return Err(anyhow!("Unable to build the probe swap instructions"));
+        }
+
+        let mut instructions: Vec<Instruction> = vec![ComputeBudgetInstruction::set_compute_unit_limit(1_400_000)];
+        for swap in swaps {
+            instructions.push(create_associated_token_account_idempotent(&self.payer.pubkey(), &self.payer.pubkey(), &from_str(&swap.token_out)?, &spl_token::id()));
+        }
+        instructions.extend(swap_instructions.into_iter().map(|details| details.instruction));
+
+        let commitment_config = CommitmentConfig::confirmed();
+        let tx = VersionedTransaction::try_new(
+            VersionedMessage::V0(v0::Message::try_compile(
+                &self.payer.pubkey(),
+                &instructions,
+                &[],
+                self.rpc_client.get_latest_blockhash_with_commitment(commitment_config).await?.0,
+            )?),
+            &[&self.payer],
+        )?;
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(commitment_config),
+            accounts: Some(RpcSimulateTransactionAccountsConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                addresses: watched.iter().map(|address| address.to_string()).collect(),
+            }),
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let result = self.rpc_client.simulate_transaction_with_config(&tx, config).await?.value;
+        if let Some(err) = result.err {
+            return Ok((Some(format!("{:?}", err)), Vec::new()));
+        }
+
+        let balances: Vec<u64> = result
+            .accounts
+            .unwrap_or_default()
+            .into_iter()
+            .map(|account| {
+                account
+                    .and_then(|account| account.decode::<solana_sdk::account::Account>())
+                    .and_then(|account| spl_token::state::Account::unpack_from_slice(&account.data).ok())
+                    .map(|token_account| token_account.amount)
+                    .unwrap_or(0)
+            })
+            .collect();
+        Ok((None, balances))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = Path::new(&self.file_path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = BlacklistFile { value: self.blacklist.lock().unwrap().iter().cloned().collect() };
+        let file = File::create(&self.file_path)?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer(&mut writer, &content)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
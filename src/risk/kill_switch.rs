@@ -0,0 +1,182 @@
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+
+use crate::monitoring::notifier::{notify, NotifyEvent};
+use crate::transactions::create_transaction::SendOrSimulate;
+
+pub static KILL_SWITCH_FILE: &str = "src/risk/cache/kill_switch.json";
+// Base fee paid by a transaction which landed but failed
+pub static FAILED_TX_COST_LAMPORTS: f64 = 5_000.0;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KillSwitchState {
+    pub tripped: bool,
+    pub tripped_at: Option<String>,
+    pub reason: Option<String>,
+}
+
+// Halt all sending when the rolling 24h PnL goes under -max_daily_loss.
+// Once tripped, it stays tripped (even across restarts) until an operator re-arms it
+// with the `rearm` command, which rewrites the state file.
+#[derive(Debug)]
+pub struct KillSwitch {
+    pub max_daily_loss: f64,
+    file_path: String,
+    pnl: Mutex<VecDeque<(DateTime<Utc>, f64)>>,
+    state: Mutex<KillSwitchState>,
+}
+
+impl KillSwitch {
+    pub fn load(file_path: &str, max_daily_loss: f64) -> Self {
+        let state = read_state(file_path).unwrap_or_default();
+        if state.tripped {
+            error!("🛑 Kill switch is tripped since {:?} ({:?}), simulate only until re-armed", state.tripped_at, state.reason);
+        }
+        KillSwitch {
+            max_daily_loss,
+            file_path: file_path.to_string(),
+            pnl: Mutex::new(VecDeque::new()),
+            state: Mutex::new(state),
+        }
+    }
+
+    pub fn is_tripped(&self) -> bool {
+        self.state.lock().unwrap().tripped
+    }
+
+    // Sending is turned into simulation while the switch is tripped
+    pub fn gate(&self, simulate_or_send: SendOrSimulate) -> SendOrSimulate {
+        if self.is_tripped() {
+            return SendOrSimulate::Simulate;
+        }
+        simulate_or_send
+    }
+
+    pub fn rolling_pnl(&self) -> f64 {
+        let mut pnl = self.pnl.lock().unwrap();
+        let since = Utc::now() - chrono::Duration::hours(24);
+        while pnl.front().map(|(at, _)| *at < since).unwrap_or(false) {
+            pnl.pop_front();
+        }
+        pnl.iter().map(|(_, lamports)| lamports).sum()
+    }
+
+    pub fn record_pnl(&self, lamports: f64) {
+        self.pnl.lock().unwrap().push_back((Utc::now(), lamports));
+        let rolling_pnl = self.rolling_pnl();
+        if rolling_pnl < -self.max_daily_loss && !self.is_tripped() {
+            self.trip(&format!("24h PnL {} lamports under -{}", rolling_pnl, self.max_daily_loss));
+        }
+    }
+
+    pub fn trip(&self, reason: &str) {
+        error!("🛑 Kill switch tripped: {}", reason);
+        notify(NotifyEvent::KillSwitchTripped, "Kill switch tripped", reason);
+        let state = {
+            let mut state = self.state.lock().unwrap();
+            *state = KillSwitchState {
+                tripped: true,
+                tripped_at: Some(Utc::now().to_rfc3339()),
+                reason: Some(reason.to_string()),
+            };
+            state.clone()
+        };
+        if let Err(e) = write_state(&self.file_path, &state) {
+            error!("❌ Unable to persist kill switch state: {:?}", e);
+        }
+    }
+
+    // Pick up a re-arm written by the operator command
+    pub fn sync_from_file(&self) {
+        let file_state = match read_state(&self.file_path) {
+            Ok(state) => state,
+            Err(_) => return,
+        };
+        let mut state = self.state.lock().unwrap();
+        if state.tripped && !file_state.tripped {
+            info!("✅ Kill switch re-armed by operator, sending enabled");
+            // Losses before the re-arm don't count anymore
+            self.pnl.lock().unwrap().clear();
+            *state = file_state;
+        }
+    }
+}
+
+// Operator command: `cargo run -- rearm`
+pub fn rearm(file_path: &str) -> Result<()> {
+    let previous = read_state(file_path).unwrap_or_default();
+    write_state(file_path, &KillSwitchState::default())?;
+    info!("✅ Kill switch re-armed (was tripped: {}, reason: {:?})", previous.tripped, previous.reason);
+    Ok(())
+}
+
+pub async fn run_kill_switch_sync(kill_switch: Arc<KillSwitch>, interval: Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+        kill_switch.sync_from_file();
+    }
+}
+
+fn read_state(file_path: &str) -> Result<KillSwitchState> {
+    let file = File::open(file_path)?;
+    Ok(serde_json::from_reader(file)?)
+}
+
+fn write_state(file_path: &str, state: &KillSwitchState) -> Result<()> {
+    if let Some(parent) = Path::new(file_path).parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, state)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // State file of one test, none left from a previous run
+    fn state_file(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("mev-kill-switch-{}-{}.json", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn trips_once_the_rolling_loss_passes_the_limit() {
+        let file = state_file("trip");
+        let kill_switch = KillSwitch::load(&file, 1_000_000.0);
+        kill_switch.record_pnl(-600_000.0);
+        kill_switch.record_pnl(200_000.0);
+        kill_switch.record_pnl(-500_000.0);
+        assert!(!kill_switch.is_tripped());
+        assert_eq!(kill_switch.gate(SendOrSimulate::Send), SendOrSimulate::Send);
+        kill_switch.record_pnl(-200_000.0);
+        assert!(kill_switch.is_tripped());
+        assert_eq!(kill_switch.gate(SendOrSimulate::Send), SendOrSimulate::Simulate);
+        let _ = fs::remove_file(&file);
+    }
+
+    #[test]
+    fn stays_tripped_across_restarts_until_rearmed() {
+        let file = state_file("rearm");
+        let kill_switch = KillSwitch::load(&file, 1_000_000.0);
+        kill_switch.record_pnl(-2_000_000.0);
+        assert!(KillSwitch::load(&file, 1_000_000.0).is_tripped());
+
+        rearm(&file).unwrap();
+        kill_switch.sync_from_file();
+        assert!(!kill_switch.is_tripped());
+        // The losses before the re-arm are forgotten
+        assert_eq!(kill_switch.rolling_pnl(), 0.0);
+        let _ = fs::remove_file(&file);
+    }
+}
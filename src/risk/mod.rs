@@ -0,0 +1,10 @@
+pub mod capital;
+pub mod safety;
+pub mod honeypot;
+pub mod kill_switch;
+pub mod exposure;
+pub mod balance;
+pub mod congestion;
+pub mod authority_monitor;
+pub mod sanity;
+pub mod fee_budget;
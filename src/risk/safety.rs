@@ -0,0 +1,271 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anchor_spl::token::spl_token;
+use anyhow::{anyhow, Result};
+use log::{error, info};
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+
+use crate::common::birdeye::BirdeyeClient;
+use crate::common::constants::{BSOL_MINT, JITOSOL_MINT, MSOL_MINT, SOL_MINT, USDC_MINT, USDT_MINT};
+use crate::common::rpc_cache::{cached_mint_account, cached_token_largest_accounts};
+use crate::common::utils::from_str;
+use crate::markets::types::{DexLabel, Market};
+use quote_core::layouts::raydium_amm;
+
+pub static INCINERATOR: &str = "1nc1nerator11111111111111111111111111111111";
+// Owner of the Raydium AMM v4 vaults
+pub static RAYDIUM_AMM_AUTHORITY: &str = "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1";
+
+// What makes a token unsafe to trade
+#[derive(Debug, Clone)]
+pub struct SafetyPolicy {
+    pub reject_mint_authority: bool,
+    pub reject_freeze_authority: bool,
+    pub rejected_extensions: Vec<ExtensionType>,
+    // Max share of the supply held by the 10 largest accounts
+    pub max_top_holders_share: f64,
+    // Min share of the LP supply burned or locked, when the pool has a LP mint
+    pub min_lp_locked_ratio: Option<f64>,
+    // Owners considered as locked LP (incinerator, lockers...)
+    pub lp_lockers: HashSet<String>,
+    // Never checked
+    pub trusted_mints: HashSet<String>,
+    // Checked with the Birdeye token overview/security when the checker has a Birdeye client
+    pub min_liquidity_usd: Option<f64>,
+    pub reject_mutable_metadata: bool,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        SafetyPolicy {
+            reject_mint_authority: true,
+            reject_freeze_authority: true,
+            rejected_extensions: vec![
+                ExtensionType::TransferFeeConfig,
+                ExtensionType::TransferHook,
+                ExtensionType::PermanentDelegate,
+                ExtensionType::NonTransferable,
+                ExtensionType::DefaultAccountState,
+            ],
+            max_top_holders_share: 0.5,
+            min_lp_locked_ratio: Some(0.9),
+            lp_lockers: HashSet::from([INCINERATOR.to_string()]),
+            trusted_mints: HashSet::from([
+                SOL_MINT.to_string(),
+                USDC_MINT.to_string(),
+                USDT_MINT.to_string(),
+                MSOL_MINT.to_string(),
+                JITOSOL_MINT.to_string(),
+                BSOL_MINT.to_string(),
+            ]),
+            min_liquidity_usd: Some(10_000.0),
+            reject_mutable_metadata: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TokenSafetyReport {
+    pub mint: String,
+    pub mint_authority: Option<String>,
+    pub freeze_authority: Option<String>,
+    pub is_token_2022: bool,
+    pub extensions: Vec<String>,
+    pub top_holders_share: f64,
+    pub lp_locked_ratio: Option<f64>,
+    // From Birdeye, None without it
+    pub liquidity_usd: Option<f64>,
+    pub mutable_metadata: Option<bool>,
+    // Empty when the token is safe
+    pub rejections: Vec<String>,
+}
+
+impl TokenSafetyReport {
+    pub fn is_safe(&self) -> bool {
+        self.rejections.is_empty()
+    }
+}
+
+// Checks a mint before it's allowed into a path, results are cached for `ttl`
+pub struct TokenSafetyChecker {
+    rpc_client: RpcClient,
+    pub policy: SafetyPolicy,
+    pub ttl: Duration,
+    birdeye: Option<BirdeyeClient>,
+    cache: Mutex<HashMap<String, (Instant, TokenSafetyReport)>>,
+}
+
+impl TokenSafetyChecker {
+    pub fn new(rpc_url: String, policy: SafetyPolicy, ttl: Duration) -> Self {
+        TokenSafetyChecker {
+            rpc_client: RpcClient::new(rpc_url),
+            policy,
+            ttl,
+            birdeye: None,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_birdeye(mut self, birdeye: BirdeyeClient) -> Self {
+        self.birdeye = Some(birdeye);
+        self
+    }
+
+    // LP mint of the first Raydium AMM pool of the mint, the other venues have none
+    fn lp_mint(pools: &[Market]) -> Option<Pubkey> {
+        pools
+            .iter()
+            .filter(|pool| pool.dexLabel == DexLabel::RAYDIUM)
+            .find_map(|pool| pool.account_data.as_deref().and_then(raydium_amm).map(|amm| Pubkey::new_from_array(amm.lp_mint())))
+    }
+
+    // `pools` are the pools of the mint: their vaults are left out of the holders, their LP checked
+    pub async fn check(&self, mint: &str, pools: &[Market]) -> Result<TokenSafetyReport> {
+        if let Some((checked_at, report)) = self.cache.lock().unwrap().get(mint) {
+            if checked_at.elapsed() < self.ttl {
+                return Ok(report.clone());
+            }
+        }
+
+        let mut report = TokenSafetyReport { mint: mint.to_string(), ..TokenSafetyReport::default() };
+        let mint_pubkey = from_str(mint)?;
+        let account = cached_mint_account(&self.rpc_client, &mint_pubkey).await?;
+        report.is_token_2022 = account.owner == spl_token_2022::id();
+
+        // The base mint layout is the same for both token programs
+        let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&account.data)?;
+        report.mint_authority = Option::<Pubkey>::from(mint_state.base.mint_authority).map(|authority| authority.to_string());
+        report.freeze_authority = Option::<Pubkey>::from(mint_state.base.freeze_authority).map(|authority| authority.to_string());
+        let extensions = if report.is_token_2022 { mint_state.get_extension_types()? } else { Vec::new() };
+        report.extensions = extensions.iter().map(|extension| format!("{:?}", extension)).collect();
+
+        let supply = mint_state.base.supply;
+        report.top_holders_share = self.top_holders_share(&mint_pubkey, supply, pools).await?;
+        if let Some(lp_mint) = Self::lp_mint(pools) {
+            report.lp_locked_ratio = Some(self.lp_locked_ratio(&lp_mint).await?);
+        }
+        if let Some(birdeye) = &self.birdeye {
+            self.birdeye_checks(birdeye, mint, &mut report).await;
+        }
+
+        if self.policy.reject_mint_authority && report.mint_authority.is_some() {
+            report.rejections.push("mint authority not revoked".to_string());
+        }
+        if self.policy.reject_freeze_authority && report.freeze_authority.is_some() {
+            report.rejections.push("freeze authority not revoked".to_string());
+        }
+        for extension in extensions.iter().filter(|extension| self.policy.rejected_extensions.contains(extension)) {
+            report.rejections.push(format!("Token-2022 extension {:?}", extension));
+        }
+        if report.top_holders_share > self.policy.max_top_holders_share {
+            report.rejections.push(format!("top holders own {:.0}% of the supply", report.top_holders_share * 100.0));
+        }
+        if let (Some(min_ratio), Some(ratio)) = (self.policy.min_lp_locked_ratio, report.lp_locked_ratio) {
+            if ratio < min_ratio {
+                report.rejections.push(format!("only {:.0}% of the LP burned/locked", ratio * 100.0));
+            }
+        }
+
+        if !report.is_safe() {
+            info!("☣️  Token {} rejected: {}", mint, report.rejections.join(", "));
+        }
+        self.cache.lock().unwrap().insert(mint.to_string(), (Instant::now(), report.clone()));
+        Ok(report)
+    }
+
+    // Off-chain figures, Birdeye being down only skips them
+    async fn birdeye_checks(&self, birdeye: &BirdeyeClient, mint: &str, report: &mut TokenSafetyReport) {
+        match birdeye.token_overview(mint).await {
+            Ok(overview) => report.liquidity_usd = overview.liquidity,
+            Err(e) => error!("❌ Birdeye overview failed for {}: {:?}", mint, e),
+        }
+        match birdeye.token_security(mint).await {
+            Ok(security) => {
+                report.mutable_metadata = security.mutable_metadata;
+                // Birdeye leaves the pools and known programs out of the holders, keep the worse of both
+                report.top_holders_share = report.top_holders_share.max(security.top10_holder_percent.unwrap_or(0.0));
+            }
+            Err(e) => error!("❌ Birdeye security failed for {}: {:?}", mint, e),
+        }
+        if let (Some(min_liquidity), Some(liquidity)) = (self.policy.min_liquidity_usd, report.liquidity_usd) {
+            if liquidity < min_liquidity {
+                report.rejections.push(format!("only ${:.0} of liquidity", liquidity));
+            }
+        }
+        if self.policy.reject_mutable_metadata && report.mutable_metadata == Some(true) {
+            report.rejections.push("mutable metadata".to_string());
+        }
+    }
+
+    // Unreadable tokens are not allowed
+    pub async fn is_allowed(&self, mint: &str, pools: &[Market]) -> bool {
+        if self.policy.trusted_mints.contains(mint) {
+            return true;
+        }
+        match self.check(mint, pools).await {
+            Ok(report) => report.is_safe(),
+            Err(e) => {
+                error!("❌ Safety check failed for {}: {:?}", mint, e);
+                false
+            }
+        }
+    }
+
+    // The pool vaults (listed, or owned by a pool or the Raydium authority) hold the liquidity, not a holder
+    async fn top_holders_share(&self, mint: &Pubkey, supply: u64, pools: &[Market]) -> Result<f64> {
+        if supply == 0 {
+            return Ok(0.0);
+        }
+        let largest_accounts = cached_token_largest_accounts(&self.rpc_client, mint).await?;
+        let vaults: HashSet<&str> = pools.iter().flat_map(|pool| [pool.tokenVaultA.as_str(), pool.tokenVaultB.as_str()]).collect();
+        let mut pool_owners: HashSet<Pubkey> = pools.iter().filter_map(|pool| from_str(&pool.id).ok()).collect();
+        pool_owners.insert(from_str(RAYDIUM_AMM_AUTHORITY)?);
+
+        let candidates: Vec<(Pubkey, u64)> = largest_accounts
+            .iter()
+            .filter(|account| !vaults.contains(account.address.as_str()))
+            .filter_map(|account| Some((from_str(&account.address).ok()?, account.amount.amount.parse::<u64>().unwrap_or(0))))
+            .collect();
+        let addresses: Vec<Pubkey> = candidates.iter().map(|(address, _)| *address).collect();
+        let accounts = self.rpc_client.get_multiple_accounts(&addresses).await?;
+        let top_amount: u64 = candidates
+            .iter()
+            .zip(accounts)
+            .filter(|(_, account)| {
+                // Token account owner, the same offset for both token programs
+                let owner = account.as_ref().and_then(|account| account.data.get(32..64)).and_then(|owner| Pubkey::try_from(owner).ok());
+                !owner.is_some_and(|owner| pool_owners.contains(&owner))
+            })
+            .take(10)
+            .map(|((_, amount), _)| *amount)
+            .sum();
+        Ok(top_amount as f64 / supply as f64)
+    }
+
+    async fn lp_locked_ratio(&self, lp_mint: &Pubkey) -> Result<f64> {
+        let lp_account = cached_mint_account(&self.rpc_client, lp_mint).await?;
+        let supply = spl_token::state::Mint::unpack_from_slice(&lp_account.data)?.supply;
+        if supply == 0 {
+            // Everything burned
+            return Ok(1.0);
+        }
+        let largest_accounts = cached_token_largest_accounts(&self.rpc_client, lp_mint).await?;
+        let addresses: Vec<Pubkey> = largest_accounts.iter().filter_map(|account| from_str(&account.address).ok()).collect();
+        let accounts = self.rpc_client.get_multiple_accounts(&addresses).await?;
+
+        let mut locked: u64 = 0;
+        for account in accounts.into_iter().flatten() {
+            let token_account = spl_token::state::Account::unpack_from_slice(&account.data).map_err(|e| anyhow!("{:?}", e))?;
+            if self.policy.lp_lockers.contains(&token_account.owner.to_string()) {
+                locked += token_account.amount;
+            }
+        }
+        Ok(locked as f64 / supply as f64)
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use log::warn;
+
+use crate::arbitrage::streams::get_fresh_accounts_states;
+use crate::arbitrage::types::{SwapPathResult, SwapRouteSimulation, TokenInfos};
+use crate::markets::pyth::{pyth_oracle, PythOracle};
+use crate::markets::types::Market;
+
+// Upper bounds on a quote: an arbitrage showing a huge instantaneous profit almost always
+// comes from stale or broken pool data, not from a real opportunity
+#[derive(Debug, Clone)]
+pub struct SanityBounds {
+    // Max profit as a share of the amount in
+    pub max_profit_ratio: f64,
+    // Max gap between the price quoted by a hop and the Pyth one, widened by 3 oracle confidence intervals
+    pub max_oracle_deviation: f64,
+}
+
+impl Default for SanityBounds {
+    fn default() -> Self {
+        SanityBounds { max_profit_ratio: 0.2, max_oracle_deviation: 0.05 }
+    }
+}
+
+impl SanityBounds {
+    pub fn new(max_profit_ratio: f64) -> Self {
+        SanityBounds { max_profit_ratio, ..SanityBounds::default() }
+    }
+
+    pub fn with_max_oracle_deviation(mut self, max_oracle_deviation: f64) -> Self {
+        self.max_oracle_deviation = max_oracle_deviation;
+        self
+    }
+
+    pub fn is_plausible(&self, amount_in: u64, profit: f64) -> bool {
+        amount_in == 0 || profit / amount_in as f64 <= self.max_profit_ratio
+    }
+
+    pub fn check(&self, tokens_path: &str, amount_in: u64, profit: f64) -> bool {
+        if self.is_plausible(amount_in, profit) {
+            return true;
+        }
+        warn!(
+            "🧪 Implausible quote on {}: {} profit for {} in ({:.1}% > {:.1}%)",
+            tokens_path,
+            profit,
+            amount_in,
+            profit / amount_in as f64 * 100.0,
+            self.max_profit_ratio * 100.0
+        );
+        false
+    }
+}
+
+impl SanityBounds {
+    // Hops between two tokens priced by the oracle must quote near its price, the others are not checked
+    pub fn check_oracle(&self, spr: &SwapPathResult, tokens_infos: &HashMap<String, TokenInfos>) -> bool {
+        let Some(oracle) = pyth_oracle() else { return true };
+        self.check_routes(oracle, &spr.tokens_path, &spr.route_simulations, tokens_infos)
+    }
+
+    // Same check against any oracle, the backtest replays the prices recorded with the pools
+    pub fn check_routes(&self, oracle: &PythOracle, tokens_path: &str, routes: &[SwapRouteSimulation], tokens_infos: &HashMap<String, TokenInfos>) -> bool {
+        for route in routes {
+            let Some((deviation, confidence_ratio)) = oracle.route_deviation(route, tokens_infos) else { continue };
+            let max_deviation = self.max_oracle_deviation + 3.0 * confidence_ratio;
+            if deviation > max_deviation {
+                warn!("🔮 {} quoted {:.2}% away from the Pyth price on {} (> {:.2}%)", tokens_path, deviation * 100.0, route.pool_address, max_deviation * 100.0);
+                return false;
+            }
+        }
+        true
+    }
+}
+
+// Refetch the account data of the pools from the chain, ignoring any cached state
+pub async fn refetch_markets(markets: &[Market]) -> Vec<Market> {
+    let accounts: HashMap<String, Market> = markets.iter().map(|market| (market.id.clone(), market.clone())).collect();
+    get_fresh_accounts_states(accounts).await.into_values().collect()
+}
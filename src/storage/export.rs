@@ -0,0 +1,192 @@
+use std::fs::File;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use arrow_array::{ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+
+use crate::arbitrage::types::SwapPathResult;
+use crate::common::database::{find_trades, TradeRecord};
+use crate::storage::query::PathQuery;
+use crate::storage::{storage, Stored, TradeQuery};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub fn parse(format: &str) -> Result<Self> {
+        match format {
+            "csv" => Ok(ExportFormat::Csv),
+            "parquet" => Ok(ExportFormat::Parquet),
+            _ => Err(anyhow!("Unknown export format {}, expected csv or parquet", format)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ColumnType {
+    Int,
+    Float,
+    Text,
+}
+
+#[derive(Debug, Clone)]
+pub enum Cell {
+    Int(Option<i64>),
+    Float(f64),
+    Text(Option<String>),
+}
+
+impl Cell {
+    fn to_csv(&self) -> String {
+        match self {
+            Cell::Int(value) => value.map(|value| value.to_string()).unwrap_or_default(),
+            Cell::Float(value) => value.to_string(),
+            Cell::Text(value) => value.clone().unwrap_or_default(),
+        }
+    }
+}
+
+// One flat row per record, the same columns in CSV and Parquet
+pub trait ExportRow {
+    fn columns() -> Vec<(&'static str, ColumnType)>;
+    fn cells(&self) -> Vec<Cell>;
+}
+
+impl ExportRow for Stored<SwapPathResult> {
+    fn columns() -> Vec<(&'static str, ColumnType)> {
+        vec![
+            ("timestamp", ColumnType::Int),
+            ("path_id", ColumnType::Int),
+            ("hops", ColumnType::Int),
+            ("tokens_path", ColumnType::Text),
+            ("pools", ColumnType::Text),
+            ("token_in", ColumnType::Text),
+            ("token_out", ColumnType::Text),
+            ("amount_in", ColumnType::Int),
+            ("estimated_amount_out", ColumnType::Text),
+            ("estimated_min_amount_out", ColumnType::Text),
+            ("result", ColumnType::Float),
+        ]
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        let spr = &self.record;
+        let pools = spr.route_simulations.iter().map(|route| route.pool_address.as_str()).collect::<Vec<_>>().join(" ");
+        vec![
+            Cell::Int(Some(self.timestamp)),
+            Cell::Int(Some(spr.path_id as i64)),
+            Cell::Int(Some(spr.hops as i64)),
+            Cell::Text(Some(spr.tokens_path.clone())),
+            Cell::Text(Some(pools)),
+            Cell::Text(Some(spr.token_in.clone())),
+            Cell::Text(Some(spr.token_out.clone())),
+            Cell::Int(Some(spr.amount_in as i64)),
+            Cell::Text(Some(spr.estimated_amount_out.clone())),
+            Cell::Text(Some(spr.estimated_min_amount_out.clone())),
+            Cell::Float(spr.result),
+        ]
+    }
+}
+
+impl ExportRow for TradeRecord {
+    fn columns() -> Vec<(&'static str, ColumnType)> {
+        vec![
+            ("signature", ColumnType::Text),
+            ("timestamp", ColumnType::Int),
+            ("strategy", ColumnType::Text),
+            ("path_id", ColumnType::Int),
+            ("tokens_path", ColumnType::Text),
+            ("pools", ColumnType::Text),
+            ("fee_lamports", ColumnType::Int),
+            ("tip_lamports", ColumnType::Int),
+            ("compute_unit_price", ColumnType::Int),
+            ("compute_units", ColumnType::Int),
+            ("slot", ColumnType::Int),
+            ("status", ColumnType::Text),
+            ("error", ColumnType::Text),
+        ]
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        let pools = self.hops.iter().map(|hop| hop.pool_address.as_str()).collect::<Vec<_>>().join(" ");
+        vec![
+            Cell::Text(Some(self.signature.clone())),
+            Cell::Int(Some(self.timestamp)),
+            Cell::Text(Some(self.strategy.clone())),
+            Cell::Int(Some(self.path_id as i64)),
+            Cell::Text(Some(self.tokens_path.clone())),
+            Cell::Text(Some(pools)),
+            Cell::Int(Some(self.fee_lamports as i64)),
+            Cell::Int(Some(self.tip_lamports as i64)),
+            Cell::Int(Some(self.compute_unit_price as i64)),
+            Cell::Int(Some(self.compute_units as i64)),
+            Cell::Int(self.slot.map(|slot| slot as i64)),
+            Cell::Text(Some(format!("{:?}", self.status))),
+            Cell::Text(self.error.clone()),
+        ]
+    }
+}
+
+// Number of rows written
+pub fn export_rows<T: ExportRow>(rows: &[T], format: ExportFormat, output: &str) -> Result<usize> {
+    match format {
+        ExportFormat::Csv => write_csv(rows, output)?,
+        ExportFormat::Parquet => write_parquet(rows, output)?,
+    }
+    Ok(rows.len())
+}
+
+fn write_csv<T: ExportRow>(rows: &[T], output: &str) -> Result<()> {
+    let mut writer = csv::Writer::from_path(output)?;
+    writer.write_record(T::columns().iter().map(|(name, _)| *name))?;
+    for row in rows {
+        writer.write_record(row.cells().iter().map(|cell| cell.to_csv()))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_parquet<T: ExportRow>(rows: &[T], output: &str) -> Result<()> {
+    let columns = T::columns();
+    let cells: Vec<Vec<Cell>> = rows.iter().map(|row| row.cells()).collect();
+    let mut fields = Vec::with_capacity(columns.len());
+    let mut arrays: Vec<ArrayRef> = Vec::with_capacity(columns.len());
+    for (index, (name, column_type)) in columns.iter().enumerate() {
+        let column = cells.iter().map(|row| &row[index]);
+        let (data_type, array): (DataType, ArrayRef) = match column_type {
+            ColumnType::Int => (DataType::Int64, Arc::new(column.map(|cell| match cell { Cell::Int(value) => *value, _ => None }).collect::<Int64Array>())),
+            ColumnType::Float => (DataType::Float64, Arc::new(column.map(|cell| match cell { Cell::Float(value) => Some(*value), _ => None }).collect::<Float64Array>())),
+            ColumnType::Text => (DataType::Utf8, Arc::new(column.map(|cell| match cell { Cell::Text(value) => value.clone(), _ => None }).collect::<StringArray>())),
+        };
+        fields.push(Field::new(*name, data_type, true));
+        arrays.push(array);
+    }
+    let schema = Arc::new(Schema::new(fields));
+    let batch = RecordBatch::try_new(schema.clone(), arrays)?;
+    let mut writer = ArrowWriter::try_new(File::create(output)?, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+// Simulated paths written in the date range, oldest first
+pub async fn export_swap_path_results(collection_name: &str, query: &PathQuery, format: ExportFormat, output: &str) -> Result<usize> {
+    let since = query.since_timestamp.unwrap_or(0);
+    let until = query.until_timestamp.unwrap_or(i64::MAX);
+    let results = storage()?.find_swap_path_results(collection_name, since, until).await?;
+    export_rows(&results, format, output)
+}
+
+// Trades ledger in the date range, oldest first
+pub async fn export_trades(query: &PathQuery, format: ExportFormat, output: &str) -> Result<usize> {
+    let until = query.until_timestamp.unwrap_or(i64::MAX);
+    let mut trades = find_trades(TradeQuery { since_timestamp: query.since_timestamp.unwrap_or(0), ..TradeQuery::default() }).await?;
+    trades.retain(|trade| trade.timestamp <= until);
+    trades.sort_by_key(|trade| trade.timestamp);
+    export_rows(&trades, format, output)
+}
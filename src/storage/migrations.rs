@@ -0,0 +1,74 @@
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::storage::Storage;
+
+pub static MIGRATIONS_TABLE: &str = "schema_migrations";
+
+// One schema change, applied once per database in version order
+#[derive(Debug)]
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    // Postgres and SQLite statements
+    pub sql: &'static [&'static str],
+    // MongoDB (collection, field) ascending indexes
+    pub mongo_indexes: &'static [(&'static str, &'static str)],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedMigration {
+    pub version: u32,
+    pub name: String,
+    // Unix seconds
+    pub applied_at: i64,
+}
+
+// Append only: never edit a migration once released
+pub static MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial schema",
+        sql: &[
+            "CREATE TABLE IF NOT EXISTS documents (collection TEXT NOT NULL, timestamp BIGINT NOT NULL, slot BIGINT, document TEXT NOT NULL)",
+            "CREATE INDEX IF NOT EXISTS documents_collection ON documents (collection, timestamp)",
+            "CREATE TABLE IF NOT EXISTS trades (signature TEXT NOT NULL, timestamp BIGINT NOT NULL, strategy TEXT NOT NULL, status TEXT NOT NULL, pools TEXT NOT NULL, document TEXT NOT NULL)",
+            "CREATE INDEX IF NOT EXISTS trades_timestamp ON trades (timestamp)",
+        ],
+        mongo_indexes: &[("trades", "timestamp"), ("pnl", "timestamp")],
+    },
+    Migration {
+        version: 2,
+        name: "lookup indexes",
+        sql: &[
+            "CREATE INDEX IF NOT EXISTS documents_slot ON documents (collection, slot)",
+            "CREATE INDEX IF NOT EXISTS trades_signature ON trades (signature)",
+        ],
+        mongo_indexes: &[("trades", "signature"), ("submissions", "slot")],
+    },
+];
+
+pub async fn pending_migrations(storage: &dyn Storage) -> Result<Vec<&'static Migration>> {
+    let applied = storage.applied_migrations().await?;
+    Ok(MIGRATIONS.iter().filter(|migration| !applied.contains(&migration.version)).collect())
+}
+
+// Number of migrations applied
+pub async fn run_migrations(storage: &dyn Storage) -> Result<usize> {
+    let pending = pending_migrations(storage).await?;
+    for migration in pending.iter() {
+        info!("🗄️ Migration {} ({}) on {}", migration.version, migration.name, storage.name());
+        storage.apply_migration(migration).await?;
+    }
+    Ok(pending.len())
+}
+
+// Refuse to run on a schema older than the code
+pub async fn check_schema(storage: &dyn Storage) -> Result<()> {
+    let pending = pending_migrations(storage).await?;
+    if let Some(migration) = pending.first() {
+        return Err(anyhow!("{} pending migrations from version {} ({}), run `migrate` first", pending.len(), migration.version, migration.name));
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+pub mod export;
+pub mod migrations;
+pub mod mongo;
+pub mod query;
+pub mod sql;
+
+use std::sync::{Arc, OnceLock};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use log::info;
+use serde::Serialize;
+
+use crate::arbitrage::experiments::ExperimentReport;
+use crate::arbitrage::research::ResearchRecord;
+use crate::arbitrage::submissions::SubmissionFingerprint;
+use crate::arbitrage::types::{SwapPathResult, VecSwapPathSelected};
+use crate::common::constants::Env;
+use crate::common::database::{TradeRecord, TradeStatus};
+use crate::monitoring::daily_summary::DailySummary;
+use crate::monitoring::landing::LandingRecord;
+use crate::monitoring::pnl::PnlRecord;
+use crate::storage::migrations::Migration;
+use crate::storage::mongo::MongoStorage;
+use crate::storage::sql::SqlStorage;
+
+pub static DEFAULT_DATABASE_URL: &str = "mongodb://localhost:27017";
+pub static DEFAULT_DATABASE_NAME: &str = "MEV_Bot";
+
+// Filters of the trades ledger queries, unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct TradeQuery {
+    pub since_timestamp: i64,
+    pub strategy: Option<String>,
+    pub status: Option<TradeStatus>,
+    pub pool_address: Option<String>,
+}
+
+// A record with the time it was written
+#[derive(Debug, Clone, Serialize)]
+pub struct Stored<T> {
+    // Unix seconds
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub record: T,
+}
+
+// Everything the bot persists, whatever the database behind it
+#[async_trait]
+pub trait Storage: Send + Sync {
+    fn name(&self) -> String;
+
+    // Versions already applied, see storage::migrations
+    async fn applied_migrations(&self) -> Result<Vec<u32>>;
+    async fn apply_migration(&self, migration: &Migration) -> Result<()>;
+
+    async fn insert_swap_path_result(&self, collection_name: &str, sp_result: SwapPathResult) -> Result<()>;
+    async fn insert_best_paths(&self, collection_name: &str, best_paths: VecSwapPathSelected) -> Result<()>;
+    // Written between since and until, Unix seconds inclusive
+    async fn find_swap_path_results(&self, collection_name: &str, since_timestamp: i64, until_timestamp: i64) -> Result<Vec<Stored<SwapPathResult>>>;
+    async fn find_best_paths(&self, collection_name: &str, since_timestamp: i64, until_timestamp: i64) -> Result<Vec<Stored<VecSwapPathSelected>>>;
+    async fn insert_experiment_report(&self, collection_name: &str, report: ExperimentReport) -> Result<()>;
+    async fn insert_submission_fingerprint(&self, collection_name: &str, fingerprint: SubmissionFingerprint) -> Result<()>;
+    async fn find_submission_fingerprints_since(&self, collection_name: &str, since_slot: u64) -> Result<Vec<SubmissionFingerprint>>;
+    async fn insert_research_record(&self, collection_name: &str, record: ResearchRecord) -> Result<()>;
+    async fn insert_landing_record(&self, collection_name: &str, record: LandingRecord) -> Result<()>;
+    async fn insert_daily_summary(&self, collection_name: &str, summary: DailySummary) -> Result<()>;
+    async fn insert_pnl_record(&self, collection_name: &str, record: PnlRecord) -> Result<()>;
+    async fn find_pnl_records_since(&self, collection_name: &str, since_timestamp: i64) -> Result<Vec<PnlRecord>>;
+    async fn insert_trade(&self, trade: TradeRecord) -> Result<()>;
+    async fn find_trade_by_signature(&self, signature: &str) -> Result<Option<TradeRecord>>;
+    async fn find_trades(&self, query: TradeQuery) -> Result<Vec<TradeRecord>>;
+}
+
+// Backend picked from the URL scheme: mongodb://, postgres:// or sqlite:
+pub async fn connect_storage(database_url: &str, database_name: &str) -> Result<Arc<dyn Storage>> {
+    let storage: Arc<dyn Storage> = if database_url.starts_with("mongodb://") || database_url.starts_with("mongodb+srv://") {
+        Arc::new(MongoStorage::connect(database_url, database_name).await?)
+    } else if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") || database_url.starts_with("sqlite:") {
+        Arc::new(SqlStorage::connect(database_url).await?)
+    } else {
+        return Err(anyhow!("Unsupported database URL scheme: {}", database_url.split(':').next().unwrap_or_default()));
+    };
+    info!("🗄️ Storage on {}", storage.name());
+    Ok(storage)
+}
+
+// DATABASE_URL and DATABASE_NAME, MongoDB on localhost when unset
+pub async fn connect_storage_from_env() -> Result<Arc<dyn Storage>> {
+    let env = Env::new();
+    let database_url = if env.database_url.is_empty() { DEFAULT_DATABASE_URL.to_string() } else { env.database_url };
+    let database_name = if env.database_name.is_empty() { DEFAULT_DATABASE_NAME.to_string() } else { env.database_name };
+    connect_storage(&database_url, &database_name).await
+}
+
+static STORAGE: OnceLock<Arc<dyn Storage>> = OnceLock::new();
+
+// To call once at startup, before anything is persisted
+pub fn init_storage(storage: Arc<dyn Storage>) {
+    let _ = STORAGE.set(storage);
+}
+
+pub fn storage() -> Result<&'static Arc<dyn Storage>> {
+    STORAGE.get().ok_or_else(|| anyhow!("Storage not initialized"))
+}
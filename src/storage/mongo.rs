@@ -0,0 +1,169 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::TryStreamExt;
+use log::info;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, from_document, Document};
+use serde::de::DeserializeOwned;
+use mongodb::{Client, Collection, Database, IndexModel};
+
+use crate::arbitrage::experiments::ExperimentReport;
+use crate::arbitrage::research::ResearchRecord;
+use crate::arbitrage::submissions::SubmissionFingerprint;
+use crate::arbitrage::types::{SwapPathResult, VecSwapPathSelected};
+use crate::common::database::{TradeRecord, TRADES_COLLECTION};
+use crate::monitoring::daily_summary::DailySummary;
+use crate::monitoring::landing::LandingRecord;
+use crate::monitoring::pnl::PnlRecord;
+use crate::storage::migrations::{AppliedMigration, Migration, MIGRATIONS_TABLE};
+use crate::storage::{Storage, Stored, TradeQuery};
+
+// One collection per record type, as the bot always did
+pub struct MongoStorage {
+    url: String,
+    db: Database,
+}
+
+impl MongoStorage {
+    pub async fn connect(url: &str, database_name: &str) -> Result<Self> {
+        let client = Client::with_uri_str(url).await?;
+        // Never log the credentials of a MongoDB URL
+        let url = url.split('@').last().unwrap_or(url).to_string();
+        Ok(MongoStorage { url, db: client.database(database_name) })
+    }
+
+    fn trades(&self) -> Collection<TradeRecord> {
+        self.db.collection::<TradeRecord>(TRADES_COLLECTION)
+    }
+
+    // These documents carry no timestamp, the ObjectId creation time is used instead
+    async fn find_stored<T: DeserializeOwned>(&self, collection_name: &str, since_timestamp: i64, until_timestamp: i64) -> Result<Vec<Stored<T>>> {
+        let filter = doc! { "_id": { "$gte": object_id_at(since_timestamp), "$lt": object_id_at(until_timestamp.saturating_add(1)) } };
+        let cursor = self.db.collection::<Document>(collection_name).find(filter, None).await?;
+        let documents: Vec<Document> = cursor.try_collect().await?;
+        let mut stored = Vec::with_capacity(documents.len());
+        for document in documents {
+            let timestamp = document.get_object_id("_id")?.timestamp().timestamp_millis() / 1000;
+            stored.push(Stored { timestamp, record: from_document(document)? });
+        }
+        Ok(stored)
+    }
+}
+
+// Smallest ObjectId generated at that second
+fn object_id_at(timestamp: i64) -> ObjectId {
+    let mut bytes = [0u8; 12];
+    bytes[..4].copy_from_slice(&(timestamp.clamp(0, u32::MAX as i64) as u32).to_be_bytes());
+    ObjectId::from_bytes(bytes)
+}
+
+#[async_trait]
+impl Storage for MongoStorage {
+    fn name(&self) -> String {
+        format!("MongoDB {}", self.url)
+    }
+
+    async fn applied_migrations(&self) -> Result<Vec<u32>> {
+        let cursor = self.db.collection::<AppliedMigration>(MIGRATIONS_TABLE).find(doc! {}, None).await?;
+        let applied: Vec<AppliedMigration> = cursor.try_collect().await?;
+        Ok(applied.iter().map(|migration| migration.version).collect())
+    }
+
+    async fn apply_migration(&self, migration: &Migration) -> Result<()> {
+        for (collection_name, field) in migration.mongo_indexes {
+            let index = IndexModel::builder().keys(doc! { *field: 1 }).build();
+            self.db.collection::<Document>(collection_name).create_index(index, None).await?;
+        }
+        let applied = AppliedMigration { version: migration.version, name: migration.name.to_string(), applied_at: Utc::now().timestamp() };
+        self.db.collection::<AppliedMigration>(MIGRATIONS_TABLE).insert_one(applied, None).await?;
+        Ok(())
+    }
+
+    async fn insert_swap_path_result(&self, collection_name: &str, sp_result: SwapPathResult) -> Result<()> {
+        self.db.collection::<SwapPathResult>(collection_name).insert_one(sp_result, None).await?;
+        info!("📊 {} writed in DB", collection_name);
+        Ok(())
+    }
+
+    async fn insert_best_paths(&self, collection_name: &str, best_paths: VecSwapPathSelected) -> Result<()> {
+        self.db.collection::<VecSwapPathSelected>(collection_name).insert_one(best_paths, None).await?;
+        info!("📊 {} writed in DB", collection_name);
+        Ok(())
+    }
+
+    async fn find_swap_path_results(&self, collection_name: &str, since_timestamp: i64, until_timestamp: i64) -> Result<Vec<Stored<SwapPathResult>>> {
+        self.find_stored(collection_name, since_timestamp, until_timestamp).await
+    }
+
+    async fn find_best_paths(&self, collection_name: &str, since_timestamp: i64, until_timestamp: i64) -> Result<Vec<Stored<VecSwapPathSelected>>> {
+        self.find_stored(collection_name, since_timestamp, until_timestamp).await
+    }
+
+    async fn insert_experiment_report(&self, collection_name: &str, report: ExperimentReport) -> Result<()> {
+        self.db.collection::<ExperimentReport>(collection_name).insert_one(report, None).await?;
+        info!("📊 {} writed in DB", collection_name);
+        Ok(())
+    }
+
+    async fn insert_submission_fingerprint(&self, collection_name: &str, fingerprint: SubmissionFingerprint) -> Result<()> {
+        self.db.collection::<SubmissionFingerprint>(collection_name).insert_one(fingerprint, None).await?;
+        Ok(())
+    }
+
+    async fn find_submission_fingerprints_since(&self, collection_name: &str, since_slot: u64) -> Result<Vec<SubmissionFingerprint>> {
+        let coll = self.db.collection::<SubmissionFingerprint>(collection_name);
+        let cursor = coll.find(doc! { "slot": { "$gte": since_slot as i64 } }, None).await?;
+        Ok(cursor.try_collect().await?)
+    }
+
+    async fn insert_research_record(&self, collection_name: &str, record: ResearchRecord) -> Result<()> {
+        self.db.collection::<ResearchRecord>(collection_name).insert_one(record, None).await?;
+        Ok(())
+    }
+
+    async fn insert_landing_record(&self, collection_name: &str, record: LandingRecord) -> Result<()> {
+        self.db.collection::<LandingRecord>(collection_name).insert_one(record, None).await?;
+        Ok(())
+    }
+
+    async fn insert_daily_summary(&self, collection_name: &str, summary: DailySummary) -> Result<()> {
+        self.db.collection::<DailySummary>(collection_name).insert_one(summary, None).await?;
+        Ok(())
+    }
+
+    async fn insert_pnl_record(&self, collection_name: &str, record: PnlRecord) -> Result<()> {
+        self.db.collection::<PnlRecord>(collection_name).insert_one(record, None).await?;
+        Ok(())
+    }
+
+    async fn find_pnl_records_since(&self, collection_name: &str, since_timestamp: i64) -> Result<Vec<PnlRecord>> {
+        let coll = self.db.collection::<PnlRecord>(collection_name);
+        let cursor = coll.find(doc! { "timestamp": { "$gte": since_timestamp } }, None).await?;
+        Ok(cursor.try_collect().await?)
+    }
+
+    async fn insert_trade(&self, trade: TradeRecord) -> Result<()> {
+        self.trades().insert_one(trade, None).await?;
+        Ok(())
+    }
+
+    async fn find_trade_by_signature(&self, signature: &str) -> Result<Option<TradeRecord>> {
+        Ok(self.trades().find_one(doc! { "signature": signature }, None).await?)
+    }
+
+    async fn find_trades(&self, query: TradeQuery) -> Result<Vec<TradeRecord>> {
+        let mut filter: Document = doc! { "timestamp": { "$gte": query.since_timestamp } };
+        if let Some(strategy) = query.strategy {
+            filter.insert("strategy", strategy);
+        }
+        if let Some(status) = query.status {
+            filter.insert("status", format!("{:?}", status));
+        }
+        if let Some(pool_address) = query.pool_address {
+            filter.insert("hops.pool_address", pool_address);
+        }
+        let cursor = self.trades().find(filter, None).await?;
+        Ok(cursor.try_collect().await?)
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDate, Utc};
+
+use crate::arbitrage::types::{SwapPathResult, SwapPathSelected};
+use crate::storage::{storage, Stored};
+
+pub static SWAP_PATH_RESULTS_COLLECTION: &str = "optimism_transactions";
+pub static BEST_PATHS_COLLECTION: &str = "best_paths_selected";
+
+// Filters over the stored best paths and simulation results, unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct PathQuery {
+    // Symbol or mint, on any hop
+    pub token: Option<String>,
+    pub since_timestamp: Option<i64>,
+    pub until_timestamp: Option<i64>,
+    pub min_profit: Option<f64>,
+    pub limit: Option<usize>,
+}
+
+impl PathQuery {
+    // --token SOL --since 2024-10-01 --until 2024-10-31 --min-profit 100000 --limit 50, other flags are left to the caller
+    pub fn from_flags(flags: &HashMap<String, String>) -> Result<Self> {
+        let mut query = PathQuery::default();
+        for (flag, value) in flags {
+            match flag.as_str() {
+                "token" => query.token = Some(value.clone()),
+                "since" => query.since_timestamp = Some(parse_date(value)?),
+                // The whole day is included
+                "until" => query.until_timestamp = Some(parse_date(value)? + 24 * 60 * 60 - 1),
+                "min-profit" => query.min_profit = Some(value.parse()?),
+                "limit" => query.limit = Some(value.parse()?),
+                _ => {}
+            }
+        }
+        Ok(query)
+    }
+
+    fn since(&self) -> i64 {
+        self.since_timestamp.unwrap_or(0)
+    }
+
+    fn until(&self) -> i64 {
+        self.until_timestamp.unwrap_or_else(|| Utc::now().timestamp())
+    }
+
+    fn matches_token(&self, tokens: &[&str]) -> bool {
+        match &self.token {
+            Some(token) => tokens.iter().any(|candidate| candidate.eq_ignore_ascii_case(token)),
+            None => true,
+        }
+    }
+
+    fn matches_profit(&self, profit: f64) -> bool {
+        self.min_profit.map_or(true, |min_profit| profit >= min_profit)
+    }
+
+    fn truncate<T>(&self, mut items: Vec<T>) -> Vec<T> {
+        if let Some(limit) = self.limit {
+            items.truncate(limit);
+        }
+        items
+    }
+}
+
+// --key value pairs
+pub fn parse_flags(args: &[String]) -> Result<HashMap<String, String>> {
+    let mut flags = HashMap::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let flag = arg.strip_prefix("--").ok_or_else(|| anyhow!("Expected a --flag, got {}", arg))?;
+        let value = args.next().ok_or_else(|| anyhow!("Missing value for --{}", flag))?;
+        flags.insert(flag.to_string(), value.clone());
+    }
+    Ok(flags)
+}
+
+// YYYY-MM-DD, midnight UTC
+pub fn parse_date(date: &str) -> Result<i64> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|e| anyhow!("Invalid date {}: {}", date, e))?;
+    Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp())
+}
+
+// Simulated paths, most profitable first
+pub async fn query_swap_path_results(collection_name: &str, query: &PathQuery) -> Result<Vec<Stored<SwapPathResult>>> {
+    let stored = storage()?.find_swap_path_results(collection_name, query.since(), query.until()).await?;
+    let mut results: Vec<Stored<SwapPathResult>> = stored
+        .into_iter()
+        .filter(|stored| query.matches_profit(stored.record.result))
+        .filter(|stored| {
+            let spr = &stored.record;
+            let mut tokens: Vec<&str> = spr.tokens_path.split('-').collect();
+            tokens.extend(spr.route_simulations.iter().flat_map(|route| [route.token_in.as_str(), route.token_out.as_str()]));
+            query.matches_token(&tokens)
+        })
+        .collect();
+    results.sort_by(|a, b| b.record.result.total_cmp(&a.record.result));
+    Ok(query.truncate(results))
+}
+
+// Every selected path of the stored best paths files, most profitable first
+pub async fn query_best_paths(collection_name: &str, query: &PathQuery) -> Result<Vec<Stored<SwapPathSelected>>> {
+    let stored = storage()?.find_best_paths(collection_name, query.since(), query.until()).await?;
+    let mut paths: Vec<Stored<SwapPathSelected>> = stored
+        .into_iter()
+        .flat_map(|stored| stored.record.value.into_iter().map(move |record| Stored { timestamp: stored.timestamp, record }))
+        .filter(|stored| query.matches_profit(stored.record.result))
+        .filter(|stored| {
+            let tokens: Vec<&str> = stored.record.path.paths.iter().flat_map(|route| [route.tokenIn.as_str(), route.tokenOut.as_str()]).collect();
+            query.matches_token(&tokens)
+        })
+        .collect();
+    paths.sort_by(|a, b| b.record.result.total_cmp(&a.record.result));
+    Ok(query.truncate(paths))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn flags_into_a_query() {
+        let flags = parse_flags(&args(&["--token", "SOL", "--since", "2024-10-01", "--until", "2024-10-01", "--limit", "2"])).unwrap();
+        let query = PathQuery::from_flags(&flags).unwrap();
+        assert_eq!(query.since(), 1_727_740_800);
+        // The whole day is included
+        assert_eq!(query.until(), 1_727_740_800 + 86_399);
+        assert!(query.matches_token(&["USDC", "sol"]));
+        assert!(!query.matches_token(&["USDC"]));
+        assert_eq!(query.truncate(vec![1, 2, 3]), vec![1, 2]);
+        assert!(query.matches_profit(-1.0));
+    }
+
+    #[test]
+    fn malformed_flags_are_rejected() {
+        assert!(parse_flags(&args(&["token", "SOL"])).is_err());
+        assert!(parse_flags(&args(&["--token"])).is_err());
+        assert!(parse_date("01/10/2024").is_err());
+    }
+}
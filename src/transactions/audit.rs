@@ -0,0 +1,212 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use solana_sdk::bs58;
+use solana_sdk::hash::{hashv, Hash};
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::{Signer, SignerError};
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::transactions::signer::SignerHandle;
+
+pub static AUDIT_LOG_FILE: &str = "src/transactions/cache/audit_log.jsonl";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub index: u64,
+    pub timestamp: String,
+    // "swap", "ata_extend_lut", "sweep"...
+    pub kind: String,
+    pub signer: String,
+    pub signature: String,
+    pub path_id: Option<u32>,
+    pub tokens_path: Option<String>,
+    // Simulated profit (lamports of the base token) and compute units
+    pub simulated_profit: Option<f64>,
+    pub simulated_units: Option<u64>,
+    // Base58 of the serialized signed transaction
+    pub payload: String,
+    pub prev_hash: String,
+    // sha256(prev_hash | entry without hash)
+    pub hash: String,
+}
+
+// What is known about a transaction when it's logged
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    pub kind: String,
+    pub path_id: Option<u32>,
+    pub tokens_path: Option<String>,
+    pub simulated_profit: Option<f64>,
+    pub simulated_units: Option<u64>,
+}
+
+// Append-only JSON lines, each entry hashing the previous one: editing or removing a line
+// breaks the chain from that point. The chain is built in order on the sending path, the
+// lines are written and synced by a background thread
+pub struct AuditLog {
+    // Index and hash of the last entry, along with the writer to keep the lines in chain order
+    head: Mutex<(u64, Hash, Sender<String>)>,
+}
+
+impl AuditLog {
+    pub fn open(file_path: &str) -> Result<Self> {
+        let mut head = (0, Hash::default());
+        if let Ok(file) = File::open(file_path) {
+            if let Some(line) = BufReader::new(file).lines().map_while(Result::ok).filter(|line| !line.is_empty()).last() {
+                let entry: AuditEntry = serde_json::from_str(&line)?;
+                head = (entry.index + 1, entry.hash.parse().map_err(|e| anyhow!("Invalid audit hash: {:?}", e))?);
+            }
+        }
+        if let Some(parent) = Path::new(file_path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(file_path)?;
+        let (sender, receiver) = mpsc::channel();
+        std::thread::Builder::new().name("audit_writer".to_string()).spawn(move || write_lines(file, receiver))?;
+        Ok(AuditLog { head: Mutex::new((head.0, head.1, sender)) })
+    }
+
+    // To call before broadcasting: a transaction which can't be chained must not be sent
+    pub fn record(&self, tx: &VersionedTransaction, context: AuditContext) -> Result<AuditEntry> {
+        let mut head = self.head.lock().unwrap();
+        let mut entry = AuditEntry {
+            index: head.0,
+            timestamp: Utc::now().to_rfc3339(),
+            kind: context.kind,
+            signer: tx.message.static_account_keys().first().map(|key| key.to_string()).unwrap_or_default(),
+            signature: tx.signatures.first().map(|signature| signature.to_string()).unwrap_or_default(),
+            path_id: context.path_id,
+            tokens_path: context.tokens_path,
+            simulated_profit: context.simulated_profit,
+            simulated_units: context.simulated_units,
+            payload: bs58::encode(bincode::serialize(tx)?).into_string(),
+            prev_hash: head.1.to_string(),
+            hash: String::new(),
+        };
+        entry.hash = entry_hash(&entry)?.to_string();
+
+        head.2.send(serde_json::to_string(&entry)?).map_err(|_| anyhow!("Audit writer stopped"))?;
+        head.0 = entry.index + 1;
+        head.1 = entry.hash.parse().map_err(|e| anyhow!("Invalid audit hash: {:?}", e))?;
+        Ok(entry)
+    }
+}
+
+// Lines are buffered while more are queued, flushed and synced once the queue is empty
+fn write_lines(file: File, receiver: Receiver<String>) {
+    let mut writer = BufWriter::new(file);
+    while let Ok(line) = receiver.recv() {
+        let mut result = writeln!(writer, "{}", line);
+        for line in receiver.try_iter() {
+            result = result.and_then(|_| writeln!(writer, "{}", line));
+        }
+        if let Err(e) = result.and_then(|_| writer.flush()).and_then(|_| writer.get_ref().sync_data()) {
+            error!("❌ Unable to write the audit log: {:?}", e);
+        }
+    }
+}
+
+fn entry_hash(entry: &AuditEntry) -> Result<Hash> {
+    let mut unhashed = entry.clone();
+    unhashed.hash = String::new();
+    Ok(hashv(&[entry.prev_hash.as_bytes(), serde_json::to_string(&unhashed)?.as_bytes()]))
+}
+
+// Walk the whole log, returns the number of entries or the first broken link
+pub fn verify_audit_log(file_path: &str) -> Result<u64> {
+    let file = File::open(file_path)?;
+    let mut prev_hash = Hash::default().to_string();
+    let mut count = 0;
+    for (line_number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        let entry: AuditEntry = serde_json::from_str(&line)?;
+        if entry.index != count || entry.prev_hash != prev_hash || entry_hash(&entry)?.to_string() != entry.hash {
+            return Err(anyhow!("Audit chain broken at line {} (entry {})", line_number + 1, entry.index));
+        }
+        prev_hash = entry.hash;
+        count += 1;
+    }
+    Ok(count)
+}
+
+static AUDIT_LOG: OnceLock<Option<AuditLog>> = OnceLock::new();
+
+// To call once at startup, AUDIT_LOG_FILE is used otherwise
+pub fn init_audit_log(file_path: &str) {
+    let _ = AUDIT_LOG.set(open_or_log(file_path));
+}
+
+fn open_or_log(file_path: &str) -> Option<AuditLog> {
+    match AuditLog::open(file_path) {
+        Ok(audit_log) => {
+            info!("🧾 Audit log {} opened at entry {}", file_path, audit_log.head.lock().unwrap().0);
+            Some(audit_log)
+        }
+        Err(e) => {
+            error!("❌ Unable to open the audit log {}: {:?}", file_path, e);
+            None
+        }
+    }
+}
+
+// Log a signed transaction before its broadcast
+pub fn audit_transaction(tx: &VersionedTransaction, context: AuditContext) -> Result<()> {
+    let audit_log = AUDIT_LOG
+        .get_or_init(|| open_or_log(AUDIT_LOG_FILE))
+        .as_ref()
+        .ok_or(anyhow!("Audit log unavailable, transaction not sent"))?;
+    audit_log.record(tx, context)?;
+    Ok(())
+}
+
+// The wallet signer, logging every transaction it signs: the sender re-signs with a new
+// blockhash on retries, each of those variants is audited before it can be broadcast
+pub struct AuditedSigner {
+    signer: SignerHandle,
+    context: AuditContext,
+    // Signature and blockhash of every variant signed, to track whichever lands
+    signed: Mutex<Vec<(Signature, Hash)>>,
+}
+
+impl AuditedSigner {
+    pub fn new(signer: SignerHandle, context: AuditContext) -> Self {
+        AuditedSigner { signer, context, signed: Mutex::new(Vec::new()) }
+    }
+
+    pub fn signed(&self) -> Vec<(Signature, Hash)> {
+        self.signed.lock().unwrap().clone()
+    }
+}
+
+impl Signer for AuditedSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        self.signer.try_pubkey()
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let signature = self.signer.try_sign_message(message)?;
+        let message: VersionedMessage = bincode::deserialize(message).map_err(|e| SignerError::Custom(e.to_string()))?;
+        let blockhash = *message.recent_blockhash();
+        let tx = VersionedTransaction { signatures: vec![signature], message };
+        audit_transaction(&tx, self.context.clone()).map_err(|e| SignerError::Custom(e.to_string()))?;
+        self.signed.lock().unwrap().push((signature, blockhash));
+        Ok(signature)
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
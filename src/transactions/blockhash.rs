@@ -0,0 +1,56 @@
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use log::error;
+use solana_client::client_error::ClientError;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+
+use crate::common::circuit_breaker::send_breaker;
+use crate::common::rpc_pool::nonblocking_client_with_commitment;
+
+// Past this age the prefetched blockhash is not trusted and the caller fetches one itself
+pub static BLOCKHASH_MAX_AGE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy)]
+pub struct CachedBlockhash {
+    pub blockhash: Hash,
+    pub last_valid_block_height: u64,
+    pub commitment: CommitmentConfig,
+    pub fetched_at: Instant,
+}
+
+static LATEST_BLOCKHASH: RwLock<Option<CachedBlockhash>> = RwLock::new(None);
+
+// Fresh prefetched blockhash at this commitment, None when the prefetcher is late or not running
+pub fn cached_blockhash(commitment: CommitmentConfig) -> Option<CachedBlockhash> {
+    LATEST_BLOCKHASH
+        .read()
+        .unwrap()
+        .filter(|cached| cached.commitment == commitment && cached.fetched_at.elapsed() <= BLOCKHASH_MAX_AGE)
+}
+
+// The prefetched blockhash, getLatestBlockhash only as a fallback
+pub fn latest_blockhash(rpc_client: &RpcClient, commitment: CommitmentConfig) -> Result<Hash, ClientError> {
+    match cached_blockhash(commitment) {
+        Some(cached) => Ok(cached.blockhash),
+        None => Ok(rpc_client.get_latest_blockhash_with_commitment(commitment)?.0),
+    }
+}
+
+// Refresh the blockhash from the current send endpoint every interval (~400ms, one slot)
+pub async fn run_blockhash_prefetcher(commitment: CommitmentConfig, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        ticker.tick().await;
+        let Some(rpc_url) = send_breaker().current_url() else { continue };
+        match nonblocking_client_with_commitment(&rpc_url, commitment).get_latest_blockhash_with_commitment(commitment).await {
+            Ok((blockhash, last_valid_block_height)) => {
+                *LATEST_BLOCKHASH.write().unwrap() = Some(CachedBlockhash { blockhash, last_valid_block_height, commitment, fetched_at: Instant::now() });
+            }
+            Err(e) => error!("❌ Blockhash prefetch failed on {}: {}", rpc_url, e),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use log::{error, info, warn};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::Signature;
+use tokio::sync::Notify;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmationStatus {
+    // Reached the target commitment
+    Confirmed { slot: u64 },
+    // Landed with an error, the fees are paid but nothing was swapped
+    Failed { slot: u64, error: String },
+    // Seen in a slot, then its status went missing: the slot was skipped / forked out, or the
+    // node just doesn't serve it anymore. The outcome is uncertain
+    Unknown { slot: u64 },
+    // Never seen before the blockhash expired
+    Expired,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfirmationPolicy {
+    // confirmed (fast, may very rarely roll back) or finalized
+    pub commitment: CommitmentConfig,
+    pub poll_interval: Duration,
+}
+
+impl Default for ConfirmationPolicy {
+    fn default() -> Self {
+        ConfirmationPolicy {
+            commitment: CommitmentConfig::confirmed(),
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+static CONFIRMATION_POLICY: OnceLock<ConfirmationPolicy> = OnceLock::new();
+
+// To call once at startup, the default policy (confirmed) is used otherwise
+pub fn init_confirmation_policy(policy: ConfirmationPolicy) {
+    info!("⛓️ Transactions tracked until {:?}", policy.commitment.commitment);
+    let _ = CONFIRMATION_POLICY.set(policy);
+}
+
+pub fn confirmation_policy() -> ConfirmationPolicy {
+    CONFIRMATION_POLICY.get().cloned().unwrap_or_default()
+}
+
+// Follow a signature until it reaches the target commitment or can't land anymore.
+// A signature seen processed then gone is Unknown: the caller decides whether to resend
+// it (blockhash still valid) or to reconcile the inventory.
+pub async fn track_signature(rpc_client: &RpcClient, signature: &Signature, blockhash: &Hash) -> Result<ConfirmationStatus> {
+    Ok(track_signatures(rpc_client, &[(*signature, *blockhash)]).await?.1)
+}
+
+// Same for the variants of one transaction (re-signed with a new blockhash on retries),
+// at most one of them can land: returns it with its status. Expired once every blockhash is
+pub async fn track_signatures(rpc_client: &RpcClient, variants: &[(Signature, Hash)]) -> Result<(Signature, ConfirmationStatus)> {
+    let policy = confirmation_policy();
+    let signatures: Vec<Signature> = variants.iter().map(|(signature, _)| *signature).collect();
+    let first = *signatures.first().ok_or(anyhow!("No signature to track"))?;
+    let mut seen: Option<(Signature, u64)> = None;
+    loop {
+        let statuses = rpc_client.get_signature_statuses(&signatures).await?.value;
+        let landed = signatures.iter().zip(statuses).find_map(|(signature, status)| status.map(|status| (*signature, status)));
+        match landed {
+            Some((signature, status)) => {
+                if let Some(err) = status.err.clone() {
+                    // An error is final only once the slot itself is at the target commitment
+                    if status.satisfies_commitment(policy.commitment) {
+                        return Ok((signature, ConfirmationStatus::Failed { slot: status.slot, error: format!("{:?}", err) }));
+                    }
+                }
+                if status.satisfies_commitment(policy.commitment) {
+                    return Ok((signature, ConfirmationStatus::Confirmed { slot: status.slot }));
+                }
+                if seen != Some((signature, status.slot)) {
+                    info!("⛓️ {} processed in slot {}, waiting for {:?}", signature, status.slot, policy.commitment.commitment);
+                    seen = Some((signature, status.slot));
+                }
+            }
+            None => {
+                if let Some((signature, slot)) = seen {
+                    warn!("⛓️ {} status missing after being processed in slot {}, outcome unknown", signature, slot);
+                    return Ok((signature, ConfirmationStatus::Unknown { slot }));
+                }
+                let mut expired = true;
+                for (_, blockhash) in variants {
+                    expired &= !rpc_client.is_blockhash_valid(blockhash, CommitmentConfig::processed()).await?;
+                }
+                if expired {
+                    return Ok((first, ConfirmationStatus::Expired));
+                }
+            }
+        }
+        tokio::time::sleep(policy.poll_interval).await;
+    }
+}
+
+static RECONCILE: OnceLock<Notify> = OnceLock::new();
+
+fn reconcile_notify() -> &'static Notify {
+    RECONCILE.get_or_init(Notify::new)
+}
+
+// Ask the balance/exposure refresh loops to re-read the wallet now, after a trade with an
+// uncertain outcome
+pub fn request_inventory_reconcile() {
+    error!("⛓️ Trade outcome uncertain, inventory reconcile requested");
+    reconcile_notify().notify_waiters();
+}
+
+// Wait for the interval or for a reconcile request, whichever comes first
+pub async fn wait_refresh(interval: Duration) {
+    tokio::select! {
+        _ = tokio::time::sleep(interval) => {}
+        _ = reconcile_notify().notified() => {}
+    }
+}
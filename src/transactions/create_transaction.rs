@@ -1,10 +1,10 @@
 use anchor_spl::token::spl_token;
+use chrono::Utc;
 use itertools::Itertools;
 use log::{error, info};
 use serde::{Deserialize, Serialize};
 use solana_client::{
     connection_cache::ConnectionCache,
-    rpc_client::RpcClient,
     rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig},
     send_and_confirm_transactions_in_parallel::{send_and_confirm_transactions_in_parallel, SendAndConfirmConfig},
     tpu_client::{TpuClient, TpuClientConfig},
@@ -20,7 +20,7 @@ use solana_sdk::{
     instruction::Instruction,
     message::{v0, VersionedMessage},
     pubkey::Pubkey,
-    signature::{read_keypair_file, Keypair, Signer},
+    signature::{Signature, Signer},
     transaction::VersionedTransaction,
 };
 use solana_transaction_status::UiTransactionEncoding;
@@ -28,24 +28,88 @@ use anyhow::Result;
 use spl_associated_token_account::{get_associated_token_address, instruction::create_associated_token_account};
 use std::{fs::File, io::BufReader, path::Path, sync::Arc};
 
+use crate::common::circuit_breaker::send_breaker;
 use crate::common::constants::Env;
+use crate::common::database::{TradeHop, TradeRecord, TradeStatus};
+use crate::common::feeds::{feeds, FeedKind};
+use crate::common::rate_limiter::throttle;
+use crate::common::rpc_pool::{blocking_client, nonblocking_client, nonblocking_client_with_commitment};
+use crate::common::rpc_race::rpc_racer;
 use crate::common::utils::from_str;
+use crate::monitoring::events::{publish, BotEvent};
+use crate::monitoring::latency::{Stage, StageTimestamps};
+use crate::monitoring::metrics::metrics;
+use crate::risk::fee_budget::{fee_budget, transaction_fee};
 use crate::transactions::{
     meteoradlmm_swap::{construct_meteora_instructions, SwapParametersMeteora},
     orca_whirlpool_swap::{construct_orca_whirlpool_instructions, SwapParametersOrcaWhirlpool},
+    audit::{audit_transaction, AuditContext, AuditedSigner},
+    blockhash::latest_blockhash,
+    confirmation::{confirmation_policy, request_inventory_reconcile, track_signatures, ConfirmationStatus},
+    in_flight::{in_flight_limiter, send_permit},
+    errors::SendError,
+    presigned::presigned_transactions,
+    private_send::{is_private_send, send_private, send_private_tracked, tip_instruction, tip_lamports},
+    signer::wallet_signer,
+    templates::instruction_templates,
 };
 
-pub async fn create_and_send_swap_transaction(simulate_or_send: SendOrSimulate, chain: ChainType, transaction_infos: SwapPathResult) -> Result<()> {
+// Priority fee (micro-lamports per compute unit) of the swap transactions outside congestion
+pub static BASE_COMPUTE_UNIT_PRICE: u64 = 100;
+
+// The simulation runs without the tip transfer appended afterwards, the limit leaves room for it
+pub fn compute_unit_limit(simulated_units: u64) -> u64 {
+    (simulated_units + simulated_units / 10 + 1_000).min(1_400_000)
+}
+
+// Returns true when the swap transaction has been sent and confirmed
+pub async fn create_and_send_swap_transaction(simulate_or_send: SendOrSimulate, chain: ChainType, transaction_infos: SwapPathResult, compute_unit_price: u64) -> Result<bool, SendError> {
+    create_and_send_swap_transaction_timed(simulate_or_send, chain, transaction_infos, compute_unit_price, "direct", &mut StageTimestamps::default()).await
+}
+
+// Same, recording the strategy in the trade ledger and marking the build/sign/send/land stages
+// on the opportunity timestamps
+pub async fn create_and_send_swap_transaction_timed(simulate_or_send: SendOrSimulate, chain: ChainType, transaction_infos: SwapPathResult, compute_unit_price: u64, strategy: &str, stages: &mut StageTimestamps) -> Result<bool, SendError> {
+    // Wait for a slot among the unconfirmed transactions, released once this one is tracked
+    let _in_flight = match simulate_or_send {
+        SendOrSimulate::Send => Some(in_flight_limiter().acquire().await),
+        SendOrSimulate::Simulate => None,
+    };
+    if chain == ChainType::Devnet {
+        return swap_transaction_on(Env::new().devnet_rpc_url, simulate_or_send, transaction_infos, compute_unit_price, strategy, stages).await;
+    }
+    if chain == ChainType::Localnet {
+        return swap_transaction_on(LOCALNET_RPC_URL.to_string(), simulate_or_send, transaction_infos, compute_unit_price, strategy, stages).await;
+    }
+    // Mainnet sends go through the first healthy endpoint, RPC errors count toward its breaker
+    let rpc_url = match send_breaker().current_url() {
+        Some(rpc_url) => rpc_url,
+        None => {
+            error!("🔌 No healthy RPC endpoint, swap transaction not sent");
+            return Err(SendError::NoHealthyRpc);
+        }
+    };
+    let result = swap_transaction_on(rpc_url.clone(), simulate_or_send, transaction_infos, compute_unit_price, strategy, stages).await;
+    // A route rejected by its programs is not the endpoint's fault
+    match &result {
+        Err(e) if !e.is_rpc_error() => {}
+        result => send_breaker().record(&rpc_url, result),
+    }
+    if simulate_or_send == SendOrSimulate::Send {
+        match result {
+            Ok(true) => metrics().txs_landed.inc(),
+            _ => metrics().txs_failed.inc(),
+        }
+    }
+    result
+}
+
+async fn swap_transaction_on(rpc_url: String, simulate_or_send: SendOrSimulate, transaction_infos: SwapPathResult, compute_unit_price: u64, strategy: &str, stages: &mut StageTimestamps) -> Result<bool, SendError> {
     info!("🔄 Create swap transaction.... ");
     
-    let env = Env::new();
-    let rpc_url = match chain {
-        ChainType::Mainnet => env.rpc_url_tx.clone(),
-        ChainType::Devnet => env.devnet_rpc_url.clone(),
-    };
-    let rpc_client = RpcClient::new(rpc_url);
+    let rpc_client = blocking_client(&rpc_url);
 
-    let payer = read_keypair_file(&env.payer_keypair_path).expect("Wallet keypair file not found");
+    let payer = wallet_signer();
     info!("💳 Wallet {:?}", payer.pubkey());
 
     info!("🆔 Create/Send Swap instruction....");
@@ -62,48 +126,70 @@ pub async fn create_and_send_swap_transaction(simulate_or_send: SendOrSimulate,
         market: None,
     }];
 
+    let mut audit_context = AuditContext {
+        kind: "swap".to_string(),
+        path_id: Some(transaction_infos.path_id),
+        tokens_path: Some(transaction_infos.tokens_path.clone()),
+        simulated_profit: Some(transaction_infos.result),
+        simulated_units: None,
+    };
+    let hops: Vec<TradeHop> = transaction_infos
+        .route_simulations
+        .iter()
+        .map(|route| TradeHop {
+            pool_address: route.pool_address.clone(),
+            dex: route.dex_label.clone(),
+            token_in: route.token_in.clone(),
+            token_out: route.token_out.clone(),
+            amount_in: route.amount_in,
+            amount_out: route.estimated_amount_out.clone(),
+            min_amount_out: route.estimated_min_amount_out.clone(),
+        })
+        .collect();
+    // Signature, fees and units filled in once signed
+    let mut trade = TradeRecord {
+        signature: String::new(),
+        timestamp: 0,
+        strategy: strategy.to_string(),
+        path_id: transaction_infos.path_id,
+        tokens_path: transaction_infos.tokens_path.clone(),
+        hops,
+        fee_lamports: 0,
+        tip_lamports: 0,
+        compute_unit_price: 0,
+        compute_units: 0,
+        slot: None,
+        status: TradeStatus::SendFailed,
+        error: None,
+    };
+
+    // Pre-signed at the current blockhash: no simulation nor signature on the way out
+    if simulate_or_send == SendOrSimulate::Send {
+        let compute_unit_price = fee_budget().compute_unit_price(compute_unit_price, BASE_COMPUTE_UNIT_PRICE);
+        if let Some(presigned) = presigned_transactions().and_then(|cache| cache.take(&transaction_infos, compute_unit_price)) {
+            info!("✍️ Pre-signed variant sent at {} µlamports/CU, tip {}", presigned.level.compute_unit_price, presigned.level.tip_lamports);
+            stages.mark(Stage::Build);
+            stages.mark(Stage::Sign);
+            trade.compute_unit_price = presigned.level.compute_unit_price;
+            trade.compute_units = presigned.compute_units;
+            trade.tip_lamports = presigned.level.tip_lamports;
+            return send_swap(&rpc_url, presigned.transaction, trade, audit_context, stages).await;
+        }
+    }
+
     let swaps_construct_instructions: Vec<InstructionDetails> = construct_transaction(transaction_infos).await;
     let mut swap_instructions: Vec<InstructionDetails> = vec![compute_budget_instruction, priority_fees_instruction, swaps_construct_instructions].concat();
 
     if swap_instructions.is_empty() {
         error!("Error in create_transaction(), zero instructions");
-        return Ok(());
-    }
-    
-    let mut lut_addresses: Vec<Pubkey> = Vec::new();
-    for si in swap_instructions.clone() {
-        if let Some(market_addr) = si.market.as_ref().map(|m| m.address) {
-            let (have_lut_address, lut_address) = get_lut_address_for_market(market_addr, false)?;
-            if have_lut_address {
-                if let Some(lut_addr) = lut_address {
-                    if !lut_addresses.contains(&lut_addr) {
-                        info!("LUT address {} pushed!", lut_addr);
-                        lut_addresses.push(lut_addr);
-                    }
-                }
-            } else {
-                error!("❌ No LUT address already crafted for the market {:?}", market_addr);
-            }
-        } else {
-            info!("Skip get LUT table for non swap instruction: {:?}", si.details);
-        }
+        return Ok(false);
     }
     
     let si_details: Vec<String> = swap_instructions.clone().into_iter().map(|instruc_details| instruc_details.details).collect();
     info!("📋 Swap instructions Details: {:?}", si_details);
     info!("Swap instructions: {:?}", swap_instructions);
 
-    let mut vec_address_lut: Vec<AddressLookupTableAccount> = Vec::new();
-    for lut_address in lut_addresses {
-        let raw_lut_account = rpc_client.get_account(&lut_address)?;
-        let address_lookup_table = AddressLookupTable::deserialize(&raw_lut_account.data)?;
-        let address_lookup_table_account = AddressLookupTableAccount {
-            key: lut_address,
-            addresses: address_lookup_table.addresses.to_vec(),
-        };
-        println!("Address in lookup_table: {}", address_lookup_table_account.addresses.len());
-        vec_address_lut.push(address_lookup_table_account);
-    }
+    let vec_address_lut = lookup_tables(&rpc_url, &swap_instructions).await?;
 
     let mut instructions: Vec<Instruction> = swap_instructions.into_iter().map(|instruc_details| instruc_details.instruction).collect();
     let commitment_config = CommitmentConfig::confirmed();
@@ -112,7 +198,7 @@ pub async fn create_and_send_swap_transaction(simulate_or_send: SendOrSimulate,
             &payer.pubkey(),
             &instructions,
             &vec_address_lut,
-            rpc_client.get_latest_blockhash_with_commitment(commitment_config)?.0,
+            latest_blockhash(&rpc_client, commitment_config)?,
         )?),
         &[&payer],
     )?;
@@ -123,14 +209,17 @@ pub async fn create_and_send_swap_transaction(simulate_or_send: SendOrSimulate,
         ..RpcSimulateTransactionConfig::default()
     };
     
-    let result = rpc_client.simulate_transaction_with_config(&tx, config)?.value;
-    let logs_simulation = result.logs.unwrap_or_default();
-    if logs_simulation.is_empty() {
-        error!("❌ Get out! Simulate Error: {:?}", result.err);
-        return Ok(());
-    } else {
-        info!("🧾 Simulate Tx Ata/Extend Logs: {:?}", result.logs);
+    // First answer among the send endpoints
+    let (tx_ref, config_ref) = (&tx, &config);
+    let result = rpc_racer()
+        .race(send_breaker(), &rpc_url, commitment_config, |client| async move { client.simulate_transaction_with_config(tx_ref, config_ref.clone()).await })
+        .await?
+        .value;
+    if let Some(err) = result.err {
+        error!("❌ Get out! Simulate Error: {:?}", err);
+        return Err(SendError::from_simulation(err));
     }
+    info!("🧾 Simulate Tx Logs: {:?}", result.logs);
 
     let result_cu: u64 = result.units_consumed.unwrap_or(150_000);
     info!("🔢 Computed Units: {}", result_cu);
@@ -139,71 +228,208 @@ pub async fn create_and_send_swap_transaction(simulate_or_send: SendOrSimulate,
     let average_fees = average(fees.iter().map(|iter| iter.prioritization_fee).collect());
     info!("🔢 Average Prioritization fees price: {}", average_fees);
 
-    let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(result_cu as u32);
-    let priority_fees_ix = ComputeBudgetInstruction::set_compute_unit_price(100);
+    let compute_unit_price = fee_budget().compute_unit_price(compute_unit_price, BASE_COMPUTE_UNIT_PRICE);
+    let compute_unit_limit = compute_unit_limit(result_cu);
+    let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit as u32);
+    let priority_fees_ix = ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price);
     instructions[0] = priority_fees_ix;
     instructions[1] = compute_budget_ix;
 
     if simulate_or_send == SendOrSimulate::Send {
-        let transaction_config = RpcSendTransactionConfig {
-            skip_preflight: true,
-            preflight_commitment: Some(CommitmentLevel::Confirmed),
-            encoding: Some(UiTransactionEncoding::Base58),
-            max_retries: Some(0),
-            min_context_slot: None,
-        };
- 
-        let new_payer = read_keypair_file(&env.payer_keypair_path).expect("Wallet keypair file not found");
+        stages.mark(Stage::Build);
+        let new_payer = wallet_signer();
+        if let Some(tip_ix) = tip_instruction(&new_payer.pubkey()) {
+            instructions.push(tip_ix);
+        }
         let txn = VersionedTransaction::try_new(
             VersionedMessage::V0(v0::Message::try_compile(
                 &new_payer.pubkey(),
                 &instructions,
                 &vec_address_lut,
-                rpc_client.get_latest_blockhash_with_commitment(commitment_config)?.0,
+                latest_blockhash(&rpc_client, commitment_config)?,
             )?),
             &[&new_payer],
         )?;
-        
-        let non_blocking_rpc_client = solana_client::nonblocking::rpc_client::RpcClient::new(env.rpc_url_tx.clone());
-        let arc_rpc_client = Arc::new(non_blocking_rpc_client);
-        let connection_cache = ConnectionCache::new_quic("connection_cache_cli_program_quic", 1);
-        let signer: [Arc<dyn Signer>; 1] = [Arc::new(new_payer)];
+        stages.mark(Stage::Sign);
+        trade.compute_unit_price = compute_unit_price;
+        trade.compute_units = compute_unit_limit;
+        trade.tip_lamports = tip_lamports();
+        return send_swap(&rpc_url, txn, trade, audit_context, stages).await;
+    }
+    Ok(false)
+}
 
-        let iteration_number = 2;
-        let transaction_errors = if let ConnectionCache::Quic(cache) = connection_cache {
-            let tpu_client = TpuClient::new_with_connection_cache(
-                arc_rpc_client.clone(),
-                &env.wss_rpc_url,
-                TpuClientConfig::default(),
-                cache,
-            )
-            .await?;
-            send_and_confirm_transactions_in_parallel(
-                arc_rpc_client,
-                Some(tpu_client),
-                &[txn],
-                &signer,
-                SendAndConfirmConfig {
-                    resign_txs_count: Some(iteration_number),
-                    with_spinner: true,
-                },
-            )
-            .await
-            .map_err(|err| {
-                error!("Transaction send failed: {}", err);
-                format!("Data writes to account failed: {}", err)
-            })?
-            .into_iter()
-            .map(|err| format!("Data writes to account failed: {:?}", err))
-            .collect::<String>()
+// Lookup tables of the markets of the swap instructions, read in one call raced across the send endpoints
+pub async fn lookup_tables(rpc_url: &str, swap_instructions: &[InstructionDetails]) -> Result<Vec<AddressLookupTableAccount>, SendError> {
+    let mut lut_addresses: Vec<Pubkey> = Vec::new();
+    for si in swap_instructions {
+        if let Some(market_addr) = si.market.as_ref().map(|m| m.address) {
+            let (have_lut_address, lut_address) = get_lut_address_for_market(market_addr, false)?;
+            if have_lut_address {
+                if let Some(lut_addr) = lut_address {
+                    if !lut_addresses.contains(&lut_addr) {
+                        info!("LUT address {} pushed!", lut_addr);
+                        lut_addresses.push(lut_addr);
+                    }
+                }
+            } else {
+                error!("❌ No LUT address already crafted for the market {:?}", market_addr);
+            }
         } else {
-            String::new()
+            info!("Skip get LUT table for non swap instruction: {:?}", si.details);
+        }
+    }
+    if lut_addresses.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let lut_addresses_ref = &lut_addresses;
+    let raw_lut_accounts = rpc_racer()
+        .race(send_breaker(), rpc_url, CommitmentConfig::default(), |client| async move { client.get_multiple_accounts(lut_addresses_ref).await })
+        .await?;
+    let mut vec_address_lut: Vec<AddressLookupTableAccount> = Vec::new();
+    for (lut_address, raw_lut_account) in lut_addresses.into_iter().zip(raw_lut_accounts) {
+        let Some(raw_lut_account) = raw_lut_account else {
+            error!("❌ LUT account {} not found", lut_address);
+            continue;
         };
-        if !transaction_errors.is_empty() {
-            error!("❌ Swap transaction is not executed: {:?}", transaction_errors);
+        let address_lookup_table = AddressLookupTable::deserialize(&raw_lut_account.data)?;
+        let address_lookup_table_account = AddressLookupTableAccount {
+            key: lut_address,
+            addresses: address_lookup_table.addresses.to_vec(),
+        };
+        println!("Address in lookup_table: {}", address_lookup_table_account.addresses.len());
+        vec_address_lut.push(address_lookup_table_account);
+    }
+    Ok(vec_address_lut)
+}
+
+// Fee budget, audit log, trade ledger then send of a signed swap transaction.
+// Returns true when it landed
+async fn send_swap(rpc_url: &str, txn: VersionedTransaction, mut trade: TradeRecord, mut audit_context: AuditContext, stages: &mut StageTimestamps) -> Result<bool, SendError> {
+    let fee = transaction_fee(trade.compute_unit_price, trade.compute_units, trade.tip_lamports);
+    if !fee_budget().try_spend(fee) {
+        return Ok(false);
+    }
+    metrics().txs_sent.inc();
+    metrics().fee_spend_lamports.inc_by(fee);
+    metrics().tip_spend_lamports.inc_by(trade.tip_lamports);
+    audit_context.simulated_units = Some(trade.compute_units);
+    trade.signature = txn.signatures[0].to_string();
+    trade.timestamp = Utc::now().timestamp();
+    trade.fee_lamports = fee - trade.tip_lamports;
+    // The single exit point from here: every outcome, errors included, is in the ledger
+    let (signature, status) = match send_and_track(rpc_url, txn, &trade, audit_context, stages).await {
+        Ok(tracked) => tracked,
+        Err(e) => {
+            trade.error = Some(e.to_string());
+            ledger_trade(trade);
+            return match e {
+                SendError::Rejected(_) => Ok(false),
+                e => Err(e),
+            };
         }
+    };
+    trade.signature = signature.to_string();
+    let landed = matches!(status, ConfirmationStatus::Confirmed { .. });
+    if landed {
+        stages.mark(Stage::Land);
     }
-    Ok(())
+    if let ConfirmationStatus::Confirmed { slot } = &status {
+        let pools = trade.hops.iter().map(|hop| hop.pool_address.clone()).collect();
+        publish(BotEvent::TxLanded { signature: trade.signature.clone(), slot: *slot, pools });
+    }
+    (trade.status, trade.slot, trade.error) = match status {
+        ConfirmationStatus::Confirmed { slot } => (TradeStatus::Landed, Some(slot), None),
+        ConfirmationStatus::Failed { slot, error } => (TradeStatus::Failed, Some(slot), Some(error)),
+        ConfirmationStatus::Unknown { slot } => (TradeStatus::Unknown, Some(slot), None),
+        ConfirmationStatus::Expired => (TradeStatus::Expired, None, None),
+    };
+    ledger_trade(trade);
+    Ok(landed)
+}
+
+// Audit, send and track of the swap transaction. Returns the signature which landed
+// (the sender may re-sign it) and its final status
+async fn send_and_track(rpc_url: &str, txn: VersionedTransaction, trade: &TradeRecord, audit_context: AuditContext, stages: &mut StageTimestamps) -> Result<(Signature, ConfirmationStatus), SendError> {
+    audit_transaction(&txn, audit_context.clone())?;
+    publish(BotEvent::TxSent {
+        signature: trade.signature.clone(),
+        strategy: trade.strategy.clone(),
+        tokens_path: trade.tokens_path.clone(),
+        fee_lamports: trade.fee_lamports,
+        tip_lamports: trade.tip_lamports,
+    });
+    // The in-flight slot is held by the caller, the endpoint rate limit is taken here
+    throttle(rpc_url).await;
+    stages.mark(Stage::Send);
+    let signature = txn.signatures[0];
+    // Never exposed to the public mempool in private mode
+    if is_private_send() {
+        return Ok((signature, send_private_tracked(&txn, rpc_url).await?));
+    }
+    let blockhash = *txn.message.recent_blockhash();
+    let arc_rpc_client = nonblocking_client_with_commitment(rpc_url, confirmation_policy().commitment);
+    let tracking_rpc_client = arc_rpc_client.clone();
+    let connection_cache = ConnectionCache::new_quic("connection_cache_cli_program_quic", 1);
+    let audited_signer = Arc::new(AuditedSigner::new(wallet_signer(), audit_context));
+    let signer: [Arc<dyn Signer>; 1] = [audited_signer.clone()];
+
+    let iteration_number = 2;
+    let transaction_errors = if let ConnectionCache::Quic(cache) = connection_cache {
+        let tpu_client = TpuClient::new_with_connection_cache(
+            arc_rpc_client.clone(),
+            &feeds().url(FeedKind::AccountWs).unwrap_or_default(),
+            TpuClientConfig::default(),
+            cache,
+        )
+        .await?;
+        send_and_confirm_transactions_in_parallel(
+            arc_rpc_client,
+            Some(tpu_client),
+            &[txn],
+            &signer,
+            SendAndConfirmConfig {
+                resign_txs_count: Some(iteration_number),
+                with_spinner: true,
+            },
+        )
+        .await
+        .map_err(|err| {
+            error!("Transaction send failed: {}", err);
+            let message = err.to_string().to_lowercase();
+            if message.contains("blockhash") || message.contains("resign") || message.contains("retries") {
+                SendError::Expired(err.to_string())
+            } else {
+                SendError::Tpu(err)
+            }
+        })?
+        .into_iter()
+        .map(|err| format!("Data writes to account failed: {:?}", err))
+        .collect::<String>()
+    } else {
+        String::new()
+    };
+    if !transaction_errors.is_empty() {
+        error!("❌ Swap transaction is not executed: {:?}", transaction_errors);
+        return Err(SendError::Rejected(transaction_errors));
+    }
+    // Don't assume success: a confirmed slot can still be skipped before finalization.
+    // The sender re-signs on retries, whichever variant landed is the trade
+    let mut variants = vec![(signature, blockhash)];
+    variants.extend(audited_signer.signed().into_iter().filter(|(signed, _)| *signed != signature));
+    let (landed_signature, status) = track_signatures(&tracking_rpc_client, &variants).await?;
+    match &status {
+        ConfirmationStatus::Confirmed { .. } => {}
+        ConfirmationStatus::Unknown { .. } => request_inventory_reconcile(),
+        status => error!("❌ Swap transaction {} not landed: {:?}", landed_signature, status),
+    }
+    Ok((landed_signature, status))
+}
+
+// Written by the database writer, the trade is already in the audit log
+fn ledger_trade(trade: TradeRecord) {
+    publish(BotEvent::TradeRecorded { trade });
 }
 
 pub async fn create_ata_extendlut_transaction(chain: ChainType, simulate_or_send: SendOrSimulate, transaction_infos: SwapPathResult, lut_address: Pubkey, tokens: Vec<Pubkey>) -> Result<()> {
@@ -213,10 +439,11 @@ pub async fn create_ata_extendlut_transaction(chain: ChainType, simulate_or_send
     let rpc_url = match chain {
         ChainType::Mainnet => env.rpc_url_tx.clone(),
         ChainType::Devnet => env.devnet_rpc_url.clone(),
+        ChainType::Localnet => LOCALNET_RPC_URL.to_string(),
     };
-    let rpc_client = RpcClient::new(rpc_url);
+    let rpc_client = blocking_client(&rpc_url);
 
-    let payer = read_keypair_file(&env.payer_keypair_path).expect("Wallet keypair file not found");
+    let payer = wallet_signer();
     info!("💳 Wallet {:?}", payer.pubkey());
 
     let mut vec_pda_instructions: Vec<Instruction> = Vec::new();
@@ -237,6 +464,12 @@ pub async fn create_ata_extendlut_transaction(chain: ChainType, simulate_or_send
         }
     }
 
+    let audit_context = AuditContext {
+        kind: "ata_extend_lut".to_string(),
+        path_id: Some(transaction_infos.path_id),
+        tokens_path: Some(transaction_infos.tokens_path.clone()),
+        ..AuditContext::default()
+    };
     let mut swap_instructions: Vec<InstructionDetails> = construct_transaction(transaction_infos).await;
     for i in (0..swap_instructions.len()).rev() {
         if let Some(market_addr) = swap_instructions[i].market.as_ref().map(|m| m.address) {
@@ -286,8 +519,8 @@ pub async fn create_ata_extendlut_transaction(chain: ChainType, simulate_or_send
     let priority_fees_ix = ComputeBudgetInstruction::set_compute_unit_price(0);
     let compute_budget_instruction = vec![priority_fees_ix, compute_budget_ix];
     let vec_extend_instructions: Vec<Instruction> = vec_details_extend_instructions
-        .into_iter()
-        .map(|instruc_details| instruc_details.instruction)
+        .iter()
+        .map(|instruc_details| instruc_details.instruction.clone())
         .collect();
 
     let mut vec_all_instructions: Vec<Instruction> = vec![compute_budget_instruction, vec_pda_instructions, vec_extend_instructions].concat();
@@ -324,7 +557,8 @@ pub async fn create_ata_extendlut_transaction(chain: ChainType, simulate_or_send
     let average_fees = average(fees.iter().map(|iter| iter.prioritization_fee).collect());
     info!("🔢 Average Prioritization fees price: {}", average_fees);
 
-    let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(result_cu as u32);
+    let compute_unit_limit = compute_unit_limit(result_cu);
+    let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit as u32);
     let priority_fees_ix = ComputeBudgetInstruction::set_compute_unit_price(10);
     vec_all_instructions[0] = priority_fees_ix;
     vec_all_instructions[1] = compute_budget_ix;
@@ -338,7 +572,10 @@ pub async fn create_ata_extendlut_transaction(chain: ChainType, simulate_or_send
             min_context_slot: None,
         };
  
-        let new_payer = read_keypair_file(&env.payer_keypair_path).expect("Wallet keypair not found");
+        let new_payer = wallet_signer();
+        if let Some(tip_ix) = tip_instruction(&new_payer.pubkey()) {
+            vec_all_instructions.push(tip_ix);
+        }
         let txn = VersionedTransaction::try_new(
             VersionedMessage::V0(v0::Message::try_compile(
                 &new_payer.pubkey(),
@@ -348,17 +585,33 @@ pub async fn create_ata_extendlut_transaction(chain: ChainType, simulate_or_send
             )?),
             &[&new_payer],
         )?;
+        if !fee_budget().try_spend(transaction_fee(10, compute_unit_limit, tip_lamports())) {
+            return Ok(());
+        }
+        let _in_flight = send_permit(&rpc_url).await;
+        let audit_context = AuditContext { simulated_units: Some(result_cu), ..audit_context };
+        audit_transaction(&txn, audit_context.clone())?;
+        if is_private_send() {
+            if send_private(&txn, &rpc_url).await? {
+                for details_instruction in vec_details_extend_instructions {
+                    let _ = write_lut_for_market(details_instruction.market.unwrap().address, lut_address, false)?;
+                }
+                info!("✅ Ata/Extend transaction executed");
+            } else {
+                error!("❌ Ata/Extend transaction is not executed");
+            }
+            return Ok(());
+        }
         
-        let non_blocking_rpc_client = solana_client::nonblocking::rpc_client::RpcClient::new(env.rpc_url_tx.clone());
-        let arc_rpc_client = Arc::new(non_blocking_rpc_client);
+        let arc_rpc_client = nonblocking_client(&env.rpc_url_tx);
         let connection_cache = ConnectionCache::new_quic("connection_cache_cli_program_quic", 1);
-        let signer: [Arc<dyn Signer>; 1] = [Arc::new(new_payer)];
+        let signer: [Arc<dyn Signer>; 1] = [Arc::new(AuditedSigner::new(new_payer, audit_context))];
 
         let iteration_number = 5;
         let transaction_errors = if let ConnectionCache::Quic(cache) = connection_cache {
             let tpu_client = TpuClient::new_with_connection_cache(
                 arc_rpc_client.clone(),
-                &env.wss_rpc_url,
+                &feeds().url(FeedKind::AccountWs).unwrap_or_default(),
                 TpuClientConfig::default(),
                 cache,
             )
@@ -402,6 +655,11 @@ pub async fn construct_transaction(transaction_infos: SwapPathResult) -> Vec<Ins
     let mut swap_instructions: Vec<InstructionDetails> = Vec::new();
     
     for (i, route_sim) in transaction_infos.route_simulations.iter().enumerate() {
+        // Precompiled with the selected paths: only the amounts are patched in
+        if let Some(template) = instruction_templates().get(route_sim).await {
+            swap_instructions.extend(template.instantiate(route_sim.amount_in, route_sim.estimated_amount_out.parse().unwrap_or_default()));
+            continue;
+        }
         match route_sim.dex_label {
             DexLabel::METEORA => {
                 let swap_params: SwapParametersMeteora = SwapParametersMeteora {
@@ -455,10 +713,11 @@ pub async fn create_lut(chain: ChainType) -> Result<()> {
     let rpc_url = match chain {
         ChainType::Mainnet => env.rpc_url.clone(),
         ChainType::Devnet => env.devnet_rpc_url.clone(),
+        ChainType::Localnet => LOCALNET_RPC_URL.to_string(),
     };
-    let rpc_client = RpcClient::new(rpc_url);
+    let rpc_client = blocking_client(&rpc_url);
 
-    let payer = read_keypair_file(&env.payer_keypair_path).expect("Wallet keypair not found");
+    let payer = wallet_signer();
     let slot = rpc_client.get_slot_with_commitment(CommitmentConfig::finalized())?;
     let (create_instruction, lut_address) = create_lookup_table(payer.pubkey(), payer.pubkey(), slot - 200);
 
@@ -477,9 +736,11 @@ pub async fn create_lut(chain: ChainType) -> Result<()> {
         ..RpcSendTransactionConfig::default()
     };
 
+    let _in_flight = send_permit(&rpc_url).await;
     let signature = rpc_client.send_transaction_with_config(&tx, transaction_config)?;
     let explorer_url = match chain {
         ChainType::Devnet => format!("https://explorer.solana.com/tx/{}?cluster=devnet", signature),
+        ChainType::Localnet => format!("https://explorer.solana.com/tx/{}?cluster=custom&customUrl={}", signature, LOCALNET_RPC_URL),
         ChainType::Mainnet => format!("https://explorer.solana.com/tx/{}", signature),
     };
     info!("{}", explorer_url);
@@ -501,8 +762,9 @@ pub async fn is_available_lut(chain: ChainType, lut_address: Pubkey) -> Result<b
     let rpc_url = match chain {
         ChainType::Mainnet => env.rpc_url.clone(),
         ChainType::Devnet => env.devnet_rpc_url.clone(),
+        ChainType::Localnet => LOCALNET_RPC_URL.to_string(),
     };
-    let rpc_client = RpcClient::new(rpc_url);
+    let rpc_client = blocking_client(&rpc_url);
 
     let raw_lut_account = rpc_client.get_account(&lut_address)?;
     let address_lookup_table = AddressLookupTable::deserialize(&raw_lut_account.data)?;
@@ -580,7 +842,7 @@ pub enum TransactionType {
     CreateSwap,
 }
 
-#[derive(PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum SendOrSimulate {
     Simulate,
     Send,
@@ -590,4 +852,8 @@ pub enum SendOrSimulate {
 pub enum ChainType {
     Mainnet,
     Devnet,
-}
\ No newline at end of file
+    // solana-test-validator of the localnet harness, on LOCALNET_RPC_URL
+    Localnet,
+}
+
+pub static LOCALNET_RPC_URL: &str = "http://127.0.0.1:8899";
\ No newline at end of file
@@ -0,0 +1,55 @@
+use solana_client::client_error::ClientError;
+use solana_client::tpu_client::TpuSenderError;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::message::CompileError;
+use solana_sdk::signer::SignerError;
+use solana_sdk::transaction::TransactionError;
+use thiserror::Error;
+
+// Why a swap transaction was not sent or not landed
+#[derive(Debug, Error)]
+pub enum SendError {
+    #[error("no healthy RPC endpoint")]
+    NoHealthyRpc,
+    #[error("RPC call failed: {0}")]
+    Rpc(#[from] ClientError),
+    #[error("TPU send failed: {0}")]
+    Tpu(#[from] TpuSenderError),
+    // Still unconfirmed when its blockhash expired, after every re-sign
+    #[error("blockhash expired: {0}")]
+    Expired(String),
+    #[error("lookup table not decoded: {0}")]
+    LookupTable(#[from] InstructionError),
+    #[error("message not compiled: {0}")]
+    Compile(#[from] CompileError),
+    #[error("transaction not signed: {0}")]
+    Signing(#[from] SignerError),
+    // Sent, but reported failed by the sender
+    #[error("transaction rejected: {0}")]
+    Rejected(String),
+    // The programs of the route rejected the swap in simulation
+    #[error("simulation failed: {0}")]
+    Simulation(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl SendError {
+    // Only an instruction error blames the route, anything else (blockhash, account in use...) is transient
+    pub fn from_simulation(err: TransactionError) -> Self {
+        match err {
+            TransactionError::InstructionError(..) => SendError::Simulation(format!("{:?}", err)),
+            err => SendError::Other(anyhow::anyhow!("simulation failed: {:?}", err)),
+        }
+    }
+
+    // The endpoint is at fault, the same transaction can go through another one
+    pub fn is_rpc_error(&self) -> bool {
+        matches!(self, SendError::NoHealthyRpc | SendError::Rpc(_) | SendError::Tpu(_))
+    }
+
+    // The route itself is at fault, sending it again fails the same way
+    pub fn is_program_error(&self) -> bool {
+        matches!(self, SendError::Simulation(_))
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use log::{info, warn};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::common::rate_limiter::throttle;
+
+// Caps the number of sent but not yet confirmed transactions, whatever sends them
+// (executor pool, optimism files, inventory dumps). Further sends wait in FIFO order.
+#[derive(Debug)]
+pub struct InFlightLimiter {
+    pub max_in_flight: usize,
+    permits: Arc<Semaphore>,
+    waiting: AtomicUsize,
+}
+
+// Held from the send until the confirmation is known
+#[derive(Debug)]
+pub struct InFlightPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl InFlightLimiter {
+    pub fn new(max_in_flight: usize) -> Self {
+        let max_in_flight = max_in_flight.max(1);
+        InFlightLimiter {
+            max_in_flight,
+            permits: Arc::new(Semaphore::new(max_in_flight)),
+            waiting: AtomicUsize::new(0),
+        }
+    }
+
+    pub async fn acquire(&self) -> InFlightPermit {
+        if self.permits.available_permits() == 0 {
+            let waiting = self.waiting.fetch_add(1, Ordering::Relaxed) + 1;
+            warn!("⏳ {} transactions in flight, {} queued", self.max_in_flight, waiting);
+            let permit = self.permits.clone().acquire_owned().await.expect("In-flight semaphore closed");
+            self.waiting.fetch_sub(1, Ordering::Relaxed);
+            return InFlightPermit { _permit: permit };
+        }
+        let permit = self.permits.clone().acquire_owned().await.expect("In-flight semaphore closed");
+        InFlightPermit { _permit: permit }
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.max_in_flight - self.permits.available_permits()
+    }
+
+    pub fn waiting(&self) -> usize {
+        self.waiting.load(Ordering::Relaxed)
+    }
+}
+
+static IN_FLIGHT_LIMITER: OnceLock<InFlightLimiter> = OnceLock::new();
+
+// To call once at startup, 4 transactions in flight max otherwise
+pub fn init_in_flight_limiter(max_in_flight: usize) {
+    info!("⏳ {} unconfirmed transactions max", max_in_flight);
+    let _ = IN_FLIGHT_LIMITER.set(InFlightLimiter::new(max_in_flight));
+}
+
+pub fn in_flight_limiter() -> &'static InFlightLimiter {
+    IN_FLIGHT_LIMITER.get_or_init(|| InFlightLimiter::new(4))
+}
+
+// For the sends outside of the swaps (ATA/LUT, sweep): a slot among the unconfirmed
+// transactions, then the rate limit of the endpoint
+pub async fn send_permit(rpc_url: &str) -> InFlightPermit {
+    let permit = in_flight_limiter().acquire().await;
+    throttle(rpc_url).await;
+    permit
+}
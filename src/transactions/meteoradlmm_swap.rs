@@ -20,7 +20,6 @@ use std::mem::size_of;
 
 use log::{info, error};
 use solana_sdk::instruction::Instruction;
-use solana_sdk::signature::read_keypair_file;
 use spl_associated_token_account::instruction::create_associated_token_account;
 
 use crate::common::constants::Env;
@@ -28,6 +27,7 @@ use crate::common::utils::from_str;
 use crate::markets::meteora::AccountData;
 use crate::markets::types::DexLabel;
 use crate::transactions::create_transaction::{InstructionDetails, MarketInfos};
+use crate::transactions::signer::wallet_signer;
 
 
 #[derive(Debug, Clone)]
@@ -53,7 +53,7 @@ pub async fn construct_meteora_instructions(params: SwapParametersMeteora) -> Ve
 
     let mut swap_instructions: Vec<InstructionDetails> = Vec::new();
     let env = Env::new();
-    let payer = read_keypair_file(env.payer_keypair_path).expect("Wallet keypair file not found");
+    let payer = wallet_signer();
     
     let amm_program = from_str("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo").unwrap();
     
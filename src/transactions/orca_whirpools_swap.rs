@@ -24,7 +24,6 @@ use std::str::FromStr;
 
 use log::{error, info};
 use solana_sdk::instruction::Instruction;
-use solana_sdk::signature::read_keypair_file;
 use spl_associated_token_account::instruction::create_associated_token_account;
 
 use crate::common::constants::Env;
@@ -33,6 +32,7 @@ use crate::markets::meteora::AccountData;
 use crate::markets::orca_whirpools::WhirlpoolAccountState;
 use crate::markets::types::DexLabel;
 use crate::transactions::create_transaction::{InstructionDetails, MarketInfos};
+use crate::transactions::signer::wallet_signer;
 
 
 #[derive(Debug, Clone)]
@@ -57,7 +57,7 @@ pub async fn construct_orca_whirpools_instructions(params: SwapParametersOrcaWhi
 
     let mut swap_instructions: Vec<InstructionDetails> = Vec::new();
     let env = Env::new();
-    let payer = read_keypair_file(env.payer_keypair_path).expect("Wallet keypair file not found");
+    let payer = wallet_signer();
     
     let amm_program = from_str("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc").unwrap();
     
@@ -0,0 +1,302 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use log::{debug, error, info};
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::{v0, VersionedMessage};
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::arbitrage::ranking::path_result_key;
+use crate::arbitrage::types::SwapPathResult;
+use crate::common::circuit_breaker::send_breaker;
+use crate::common::rpc_pool::nonblocking_client;
+use crate::common::tasks::spawn_named;
+use crate::transactions::blockhash::cached_blockhash;
+use crate::transactions::create_transaction::{compute_unit_limit, construct_transaction, lookup_tables, BASE_COMPUTE_UNIT_PRICE};
+use crate::transactions::errors::SendError;
+use crate::transactions::private_send::{tip_lamports, tip_transfer};
+use crate::transactions::signer::{wallet_signer, SignerHandle};
+
+// Past this age a prepared path is dropped, its quote is stale anyway
+pub static PRESIGNED_MAX_AGE: Duration = Duration::from_secs(10);
+// Compute unit limit of the simulation giving the units of a prepared path
+pub static PRESIGN_PROBE_UNITS: u32 = 1_400_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TipLevel {
+    // Micro-lamports per compute unit
+    pub compute_unit_price: u64,
+    pub tip_lamports: u64,
+}
+
+impl TipLevel {
+    // Base priority fee and send mode tip scaled by each multiplier, e.g. [1, 2, 5].
+    // To call once the send mode is set
+    pub fn scaled(multipliers: &[u64]) -> Vec<TipLevel> {
+        multipliers
+            .iter()
+            .map(|multiplier| TipLevel { compute_unit_price: BASE_COMPUTE_UNIT_PRICE * multiplier, tip_lamports: tip_lamports() * multiplier })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PresignedTransaction {
+    pub transaction: VersionedTransaction,
+    pub level: TipLevel,
+    pub compute_units: u64,
+}
+
+// Swap of one path at the amounts of its last quote, signed at every tip level
+#[derive(Debug)]
+struct PreparedPath {
+    // Amount in and estimated amount out of every hop, the opportunity must match them
+    amounts: Vec<(u64, String)>,
+    instructions: Vec<Instruction>,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+    compute_units: u64,
+    // Default once a variant is sent, until the next blockhash
+    blockhash: Hash,
+    variants: Vec<PresignedTransaction>,
+    prepared_at: Instant,
+}
+
+fn hop_amounts(spr: &SwapPathResult) -> Vec<(u64, String)> {
+    spr.route_simulations.iter().map(|route| (route.amount_in, route.estimated_amount_out.clone())).collect()
+}
+
+// Swap transactions of the opportunity paths built, simulated and signed ahead at a few tip levels.
+// Only the signatures are redone when the prefetched blockhash rolls, the executor picks the variant
+// matching the current priority fee and sends it as is
+#[derive(Debug)]
+pub struct PresignedCache {
+    // Ordered by compute unit price
+    pub levels: Vec<TipLevel>,
+    pub commitment: CommitmentConfig,
+    paths: RwLock<HashMap<String, PreparedPath>>,
+    // Never prepared twice concurrently
+    preparing: Mutex<HashSet<String>>,
+}
+
+impl PresignedCache {
+    pub fn new(mut levels: Vec<TipLevel>, commitment: CommitmentConfig) -> Self {
+        levels.sort_by_key(|level| level.compute_unit_price);
+        PresignedCache {
+            levels,
+            commitment,
+            paths: RwLock::new(HashMap::new()),
+            preparing: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn is_prepared(&self, key: &str, amounts: &[(u64, String)]) -> bool {
+        self.paths.read().unwrap().get(key).is_some_and(|path| path.amounts == amounts)
+    }
+
+    // Prepare the swap of an opportunity in the background, unless already prepared at these amounts
+    pub fn prepare_in_background(&'static self, spr: &SwapPathResult) {
+        let key = path_result_key(spr);
+        if self.is_prepared(&key, &hop_amounts(spr)) || !self.preparing.lock().unwrap().insert(key.clone()) {
+            return;
+        }
+        let spr = spr.clone();
+        spawn_named("presign_path", async move {
+            if let Err(e) = self.prepare(&key, spr).await {
+                error!("❌ Path {} not pre-signed: {}", key, e);
+            }
+            self.preparing.lock().unwrap().remove(&key);
+        });
+    }
+
+    async fn prepare(&self, key: &str, spr: SwapPathResult) -> Result<(), SendError> {
+        // Nothing to sign against until the prefetcher runs
+        let Some(cached) = cached_blockhash(self.commitment) else { return Ok(()) };
+        let rpc_url = send_breaker().current_url().ok_or(SendError::NoHealthyRpc)?;
+        let rpc_client = nonblocking_client(&rpc_url);
+        let amounts = hop_amounts(&spr);
+        let swap_instructions = construct_transaction(spr).await;
+        if swap_instructions.is_empty() {
+            return Err(SendError::Other(anyhow!("no swap instruction")));
+        }
+        let lookup_tables = lookup_tables(&rpc_url, &swap_instructions).await?;
+        let instructions: Vec<Instruction> = swap_instructions.into_iter().map(|details| details.instruction).collect();
+        let payer = wallet_signer();
+
+        let probe = sign_variant(&payer, &instructions, &lookup_tables, PRESIGN_PROBE_UNITS as u64, TipLevel { compute_unit_price: 0, tip_lamports: 0 }, cached.blockhash)?;
+        let config = RpcSimulateTransactionConfig {
+            replace_recent_blockhash: true,
+            commitment: Some(self.commitment),
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let result = rpc_client.simulate_transaction_with_config(&probe, config).await?.value;
+        if let Some(err) = result.err {
+            return Err(SendError::from_simulation(err));
+        }
+        // Limit of the signed variants, the simulated units leave no room for the tip transfer
+        let compute_units = compute_unit_limit(result.units_consumed.unwrap_or(150_000));
+
+        let variants = self.sign_variants(&payer, &instructions, &lookup_tables, compute_units, cached.blockhash)?;
+        info!("✍️ Path {} pre-signed at {} tip levels ({} CU)", key, variants.len(), compute_units);
+        self.paths.write().unwrap().insert(
+            key.to_string(),
+            PreparedPath { amounts, instructions, lookup_tables, compute_units, blockhash: cached.blockhash, variants, prepared_at: Instant::now() },
+        );
+        Ok(())
+    }
+
+    fn sign_variants(&self, payer: &SignerHandle, instructions: &[Instruction], lookup_tables: &[AddressLookupTableAccount], compute_units: u64, blockhash: Hash) -> Result<Vec<PresignedTransaction>, SendError> {
+        self.levels
+            .iter()
+            .map(|level| {
+                let transaction = sign_variant(payer, instructions, lookup_tables, compute_units, *level, blockhash)?;
+                Ok(PresignedTransaction { transaction, level: *level, compute_units })
+            })
+            .collect()
+    }
+
+    // Sign again the paths prepared at another blockhash, the stale ones are dropped.
+    // Signing happens outside the lock, a remote signer may take a while
+    pub fn resign(&self, blockhash: Hash) -> usize {
+        let stale: Vec<(String, Vec<Instruction>, Vec<AddressLookupTableAccount>, u64)> = {
+            let mut paths = self.paths.write().unwrap();
+            paths.retain(|_, path| path.prepared_at.elapsed() <= PRESIGNED_MAX_AGE);
+            paths
+                .iter()
+                .filter(|(_, path)| path.blockhash != blockhash)
+                .map(|(key, path)| (key.clone(), path.instructions.clone(), path.lookup_tables.clone(), path.compute_units))
+                .collect()
+        };
+        let payer = wallet_signer();
+        let mut resigned = 0;
+        for (key, instructions, lookup_tables, compute_units) in stale {
+            let signed = self.sign_variants(&payer, &instructions, &lookup_tables, compute_units, blockhash);
+            let mut paths = self.paths.write().unwrap();
+            match signed {
+                Ok(variants) => {
+                    if let Some(path) = paths.get_mut(&key) {
+                        path.variants = variants;
+                        path.blockhash = blockhash;
+                        resigned += 1;
+                    }
+                }
+                Err(e) => {
+                    error!("❌ Path {} not re-signed: {}", key, e);
+                    paths.remove(&key);
+                }
+            }
+        }
+        resigned
+    }
+
+    // Variant of the cheapest level paying at least compute_unit_price (the highest level above them all).
+    // None unless the path was prepared at the same amounts and signed at the current blockhash
+    pub fn take(&self, spr: &SwapPathResult, compute_unit_price: u64) -> Option<PresignedTransaction> {
+        let current = cached_blockhash(self.commitment)?.blockhash;
+        let key = path_result_key(spr);
+        let mut paths = self.paths.write().unwrap();
+        let path = paths.get_mut(&key)?;
+        if path.blockhash != current || path.amounts != hop_amounts(spr) {
+            return None;
+        }
+        let variant = path.variants.iter().find(|variant| variant.level.compute_unit_price >= compute_unit_price).or(path.variants.last())?.clone();
+        // A signature goes out once, the path waits for the next blockhash
+        path.blockhash = Hash::default();
+        Some(variant)
+    }
+}
+
+fn sign_variant(payer: &SignerHandle, instructions: &[Instruction], lookup_tables: &[AddressLookupTableAccount], compute_units: u64, level: TipLevel, blockhash: Hash) -> Result<VersionedTransaction, SendError> {
+    let mut all_instructions = Vec::with_capacity(instructions.len() + 3);
+    all_instructions.push(ComputeBudgetInstruction::set_compute_unit_price(level.compute_unit_price));
+    all_instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(compute_units as u32));
+    all_instructions.extend_from_slice(instructions);
+    if level.tip_lamports > 0 {
+        all_instructions.extend(tip_transfer(&payer.pubkey(), level.tip_lamports));
+    }
+    Ok(VersionedTransaction::try_new(
+        VersionedMessage::V0(v0::Message::try_compile(&payer.pubkey(), &all_instructions, lookup_tables, blockhash)?),
+        &[payer],
+    )?)
+}
+
+static PRESIGNED: OnceLock<PresignedCache> = OnceLock::new();
+
+// To call once at startup, nothing is pre-signed otherwise
+pub fn init_presigned(cache: PresignedCache) -> &'static PresignedCache {
+    info!("✍️ Opportunity swaps pre-signed at tip levels {:?}", cache.levels);
+    PRESIGNED.get_or_init(|| cache)
+}
+
+pub fn presigned_transactions() -> Option<&'static PresignedCache> {
+    PRESIGNED.get()
+}
+
+// Re-sign the prepared paths every time the prefetched blockhash rolls
+pub async fn run_presigner(cache: &'static PresignedCache, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut last_blockhash: Option<Hash> = None;
+    loop {
+        ticker.tick().await;
+        let Some(cached) = cached_blockhash(cache.commitment) else { continue };
+        if last_blockhash == Some(cached.blockhash) {
+            continue;
+        }
+        last_blockhash = Some(cached.blockhash);
+        let resigned = cache.resign(cached.blockhash);
+        if resigned > 0 {
+            debug!("✍️ {} pre-signed paths re-signed", resigned);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::system_instruction;
+
+    use super::*;
+    use crate::transactions::signer::LocalSigner;
+
+    #[test]
+    fn variant_priced_at_its_level() {
+        let payer = SignerHandle::new(Arc::new(LocalSigner::new(Keypair::new())));
+        let swap = system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+        let level = TipLevel { compute_unit_price: 5_000, tip_lamports: 0 };
+        let transaction = sign_variant(&payer, &[swap], &[], 200_000, level, Hash::new_unique()).unwrap();
+
+        let message = &transaction.message;
+        let instructions = message.instructions();
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[0].data, ComputeBudgetInstruction::set_compute_unit_price(5_000).data);
+        assert_eq!(instructions[1].data, ComputeBudgetInstruction::set_compute_unit_limit(200_000).data);
+        assert!(transaction.verify_with_results().iter().all(|verified| *verified));
+    }
+
+    #[test]
+    fn tip_added_above_zero() {
+        let payer = SignerHandle::new(Arc::new(LocalSigner::new(Keypair::new())));
+        let level = TipLevel { compute_unit_price: 0, tip_lamports: 10_000 };
+        let transaction = sign_variant(&payer, &[], &[], 200_000, level, Hash::new_unique()).unwrap();
+        assert_eq!(transaction.message.instructions().len(), 3);
+    }
+}
@@ -0,0 +1,163 @@
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+use log::{error, info};
+use serde_json::{json, Value};
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::bs58;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::common::constants::Env;
+use crate::common::feeds::{feeds, FeedKind};
+use crate::common::rpc_pool::{nonblocking_client, nonblocking_client_with_commitment};
+use crate::transactions::confirmation::{confirmation_policy, request_inventory_reconcile, track_signature, ConfirmationStatus};
+
+// Jito mainnet tip accounts, one is picked at random for every bundle
+pub static JITO_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SendMode {
+    // TPU / sendTransaction on the public RPC
+    Public,
+    // Single-transaction bundle to BLOCK_ENGINE_URL, the tip is added to the transaction
+    JitoBundle { tip_lamports: u64 },
+    // sendTransaction on PRIVATE_RPC_URL only
+    PrivateRelay,
+}
+
+static SEND_MODE: OnceLock<SendMode> = OnceLock::new();
+
+// To call once at startup, sends are public otherwise
+pub fn init_send_mode(mode: SendMode) {
+    info!("📨 Send mode: {:?}", mode);
+    let _ = SEND_MODE.set(mode);
+}
+
+pub fn send_mode() -> SendMode {
+    SEND_MODE.get().cloned().unwrap_or(SendMode::Public)
+}
+
+pub fn is_private_send() -> bool {
+    send_mode() != SendMode::Public
+}
+
+// Tip paid by every transaction in the current send mode
+pub fn tip_lamports() -> u64 {
+    match send_mode() {
+        SendMode::JitoBundle { tip_lamports } => tip_lamports,
+        _ => 0,
+    }
+}
+
+// Tip transfer to append to the instructions in bundle mode
+pub fn tip_instruction(payer: &Pubkey) -> Option<Instruction> {
+    match send_mode() {
+        SendMode::JitoBundle { tip_lamports } => tip_transfer(payer, tip_lamports),
+        _ => None,
+    }
+}
+
+// Transfer of tip_lamports to one of the tip accounts
+pub fn tip_transfer(payer: &Pubkey, tip_lamports: u64) -> Option<Instruction> {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.subsec_nanos() as usize;
+    let tip_account = JITO_TIP_ACCOUNTS[nanos % JITO_TIP_ACCOUNTS.len()];
+    Some(system_instruction::transfer(payer, &Pubkey::from_str(tip_account).ok()?, tip_lamports))
+}
+
+// Send through the private channel only, then track it on `rpc_url` to the configured commitment.
+// Returns true when the transaction landed without error.
+pub async fn send_private(tx: &VersionedTransaction, rpc_url: &str) -> Result<bool> {
+    Ok(matches!(send_private_tracked(tx, rpc_url).await?, ConfirmationStatus::Confirmed { .. }))
+}
+
+// Same, returning the final status of the transaction
+pub async fn send_private_tracked(tx: &VersionedTransaction, rpc_url: &str) -> Result<ConfirmationStatus> {
+    submit_private(tx).await?;
+
+    let signature = tx.signatures[0];
+    let blockhash = *tx.message.recent_blockhash();
+    let rpc_client = nonblocking_client_with_commitment(rpc_url, confirmation_policy().commitment);
+    loop {
+        match track_signature(&rpc_client, &signature, &blockhash).await? {
+            ConfirmationStatus::Confirmed { slot } => {
+                info!("✅ Transaction {} confirmed in slot {}", signature, slot);
+                return Ok(ConfirmationStatus::Confirmed { slot });
+            }
+            ConfirmationStatus::Failed { slot, error } => {
+                error!("❌ Transaction {} landed with error in slot {}: {}", signature, slot, error);
+                return Ok(ConfirmationStatus::Failed { slot, error });
+            }
+            ConfirmationStatus::Expired => {
+                error!("❌ Transaction {} not landed before its blockhash expired", signature);
+                return Ok(ConfirmationStatus::Expired);
+            }
+            // Same signed transaction, it can only land once
+            ConfirmationStatus::Unknown { .. } if rpc_client.is_blockhash_valid(&blockhash, CommitmentConfig::processed()).await? => {
+                info!("🔁 Resending {} after its status went missing", signature);
+                submit_private(tx).await?;
+            }
+            ConfirmationStatus::Unknown { slot } => {
+                request_inventory_reconcile();
+                return Ok(ConfirmationStatus::Unknown { slot });
+            }
+        }
+    }
+}
+
+async fn submit_private(tx: &VersionedTransaction) -> Result<()> {
+    let env = Env::new();
+    let signature = tx.signatures[0];
+    match send_mode() {
+        SendMode::Public => return Err(anyhow!("Private send requested with the public send mode")),
+        SendMode::JitoBundle { .. } => {
+            let block_engine = feeds().endpoint(FeedKind::JitoBlockEngine).ok_or_else(|| anyhow!("BLOCK_ENGINE_URL not set, bundle not sent"))?;
+            let encoded = bs58::encode(bincode::serialize(tx)?).into_string();
+            let body = json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "sendBundle",
+                "params": [[encoded]],
+            });
+            let mut request = reqwest::Client::new().post(format!("{}/api/v1/bundles", block_engine.url.trim_end_matches('/'))).json(&body);
+            if let Some((name, token)) = block_engine.auth_header() {
+                request = request.header(name, token);
+            }
+            let response: Value = request.send().await?.json().await?;
+            if let Some(err) = response.get("error") {
+                return Err(anyhow!("sendBundle failed: {}", err));
+            }
+            info!("📦 Bundle {} sent for {}", response["result"], signature);
+        }
+        SendMode::PrivateRelay => {
+            if env.private_rpc_url.is_empty() {
+                return Err(anyhow!("PRIVATE_RPC_URL not set, transaction not sent"));
+            }
+            let relay = nonblocking_client(&env.private_rpc_url);
+            let config = RpcSendTransactionConfig {
+                skip_preflight: true,
+                encoding: Some(UiTransactionEncoding::Base64),
+                max_retries: Some(0),
+                ..RpcSendTransactionConfig::default()
+            };
+            relay.send_transaction_with_config(tx, config).await?;
+            info!("🔒 Transaction {} sent to the private relay", signature);
+        }
+    }
+    Ok(())
+}
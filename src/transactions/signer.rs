@@ -0,0 +1,164 @@
+use std::fmt::Debug;
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+
+use anyhow::{anyhow, Result};
+use log::info;
+use serde::Deserialize;
+use serde_json::json;
+use solana_sdk::bs58;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature};
+use solana_sdk::signer::{Signer, SignerError};
+
+use crate::common::constants::Env;
+use crate::common::utils::from_str;
+
+// The only place holding or reaching the private key: the rest of the bot gets a
+// SignerHandle, which exposes the public key and signatures, never the key bytes
+pub trait WalletSigner: Debug + Send + Sync {
+    fn pubkey(&self) -> Pubkey;
+
+    fn sign_message(&self, message: &[u8]) -> Result<Signature>;
+}
+
+// Keypair file loaded in the bot process
+#[derive(Debug)]
+pub struct LocalSigner {
+    keypair: Keypair,
+}
+
+impl LocalSigner {
+    pub fn new(keypair: Keypair) -> Self {
+        LocalSigner { keypair }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self> {
+        let keypair = read_keypair_file(path).map_err(|e| anyhow!("Wallet keypair file not found: {}", e))?;
+        Ok(LocalSigner { keypair })
+    }
+}
+
+impl WalletSigner for LocalSigner {
+    fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+
+    fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+        Ok(self.keypair.sign_message(message))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RemoteSignResponse {
+    signature: String,
+}
+
+struct SignRequest {
+    message: Vec<u8>,
+    reply: mpsc::Sender<Result<Signature>>,
+}
+
+// Signing service reached over HTTP: POST {url}/sign {"pubkey", "message" (base58)} -> {"signature"}.
+// The requests run on a dedicated thread with its own runtime: signing is synchronous and
+// may be called from any runtime flavor, or from none
+#[derive(Debug)]
+pub struct RemoteSigner {
+    pub url: String,
+    pubkey: Pubkey,
+    requests: Mutex<mpsc::Sender<SignRequest>>,
+}
+
+impl RemoteSigner {
+    pub fn new(url: &str, pubkey: Pubkey) -> Result<Self> {
+        let url = url.trim_end_matches('/').to_string();
+        let (sender, receiver) = mpsc::channel::<SignRequest>();
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        let thread_url = url.clone();
+        std::thread::Builder::new().name("remote_signer".to_string()).spawn(move || {
+            let client = reqwest::Client::new();
+            for request in receiver {
+                let signature = runtime.block_on(request_signature(&client, &thread_url, &pubkey, &request.message));
+                let _ = request.reply.send(signature);
+            }
+        })?;
+        Ok(RemoteSigner { url, pubkey, requests: Mutex::new(sender) })
+    }
+}
+
+async fn request_signature(client: &reqwest::Client, url: &str, pubkey: &Pubkey, message: &[u8]) -> Result<Signature> {
+    let body = json!({
+        "pubkey": pubkey.to_string(),
+        "message": bs58::encode(message).into_string(),
+    });
+    let response: RemoteSignResponse = client.post(format!("{}/sign", url)).json(&body).send().await?.error_for_status()?.json().await?;
+    let signature: Signature = response.signature.parse().map_err(|e| anyhow!("Invalid remote signature: {:?}", e))?;
+    if !signature.verify(pubkey.as_ref(), message) {
+        return Err(anyhow!("Remote signer returned a signature not matching {}", pubkey));
+    }
+    Ok(signature)
+}
+
+impl WalletSigner for RemoteSigner {
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    // Waits for the signing thread, the caller's runtime is never re-entered
+    fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+        let (reply, signature) = mpsc::channel();
+        self.requests
+            .lock()
+            .unwrap()
+            .send(SignRequest { message: message.to_vec(), reply })
+            .map_err(|_| anyhow!("Remote signer thread stopped"))?;
+        signature.recv().map_err(|_| anyhow!("Remote signer thread stopped"))?
+    }
+}
+
+// Cheap to clone, usable wherever a solana Signer is expected
+#[derive(Debug, Clone)]
+pub struct SignerHandle(Arc<dyn WalletSigner>);
+
+impl SignerHandle {
+    pub fn new(signer: Arc<dyn WalletSigner>) -> Self {
+        SignerHandle(signer)
+    }
+}
+
+impl Signer for SignerHandle {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.0.pubkey())
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        self.0.sign_message(message).map_err(|e| SignerError::Custom(e.to_string()))
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+static WALLET_SIGNER: OnceLock<SignerHandle> = OnceLock::new();
+
+// SIGNER_URL + SIGNER_PUBKEY for a remote signer, the PAYER_KEYPAIR_PATH file otherwise
+pub fn signer_from_env() -> Result<SignerHandle> {
+    let env = Env::new();
+    if !env.signer_url.is_empty() {
+        let pubkey = from_str(&env.signer_pubkey)?;
+        info!("🔐 Remote signer {} for {}", env.signer_url, pubkey);
+        return Ok(SignerHandle::new(Arc::new(RemoteSigner::new(&env.signer_url, pubkey)?)));
+    }
+    Ok(SignerHandle::new(Arc::new(LocalSigner::from_file(&env.payer_keypair_path)?)))
+}
+
+// To call once at startup, the signer from the env is used otherwise
+pub fn init_wallet_signer(signer: SignerHandle) {
+    let _ = WALLET_SIGNER.set(signer);
+}
+
+pub fn wallet_signer() -> SignerHandle {
+    WALLET_SIGNER
+        .get_or_init(|| signer_from_env().expect("Wallet signer not available"))
+        .clone()
+}
@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use anchor_spl::token::spl_token;
+use anyhow::{anyhow, Result};
+use log::{error, info};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::common::constants::{Env, SOL_MINT};
+use crate::common::rpc_pool::blocking_client;
+use crate::common::utils::from_str;
+use crate::transactions::audit::{audit_transaction, AuditContext};
+use crate::transactions::create_transaction::SendOrSimulate;
+use crate::transactions::in_flight::send_permit;
+use crate::transactions::private_send::{is_private_send, send_private, tip_instruction};
+use crate::transactions::signer::wallet_signer;
+
+#[derive(Debug, Clone)]
+pub struct SweepConfig {
+    pub cold_wallet: Pubkey,
+    // SOL + wSOL kept on the hot wallet as operating capital
+    pub working_balance: u64,
+    // Don't bother sending a transaction for less than this
+    pub min_sweep: u64,
+    // Native SOL never swept, for rent and fees
+    pub fee_reserve: u64,
+    pub simulate_or_send: SendOrSimulate,
+}
+
+// Transfer everything above the working balance to the cold wallet, native SOL first then wSOL.
+// Returns the amount swept in lamports.
+pub async fn sweep_profits(config: &SweepConfig) -> Result<u64> {
+    let env = Env::new();
+    let rpc_client = blocking_client(&env.rpc_url_tx);
+    let payer = wallet_signer();
+
+    let sol_mint = from_str(SOL_MINT)?;
+    let wsol_ata = get_associated_token_address(&payer.pubkey(), &sol_mint);
+    let sol_balance = rpc_client.get_balance(&payer.pubkey())?;
+    let wsol_balance = match rpc_client.get_token_account_balance(&wsol_ata) {
+        Ok(balance) => balance.amount.parse::<u64>().unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    let excess = (sol_balance + wsol_balance).saturating_sub(config.working_balance);
+    if excess < config.min_sweep {
+        info!("🧹 Nothing to sweep ({} lamports above the working balance)", excess);
+        return Ok(0);
+    }
+    let from_sol = excess.min(sol_balance.saturating_sub(config.fee_reserve));
+    let from_wsol = (excess - from_sol).min(wsol_balance);
+    // The fee reserve or a missing wSOL account can leave nothing movable
+    if from_sol + from_wsol < config.min_sweep {
+        info!("🧹 Nothing to sweep ({} SOL + {} wSOL lamports movable)", from_sol, from_wsol);
+        return Ok(0);
+    }
+
+    let mut instructions: Vec<Instruction> = Vec::new();
+    if from_sol > 0 {
+        instructions.push(system_instruction::transfer(&payer.pubkey(), &config.cold_wallet, from_sol));
+    }
+    if from_wsol > 0 {
+        let cold_wsol_ata = get_associated_token_address(&config.cold_wallet, &sol_mint);
+        instructions.push(create_associated_token_account_idempotent(&payer.pubkey(), &config.cold_wallet, &sol_mint, &spl_token::id()));
+        instructions.push(spl_token::instruction::transfer(&spl_token::id(), &wsol_ata, &cold_wsol_ata, &payer.pubkey(), &[], from_wsol)?);
+    }
+
+    if let Some(tip_ix) = tip_instruction(&payer.pubkey()) {
+        instructions.push(tip_ix);
+    }
+    let blockhash = rpc_client.get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())?.0;
+    let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], blockhash);
+    if config.simulate_or_send == SendOrSimulate::Simulate {
+        let result = rpc_client.simulate_transaction(&tx)?.value;
+        info!("🧹 Sweep simulation of {} SOL + {} wSOL lamports to {}: {:?}", from_sol, from_wsol, config.cold_wallet, result.err);
+        return Ok(0);
+    }
+    let _in_flight = send_permit(&env.rpc_url_tx).await;
+    let audit_context = AuditContext { kind: "sweep".to_string(), ..AuditContext::default() };
+    audit_transaction(&VersionedTransaction::from(tx.clone()), audit_context)?;
+    if is_private_send() {
+        let signature = tx.signatures[0];
+        if !send_private(&VersionedTransaction::from(tx), &env.rpc_url_tx).await? {
+            return Err(anyhow!("Sweep transaction {} not landed", signature));
+        }
+        info!("🧹 Swept {} SOL + {} wSOL lamports to {}: {}", from_sol, from_wsol, config.cold_wallet, signature);
+        return Ok(from_sol + from_wsol);
+    }
+    let signature = rpc_client.send_and_confirm_transaction(&tx)?;
+    info!("🧹 Swept {} SOL + {} wSOL lamports to {}: {}", from_sol, from_wsol, config.cold_wallet, signature);
+    Ok(from_sol + from_wsol)
+}
+
+pub async fn run_profit_sweep(config: SweepConfig, interval: Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+        if let Err(e) = sweep_profits(&config).await {
+            error!("❌ Profit sweep failed: {:?}", e);
+        }
+    }
+}
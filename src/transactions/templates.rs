@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use log::{error, info};
+use solana_sdk::instruction::Instruction;
+
+use crate::arbitrage::types::{Route, SwapPathSelected, SwapRouteSimulation};
+use crate::common::utils::from_str;
+use crate::markets::pool_cache::pool_cache;
+use crate::markets::types::DexLabel;
+use crate::markets::zero_copy::{meteora_lb_pair, whirlpool};
+use crate::transactions::create_transaction::InstructionDetails;
+use crate::transactions::meteoradlmm_swap::{bin_id_to_bin_array_index, construct_meteora_instructions, SwapParametersMeteora};
+use quote_core::quote::{is_mint, whirlpool_tick_array_starts};
+use crate::transactions::orca_whirlpool_swap::{construct_orca_whirlpool_instructions, SwapParametersOrcaWhirlpool};
+
+// Swap instruction data: 8 bytes sighash, amount in, then the min amount out (other amount threshold on Orca)
+pub static AMOUNT_IN_OFFSET: usize = 8;
+pub static MIN_AMOUNT_OUT_OFFSET: usize = 16;
+
+// Instructions of one hop compiled once: account metas resolved (pool state, vaults, ATAs, tick/bin arrays),
+// only the amounts are patched in at execution time
+#[derive(Debug, Clone)]
+pub struct HopTemplate {
+    pub instructions: Vec<InstructionDetails>,
+    // Tick array (Whirlpool) or bin array (Meteora) the swap accounts were resolved around
+    pub array_index: Option<i32>,
+}
+
+impl HopTemplate {
+    pub fn instantiate(&self, amount_in: u64, min_amount_out: u64) -> Vec<InstructionDetails> {
+        self.instructions
+            .iter()
+            .map(|details| {
+                let mut details = details.clone();
+                // Only the swap instruction depends on the amounts
+                if details.market.is_some() {
+                    patch_amounts(&mut details.instruction, amount_in, min_amount_out);
+                }
+                details
+            })
+            .collect()
+    }
+}
+
+fn patch_amounts(instruction: &mut Instruction, amount_in: u64, min_amount_out: u64) {
+    if instruction.data.len() < MIN_AMOUNT_OUT_OFFSET + 8 {
+        return;
+    }
+    instruction.data[AMOUNT_IN_OFFSET..AMOUNT_IN_OFFSET + 8].copy_from_slice(&amount_in.to_le_bytes());
+    instruction.data[MIN_AMOUNT_OUT_OFFSET..MIN_AMOUNT_OUT_OFFSET + 8].copy_from_slice(&min_amount_out.to_le_bytes());
+}
+
+fn hop_key(pool_address: &str, token_in: &str) -> String {
+    format!("{}:{}", pool_address, token_in)
+}
+
+// First tick array start of a Whirlpool swap or active bin array of a Meteora pair, from the pool cache:
+// the arrays a template holds are only good while the price stays in them
+fn array_index(dex: &DexLabel, pool_address: &str, token_in: &str) -> Option<i32> {
+    match dex {
+        DexLabel::ORCA_WHIRLPOOLS => pool_cache().read(pool_address, |data| {
+            let pool = whirlpool(data)?;
+            let a_to_b = is_mint(token_in, pool.token_mint_a());
+            Some(whirlpool_tick_array_starts(pool.tick_current_index(), pool.tick_spacing(), a_to_b)?[0])
+        })?,
+        DexLabel::METEORA => pool_cache().read(pool_address, |data| bin_id_to_bin_array_index(meteora_lb_pair(data)?.active_id()).ok())?,
+        _ => None,
+    }
+}
+
+// Hop templates of the selected paths, compiled when a path set is loaded and recompiled when
+// the pool price moves to another tick or bin array
+#[derive(Debug, Default)]
+pub struct TemplateCache {
+    hops: RwLock<HashMap<String, Arc<HopTemplate>>>,
+}
+
+impl TemplateCache {
+    // Template of a hop of the selected paths, rebuilt first when its arrays no longer hold the price
+    pub async fn get(&self, hop: &SwapRouteSimulation) -> Option<Arc<HopTemplate>> {
+        let template = self.hops.read().unwrap().get(&hop_key(&hop.pool_address, &hop.token_in)).cloned()?;
+        if template.array_index.is_some() && template.array_index == array_index(&hop.dex_label, &hop.pool_address, &hop.token_in) {
+            return Some(template);
+        }
+        info!("🧩 Pool {} left the arrays of its template, recompiled", hop.pool_address);
+        self.compile(&hop.dex_label, &hop.pool_address, &hop.token_in, &hop.token_out, hop.token_0to1).await
+    }
+
+    pub async fn compile_route(&self, route: &Route) -> Option<Arc<HopTemplate>> {
+        self.compile(&route.dex, &route.pool_address, &route.tokenIn, &route.tokenOut, route.token_0to1).await
+    }
+
+    async fn compile(&self, dex: &DexLabel, pool_address: &str, token_in: &str, token_out: &str, token_0to1: bool) -> Option<Arc<HopTemplate>> {
+        let array_index = array_index(dex, pool_address, token_in);
+        let instructions = build_hop_instructions(dex, pool_address, token_in, token_out, token_0to1, 0, 0).await;
+        if instructions.is_empty() {
+            self.hops.write().unwrap().remove(&hop_key(pool_address, token_in));
+            return None;
+        }
+        let template = Arc::new(HopTemplate { instructions, array_index });
+        self.hops.write().unwrap().insert(hop_key(pool_address, token_in), template.clone());
+        Some(template)
+    }
+
+    // Replaces the previous templates, keeping those whose pool is still in the same array
+    pub async fn compile_paths(&self, paths: &[SwapPathSelected]) {
+        let previous = std::mem::take(&mut *self.hops.write().unwrap());
+        let mut compiled = 0;
+        for route in paths.iter().flat_map(|path| path.path.paths.iter()) {
+            let key = hop_key(&route.pool_address, &route.tokenIn);
+            if self.hops.read().unwrap().contains_key(&key) {
+                continue;
+            }
+            match previous.get(&key).filter(|template| template.array_index.is_some() && template.array_index == array_index(&route.dex, &route.pool_address, &route.tokenIn)) {
+                Some(template) => {
+                    self.hops.write().unwrap().insert(key, template.clone());
+                }
+                None => {
+                    if self.compile_route(route).await.is_none() {
+                        error!("❌ No instruction template for pool {}", route.pool_address);
+                        continue;
+                    }
+                }
+            }
+            compiled += 1;
+        }
+        info!("🧩 {} hop instruction templates for {} paths", compiled, paths.len());
+    }
+}
+
+async fn build_hop_instructions(dex: &DexLabel, pool_address: &str, token_in: &str, token_out: &str, token_0to1: bool, amount_in: u64, minimum_amount_out: u64) -> Vec<InstructionDetails> {
+    match dex {
+        DexLabel::METEORA => {
+            construct_meteora_instructions(SwapParametersMeteora {
+                lb_pair: from_str(pool_address).unwrap_or_default(),
+                amount_in,
+                swap_for_y: token_0to1,
+                input_token: from_str(token_in).unwrap_or_default(),
+                output_token: from_str(token_out).unwrap_or_default(),
+                minimum_amount_out,
+            })
+            .await
+        }
+        DexLabel::ORCA_WHIRLPOOLS => {
+            construct_orca_whirlpool_instructions(SwapParametersOrcaWhirlpool {
+                whirlpools: from_str(pool_address).unwrap_or_default(),
+                input_token: from_str(token_in).unwrap_or_default(),
+                output_token: from_str(token_out).unwrap_or_default(),
+                amount_in,
+                minimum_amount_out,
+            })
+            .await
+        }
+        _ => Vec::new(),
+    }
+}
+
+static TEMPLATE_CACHE: OnceLock<TemplateCache> = OnceLock::new();
+
+pub fn instruction_templates() -> &'static TemplateCache {
+    TEMPLATE_CACHE.get_or_init(TemplateCache::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+    use crate::transactions::create_transaction::MarketInfos;
+
+    fn details(data: Vec<u8>, swap: bool) -> InstructionDetails {
+        InstructionDetails {
+            instruction: Instruction { program_id: Pubkey::new_unique(), accounts: Vec::new(), data },
+            details: String::new(),
+            market: swap.then(|| MarketInfos { dex_label: DexLabel::ORCA_WHIRLPOOLS, address: Pubkey::new_unique() }),
+        }
+    }
+
+    #[test]
+    fn amounts_patched_into_the_swap_instruction_only() {
+        let template = HopTemplate { instructions: vec![details(vec![7; 32], false), details(vec![0; 33], true)], array_index: Some(0) };
+        let instructions = template.instantiate(1_000, 990);
+        assert_eq!(instructions[0].instruction.data, vec![7; 32]);
+        let swap = &instructions[1].instruction.data;
+        assert_eq!(swap[..AMOUNT_IN_OFFSET], [0; 8]);
+        assert_eq!(swap[AMOUNT_IN_OFFSET..AMOUNT_IN_OFFSET + 8], 1_000u64.to_le_bytes());
+        assert_eq!(swap[MIN_AMOUNT_OUT_OFFSET..MIN_AMOUNT_OUT_OFFSET + 8], 990u64.to_le_bytes());
+        assert_eq!(swap[32], 0);
+        // The template itself is left as compiled
+        assert_eq!(template.instructions[1].instruction.data, vec![0; 33]);
+    }
+
+    #[test]
+    fn short_swap_data_left_untouched() {
+        let template = HopTemplate { instructions: vec![details(vec![1; 20], true)], array_index: None };
+        assert_eq!(template.instantiate(1_000, 990)[0].instruction.data, vec![1; 20]);
+    }
+}
@@ -15,14 +15,19 @@ use safe_transmute::{
 use serum_dex::state::{gen_vault_signer_key, AccountFlag, Market, MarketState, MarketStateV2};
 
 use crate::common::constants::Env;
+use crate::common::rpc_pool::blocking_client;
 
-use super::create_transaction::ChainType;
+use super::create_transaction::{ChainType, LOCALNET_RPC_URL};
 
 
 pub async fn check_tx_status(commitment_config: CommitmentConfig, chain: ChainType ,signature: Signature) -> Result<bool> {
     let env = Env::new();
-    let rpc_url = if chain.clone() == ChainType::Mainnet { env.rpc_url } else { env.devnet_rpc_url };
-    let rpc_client: RpcClient = RpcClient::new(rpc_url);
+    let rpc_url = match chain {
+        ChainType::Mainnet => env.rpc_url,
+        ChainType::Devnet => env.devnet_rpc_url,
+        ChainType::Localnet => LOCALNET_RPC_URL.to_string(),
+    };
+    let rpc_client = blocking_client(&rpc_url);
 
     let start = Instant::now();
     let mut counter = 0;
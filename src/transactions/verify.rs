@@ -0,0 +1,196 @@
+use std::fs::{self, File};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding, UiTransactionTokenBalance};
+
+use crate::arbitrage::types::SwapPathResult;
+
+pub static CALIBRATION_FILE: &str = "src/transactions/cache/calibration.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeVerification {
+    pub signature: String,
+    pub tokens_path: String,
+    pub amount_in: u64,
+    // Simulated profit in lamports of the base token
+    pub expected_profit: f64,
+    // Base token delta of the wallet, minus the transaction fee
+    pub realized_profit: f64,
+    // |realized - expected| / |expected|
+    pub deviation: f64,
+    pub flagged: bool,
+    pub verified_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CalibrationReport {
+    pub trades: u64,
+    pub flagged: u64,
+    pub expected_profit_sum: f64,
+    pub realized_profit_sum: f64,
+    pub last_trades: Vec<TradeVerification>,
+}
+
+impl CalibrationReport {
+    // Share of the simulated profit actually realized, to calibrate the thresholds
+    pub fn realization_ratio(&self) -> f64 {
+        if self.expected_profit_sum == 0.0 {
+            return 1.0;
+        }
+        self.realized_profit_sum / self.expected_profit_sum
+    }
+}
+
+// Compare the confirmed balance changes with the simulated SwapPathResult
+pub struct TradeVerifier {
+    rpc_client: RpcClient,
+    pub wallet: Pubkey,
+    // Max relative deviation before a trade is flagged
+    pub tolerance: f64,
+    file_path: String,
+    report: Mutex<CalibrationReport>,
+}
+
+impl TradeVerifier {
+    pub fn new(rpc_url: String, wallet: Pubkey, tolerance: f64, file_path: &str) -> Self {
+        let report = File::open(file_path)
+            .map_err(anyhow::Error::from)
+            .and_then(|file| Ok(serde_json::from_reader::<_, CalibrationReport>(file)?))
+            .unwrap_or_default();
+        TradeVerifier {
+            rpc_client: RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed()),
+            wallet,
+            tolerance,
+            file_path: file_path.to_string(),
+            report: Mutex::new(report),
+        }
+    }
+
+    // Transactions are re-signed on retries, so the landed signature is looked up in the
+    // recent wallet history: the newest successful one touching every pool of the path,
+    // static keys and lookup table accounts alike
+    pub async fn find_trade_signature(&self, spr: &SwapPathResult, since_unix: i64) -> Result<Signature> {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            limit: Some(20),
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..GetConfirmedSignaturesForAddress2Config::default()
+        };
+        let signatures = self.rpc_client.get_signatures_for_address_with_config(&self.wallet, config).await?;
+        for status in signatures {
+            if status.err.is_some() || status.block_time.map(|block_time| block_time < since_unix).unwrap_or(true) {
+                continue;
+            }
+            let signature = Signature::from_str(&status.signature)?;
+            let tx = self.get_transaction(&signature).await?;
+            let mut accounts = serde_json::to_string(&tx.transaction.transaction)?;
+            // The pools are mostly loaded from lookup tables, those accounts are only in the meta
+            if let Some(OptionSerializer::Some(loaded)) = tx.transaction.meta.as_ref().map(|meta| &meta.loaded_addresses) {
+                for address in loaded.writable.iter().chain(loaded.readonly.iter()) {
+                    accounts.push(' ');
+                    accounts.push_str(address);
+                }
+            }
+            if spr.route_simulations.iter().all(|route| accounts.contains(&route.pool_address)) {
+                return Ok(signature);
+            }
+        }
+        Err(anyhow!("No landed transaction found for {}", spr.tokens_path))
+    }
+
+    async fn get_transaction(&self, signature: &Signature) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        Ok(self.rpc_client.get_transaction_with_config(signature, config).await?)
+    }
+
+    pub async fn verify(&self, signature: &Signature, spr: &SwapPathResult) -> Result<TradeVerification> {
+        let tx = self.get_transaction(signature).await?;
+        let meta = tx.transaction.meta.ok_or(anyhow!("No meta for {}", signature))?;
+        let wallet = self.wallet.to_string();
+        let base_balance = |balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>| -> f64 {
+            match balances {
+                OptionSerializer::Some(balances) => balances
+                    .iter()
+                    .filter(|balance| balance.mint == spr.token_in && balance.owner == OptionSerializer::Some(wallet.clone()))
+                    .map(|balance| balance.ui_token_amount.amount.parse::<f64>().unwrap_or(0.0))
+                    .sum(),
+                _ => 0.0,
+            }
+        };
+        let delta = base_balance(&meta.post_token_balances) - base_balance(&meta.pre_token_balances);
+        let realized_profit = delta - meta.fee as f64;
+
+        let deviation = (realized_profit - spr.result).abs() / spr.result.abs().max(1.0);
+        let verification = TradeVerification {
+            signature: signature.to_string(),
+            tokens_path: spr.tokens_path.clone(),
+            amount_in: spr.amount_in,
+            expected_profit: spr.result,
+            realized_profit,
+            deviation,
+            flagged: deviation > self.tolerance,
+            verified_at: Utc::now().to_rfc3339(),
+        };
+        if verification.flagged {
+            warn!("🚩 Trade {} deviates {:.0}% from simulation: expected {} realized {}", signature, deviation * 100.0, spr.result, realized_profit);
+        } else {
+            info!("🧮 Trade {} verified: expected {} realized {}", signature, spr.result, realized_profit);
+        }
+        self.record(verification.clone());
+        Ok(verification)
+    }
+
+    // Find the landed transaction of a path sent after `since_unix`, then verify it
+    pub async fn verify_path(&self, spr: &SwapPathResult, since_unix: i64) -> Result<TradeVerification> {
+        let signature = self.find_trade_signature(spr, since_unix).await?;
+        self.verify(&signature, spr).await
+    }
+
+    pub fn report(&self) -> CalibrationReport {
+        self.report.lock().unwrap().clone()
+    }
+
+    fn record(&self, verification: TradeVerification) {
+        let report = {
+            let mut report = self.report.lock().unwrap();
+            report.trades += 1;
+            if verification.flagged {
+                report.flagged += 1;
+            }
+            report.expected_profit_sum += verification.expected_profit;
+            report.realized_profit_sum += verification.realized_profit;
+            report.last_trades.push(verification);
+            let overflow = report.last_trades.len().saturating_sub(100);
+            report.last_trades.drain(..overflow);
+            report.clone()
+        };
+        if let Err(e) = write_report(&self.file_path, &report) {
+            error!("❌ Unable to write calibration report: {:?}", e);
+        }
+    }
+}
+
+fn write_report(file_path: &str, report: &CalibrationReport) -> Result<()> {
+    if let Some(parent) = Path::new(file_path).parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = File::create(file_path)?;
+    serde_json::to_writer_pretty(file, report)?;
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::Result;
+use log::{error, info};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::arbitrage::types::{SwapPath, SwapPathResult, SwapPathSelected, SwapRouteSimulation};
+use crate::common::constants::Env;
+use crate::common::rpc_pool::nonblocking_client;
+use crate::common::utils::from_str;
+use crate::transactions::create_transaction::{create_ata_extendlut_transaction, get_lut_address_for_market, ChainType, SendOrSimulate, LOCALNET_RPC_URL};
+use crate::transactions::signer::wallet_signer;
+
+// Accounts setup done before a path set goes live, so the first real opportunity
+// isn't spent creating ATAs or extending the LUT.
+// Only Orca Whirlpools and Meteora are executed, none of them needs open-orders accounts.
+// Delay before an incomplete warm-up is tried again
+pub static WARMUP_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone)]
+pub struct WarmupConfig {
+    pub chain: ChainType,
+    pub simulate_or_send: SendOrSimulate,
+    pub lut_address: Pubkey,
+    // Amount used to build the swap instructions, only the accounts matter
+    pub amount_in: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WarmupReport {
+    pub paths_checked: usize,
+    pub paths_prepared: usize,
+    pub missing_atas: Vec<Pubkey>,
+    pub markets_without_lut: Vec<Pubkey>,
+}
+
+impl WarmupReport {
+    // Something is still missing, to try again on the next refresh
+    pub fn is_incomplete(&self) -> bool {
+        !self.missing_atas.is_empty() || !self.markets_without_lut.is_empty()
+    }
+}
+
+fn path_tokens(path: &SwapPath) -> Vec<Pubkey> {
+    let mut seen: HashSet<Pubkey> = HashSet::new();
+    path.paths
+        .iter()
+        .flat_map(|route| [route.tokenIn.clone(), route.tokenOut.clone()])
+        .filter_map(|token| from_str(&token).ok())
+        .filter(|token| seen.insert(*token))
+        .collect()
+}
+
+fn markets_without_lut(path: &SwapPath) -> Vec<Pubkey> {
+    path.paths
+        .iter()
+        .filter_map(|route| from_str(&route.pool_address).ok())
+        .filter(|market| !matches!(get_lut_address_for_market(*market, false), Ok((true, _))))
+        .collect()
+}
+
+// SwapPathResult with the path accounts, enough for construct_transaction
+pub fn placeholder_path_result(path: &SwapPath, amount_in: u64) -> SwapPathResult {
+    let route_simulations: Vec<SwapRouteSimulation> = path
+        .paths
+        .iter()
+        .map(|route| SwapRouteSimulation {
+            id_route: route.id,
+            pool_address: route.pool_address.clone(),
+            dex_label: route.dex.clone(),
+            token_0to1: route.token_0to1,
+            token_in: route.tokenIn.clone(),
+            token_out: route.tokenOut.clone(),
+            amount_in,
+            estimated_amount_out: "0".to_string(),
+            estimated_min_amount_out: "0".to_string(),
+        })
+        .collect();
+    let token_in = path.paths.first().map(|route| route.tokenIn.clone()).unwrap_or_default();
+    SwapPathResult {
+        path_id: 0,
+        hops: path.hops,
+        tokens_path: String::new(),
+        route_simulations,
+        token_in: token_in.clone(),
+        token_in_symbol: String::new(),
+        token_out: token_in,
+        token_out_symbol: String::new(),
+        amount_in,
+        estimated_amount_out: "0".to_string(),
+        estimated_min_amount_out: "0".to_string(),
+        result: 0.0,
+    }
+}
+
+pub async fn warm_up_paths(config: &WarmupConfig, paths: &[SwapPathSelected]) -> Result<WarmupReport> {
+    info!("🔥 Warm-up of {} paths...", paths.len());
+    let env = Env::new();
+    let rpc_url = match config.chain {
+        ChainType::Mainnet => env.rpc_url.clone(),
+        ChainType::Devnet => env.devnet_rpc_url.clone(),
+        ChainType::Localnet => LOCALNET_RPC_URL.to_string(),
+    };
+    let rpc_client = nonblocking_client(&rpc_url);
+    let payer = wallet_signer();
+
+    let mut report = WarmupReport::default();
+    // Only the ATAs found on chain are skipped for the next paths, a failed creation is tried again
+    let mut existing_atas: HashSet<Pubkey> = HashSet::new();
+    for selected in paths {
+        report.paths_checked += 1;
+        let tokens = path_tokens(&selected.path);
+        let mut missing_atas: Vec<Pubkey> = Vec::new();
+        for token in &tokens {
+            if existing_atas.contains(token) {
+                continue;
+            }
+            let ata = get_associated_token_address(&payer.pubkey(), token);
+            match rpc_client.get_account(&ata).await {
+                Ok(_) => {
+                    existing_atas.insert(*token);
+                }
+                Err(_) => missing_atas.push(*token),
+            }
+        }
+
+        // The LUT is extended with one market per transaction
+        let mut attempts = 0;
+        let mut missing_markets = markets_without_lut(&selected.path);
+        while (!missing_atas.is_empty() || !missing_markets.is_empty()) && attempts <= selected.path.hops {
+            let spr = placeholder_path_result(&selected.path, config.amount_in);
+            if let Err(e) = create_ata_extendlut_transaction(config.chain.clone(), config.simulate_or_send, spr, config.lut_address, tokens.clone()).await {
+                error!("❌ Warm-up transaction failed: {:?}", e);
+                break;
+            }
+            attempts += 1;
+            let mut still_missing = Vec::with_capacity(missing_atas.len());
+            for token in missing_atas {
+                match rpc_client.get_account(&get_associated_token_address(&payer.pubkey(), &token)).await {
+                    Ok(_) => {
+                        existing_atas.insert(token);
+                    }
+                    Err(_) => still_missing.push(token),
+                }
+            }
+            missing_atas = still_missing;
+            missing_markets = markets_without_lut(&selected.path);
+        }
+        if attempts > 0 {
+            report.paths_prepared += 1;
+        }
+        report.missing_atas.extend(missing_atas);
+        report.markets_without_lut.extend(missing_markets);
+    }
+
+    report.missing_atas.sort();
+    report.missing_atas.dedup();
+    if !report.is_incomplete() {
+        info!("🔥 Warm-up done: {} paths checked, {} prepared", report.paths_checked, report.paths_prepared);
+    } else {
+        error!("⚠️ Warm-up incomplete: {} ATAs and {} LUT markets still missing", report.missing_atas.len(), report.markets_without_lut.len());
+    }
+    Ok(report)
+}
@@ -0,0 +1,97 @@
+// Allocation budget of the local quotes, measured with a counting global allocator.
+// In its own test binary: the allocator replaces the global one of every test it is linked with
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use solana_sdk::pubkey::Pubkey;
+
+use MEV_Bot_Solana::arbitrage::simulate::simulate_path_local;
+use MEV_Bot_Solana::arbitrage::types::{Route, SwapPath};
+use MEV_Bot_Solana::markets::types::{DexLabel, Market};
+
+// Heap allocations counted per thread, so the tests running alongside don't skew the count
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations_during<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATIONS.with(|count| count.get());
+    let result = f();
+    (result, ALLOCATIONS.with(|count| count.get()) - before)
+}
+
+// Whirlpool account with just what the local quote reads: tick spacing, fee rate, liquidity, sqrt price,
+// current tick in the tick array starting at -22528, mint A
+fn whirlpool_data(mint_a: &Pubkey) -> Vec<u8> {
+    let mut data = vec![0u8; 653];
+    data[41..43].copy_from_slice(&64u16.to_le_bytes());
+    data[45..47].copy_from_slice(&3_000u16.to_le_bytes());
+    data[49..65].copy_from_slice(&120_000_000_000_000u128.to_le_bytes());
+    data[65..81].copy_from_slice(&7_143_265_591_698_411_110u128.to_le_bytes());
+    data[81..85].copy_from_slice(&(-18_976i32).to_le_bytes());
+    data[101..133].copy_from_slice(mint_a.as_ref());
+    data
+}
+
+// Tick array without any initialized tick, the swap stays in the current range
+fn tick_array_data(whirlpool: &Pubkey) -> Vec<u8> {
+    let mut data = vec![0u8; 9988];
+    data[8..12].copy_from_slice(&(-22_528i32).to_le_bytes());
+    data[9956..9988].copy_from_slice(whirlpool.as_ref());
+    data
+}
+
+// Local quote of a path: the Vec of the hops plus the 5 Strings of each SwapRouteSimulation, nothing in the math itself
+#[test]
+fn simulate_path_local_allocations() {
+    let sol = Pubkey::new_unique();
+    let usdc = Pubkey::new_unique();
+    let market = |id: &Pubkey| Market {
+        tokenMintA: sol.to_string(),
+        tokenVaultA: Pubkey::new_unique().to_string(),
+        tokenMintB: usdc.to_string(),
+        tokenVaultB: Pubkey::new_unique().to_string(),
+        dexLabel: DexLabel::ORCA_WHIRLPOOLS,
+        fee: 3_000,
+        id: id.to_string(),
+        account_data: None,
+        liquidity: None,
+    };
+    let route = |id: u32, pool: &Pubkey, token_in: &Pubkey, token_out: &Pubkey| Route {
+        id,
+        dex: DexLabel::ORCA_WHIRLPOOLS,
+        pool_address: pool.to_string(),
+        token_0to1: *token_in == sol,
+        tokenIn: token_in.to_string(),
+        tokenOut: token_out.to_string(),
+        fee: 3_000,
+    };
+    let (pool_1, pool_2) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let markets = vec![market(&pool_1), market(&pool_2)];
+    let path = SwapPath { hops: 2, paths: vec![route(1, &pool_1, &sol, &usdc), route(2, &pool_2, &usdc, &sol)], id_paths: vec![1, 2] };
+    let accounts: HashMap<String, Vec<u8>> = [pool_1, pool_2]
+        .iter()
+        .flat_map(|pool| [(pool.to_string(), whirlpool_data(&sol)), (Pubkey::new_unique().to_string(), tick_array_data(pool))])
+        .collect();
+
+    let (quote, allocations) = allocations_during(|| simulate_path_local(&accounts, 1_000_000_000, &path, &markets));
+    assert!(quote.is_some());
+    assert!(allocations <= 1 + 5 * path.hops as usize, "{} allocations for a {} hops quote", allocations, path.hops);
+}